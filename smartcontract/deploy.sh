@@ -3,6 +3,9 @@
 # Exit on error
 set -e
 
+# Account allowed to run admin operations on rounds and lottery-rounds
+ADMIN_OWNER=${ADMIN_OWNER:?Set ADMIN_OWNER to the account owner that administers rounds and lottery-rounds}
+
 # Deploy native
 echo "Deploying native..."
 # Uses 'linera project publish-and-create' which automatically handles compilation
@@ -25,9 +28,9 @@ echo "Leaderboard=$LEADERBOARD_ID" >> app_ids.txt
 # Deploy rounds
 echo "Deploying rounds..."
 # Points to 'rounds' directory
-# Parameters: native_app_id, leaderboard_app_id
+# Parameters: native_app_id, leaderboard_app_id, admin
 ROUNDS_ID=$(linera project publish-and-create rounds \
-    --json-parameters "{\"native_app_id\":\"$NATIVE_ID\", \"leaderboard_app_id\":\"$LEADERBOARD_ID\"}")
+    --json-parameters "{\"native_app_id\":\"$NATIVE_ID\", \"leaderboard_app_id\":\"$LEADERBOARD_ID\", \"admin\":\"$ADMIN_OWNER\"}")
 
 echo "Rounds deployed with ID: $ROUNDS_ID"
 echo "ROUNDS=$ROUNDS_ID" >> app_ids.txt
@@ -54,7 +57,7 @@ echo "$OPERATION_JSON"
 # Deploy lottery-rounds
 echo "Deploying lottery-rounds..."
 LOTTERY_ROUNDS_ID=$(linera project publish-and-create lottery-rounds \
-    --json-parameters "{\"native_app_id\":\"$NATIVE_ID\"}")
+    --json-parameters "{\"native_app_id\":\"$NATIVE_ID\", \"admin\":\"$ADMIN_OWNER\"}")
 
 echo "Lottery Rounds deployed with ID: $LOTTERY_ROUNDS_ID"
 echo "LOTTERY_ROUNDS=$LOTTERY_ROUNDS_ID" >> app_ids.txt
@@ -6,16 +6,17 @@
 mod state;
 
 use linera_sdk::{
-    linera_base_types::WithContractAbi,
+    abis::fungible::Account,
+    linera_base_types::{Amount, ApplicationId, WithContractAbi},
     views::{RootView, View},
     Contract, ContractRuntime,
 };
-use leaderboard::{LeaderboardAbi, Operation};
+use leaderboard::{LeaderboardAbi, LeaderboardResponse, Operation};
+use native_fungible_abi::{ExtendedOperation, ExtendedResponse};
 use self::state::LeaderboardState;
 
 pub struct LeaderboardContract {
     state: LeaderboardState,
-    #[allow(dead_code)]
     runtime: ContractRuntime<Self>,
 }
 
@@ -45,7 +46,109 @@ impl Contract for LeaderboardContract {
     async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response {
         match operation {
             Operation::UpdateScore { owner, chain_id, is_win, amount } => {
-                self.state.update_score(owner, chain_id, is_win, amount).await;
+                let caller = self.runtime.authenticated_caller_id()
+                    .expect("UpdateScore can only be called by a game application");
+                if !self.state.is_game_app(caller).await {
+                    panic!("Application {} is not allowed to report scores", caller);
+                }
+                let timestamp = self.runtime.system_time().micros();
+                self.state.update_score(owner, chain_id, is_win, amount, timestamp).await;
+                LeaderboardResponse::Ok
+            }
+            Operation::GetAchievements { owner } => {
+                LeaderboardResponse::Achievements(self.state.get_achievements(owner).await)
+            }
+            Operation::GetLeaderboardEntry { chain_id, owner } => {
+                LeaderboardResponse::Entry(self.state.get_entry(&chain_id, owner).await)
+            }
+            Operation::SetDecayHalfLife { half_life_days } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("SetDecayHalfLife can only be executed on the application creator chain");
+                }
+                match self.state.set_half_life_days(half_life_days) {
+                    Ok(()) => LeaderboardResponse::Ok,
+                    Err(e) => panic!("Failed to set decay half-life: {}", e),
+                }
+            }
+            Operation::SetNativeAppId { native_app_id, prize_wallet } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("SetNativeAppId can only be executed on the application creator chain");
+                }
+                match native_app_id.parse::<ApplicationId>() {
+                    Ok(app_id) => self.state.native_app_id.set(Some(app_id.with_abi())),
+                    Err(e) => panic!("Failed to parse Winzareal ApplicationId: {:?}", e),
+                }
+                self.state.prize_wallet.set(Some(prize_wallet));
+                LeaderboardResponse::Ok
+            }
+            Operation::SetGameApps { game_apps } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("SetGameApps can only be executed on the application creator chain");
+                }
+                self.state.set_game_apps(game_apps);
+                LeaderboardResponse::Ok
+            }
+            Operation::FundLeaderboardPrize { amount } => {
+                if amount == Amount::ZERO {
+                    panic!("Prize funding must be greater than zero");
+                }
+                let owner = self.runtime.authenticated_signer()
+                    .expect("FundLeaderboardPrize requires an authenticated signer");
+                let native_app_id = self.state.native_app_id.get().expect("Winzareal app ID not set");
+                let prize_wallet = self.state.prize_wallet.get().expect("Prize wallet not set");
+                let creator_chain_id = self.runtime.application_creator_chain_id();
+                let _response: ExtendedResponse = self.runtime.call_application(
+                    true,
+                    native_app_id,
+                    &ExtendedOperation::Transfer {
+                        owner,
+                        amount,
+                        target_account: Account {
+                            chain_id: creator_chain_id,
+                            owner: prize_wallet,
+                        },
+                        prediction: None,
+                        memo: None,
+                    },
+                );
+                self.state.fund_prize_pool(amount);
+                LeaderboardResponse::PrizePool(*self.state.prize_pool.get())
+            }
+            Operation::DistributeSeasonPrizes { top_n } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("DistributeSeasonPrizes can only be executed on the application creator chain");
+                }
+                let native_app_id = self.state.native_app_id.get().expect("Winzareal app ID not set");
+                let prize_wallet = self.state.prize_wallet.get().expect("Prize wallet not set");
+                // SendReward pays from the signer, so only the prize wallet can pay the pool out
+                if self.runtime.authenticated_signer() != Some(prize_wallet) {
+                    panic!("Season prizes must be distributed by the prize wallet {}", prize_wallet);
+                }
+                let block = self.runtime.block_height();
+                if *self.state.last_distribution_block.get() == Some(block) {
+                    panic!("Season prizes were already distributed in block {}", block);
+                }
+                let now = self.runtime.system_time().micros();
+                let prizes = match self.state.season_prizes(top_n, now).await {
+                    Ok(prizes) => prizes,
+                    Err(e) => panic!("Failed to distribute season prizes: {}", e),
+                };
+                for (stats, amount) in &prizes {
+                    let _response: ExtendedResponse = self.runtime.call_application(
+                        true,
+                        native_app_id,
+                        &ExtendedOperation::SendReward {
+                            recipient: stats.owner,
+                            amount: *amount,
+                            source_chain_id: Some(stats.chain_id.clone()),
+                        },
+                    );
+                }
+                self.state.finish_distribution(block);
+                LeaderboardResponse::SeasonPrizes(prizes.into_iter().map(|(stats, amount)| (stats.owner, amount)).collect())
+            }
+            Operation::GetLeaderboardPrizePool => {
+                LeaderboardResponse::PrizePool(*self.state.prize_pool.get())
             }
         }
     }
@@ -58,3 +161,272 @@ impl Contract for LeaderboardContract {
         self.state.save().await.expect("Failed to save state");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use futures::FutureExt as _;
+    use leaderboard::{AchievementType, LeaderboardResponse, Operation, MICROS_PER_DAY};
+    use linera_sdk::{
+        linera_base_types::{AccountOwner, Amount, ApplicationId, BlockHeight, ChainId, CryptoHash, Timestamp},
+        util::BlockingWait,
+        views::View,
+        Contract, ContractRuntime,
+    };
+    use native_fungible_abi::{ExtendedOperation, ExtendedResponse};
+
+    use super::{LeaderboardContract, LeaderboardState};
+
+    fn game_app() -> ApplicationId {
+        ApplicationId::new(CryptoHash::test_hash("rounds"))
+    }
+
+    /// A leaderboard that accepts scores from `game_app`, which is the calling application
+    fn create_contract() -> LeaderboardContract {
+        let chain = ChainId(CryptoHash::test_hash("leaderboard"));
+        let runtime = ContractRuntime::new()
+            .with_chain_id(chain)
+            .with_application_creator_chain_id(chain)
+            .with_authenticated_caller_id(game_app())
+            .with_system_time(Timestamp::from(1_000));
+        let state = LeaderboardState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to load state");
+        let mut contract = LeaderboardContract { state, runtime };
+        contract.execute_operation(Operation::SetGameApps { game_apps: vec![game_app()] }).now_or_never().unwrap();
+        contract
+    }
+
+    #[test]
+    #[should_panic(expected = "is not allowed to report scores")]
+    fn scores_from_other_applications_are_rejected() {
+        let mut contract = create_contract();
+        contract.runtime.set_authenticated_caller_id(Some(ApplicationId::new(CryptoHash::test_hash("impostor"))));
+
+        record(&mut contract, AccountOwner::from(CryptoHash::test_hash("alice")), true, Amount::from_tokens(1_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "UpdateScore can only be called by a game application")]
+    fn scores_from_users_are_rejected() {
+        let mut contract = create_contract();
+        contract.runtime.set_authenticated_caller_id(None);
+
+        record(&mut contract, AccountOwner::from(CryptoHash::test_hash("alice")), true, Amount::from_tokens(1_000));
+    }
+
+    fn record(contract: &mut LeaderboardContract, owner: AccountOwner, is_win: bool, amount: Amount) {
+        contract
+            .execute_operation(Operation::UpdateScore { owner, chain_id: "chain".to_string(), is_win, amount })
+            .now_or_never()
+            .expect("Execution should not await anything");
+    }
+
+    fn achievements(contract: &mut LeaderboardContract, owner: AccountOwner) -> Vec<(AchievementType, u64)> {
+        match contract.execute_operation(Operation::GetAchievements { owner }).now_or_never().unwrap() {
+            LeaderboardResponse::Achievements(achievements) => achievements,
+            other => panic!("Unexpected response {:?}", other),
+        }
+    }
+
+    #[test]
+    fn win_milestones_are_awarded_once() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+
+        record(&mut contract, alice, false, Amount::ONE);
+        assert!(achievements(&mut contract, alice).is_empty());
+
+        record(&mut contract, alice, true, Amount::ONE);
+        assert_eq!(
+            achievements(&mut contract, alice),
+            vec![(AchievementType::FirstWin, 1_000), (AchievementType::LargestWin, 1_000)]
+        );
+
+        contract.runtime.set_system_time(Timestamp::from(2_000));
+        for _ in 1..9 {
+            record(&mut contract, alice, true, Amount::ONE);
+        }
+        assert!(!achievements(&mut contract, alice).iter().any(|(kind, _)| *kind == AchievementType::TenWins));
+
+        contract.runtime.set_system_time(Timestamp::from(3_000));
+        record(&mut contract, alice, true, Amount::ONE);
+        assert_eq!(
+            achievements(&mut contract, alice),
+            vec![
+                (AchievementType::FirstWin, 1_000),
+                (AchievementType::TenWins, 3_000),
+                (AchievementType::LargestWin, 1_000),
+            ]
+        );
+
+        contract.runtime.set_system_time(Timestamp::from(4_000));
+        for _ in 10..99 {
+            record(&mut contract, alice, true, Amount::ONE);
+        }
+        assert_eq!(achievements(&mut contract, alice).len(), 3);
+
+        contract.runtime.set_system_time(Timestamp::from(5_000));
+        record(&mut contract, alice, true, Amount::ONE);
+        record(&mut contract, alice, true, Amount::ONE);
+        assert_eq!(
+            achievements(&mut contract, alice),
+            vec![
+                (AchievementType::FirstWin, 1_000),
+                (AchievementType::TenWins, 3_000),
+                (AchievementType::HundredWins, 5_000),
+                (AchievementType::LargestWin, 1_000),
+            ]
+        );
+    }
+
+    #[test]
+    fn largest_win_goes_to_the_record_holder() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+
+        record(&mut contract, alice, true, Amount::from_tokens(5));
+        record(&mut contract, bob, true, Amount::from_tokens(3));
+        assert!(!achievements(&mut contract, bob).contains(&(AchievementType::LargestWin, 1_000)));
+
+        contract.runtime.set_system_time(Timestamp::from(2_000));
+        record(&mut contract, bob, true, Amount::from_tokens(6));
+        assert!(achievements(&mut contract, bob).contains(&(AchievementType::LargestWin, 2_000)));
+
+        contract.runtime.set_system_time(Timestamp::from(3_000));
+        record(&mut contract, alice, true, Amount::from_tokens(7));
+        assert!(achievements(&mut contract, alice).contains(&(AchievementType::LargestWin, 1_000)));
+    }
+
+    #[test]
+    fn leaderboard_entry_reports_rank() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let carol = AccountOwner::from(CryptoHash::test_hash("carol"));
+
+        record(&mut contract, alice, true, Amount::from_tokens(10));
+        record(&mut contract, bob, true, Amount::from_tokens(5));
+        record(&mut contract, bob, false, Amount::from_tokens(2));
+        record(&mut contract, carol, true, Amount::from_tokens(1));
+
+        let entry = |contract: &mut LeaderboardContract, chain_id: &str, owner| {
+            let operation = Operation::GetLeaderboardEntry { chain_id: chain_id.to_string(), owner };
+            match contract.execute_operation(operation).now_or_never().unwrap() {
+                LeaderboardResponse::Entry(entry) => entry,
+                other => panic!("Unexpected response {:?}", other),
+            }
+        };
+
+        let bob_entry = entry(&mut contract, "chain", bob).expect("Bob should be on the board");
+        assert_eq!(bob_entry.rank, 2);
+        assert_eq!(bob_entry.stats.owner, bob);
+        assert_eq!((bob_entry.stats.wins, bob_entry.stats.losses), (1, 1));
+        assert_eq!(bob_entry.stats.total_won, Amount::from_tokens(5));
+
+        assert!(entry(&mut contract, "other-chain", bob).is_none());
+        let dave = AccountOwner::from(CryptoHash::test_hash("dave"));
+        assert!(entry(&mut contract, "chain", dave).is_none());
+    }
+
+    #[test]
+    fn old_wins_decay_out_of_the_weighted_ranking() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        contract.execute_operation(Operation::SetDecayHalfLife { half_life_days: 10 }).now_or_never().unwrap();
+
+        record(&mut contract, alice, true, Amount::from_tokens(100));
+        contract.runtime.set_system_time(Timestamp::from(1_000 + 30 * MICROS_PER_DAY));
+        record(&mut contract, bob, true, Amount::from_tokens(20));
+
+        // Three half-lives later alice's 100 tokens weigh 12.5, below bob's fresh 20
+        let now = 1_000 + 30 * MICROS_PER_DAY;
+        let weighted = contract.state.ranked_players_weighted(now).now_or_never().unwrap();
+        assert_eq!(weighted.iter().map(|stats| stats.owner).collect::<Vec<_>>(), vec![bob, alice]);
+        assert_eq!(weighted[1].weighted_score, u128::from(Amount::from_millis(12_500)));
+        let raw = contract.state.ranked_players().now_or_never().unwrap();
+        assert_eq!(raw[0].owner, alice);
+
+        // A loss decays the score first, then subtracts
+        contract.runtime.set_system_time(Timestamp::from(1_000 + 40 * MICROS_PER_DAY));
+        record(&mut contract, bob, false, Amount::from_tokens(4));
+        let bob_stats = contract.state.players.get(&bob).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(bob_stats.weighted_score, u128::from(Amount::from_tokens(6)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Season prizes were already distributed in block 7")]
+    fn season_prizes_are_paid_out_once_per_block() {
+        let chain = ChainId(CryptoHash::test_hash("leaderboard"));
+        let native_app_id = ApplicationId::new(CryptoHash::test_hash("native"));
+        let wallet = AccountOwner::from(CryptoHash::test_hash("wallet"));
+        let sponsor = AccountOwner::from(CryptoHash::test_hash("sponsor"));
+        let token_calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = token_calls.clone();
+        let runtime = ContractRuntime::new()
+            .with_chain_id(chain)
+            .with_application_creator_chain_id(chain)
+            .with_system_time(Timestamp::from(1_000))
+            .with_block_height(BlockHeight(7))
+            .with_authenticated_signer(sponsor)
+            .with_authenticated_caller_id(game_app())
+            .with_call_application_handler(move |_authenticated, application_id, operation| {
+                assert_eq!(application_id, native_app_id);
+                recorder.lock().unwrap().push(bcs::from_bytes::<ExtendedOperation>(&operation).unwrap());
+                bcs::to_bytes(&ExtendedResponse::Ok).unwrap()
+            });
+        let state = LeaderboardState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to load state");
+        let mut contract = LeaderboardContract { state, runtime };
+        contract.execute_operation(Operation::SetGameApps { game_apps: vec![game_app()] }).now_or_never().unwrap();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let carol = AccountOwner::from(CryptoHash::test_hash("carol"));
+        record(&mut contract, alice, true, Amount::from_tokens(30));
+        record(&mut contract, bob, true, Amount::from_tokens(20));
+        record(&mut contract, carol, false, Amount::from_tokens(5));
+
+        let execute = |contract: &mut LeaderboardContract, operation| {
+            contract.execute_operation(operation).now_or_never().expect("Execution should not await anything")
+        };
+        execute(&mut contract, Operation::SetNativeAppId { native_app_id: native_app_id.to_string(), prize_wallet: wallet });
+        execute(&mut contract, Operation::FundLeaderboardPrize { amount: Amount::from_tokens(10) });
+        assert!(matches!(
+            token_calls.lock().unwrap().as_slice(),
+            [ExtendedOperation::Transfer { owner, amount, target_account, prediction: None, .. }]
+                if *owner == sponsor && *amount == Amount::from_tokens(10) && target_account.owner == wallet
+        ));
+        match execute(&mut contract, Operation::GetLeaderboardPrizePool) {
+            LeaderboardResponse::PrizePool(pool) => assert_eq!(pool, Amount::from_tokens(10)),
+            other => panic!("Unexpected response {:?}", other),
+        }
+
+        // Carol has no positive score, so the pool is split 50:30 between alice and bob
+        contract.runtime.set_authenticated_signer(Some(wallet));
+        token_calls.lock().unwrap().clear();
+        match execute(&mut contract, Operation::DistributeSeasonPrizes { top_n: 3 }) {
+            LeaderboardResponse::SeasonPrizes(prizes) => assert_eq!(
+                prizes,
+                vec![(alice, Amount::from_millis(6_250)), (bob, Amount::from_millis(3_750))]
+            ),
+            other => panic!("Unexpected response {:?}", other),
+        }
+        let rewards: Vec<(AccountOwner, Amount)> = token_calls.lock().unwrap().iter().map(|operation| match operation {
+            ExtendedOperation::SendReward { recipient, amount, .. } => (*recipient, *amount),
+            other => panic!("Unexpected token operation {:?}", other),
+        }).collect();
+        assert_eq!(rewards, vec![(alice, Amount::from_millis(6_250)), (bob, Amount::from_millis(3_750))]);
+        assert_eq!(*contract.state.prize_pool.get(), Amount::ZERO);
+        assert_eq!(*contract.state.last_distribution_block.get(), Some(BlockHeight(7)));
+
+        // A refill cannot be paid out again in the same block
+        contract.runtime.set_authenticated_signer(Some(sponsor));
+        execute(&mut contract, Operation::FundLeaderboardPrize { amount: Amount::ONE });
+        contract.runtime.set_authenticated_signer(Some(wallet));
+        execute(&mut contract, Operation::DistributeSeasonPrizes { top_n: 1 });
+    }
+}
@@ -1,11 +1,14 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use async_graphql::{Request, Response, SimpleObject};
-use linera_sdk::linera_base_types::{AccountOwner, Amount, ContractAbi, ServiceAbi};
+use async_graphql::{ComplexObject, Request, Response, SimpleObject};
+use linera_sdk::linera_base_types::{AccountOwner, Amount, ApplicationId, ContractAbi, ServiceAbi};
 use serde::{Deserialize, Serialize};
 
+pub use native_fungible_abi::list_query::SortOrder;
+
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
 pub struct PlayerStats {
     pub owner: AccountOwner,
     pub chain_id: String,
@@ -14,13 +17,102 @@ pub struct PlayerStats {
     pub total_won: Amount,
     pub total_lost: Amount,
     // Rank will be calculated dynamically in service
+    /// Net winnings in attos, decayed by the half-life as of `last_update_at`
+    #[graphql(skip)]
+    pub weighted_score: u128,
+    pub last_update_at: u64,
+}
+
+#[ComplexObject]
+impl PlayerStats {
+    /// Decayed net winnings in attos (GraphQL has no 128-bit integers)
+    async fn weighted_score(&self) -> String {
+        self.weighted_score.to_string()
+    }
+}
+
+pub const MICROS_PER_DAY: u64 = 86_400_000_000;
+
+/// Half-life of the weighted score until an admin sets another one
+pub const DEFAULT_HALF_LIFE_DAYS: u64 = 30;
+
+/// Decay `score` over `elapsed_micros`: halved (shifted right) once per whole half-life,
+/// then scaled linearly toward the next halving for the leftover whole days.
+pub fn decay_score(score: u128, elapsed_micros: u64, half_life_days: u64) -> u128 {
+    if half_life_days == 0 {
+        return score;
+    }
+    let days = elapsed_micros / MICROS_PER_DAY;
+    let halvings = days / half_life_days;
+    if halvings >= u128::BITS as u64 {
+        return 0;
+    }
+    let score = score >> halvings;
+
+    // score * (span - leftover) / span, split so the multiplication cannot overflow
+    let span = 2 * u128::from(half_life_days);
+    let kept = span - u128::from(days % half_life_days);
+    score / span * kept + score % span * kept / span
+}
+
+/// Share of the season prize pool for each paid rank, in basis points (1st, 2nd, 3rd)
+pub const SEASON_PRIZE_WEIGHTS_BPS: [u128; 3] = [5_000, 3_000, 2_000];
+
+/// Split `pool` between the top `winners` players (at most three) by rank weight.
+/// With fewer winners the weights are scaled up so the whole pool is paid out;
+/// the rounding remainder goes to first place.
+pub fn season_prize_shares(pool: Amount, winners: usize) -> Vec<Amount> {
+    let weights = &SEASON_PRIZE_WEIGHTS_BPS[..winners.min(SEASON_PRIZE_WEIGHTS_BPS.len())];
+    let total_weight: u128 = weights.iter().sum();
+    if total_weight == 0 {
+        return Vec::new();
+    }
+    let pool = u128::from(pool);
+    // pool * weight / total_weight, split so the multiplication cannot overflow
+    let mut shares: Vec<u128> = weights.iter()
+        .map(|weight| pool / total_weight * weight + pool % total_weight * weight / total_weight)
+        .collect();
+    shares[0] += pool - shares.iter().sum::<u128>();
+    shares.into_iter().map(Amount::from_attos).collect()
+}
+
+/// A player's stats with their position on the board (1 = most won)
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct LeaderboardEntry {
+    pub stats: PlayerStats,
+    pub rank: u64,
+}
+
+/// Milestones a player can reach; each is awarded at most once per player
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, async_graphql::Enum)]
+pub enum AchievementType {
+    FirstWin,       // 1 win
+    TenWins,        // 10 wins
+    HundredWins,    // 100 wins
+    LargestWin,     // Set the largest single win recorded on the leaderboard
+    FirstTicket,    // Lottery milestones, not reported through UpdateScore yet
+    HundredTickets,
+    Jackpot,
+}
+
+/// Win counts at which a win-count achievement is awarded
+pub const WIN_MILESTONES: [(u64, AchievementType); 3] = [
+    (1, AchievementType::FirstWin),
+    (10, AchievementType::TenWins),
+    (100, AchievementType::HundredWins),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct Achievement {
+    pub achievement: AchievementType,
+    pub awarded_at: u64,
 }
 
 pub struct LeaderboardAbi;
 
 impl ContractAbi for LeaderboardAbi {
     type Operation = Operation;
-    type Response = ();
+    type Response = LeaderboardResponse;
 }
 
 impl ServiceAbi for LeaderboardAbi {
@@ -30,10 +122,86 @@ impl ServiceAbi for LeaderboardAbi {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum Operation {
+    /// Record a game result. Only accepted from the game applications set with
+    /// SetGameApps, since scores decide who is paid the season prizes.
     UpdateScore {
         owner: AccountOwner,
         chain_id: String,
         is_win: bool,
         amount: Amount,
+    },
+    /// Get a player's achievements with the time each was awarded
+    GetAchievements { owner: AccountOwner },
+    /// Get a single player's stats and rank
+    GetLeaderboardEntry { chain_id: String, owner: AccountOwner },
+    /// Set the half-life of the weighted score in days (creator chain only)
+    SetDecayHalfLife { half_life_days: u64 },
+    /// Set the Winzareal token app and the wallet holding the season prize pool (creator
+    /// chain only). Prizes are paid with SendReward, which spends from the signer, so the
+    /// prize wallet is also the only account allowed to distribute them.
+    SetNativeAppId { native_app_id: String, prize_wallet: AccountOwner },
+    /// Replace the game applications allowed to call UpdateScore (creator chain only)
+    SetGameApps { game_apps: Vec<ApplicationId> },
+    /// Move `amount` from the signer to the prize wallet and add it to the season prize pool
+    FundLeaderboardPrize { amount: Amount },
+    /// Pay the prize pool to the top `top_n` (1 to 3) players by weighted score and empty it
+    /// (creator chain only, signed by the prize wallet, at most once per block)
+    DistributeSeasonPrizes { top_n: u64 },
+    GetLeaderboardPrizePool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum LeaderboardResponse {
+    Ok,
+    Achievements(Vec<(AchievementType, u64)>),
+    Entry(Option<LeaderboardEntry>),
+    PrizePool(Amount),
+    /// Prize sent to each winner, first place first
+    SeasonPrizes(Vec<(AccountOwner, Amount)>),
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::Amount;
+
+    use super::{decay_score, season_prize_shares, MICROS_PER_DAY};
+
+    #[test]
+    fn test_score_halves_every_half_life() {
+        assert_eq!(decay_score(1_000, 0, 30), 1_000);
+        assert_eq!(decay_score(1_000, 30 * MICROS_PER_DAY, 30), 500);
+        assert_eq!(decay_score(1_000, 60 * MICROS_PER_DAY, 30), 250);
+        assert_eq!(decay_score(1_000, 300 * MICROS_PER_DAY, 30), 0);
+    }
+
+    #[test]
+    fn test_partial_half_life_decays_per_whole_day() {
+        // Less than a day changes nothing
+        assert_eq!(decay_score(1_000, MICROS_PER_DAY - 1, 30), 1_000);
+        // 15 of 30 days: 1000 * 45 / 60
+        assert_eq!(decay_score(1_000, 15 * MICROS_PER_DAY, 30), 750);
+        // One half-life and 15 days: 500 * 45 / 60
+        assert_eq!(decay_score(1_000, 45 * MICROS_PER_DAY, 30), 375);
+    }
+
+    #[test]
+    fn test_decay_handles_extremes() {
+        assert_eq!(decay_score(1_000, u64::MAX, 1), 0);
+        assert_eq!(decay_score(u128::MAX, 30 * MICROS_PER_DAY, 30), u128::MAX >> 1);
+        assert!(decay_score(u128::MAX, 15 * MICROS_PER_DAY, 30) > u128::MAX >> 1);
+        assert_eq!(decay_score(1_000, 90 * MICROS_PER_DAY, 0), 1_000);
+    }
+
+    #[test]
+    fn test_season_prizes_follow_rank_weights() {
+        let shares = season_prize_shares(Amount::from_tokens(10), 3);
+        assert_eq!(shares, vec![Amount::from_tokens(5), Amount::from_tokens(3), Amount::from_tokens(2)]);
+        // Two winners split 50:30 of the whole pool; the remainder goes to first place
+        assert_eq!(season_prize_shares(Amount::from_attos(8), 2), vec![Amount::from_attos(5), Amount::from_attos(3)]);
+        assert_eq!(season_prize_shares(Amount::from_attos(7), 3), vec![Amount::from_attos(4), Amount::from_attos(2), Amount::from_attos(1)]);
+        assert_eq!(season_prize_shares(Amount::from_tokens(10), 10).len(), 3);
+        assert!(season_prize_shares(Amount::from_tokens(10), 0).is_empty());
+        let total = season_prize_shares(Amount::MAX, 3).into_iter().fold(Amount::ZERO, |total, share| total.saturating_add(share));
+        assert_eq!(total, Amount::MAX);
     }
 }
@@ -12,7 +12,7 @@ use linera_sdk::{
     Service, ServiceRuntime,
 };
 use std::sync::Arc;
-use leaderboard::{LeaderboardAbi, PlayerStats};
+use leaderboard::{Achievement, LeaderboardAbi, LeaderboardEntry, PlayerStats, SortOrder};
 use self::state::LeaderboardState;
 
 pub struct LeaderboardService {
@@ -37,7 +37,8 @@ impl Service for LeaderboardService {
     async fn handle_query(&self, query: Request) -> Response {
         let schema = Schema::build(
             QueryRoot { 
-                storage_context: self.runtime.root_view_storage_context()
+                storage_context: self.runtime.root_view_storage_context(),
+                now: self.runtime.system_time().micros(),
             },
             EmptyMutation,
             EmptySubscription,
@@ -47,36 +48,69 @@ impl Service for LeaderboardService {
     }
 }
 
+/// The first `limit` of `ranked` (best first) players, reversed for an ascending order
+fn take_ranked(mut ranked: Vec<PlayerStats>, order: Option<SortOrder>, limit: usize) -> Vec<PlayerStats> {
+    if order == Some(SortOrder::Asc) {
+        ranked.reverse();
+    }
+    ranked.truncate(limit);
+    ranked
+}
+
 struct QueryRoot {
     storage_context: linera_sdk::views::ViewStorageContext,
+    now: u64,
 }
 
 #[Object]
 impl QueryRoot {
-    async fn player(&self, owner: AccountOwner) -> Option<PlayerStats> {
+    /// A single player's stats and rank, null for players not on the board
+    async fn player(&self, chain_id: String, owner: AccountOwner) -> Option<LeaderboardEntry> {
         let state = LeaderboardState::load(self.storage_context.clone())
             .await
             .expect("Failed to load state");
-        state.players.get(&owner).await.ok().flatten()
+        state.get_entry(&chain_id, owner).await
     }
 
-    async fn top_players(&self, limit: usize) -> Vec<PlayerStats> {
+    /// Players by rank, best first unless `order` is Asc (worst first)
+    async fn top_players(&self, limit: usize, order: Option<SortOrder>) -> Vec<PlayerStats> {
         let state = LeaderboardState::load(self.storage_context.clone())
             .await
             .expect("Failed to load state");
-            
-        let mut players = Vec::new();
-        if let Ok(indices) = state.players.indices().await {
-            for owner in indices {
-                if let Ok(Some(stats)) = state.players.get(&owner).await {
-                    players.push(stats);
-                }
-            }
-        }
-        
-        // Sort by total won (descending)
-        players.sort_by(|a, b| b.total_won.cmp(&a.total_won));
-        
-        players.into_iter().take(limit).collect()
+        take_ranked(state.ranked_players().await, order, limit)
+    }
+
+    /// Top players by net winnings decayed to now (recent results weigh more)
+    async fn top_players_weighted(&self, limit: usize, order: Option<SortOrder>) -> Vec<PlayerStats> {
+        let state = LeaderboardState::load(self.storage_context.clone())
+            .await
+            .expect("Failed to load state");
+        take_ranked(state.ranked_players_weighted(self.now).await, order, limit)
+    }
+
+    /// Days for the weighted score to halve
+    async fn decay_half_life_days(&self) -> u64 {
+        let state = LeaderboardState::load(self.storage_context.clone())
+            .await
+            .expect("Failed to load state");
+        state.half_life_days()
+    }
+
+    /// Funded season prizes waiting to be distributed
+    async fn prize_pool(&self) -> String {
+        let state = LeaderboardState::load(self.storage_context.clone())
+            .await
+            .expect("Failed to load state");
+        state.prize_pool.get().to_string()
+    }
+
+    async fn player_achievements(&self, owner: AccountOwner) -> Vec<Achievement> {
+        let state = LeaderboardState::load(self.storage_context.clone())
+            .await
+            .expect("Failed to load state");
+        state.get_achievements(owner).await
+            .into_iter()
+            .map(|(achievement, awarded_at)| Achievement { achievement, awarded_at })
+            .collect()
     }
 }
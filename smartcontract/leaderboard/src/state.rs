@@ -1,18 +1,38 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use linera_sdk::views::{linera_views, MapView, RootView, ViewStorageContext};
-use linera_sdk::linera_base_types::{AccountOwner, Amount};
-use leaderboard::PlayerStats;
+use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, SetView, View, ViewStorageContext};
+use linera_sdk::linera_base_types::{AccountOwner, Amount, ApplicationId, BlockHeight};
+use leaderboard::{
+    decay_score, season_prize_shares, AchievementType, LeaderboardEntry, PlayerStats, DEFAULT_HALF_LIFE_DAYS,
+    SEASON_PRIZE_WEIGHTS_BPS, WIN_MILESTONES,
+};
+use native_fungible_abi::ExtendedNativeFungibleTokenAbi;
 
 #[derive(RootView)]
 #[view(context = ViewStorageContext)]
 pub struct LeaderboardState {
     pub players: MapView<AccountOwner, PlayerStats>,
+    /// (owner, achievement) -> timestamp it was awarded
+    pub achievements: MapView<(AccountOwner, AchievementType), u64>,
+    /// Largest single win recorded so far
+    pub largest_win: RegisterView<Amount>,
+    /// Half-life of the weighted score in days (None = DEFAULT_HALF_LIFE_DAYS)
+    pub decay_half_life_days: RegisterView<Option<u64>>,
+    /// Winzareal token app used to fund and pay season prizes
+    pub native_app_id: RegisterView<Option<ApplicationId<ExtendedNativeFungibleTokenAbi>>>,
+    /// Account on the creator chain holding the season prize pool
+    pub prize_wallet: RegisterView<Option<AccountOwner>>,
+    /// Funded but not yet distributed season prizes
+    pub prize_pool: RegisterView<Amount>,
+    /// Block of the last season prize distribution
+    pub last_distribution_block: RegisterView<Option<BlockHeight>>,
+    /// Game applications allowed to report scores
+    pub game_apps: SetView<ApplicationId>,
 }
 
 impl LeaderboardState {
-    pub async fn update_score(&mut self, owner: AccountOwner, chain_id: String, is_win: bool, amount: Amount) {
+    pub async fn update_score(&mut self, owner: AccountOwner, chain_id: String, is_win: bool, amount: Amount, timestamp: u64) {
         let mut stats = self.players.get(&owner).await.expect("Failed to get player").unwrap_or(PlayerStats {
             owner,
             chain_id: chain_id.clone(),
@@ -20,6 +40,8 @@ impl LeaderboardState {
             losses: 0,
             total_won: Amount::ZERO,
             total_lost: Amount::ZERO,
+            weighted_score: 0,
+            last_update_at: timestamp,
         });
 
         // Update chain ID to latest used
@@ -33,6 +55,138 @@ impl LeaderboardState {
             stats.total_lost = stats.total_lost.saturating_add(amount);
         }
 
+        // Decay the score up to now before applying this result
+        let elapsed = timestamp.saturating_sub(stats.last_update_at);
+        let decayed = decay_score(stats.weighted_score, elapsed, self.half_life_days());
+        stats.weighted_score = if is_win {
+            decayed.saturating_add(u128::from(amount))
+        } else {
+            decayed.saturating_sub(u128::from(amount))
+        };
+        stats.last_update_at = timestamp;
+
+        if is_win {
+            for (wins, achievement) in WIN_MILESTONES {
+                if stats.wins >= wins {
+                    self.award(owner, achievement, timestamp).await;
+                }
+            }
+            if amount > *self.largest_win.get() {
+                self.largest_win.set(amount);
+                self.award(owner, AchievementType::LargestWin, timestamp).await;
+            }
+        }
+
         self.players.insert(&owner, stats).expect("Failed to insert player stats");
     }
+
+    /// Record an achievement unless the player already has it
+    async fn award(&mut self, owner: AccountOwner, achievement: AchievementType, timestamp: u64) {
+        let key = (owner, achievement);
+        if !self.achievements.contains_key(&key).await.expect("Failed to check achievement") {
+            self.achievements.insert(&key, timestamp).expect("Failed to insert achievement");
+        }
+    }
+
+    /// All players, sorted by total won (descending)
+    pub async fn ranked_players(&self) -> Vec<PlayerStats> {
+        let mut players = Vec::new();
+        for owner in self.players.indices().await.expect("Failed to get players") {
+            if let Some(stats) = self.players.get(&owner).await.expect("Failed to get player") {
+                players.push(stats);
+            }
+        }
+        players.sort_by(|a, b| b.total_won.cmp(&a.total_won));
+        players
+    }
+
+    /// Half-life of the weighted score in days
+    pub fn half_life_days(&self) -> u64 {
+        self.decay_half_life_days.get().unwrap_or(DEFAULT_HALF_LIFE_DAYS)
+    }
+
+    pub fn set_half_life_days(&mut self, half_life_days: u64) -> Result<(), String> {
+        if half_life_days == 0 {
+            return Err("Half-life must be at least one day".to_string());
+        }
+        self.decay_half_life_days.set(Some(half_life_days));
+        Ok(())
+    }
+
+    /// All players with their weighted score decayed to `now`, highest first
+    pub async fn ranked_players_weighted(&self, now: u64) -> Vec<PlayerStats> {
+        let half_life_days = self.half_life_days();
+        let mut players = self.ranked_players().await;
+        for stats in &mut players {
+            stats.weighted_score = decay_score(stats.weighted_score, now.saturating_sub(stats.last_update_at), half_life_days);
+        }
+        players.sort_by(|a, b| b.weighted_score.cmp(&a.weighted_score));
+        players
+    }
+
+    /// A player's stats and rank, None if they are not on the board for `chain_id`
+    pub async fn get_entry(&self, chain_id: &str, owner: AccountOwner) -> Option<LeaderboardEntry> {
+        let players = self.ranked_players().await;
+        let position = players.iter().position(|stats| stats.owner == owner && stats.chain_id == chain_id)?;
+        let stats = players.into_iter().nth(position)?;
+        Some(LeaderboardEntry { stats, rank: position as u64 + 1 })
+    }
+
+    /// Replace the game applications allowed to report scores
+    pub fn set_game_apps(&mut self, game_apps: Vec<ApplicationId>) {
+        self.game_apps.clear();
+        for app_id in game_apps {
+            self.game_apps.insert(&app_id).expect("Failed to add game app");
+        }
+    }
+    
+    /// Whether `app_id` may report scores
+    pub async fn is_game_app(&self, app_id: ApplicationId) -> bool {
+        self.game_apps.contains(&app_id).await.expect("Failed to check game app")
+    }
+    
+    pub fn fund_prize_pool(&mut self, amount: Amount) {
+        let pool = self.prize_pool.get().saturating_add(amount);
+        self.prize_pool.set(pool);
+    }
+
+    /// The top `top_n` players by weighted score at `now` with their share of the prize pool.
+    /// Players without a positive weighted score are not paid.
+    pub async fn season_prizes(&self, top_n: u64, now: u64) -> Result<Vec<(PlayerStats, Amount)>, String> {
+        if top_n == 0 || top_n > SEASON_PRIZE_WEIGHTS_BPS.len() as u64 {
+            return Err(format!("top_n must be between 1 and {}, got {}", SEASON_PRIZE_WEIGHTS_BPS.len(), top_n));
+        }
+        let pool = *self.prize_pool.get();
+        if pool == Amount::ZERO {
+            return Err("Prize pool is empty".to_string());
+        }
+        let winners: Vec<PlayerStats> = self.ranked_players_weighted(now).await.into_iter()
+            .filter(|stats| stats.weighted_score > 0)
+            .take(top_n as usize)
+            .collect();
+        if winners.is_empty() {
+            return Err("No player has a positive weighted score".to_string());
+        }
+        let shares = season_prize_shares(pool, winners.len());
+        Ok(winners.into_iter().zip(shares).collect())
+    }
+
+    /// Empty the prize pool after it was paid out in `block`
+    pub fn finish_distribution(&mut self, block: BlockHeight) {
+        self.prize_pool.set(Amount::ZERO);
+        self.last_distribution_block.set(Some(block));
+    }
+
+    pub async fn get_achievements(&self, owner: AccountOwner) -> Vec<(AchievementType, u64)> {
+        let mut achievements = Vec::new();
+        for (player, achievement) in self.achievements.indices().await.expect("Failed to get achievements") {
+            if player == owner {
+                if let Some(awarded_at) = self.achievements.get(&(player, achievement)).await.expect("Failed to get achievement") {
+                    achievements.push((achievement, awarded_at));
+                }
+            }
+        }
+        achievements.sort();
+        achievements
+    }
 }
@@ -5,6 +5,8 @@
 
 mod state;
 
+use std::collections::BTreeSet;
+
 use linera_sdk::{
     abis::fungible::{
         Account as FungibleAccount, InitialState, Parameters,
@@ -15,71 +17,9 @@ use linera_sdk::{
 };
 use native_fungible::{
     Message, TICKER_SYMBOL, ExtendedNativeFungibleTokenAbi, ExtendedOperation, ExtendedResponse,
-    LotteryRound as LibLotteryRound, RoundStatus as LibRoundStatus, WinnerPool as LibWinnerPool,
-    TicketPurchase as LibTicketPurchase, TicketPurchaseInfo as LibTicketPurchaseInfo,
-    LotteryWinnerInfo as LibLotteryWinnerInfo,
+    TicketPurchaseInfo as LibTicketPurchaseInfo, LotteryWinnerInfo as LibLotteryWinnerInfo,
 };
-use self::state::{NativeFungibleTokenState, LotteryRound, RoundStatus, WinnerPool, TicketPurchase};
-
-// Conversion functions between lib types and state types
-fn round_status_to_lib(status: RoundStatus) -> LibRoundStatus {
-    match status {
-        RoundStatus::Active => LibRoundStatus::Active,
-        RoundStatus::Closed => LibRoundStatus::Closed,
-        RoundStatus::Complete => LibRoundStatus::Complete,
-    }
-}
-
-fn winner_pool_to_lib(pool: WinnerPool) -> LibWinnerPool {
-    match pool {
-        WinnerPool::Pool1 => LibWinnerPool::Pool1,
-        WinnerPool::Pool2 => LibWinnerPool::Pool2,
-        WinnerPool::Pool3 => LibWinnerPool::Pool3,
-        WinnerPool::Pool4 => LibWinnerPool::Pool4,
-        WinnerPool::Complete => LibWinnerPool::Complete,
-    }
-}
-
-fn lottery_round_to_lib(round: LotteryRound) -> LibLotteryRound {
-    LibLotteryRound {
-        id: round.id,
-        created_at: round.created_at,
-        closed_at: round.closed_at,
-        status: round_status_to_lib(round.status),
-        ticket_price: round.ticket_price,
-        total_tickets_sold: round.total_tickets_sold,
-        next_ticket_number: round.next_ticket_number,
-        prize_pool: round.prize_pool,
-        current_winner_pool: winner_pool_to_lib(round.current_winner_pool),
-        pool1_count: round.pool1_count,
-        pool2_count: round.pool2_count,
-        pool3_count: round.pool3_count,
-        pool4_count: round.pool4_count,
-        pool1_winners_drawn: round.pool1_winners_drawn,
-        pool2_winners_drawn: round.pool2_winners_drawn,
-        pool3_winners_drawn: round.pool3_winners_drawn,
-        pool4_winners_drawn: round.pool4_winners_drawn,
-    }
-}
-
-fn lottery_round_option_to_lib(round: Option<LotteryRound>) -> Option<LibLotteryRound> {
-    round.map(lottery_round_to_lib)
-}
-
-fn lottery_rounds_to_lib(rounds: Vec<LotteryRound>) -> Vec<LibLotteryRound> {
-    rounds.into_iter().map(lottery_round_to_lib).collect()
-}
-
-fn ticket_purchase_to_lib(purchase: TicketPurchase) -> LibTicketPurchase {
-    LibTicketPurchase {
-        owner: purchase.owner,
-        first_ticket: purchase.first_ticket,
-        last_ticket: purchase.last_ticket,
-        total_tickets: purchase.total_tickets,
-        amount_paid: purchase.amount_paid,
-        source_chain_id: purchase.source_chain_id,
-    }
-}
+use self::state::NativeFungibleTokenState;
 
 pub struct NativeFungibleTokenContract {
     state: NativeFungibleTokenState,
@@ -258,7 +198,7 @@ impl Contract for NativeFungibleTokenContract {
                 let owner = self.runtime.authenticated_signer().expect("Authentication required");
                 let current_balance = self.runtime.owner_balance(owner);
                 match self.state.purchase_tickets(owner, amount, current_balance, None).await {
-                    Ok(purchase) => ExtendedResponse::TicketPurchase(ticket_purchase_to_lib(purchase)),
+                    Ok(purchase) => ExtendedResponse::TicketPurchase(purchase),
                     Err(e) => panic!("Failed to purchase tickets: {}", e),
                 }
             }
@@ -308,8 +248,12 @@ impl Contract for NativeFungibleTokenContract {
                                         // Transfer prize from payer to winner
                                         self.runtime.transfer(payer, target_account, prize_amount);
                                         
-                                        // Send notify message
-                                        let message = Message::Notify;
+                                        // Tell the winner's home chain what it won
+                                        let message = Message::PrizeReceived {
+                                            round_id,
+                                            ticket_number,
+                                            amount: prize_amount,
+                                        };
                                         self.runtime
                                             .prepare_message(message)
                                             .with_authentication()
@@ -351,7 +295,7 @@ impl Contract for NativeFungibleTokenContract {
                 match self.state.get_active_round().await {
                     Ok(Some(round_id)) => {
                         match self.state.get_round(round_id).await {
-                            Ok(Some(round)) => ExtendedResponse::LotteryRound(lottery_round_option_to_lib(Some(round))),
+                            Ok(Some(round)) => ExtendedResponse::LotteryRound(Some(round)),
                             Ok(None) => ExtendedResponse::LotteryRound(None),
                             Err(e) => panic!("Failed to get round: {}", e),
                         }
@@ -363,7 +307,7 @@ impl Contract for NativeFungibleTokenContract {
             
             ExtendedOperation::GetRound { id } => {
                 match self.state.get_round(id).await {
-                    Ok(Some(round)) => ExtendedResponse::LotteryRound(lottery_round_option_to_lib(Some(round))),
+                    Ok(Some(round)) => ExtendedResponse::LotteryRound(Some(round)),
                     Ok(None) => ExtendedResponse::LotteryRound(None),
                     Err(e) => panic!("Failed to get round: {}", e),
                 }
@@ -371,7 +315,7 @@ impl Contract for NativeFungibleTokenContract {
             
             ExtendedOperation::GetAllRounds => {
                 match self.state.get_all_rounds().await {
-                    Ok(rounds) => ExtendedResponse::LotteryRounds(lottery_rounds_to_lib(rounds)),
+                    Ok(rounds) => ExtendedResponse::LotteryRounds(rounds),
                     Err(e) => panic!("Failed to get all rounds: {}", e),
                 }
             }
@@ -397,7 +341,7 @@ impl Contract for NativeFungibleTokenContract {
             
             ExtendedOperation::GetUserTickets { round_id, owner } => {
                 match self.state.get_user_tickets(round_id, owner).await {
-                    Ok(Some(purchase)) => ExtendedResponse::TicketPurchase(ticket_purchase_to_lib(purchase)),
+                    Ok(Some(purchase)) => ExtendedResponse::TicketPurchase(purchase),
                     Ok(None) => panic!("No tickets found for user"),
                     Err(e) => panic!("Failed to get user tickets: {}", e),
                 }
@@ -429,6 +373,21 @@ impl Contract for NativeFungibleTokenContract {
                     Err(e) => panic!("Failed to get round winners: {}", e),
                 }
             }
+            
+            ExtendedOperation::ReconcileBalances { owners } => {
+                let mut owners: BTreeSet<AccountOwner> = owners.into_iter().collect();
+                match self.state.accounts.indices().await {
+                    Ok(ledger_owners) => owners.extend(ledger_owners),
+                    Err(e) => panic!("Failed to get account indices: {:?}", e),
+                }
+                let runtime_balances = owners.into_iter()
+                    .map(|owner| (owner, self.runtime.owner_balance(owner)))
+                    .collect();
+                match self.state.reconcile_balances(runtime_balances).await {
+                    Ok(mismatches) => ExtendedResponse::BalanceMismatches(mismatches),
+                    Err(e) => panic!("Failed to reconcile balances: {}", e),
+                }
+            }
         }
     }
 
@@ -439,6 +398,11 @@ impl Contract for NativeFungibleTokenContract {
                 // This ensures the application is available for cross-chain operations
                 // Note: Application is now available on chain
             }
+            Message::PrizeReceived { round_id, ticket_number, amount } => {
+                if let Err(e) = self.state.record_received_prize(round_id, ticket_number, amount) {
+                    panic!("Failed to record received prize: {}", e);
+                }
+            }
             Message::TransferForTickets { owner: _, amount, source_chain_id, source_owner } => {
                 // Handle cross-chain transfer for ticket purchase
                 // Immediately purchase tickets for the source owner since they initiated the transfer
@@ -494,4 +458,178 @@ impl NativeFungibleTokenContract {
             owner: account.owner,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt as _;
+    use linera_sdk::{
+        abis::fungible::Parameters,
+        linera_base_types::{AccountOwner, Amount, BlockHeight, ChainId, CryptoHash, Timestamp},
+        util::BlockingWait,
+        views::View,
+        Contract, ContractRuntime,
+    };
+    use native_fungible::{BalanceMismatch, ExtendedOperation, ExtendedResponse, Message, ReceivedPrize};
+
+    use super::{NativeFungibleTokenContract, NativeFungibleTokenState};
+
+    #[test]
+    fn ticket_purchase_diverges_ledger_from_runtime_balance() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let runtime = ContractRuntime::new()
+            .with_application_parameters(Parameters::new("NAT"))
+            .with_owner_balances([(alice, Amount::from_tokens(10)), (bob, Amount::from_tokens(3))]);
+        let state = NativeFungibleTokenState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to load state");
+        let mut contract = NativeFungibleTokenContract { state, runtime };
+        contract.state.accounts.insert(&alice, Amount::from_tokens(10)).unwrap();
+
+        // Only bob, who has no ledger entry, disagrees so far
+        let response = contract.execute_operation(ExtendedOperation::ReconcileBalances { owners: vec![bob] })
+            .now_or_never()
+            .unwrap();
+        let ExtendedResponse::BalanceMismatches(mismatches) = response else {
+            panic!("ReconcileBalances should return mismatches");
+        };
+        assert_eq!(mismatches, vec![BalanceMismatch {
+            owner: bob,
+            ledger_balance: Amount::ZERO,
+            runtime_balance: Amount::from_tokens(3),
+        }]);
+
+        // Buying tickets deducts from the ledger but leaves the runtime balance untouched
+        contract.state.create_lottery_round(Amount::ONE, 0).now_or_never().unwrap().unwrap();
+        contract.state
+            .purchase_tickets(alice, Amount::from_tokens(4), Amount::from_tokens(10), None)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let mismatches = contract.state
+            .reconcile_balances(vec![(alice, Amount::from_tokens(10)), (bob, Amount::from_tokens(3))])
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert!(mismatches.contains(&BalanceMismatch {
+            owner: alice,
+            ledger_balance: Amount::from_tokens(6),
+            runtime_balance: Amount::from_tokens(10),
+        }));
+    }
+
+    #[test]
+    fn cross_chain_winner_chain_is_told_the_prize_details() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let payer = AccountOwner::from(CryptoHash::test_hash("payer"));
+        let home = ChainId(CryptoHash::test_hash("home"));
+        let runtime = ContractRuntime::new()
+            .with_application_parameters(Parameters::new("NAT"))
+            .with_chain_id(ChainId(CryptoHash::test_hash("lottery")))
+            .with_authenticated_signer(payer)
+            .with_owner_balances([(payer, Amount::from_tokens(100))])
+            .with_system_time(Timestamp::from(1_000))
+            .with_block_height(BlockHeight(7));
+        let state = NativeFungibleTokenState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to load state");
+        let mut contract = NativeFungibleTokenContract { state, runtime };
+
+        let round_id = contract.state.create_lottery_round(Amount::ONE, 0).now_or_never().unwrap().unwrap();
+        contract.state
+            .purchase_tickets(alice, Amount::from_tokens(4), Amount::from_tokens(4), Some(home.to_string()))
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        contract.state.close_lottery_round(500).now_or_never().unwrap().unwrap();
+
+        let response = contract.execute_operation(ExtendedOperation::GenerateWinner { round_id }).now_or_never().unwrap();
+        let ExtendedResponse::WinnerGenerated { ticket_number, prize_amount, .. } = response else {
+            panic!("GenerateWinner should return the winner");
+        };
+        assert!(prize_amount > Amount::ZERO);
+
+        let amount = {
+            let messages = contract.runtime.created_send_message_requests();
+            assert_eq!(messages.len(), 1);
+            assert_eq!(messages[0].destination, home);
+            let Message::PrizeReceived { round_id: sent_round_id, ticket_number: sent_ticket, amount } = messages[0].message else {
+                panic!("The winner's chain should get a PrizeReceived message");
+            };
+            assert_eq!((sent_round_id, sent_ticket), (round_id, ticket_number));
+            amount
+        };
+        assert_eq!(amount, prize_amount);
+
+        // The home chain records the acknowledged prize
+        contract.execute_message(Message::PrizeReceived { round_id, ticket_number, amount }).now_or_never().unwrap();
+        let received = contract.state.get_received_prizes().now_or_never().unwrap().unwrap();
+        assert_eq!(received, vec![ReceivedPrize { round_id, ticket_number, amount: prize_amount }]);
+    }
+
+    #[test]
+    fn rounds_stored_in_the_former_state_layout_still_load() {
+        // Copies of the round types the state module used to define
+        #[derive(serde::Serialize)]
+        #[allow(dead_code)]
+        enum OldRoundStatus { Active, Closed, Complete }
+        #[derive(serde::Serialize)]
+        #[allow(dead_code)]
+        enum OldWinnerPool { Pool1, Pool2, Pool3, Pool4, Complete }
+        #[derive(serde::Serialize)]
+        struct OldLotteryRound {
+            id: u64,
+            created_at: u64,
+            closed_at: Option<u64>,
+            status: OldRoundStatus,
+            ticket_price: Amount,
+            total_tickets_sold: u64,
+            next_ticket_number: u64,
+            prize_pool: Amount,
+            current_winner_pool: OldWinnerPool,
+            pool1_count: u64,
+            pool2_count: u64,
+            pool3_count: u64,
+            pool4_count: u64,
+            pool1_winners_drawn: u64,
+            pool2_winners_drawn: u64,
+            pool3_winners_drawn: u64,
+            pool4_winners_drawn: u64,
+        }
+
+        let old = OldLotteryRound {
+            id: 3,
+            created_at: 100,
+            closed_at: Some(200),
+            status: OldRoundStatus::Closed,
+            ticket_price: Amount::ONE,
+            total_tickets_sold: 40,
+            next_ticket_number: 41,
+            prize_pool: Amount::from_tokens(40),
+            current_winner_pool: OldWinnerPool::Pool3,
+            pool1_count: 6,
+            pool2_count: 2,
+            pool3_count: 2,
+            pool4_count: 1,
+            pool1_winners_drawn: 6,
+            pool2_winners_drawn: 2,
+            pool3_winners_drawn: 1,
+            pool4_winners_drawn: 0,
+        };
+        let bytes = bcs::to_bytes(&old).unwrap();
+        let round: super::state::LotteryRound = bcs::from_bytes(&bytes).unwrap();
+
+        assert_eq!((round.id, round.created_at, round.closed_at), (3, 100, Some(200)));
+        assert_eq!(round.status, super::state::RoundStatus::Closed);
+        assert_eq!(round.current_winner_pool, super::state::WinnerPool::Pool3);
+        assert_eq!((round.ticket_price, round.prize_pool), (Amount::ONE, Amount::from_tokens(40)));
+        assert_eq!((round.total_tickets_sold, round.next_ticket_number), (40, 41));
+        assert_eq!([round.pool1_count, round.pool2_count, round.pool3_count, round.pool4_count], [6, 2, 2, 1]);
+        assert_eq!(
+            [round.pool1_winners_drawn, round.pool2_winners_drawn, round.pool3_winners_drawn, round.pool4_winners_drawn],
+            [6, 2, 1, 0],
+        );
+    }
+}
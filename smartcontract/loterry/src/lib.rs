@@ -3,21 +3,44 @@
 
 /*! ABI of the Native Fungible Token with Lottery */
 
-use async_graphql::{Request, Response, SimpleObject, InputObject};
+use async_graphql::{ComplexObject, Request, Response, SimpleObject, InputObject};
 use linera_sdk::linera_base_types::{AccountOwner, Amount, ContractAbi, ServiceAbi, ChainId};
+use native_fungible_abi::amount_format::{amount_attos, amount_display};
+use native_fungible_abi::list_query::CreatedRange;
 use serde::{Deserialize, Serialize};
 
+pub use native_fungible_abi::list_query::SortOrder;
+pub use native_fungible_abi::payout_summary::RoundPayoutSummary;
+
 pub const TICKER_SYMBOL: &str = "NAT";
 
 #[derive(Deserialize, SimpleObject)]
+#[graphql(complex)]
 pub struct AccountEntry {
     pub key: AccountOwner,
     pub value: Amount,
 }
 
+#[ComplexObject]
+impl AccountEntry {
+    async fn value_attos(&self) -> String {
+        amount_attos(self.value)
+    }
+
+    async fn value_display(&self) -> String {
+        amount_display(self.value)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub enum Message {
     Notify,
+    /// Sent to a cross-chain winner's home chain with the prize it was just paid
+    PrizeReceived {
+        round_id: u64,
+        ticket_number: u64,
+        amount: Amount,
+    },
     // Cross-chain transfer for ticket purchase
     TransferForTickets {
         owner: AccountOwner,
@@ -42,6 +65,20 @@ pub enum RoundStatus {
     Complete, // All winners drawn
 }
 
+/// Filter of a round list query; the creation bounds are exclusive timestamps in micros
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, InputObject)]
+pub struct RoundFilter {
+    pub status: Option<RoundStatus>,
+    pub created_after: Option<u64>,
+    pub created_before: Option<u64>,
+}
+
+impl RoundFilter {
+    pub fn created_range(&self) -> CreatedRange {
+        CreatedRange { after: self.created_after, before: self.created_before }
+    }
+}
+
 // Winner pool identifier
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
 pub enum WinnerPool {
@@ -111,6 +148,22 @@ pub struct LotteryWinnerInfo {
     pub source_chain_id: Option<String>,
 }
 
+// A cross-chain prize acknowledged on the winner's home chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct ReceivedPrize {
+    pub round_id: u64,
+    pub ticket_number: u64,
+    pub amount: Amount,
+}
+
+// An owner whose internal ledger balance disagrees with the native runtime balance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct BalanceMismatch {
+    pub owner: AccountOwner,
+    pub ledger_balance: Amount,  // Balance in the app's `accounts` MapView
+    pub runtime_balance: Amount, // Balance reported by the runtime
+}
+
 // ABI для контракту
 pub struct ExtendedNativeFungibleTokenAbi;
 
@@ -180,6 +233,11 @@ pub enum ExtendedOperation {
     GetUserTickets { round_id: u64, owner: AccountOwner },
     /// Get winners for a round
     GetRoundWinners { round_id: u64 },
+    
+    // Diagnostics
+    /// List owners whose `accounts` ledger entry disagrees with the runtime balance,
+    /// checking every ledger owner plus `owners` (for balances the ledger never saw)
+    ReconcileBalances { owners: Vec<AccountOwner> },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -202,4 +260,5 @@ pub enum ExtendedResponse {
         prize_amount: Amount,
         new_round_created: bool,
     },
+    BalanceMismatches(Vec<BalanceMismatch>),
 }
\ No newline at end of file
@@ -15,10 +15,11 @@ use linera_sdk::{
 };
 use native_fungible::{
     AccountEntry, TICKER_SYMBOL, ExtendedNativeFungibleTokenAbi, ExtendedOperation, AccountInput,
-    LotteryRound as LibLotteryRound, RoundStatus as LibRoundStatus, WinnerPool as LibWinnerPool,
     TicketPurchaseInfo as LibTicketPurchaseInfo, LotteryWinnerInfo as LibLotteryWinnerInfo,
+    BalanceMismatch, ReceivedPrize, RoundFilter, RoundPayoutSummary, SortOrder,
 };
-use self::state::{NativeFungibleTokenState, LotteryRound, RoundStatus, WinnerPool};
+use native_fungible_abi::ticket_quote::{quote_purchase, PurchaseQuote, QuoteError};
+use self::state::{NativeFungibleTokenState, LotteryRound, RoundStatus};
 
 linera_sdk::service!(NativeFungibleTokenService);
 
@@ -115,47 +116,6 @@ struct QueryRoot {
     storage_context: linera_sdk::views::ViewStorageContext,
 }
 
-// Helper functions to convert types
-fn convert_status(status: RoundStatus) -> LibRoundStatus {
-    match status {
-        RoundStatus::Active => LibRoundStatus::Active,
-        RoundStatus::Closed => LibRoundStatus::Closed,
-        RoundStatus::Complete => LibRoundStatus::Complete,
-    }
-}
-
-fn convert_winner_pool(pool: WinnerPool) -> LibWinnerPool {
-    match pool {
-        WinnerPool::Pool1 => LibWinnerPool::Pool1,
-        WinnerPool::Pool2 => LibWinnerPool::Pool2,
-        WinnerPool::Pool3 => LibWinnerPool::Pool3,
-        WinnerPool::Pool4 => LibWinnerPool::Pool4,
-        WinnerPool::Complete => LibWinnerPool::Complete,
-    }
-}
-
-fn convert_round(round: &LotteryRound) -> LibLotteryRound {
-    LibLotteryRound {
-        id: round.id,
-        created_at: round.created_at,
-        closed_at: round.closed_at,
-        status: convert_status(round.status),
-        ticket_price: round.ticket_price,
-        total_tickets_sold: round.total_tickets_sold,
-        next_ticket_number: round.next_ticket_number,
-        prize_pool: round.prize_pool,
-        current_winner_pool: convert_winner_pool(round.current_winner_pool),
-        pool1_count: round.pool1_count,
-        pool2_count: round.pool2_count,
-        pool3_count: round.pool3_count,
-        pool4_count: round.pool4_count,
-        pool1_winners_drawn: round.pool1_winners_drawn,
-        pool2_winners_drawn: round.pool2_winners_drawn,
-        pool3_winners_drawn: round.pool3_winners_drawn,
-        pool4_winners_drawn: round.pool4_winners_drawn,
-    }
-}
-
 #[Object]
 impl QueryRoot {
     async fn ticker_symbol(&self) -> Result<String, async_graphql::Error> {
@@ -174,13 +134,13 @@ impl QueryRoot {
     }
     
     // Lottery queries
-    async fn active_round(&self) -> Option<LibLotteryRound> {
+    async fn active_round(&self) -> Option<LotteryRound> {
         match NativeFungibleTokenState::load(self.storage_context.clone()).await {
             Ok(state) => {
                 match state.get_active_round().await {
                     Ok(Some(round_id)) => {
                         match state.get_round(round_id).await {
-                            Ok(Some(round)) => Some(convert_round(&round)),
+                            Ok(Some(round)) => Some(round),
                             _ => None,
                         }
                     },
@@ -191,14 +151,40 @@ impl QueryRoot {
         }
     }
     
-    async fn round(&self, id: u64) -> Option<LibLotteryRound> {
+    /// Quote how many tickets `amount` buys in the active round
+    async fn purchase_quote(&self, amount: String) -> Result<PurchaseQuote, async_graphql::Error> {
+        use linera_sdk::linera_base_types::Amount;
+        let amount = amount.parse::<Amount>()
+            .map_err(|e| async_graphql::Error::new(format!("Invalid amount {}: {}", amount, e)))?;
+        let state = NativeFungibleTokenState::load(self.storage_context.clone()).await?;
+        let round = match state.get_active_round().await? {
+            Some(round_id) => state.get_round(round_id).await?,
+            None => None,
+        };
+        match round {
+            Some(round) if round.status == RoundStatus::Active => Ok(quote_purchase(round.id, round.ticket_price, amount)?),
+            _ => Err(QuoteError::NoActiveRound.into()),
+        }
+    }
+    
+    async fn round(&self, id: u64) -> Option<LotteryRound> {
         self.all_rounds.iter()
             .find(|round| round.id == id)
-            .map(convert_round)
+            .cloned()
     }
     
-    async fn all_rounds(&self) -> Vec<LibLotteryRound> {
-        self.all_rounds.iter().map(convert_round).collect()
+    async fn all_rounds(&self) -> Vec<LotteryRound> {
+        self.all_rounds.clone()
+    }
+    
+    /// Up to `limit` rounds matching `filter` (all rounds when omitted), by ID in `order` (ascending by default)
+    async fn rounds(&self, filter: Option<RoundFilter>, order: Option<SortOrder>, limit: u64) -> Vec<LotteryRound> {
+        let filter = filter.unwrap_or_default();
+        match NativeFungibleTokenState::load(self.storage_context.clone()).await {
+            Ok(state) => state.filter_rounds(filter.status, filter.created_range(), order.unwrap_or_default(), limit).await
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
     }
     
     async fn round_ticket_purchases(&self, round_id: u64) -> Vec<LibTicketPurchaseInfo> {
@@ -254,6 +240,30 @@ impl QueryRoot {
             Err(_) => Vec::new(),
         }
     }
+    
+    /// Awarded, paid and unclaimed prize totals of a round
+    async fn round_payout_summary(&self, round_id: u64) -> Option<RoundPayoutSummary> {
+        match NativeFungibleTokenState::load(self.storage_context.clone()).await {
+            Ok(state) => state.round_payout_summary(round_id).await.ok(),
+            Err(_) => None,
+        }
+    }
+    
+    /// Prizes this chain's owners won on another chain's lottery
+    async fn received_prizes(&self) -> Vec<ReceivedPrize> {
+        match NativeFungibleTokenState::load(self.storage_context.clone()).await {
+            Ok(state) => state.get_received_prizes().await.unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+    
+    /// Owners whose `accounts` ledger entry disagrees with the runtime balance
+    async fn balance_mismatches(&self) -> Vec<BalanceMismatch> {
+        match NativeFungibleTokenState::load(self.storage_context.clone()).await {
+            Ok(state) => state.reconcile_balances(self.runtime.owner_balances()).await.unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
 }
 
 struct MutationRoot {
@@ -367,4 +377,9 @@ impl MutationRoot {
         });
         "GenerateWinner operation scheduled (VRF auto-generated)".to_string()
     }
+    
+    async fn reconcile_balances(&self, owners: Vec<AccountOwner>) -> String {
+        self.runtime.schedule_operation(&ExtendedOperation::ReconcileBalances { owners });
+        "ReconcileBalances operation scheduled".to_string()
+    }
 }
\ No newline at end of file
@@ -1,12 +1,19 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::collections::BTreeMap;
+
 use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext, ViewError};
 use linera_sdk::linera_base_types::{AccountOwner, Amount};
-use serde::{Deserialize, Serialize};
-use async_graphql::SimpleObject;
 use num_bigint::BigUint;
 use num_traits::cast::ToPrimitive;
+use native_fungible::{BalanceMismatch, ReceivedPrize};
+// Rounds and purchases are stored in their ABI layout; serde output is unchanged from the
+// former state-local copies, so existing entries still deserialize
+pub use native_fungible::{LotteryRound, RoundStatus, TicketPurchase, WinnerPool};
+use native_fungible_abi::checked_math::{checked_percentage, tickets_for_amount};
+use native_fungible_abi::list_query::{CreatedRange, SortOrder};
+use native_fungible_abi::payout_summary::{PayoutTotals, RoundPayoutSummary};
 
 /// Calculate prize amount for a specific winner pool
 /// Returns the portion of prize pool allocated to this pool
@@ -65,61 +72,10 @@ pub struct NativeFungibleTokenState {
     pub ticket_to_owner: MapView<(u64, u64), AccountOwner>,
     /// Winning tickets with prize info: (round_id, ticket_number) -> (owner, prize_amount, claimed)
     pub winning_tickets: MapView<(u64, u64), (AccountOwner, Amount, bool)>,
-}
-
-/// A lottery round
-#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
-pub struct LotteryRound {
-    pub id: u64,
-    pub created_at: u64,
-    pub closed_at: Option<u64>,
-    pub status: RoundStatus,
-    pub ticket_price: Amount,
-    pub total_tickets_sold: u64,
-    pub next_ticket_number: u64,
-    pub prize_pool: Amount,
-    pub current_winner_pool: WinnerPool,
-    
-    // Winner pool sizes (calculated when round closes)
-    pub pool1_count: u64,  // 15% of tickets
-    pub pool2_count: u64,  // 7% of tickets
-    pub pool3_count: u64,  // 5% of tickets
-    pub pool4_count: u64,  // 3% of tickets
-    
-    // Winner pool progress
-    pub pool1_winners_drawn: u64,
-    pub pool2_winners_drawn: u64,
-    pub pool3_winners_drawn: u64,
-    pub pool4_winners_drawn: u64,
-}
-
-/// Status of a lottery round
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
-pub enum RoundStatus {
-    Active,   // Accepting ticket purchases
-    Closed,   // Not accepting purchases, drawing winners
-    Complete, // All winners drawn
-}
-
-/// Winner pool identifier
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
-pub enum WinnerPool {
-    Pool1,    // 15% of tickets, 20% of prize
-    Pool2,    // 7% of tickets, 25% of prize
-    Pool3,    // 5% of tickets, 30% of prize
-    Pool4,    // 3% of tickets, 25% of prize
-    Complete, // All winners drawn
-}
-
-/// A user's ticket purchase
-#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
-pub struct TicketPurchase {
-    pub owner: AccountOwner,
-    pub first_ticket: u64,
-    pub last_ticket: u64,
-    pub total_tickets: u64,
-    pub amount_paid: Amount,
-    pub source_chain_id: Option<String>,
+    /// Awarded and delivered prize totals per round
+    pub round_payouts: MapView<u64, PayoutTotals>,
+    /// Prizes won on another chain's lottery by owners of this chain: (round_id, ticket_number) -> amount
+    pub received_prizes: MapView<(u64, u64), Amount>,
 }
 
 #[allow(dead_code)]
@@ -177,23 +133,17 @@ impl NativeFungibleTokenState {
             }
             
             // Calculate number of tickets
-            let amount_u128 = u128::from(amount);
-            let ticket_price_u128 = u128::from(round.ticket_price);
-            
-            if ticket_price_u128 == 0 {
-                return Err("Invalid ticket price".to_string());
-            }
-            
-            let ticket_count = amount_u128 / ticket_price_u128;
-            if ticket_count == 0 {
+            let ticket_count_u64 = tickets_for_amount(amount, round.ticket_price)
+                .map_err(|e| e.to_string())?;
+            if ticket_count_u64 == 0 {
                 return Err("Amount too small to purchase any tickets".to_string());
             }
             
-            let ticket_count_u64 = ticket_count as u64;
-            
             // Assign ticket numbers
             let first_ticket = round.next_ticket_number;
-            let last_ticket = first_ticket + ticket_count_u64 - 1;
+            let next_ticket_number = first_ticket.checked_add(ticket_count_u64)
+                .ok_or("Ticket numbers for this round are exhausted")?;
+            let last_ticket = next_ticket_number - 1;
             
             // Deduct from user's balance
             let balance = self.accounts.get(&owner).await
@@ -229,7 +179,7 @@ impl NativeFungibleTokenState {
             }
             
             // Update round
-            round.next_ticket_number = last_ticket + 1;
+            round.next_ticket_number = next_ticket_number;
             round.total_tickets_sold += ticket_count_u64;
             round.prize_pool = round.prize_pool.saturating_add(amount);
             
@@ -263,10 +213,10 @@ impl NativeFungibleTokenState {
             // Calculate winner pool sizes
             // 30% of tickets are winners: 15% + 7% + 5% + 3% = 30%
             let total_tickets = round.total_tickets_sold;
-            round.pool1_count = (total_tickets * 15) / 100; // 15% of tickets
-            round.pool2_count = (total_tickets * 7) / 100;  // 7% of tickets
-            round.pool3_count = (total_tickets * 5) / 100;  // 5% of tickets
-            round.pool4_count = (total_tickets * 3) / 100;  // 3% of tickets
+            round.pool1_count = checked_percentage(total_tickets, 1_500); // 15% of tickets
+            round.pool2_count = checked_percentage(total_tickets, 700);   // 7% of tickets
+            round.pool3_count = checked_percentage(total_tickets, 500);   // 5% of tickets
+            round.pool4_count = checked_percentage(total_tickets, 300);   // 3% of tickets
             
             // Ensure at least 1 winner in each pool if there are tickets
             if round.pool1_count == 0 && total_tickets > 0 {
@@ -362,6 +312,14 @@ impl NativeFungibleTokenState {
         // Record winning ticket
         self.winning_tickets.insert(&(round_id, selected_ticket), (owner.clone(), prize_amount, true))
             .map_err(|e: ViewError| format!("Failed to record winning ticket: {:?}", e))?;
+        // Prizes are paid out as they are drawn, so they count as delivered right away
+        let mut payouts = self.round_payouts.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round payouts: {:?}", e))?
+            .unwrap_or_default();
+        payouts.award(prize_amount);
+        payouts.pay(prize_amount);
+        self.round_payouts.insert(&round_id, payouts)
+            .map_err(|e: ViewError| format!("Failed to update round payouts: {:?}", e))?;
         
         // Update round progress
         match pool {
@@ -412,12 +370,75 @@ impl NativeFungibleTokenState {
         Ok((round_id, selected_ticket, owner, prize_amount, new_round_created))
     }
     
+    /// Compare the `accounts` ledger with the runtime balances.
+    /// Returns every owner present in either whose two balances differ, sorted by owner.
+    pub async fn reconcile_balances(&self, runtime_balances: Vec<(AccountOwner, Amount)>) -> Result<Vec<BalanceMismatch>, String> {
+        let mut balances: BTreeMap<AccountOwner, (Amount, Amount)> = runtime_balances.into_iter()
+            .map(|(owner, runtime_balance)| (owner, (Amount::ZERO, runtime_balance)))
+            .collect();
+        
+        let owners = self.accounts.indices().await
+            .map_err(|e: ViewError| format!("Failed to get account indices: {:?}", e))?;
+        for owner in owners {
+            let ledger_balance = self.accounts.get(&owner).await
+                .map_err(|e: ViewError| format!("Failed to get balance: {:?}", e))?
+                .unwrap_or_default();
+            balances.entry(owner).or_insert((Amount::ZERO, Amount::ZERO)).0 = ledger_balance;
+        }
+        
+        Ok(balances.into_iter()
+            .filter(|(_, (ledger_balance, runtime_balance))| ledger_balance != runtime_balance)
+            .map(|(owner, (ledger_balance, runtime_balance))| BalanceMismatch {
+                owner,
+                ledger_balance,
+                runtime_balance,
+            })
+            .collect())
+    }
+    
     /// Get lottery round by ID
     pub async fn get_round(&self, round_id: u64) -> Result<Option<LotteryRound>, String> {
         self.rounds.get(&round_id).await
             .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))
     }
     
+    /// Awarded, paid and unclaimed prize totals of a round, from its running totals
+    pub async fn round_payout_summary(&self, round_id: u64) -> Result<RoundPayoutSummary, String> {
+        let round = self.get_round(round_id).await?.ok_or("Round not found")?;
+        let payouts = self.round_payouts.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round payouts: {:?}", e))?
+            .unwrap_or_default();
+        
+        let distributable = [WinnerPool::Pool1, WinnerPool::Pool2, WinnerPool::Pool3, WinnerPool::Pool4]
+            .into_iter()
+            .fold(Amount::ZERO, |total, pool| total.saturating_add(calculate_prize_for_pool(round.prize_pool, pool)));
+        let winners_total = round.pool1_count + round.pool2_count + round.pool3_count + round.pool4_count;
+        let winners_drawn = round.pool1_winners_drawn + round.pool2_winners_drawn + round.pool3_winners_drawn + round.pool4_winners_drawn;
+        Ok(payouts.summary(round.prize_pool, distributable, winners_drawn, winners_total))
+    }
+    
+    /// Record a cross-chain prize acknowledged by the lottery chain
+    pub fn record_received_prize(&mut self, round_id: u64, ticket_number: u64, amount: Amount) -> Result<(), String> {
+        self.received_prizes.insert(&(round_id, ticket_number), amount)
+            .map_err(|e: ViewError| format!("Failed to record received prize: {:?}", e))
+    }
+    
+    /// All acknowledged cross-chain prizes, ordered by round and ticket
+    pub async fn get_received_prizes(&self) -> Result<Vec<ReceivedPrize>, String> {
+        let mut keys = self.received_prizes.indices().await
+            .map_err(|e: ViewError| format!("Failed to get received prize indices: {:?}", e))?;
+        keys.sort_unstable();
+        
+        let mut prizes = Vec::with_capacity(keys.len());
+        for (round_id, ticket_number) in keys {
+            if let Some(amount) = self.received_prizes.get(&(round_id, ticket_number)).await
+                .map_err(|e: ViewError| format!("Failed to get received prize: {:?}", e))? {
+                prizes.push(ReceivedPrize { round_id, ticket_number, amount });
+            }
+        }
+        Ok(prizes)
+    }
+    
     /// Get all lottery rounds
     pub async fn get_all_rounds(&self) -> Result<Vec<LotteryRound>, String> {
         let indices = self.rounds.indices().await
@@ -434,6 +455,32 @@ impl NativeFungibleTokenState {
         Ok(rounds)
     }
     
+    /// Up to `limit` rounds in `status` (any if None) created within `created`, by ID in
+    /// `order`. IDs grow with creation time, so the walk stops at the first round past the
+    /// far creation bound.
+    pub async fn filter_rounds(&self, status: Option<RoundStatus>, created: CreatedRange, order: SortOrder, limit: u64) -> Result<Vec<LotteryRound>, String> {
+        let mut round_ids = self.rounds.indices().await
+            .map_err(|e: ViewError| format!("Failed to get round indices: {:?}", e))?;
+        order.sort(&mut round_ids);
+        
+        let mut rounds = Vec::new();
+        for round_id in round_ids {
+            if rounds.len() as u64 >= limit {
+                break;
+            }
+            let Some(round) = self.get_round(round_id).await? else {
+                continue;
+            };
+            if created.is_exhausted(round.created_at, order) {
+                break;
+            }
+            if created.contains(round.created_at) && status.map_or(true, |status| round.status == status) {
+                rounds.push(round);
+            }
+        }
+        Ok(rounds)
+    }
+    
     /// Get active round ID
     pub async fn get_active_round(&self) -> Result<Option<u64>, String> {
         Ok(*self.active_round.get())
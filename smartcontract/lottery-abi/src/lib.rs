@@ -3,10 +3,23 @@
 
 /*! Shared ABI definitions for Lottery Applications */
 
-use async_graphql::{Request, Response, SimpleObject};
+use async_graphql::{ComplexObject, InputObject, Request, Response, SimpleObject};
 use linera_sdk::linera_base_types::{AccountOwner, Amount, ChainId, ContractAbi, ServiceAbi};
+use native_fungible_abi::amount_format::{amount_attos, amount_display};
+use native_fungible_abi::list_query::CreatedRange;
 use serde::{Deserialize, Serialize};
 
+pub use native_fungible_abi::csv;
+pub use native_fungible_abi::stored_fields;
+pub use native_fungible_abi::list_query::SortOrder;
+pub use native_fungible_abi::payout_summary::{PayoutTotals, RoundPayoutSummary};
+pub use native_fungible_abi::game_result::{game_results_stream, GameKind, GameResult, GAME_RESULTS_STREAM};
+pub use native_fungible_abi::checked_math::{checked_percentage, tickets_for_amount, TicketCountError};
+pub use native_fungible_abi::ticket_quote::{
+    effective_ticket_price, quote_purchase, validate_price_schedule, PriceStep, PurchaseQuote, QuoteError, MAX_PRICE_SCHEDULE_ENTRIES,
+};
+pub use native_fungible_abi::transfer_memo::{validate_memo, TransferNotice};
+
 // ========================================
 // Lottery App ABI (ticket purchase wrapper)
 // ========================================
@@ -21,13 +34,56 @@ pub enum LotteryAppMessage {
         amount: Amount,
         source_chain_id: ChainId,
         source_owner: AccountOwner,
+        /// Informational only, never affects the ticket purchase
+        memo: Option<String>,
+    },
+    /// A prize paid to `owner` by a lottery draw, delivered to the winner's chain
+    PrizeWon {
+        owner: AccountOwner,
+        round_id: u64,
+        ticket_number: u64,
+        amount: Amount,
     },
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// Initial peer app ids; either may be left out and set later with its admin operation
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct LotteryAppParameters {
-    pub native_app_id: ::linera_sdk::linera_base_types::ApplicationId,
-    pub lottery_rounds_app_id: ::linera_sdk::linera_base_types::ApplicationId,
+    #[serde(default)]
+    pub native_app_id: Option<::linera_sdk::linera_base_types::ApplicationId>,
+    #[serde(default)]
+    pub lottery_rounds_app_id: Option<::linera_sdk::linera_base_types::ApplicationId>,
+}
+
+/// Error prefix for operations that need a peer app id that has not been set yet
+pub const NOT_CONFIGURED: &str = "NOT_CONFIGURED";
+
+/// The lottery app's configuration; an unset peer app id is None
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct LotteryAppConfig {
+    pub native_app_id: Option<String>,
+    pub lottery_rounds_app_id: Option<String>,
+}
+
+/// A prize won in a lottery draw, kept in the winner's inbox on their chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct PrizeNotice {
+    pub owner: AccountOwner,
+    pub round_id: u64,
+    pub ticket_number: u64,
+    pub amount: Amount,
+}
+
+/// Ticket purchase traffic that arrived from one source chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct ChainMetrics {
+    pub chain_id: ChainId,
+    pub total_purchases: u64,
+    pub total_amount: Amount,
+    /// Distinct source owners that bought tickets from this chain
+    pub unique_buyers: u64,
+    /// Timestamp (micros) of the latest purchase
+    pub last_activity: u64,
 }
 
 pub struct LotteryAppAbi;
@@ -50,6 +106,8 @@ pub enum LotteryAppOperation {
         amount: Amount,
         target_account: linera_sdk::abis::fungible::Account,
         purchase_tickets: bool,
+        /// Optional tag for the recipient (at most 64 bytes), ignored by the lottery
+        memo: Option<String>,
     },
     /// Claim tokens from another chain
     Claim {
@@ -64,11 +122,277 @@ pub enum LotteryAppOperation {
         amount: Amount,
         source_chain_id: Option<String>,
     },
+    /// Send a draw prize to its winner and a PrizeWon notice to the winner's chain (called by lottery-rounds)
+    SendWinnerPrize {
+        recipient: AccountOwner,
+        amount: Amount,
+        source_chain_id: Option<String>,
+        round_id: u64,
+        ticket_number: u64,
+    },
+    /// Get the app's configuration
+    GetConfig,
+    /// Zero all operation/message counters
+    ResetMetrics,
+    /// Purchase metrics of every source chain that sent cross-chain ticket purchases
+    GetChainMetrics,
+    /// The `n` source chains with the highest purchase volume, highest first
+    GetTopChains { n: u64 },
+    /// Set the Native app ApplicationId (creator chain only)
+    SetNativeAppId { native_app_id: String },
+    /// Set the Lottery Rounds app ApplicationId (creator chain only)
+    SetLotteryRoundsAppId { lottery_rounds_app_id: String },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum LotteryAppResponse {
     Ok,
+    Config(LotteryAppConfig),
+    ChainMetrics(Vec<ChainMetrics>),
+}
+
+/// How long an escrowed prize can be claimed before it may be reclaimed (30 days)
+pub const ESCROW_DURATION_MICROS: u64 = 30 * 24 * 60 * 60 * 1_000_000;
+
+/// A prize held back because it could not be delivered to the winner's chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct EscrowEntry {
+    pub owner: AccountOwner,
+    pub amount: Amount,
+    pub destination_chain: String,
+    pub created_at: u64,
+    pub expires_at: u64,
+}
+
+/// Most items a single `pendingItems` query walks through
+pub const MAX_PENDING_ITEMS: u64 = 100;
+
+/// Kind of in-flight item listed by the `pendingItems` query
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
+pub enum PendingKind {
+    /// A prize or low-ticket refund held in escrow because it could not be delivered
+    EscrowedPrize,
+}
+
+/// Funds still waiting to reach their owner
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct PendingItem {
+    pub kind: PendingKind,
+    pub owner: AccountOwner,
+    pub amount: Amount,
+    pub source_chain_id: Option<String>,
+    pub created_at: u64,
+    /// Automatic delivery attempts so far (always 0 for escrow, which waits for
+    /// ClaimEscrowedPrize or ExpireEscrow)
+    pub retry_count: u64,
+}
+
+/// Largest number of owners that can subscribe to round notifications
+pub const MAX_NOTIFICATION_SUBSCRIBERS: u64 = 100;
+
+/// Largest number of observer chains closed rounds are replicated to
+pub const MAX_SYNC_TARGETS: u64 = 20;
+
+/// Largest ticket range GetWinnersByTicketRange scans in one query
+pub const MAX_WINNER_TICKET_RANGE: u64 = 1_000;
+
+/// Most ticket numbers GetOwnerTicketNumbers returns in one page
+pub const MAX_TICKET_NUMBERS_PAGE: u64 = 1_000;
+
+/// Most entries GetOperationLog returns in one page
+pub const MAX_OPERATION_LOG_PAGE: u64 = 1_000;
+
+/// A state-changing lottery operation, as recorded in the append-only operation log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct OperationLogEntry {
+    /// "create_round", "purchase", "close_round" or "winner"
+    pub op_type: String,
+    /// Signer of the operation (the buyer for purchases), AccountOwner::CHAIN when unsigned
+    pub actor: AccountOwner,
+    pub timestamp: u64,
+    pub block_height: u64,
+    /// Operation details as space-separated `key=value` pairs, starting with the round ID
+    pub data: String,
+}
+
+/// Default cap on the share of a round's tickets that may win, in basis points (40%)
+pub const DEFAULT_MAX_WINNER_PCT_BPS: u16 = 4_000;
+
+/// Lowest accepted winner share cap; the four pools alone draw 30% of the tickets
+pub const MIN_MAX_WINNER_PCT_BPS: u16 = 3_000;
+
+/// Largest number of tickets one TransferTickets operation can move
+pub const MAX_TICKETS_PER_TRANSFER: u64 = 50;
+
+/// Highest house fee a fee tier or the default fee may take from a purchase, in basis points (20%)
+pub const MAX_HOUSE_FEE_BPS: u16 = 2_000;
+
+/// Which round events an owner wants to be told about, and where
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct NotificationPreferences {
+    pub notify_on_round_close: bool,
+    pub notify_on_winner_drawn: bool,
+    /// Chain the notifications are sent to (None = the lottery chain)
+    pub destination_chain_id: Option<String>,
+}
+
+/// A player's lifetime lottery totals (responsible-gaming reporting)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct PlayerTotals {
+    pub total_spent: Amount,
+    pub total_won: Amount,
+    pub tickets_bought: u64,
+    pub rounds_played: u64,
+    pub last_purchase_at: u64,
+}
+
+/// A user's participation in one retained lottery round
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct UserRoundParticipation {
+    pub round_id: u64,
+    pub total_tickets: u64,
+    pub won: bool,
+    pub total_prize: Amount,
+}
+
+/// How many whole tickets an amount buys at the active round's price
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TicketCountPreview {
+    pub ticket_count: u64,
+    /// Part of the amount that does not buy a whole ticket
+    pub remainder: Amount,
+}
+
+/// Prize pool allocation check: sum of the four pool allocations vs the actual prize pool
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PoolIntegrity {
+    pub round_id: u64,
+    pub prize_pool: Amount,
+    pub allocated: Amount,
+    pub dust: Amount, // Integer-division remainder left unallocated
+}
+
+/// How far the winner draws of a round have got
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct DrawProgress {
+    pub round_id: u64,
+    /// Sum of the four pool counts (zero until the round closes)
+    pub winners_expected: u64,
+    pub winners_drawn: u64,
+    /// Drawn share of the expected winners in basis points (10000 once Complete)
+    pub completion_bps: u64,
+}
+
+/// Inputs of one winner draw, kept so anyone can recompute which ticket won
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct DrawAudit {
+    /// Seed passed to the ticket selection (timestamp + block height + draw offset)
+    pub vrf_value: u64,
+    /// Tickets in the round when it was drawn
+    pub total_tickets_sold: u64,
+    /// Tickets excluded from this draw, ascending: those that had already won, tickets with
+    /// no owner and, with one win per address, tickets whose owner had already won the round
+    pub previous_winners: Vec<u64>,
+    pub drawn_at: u64,
+}
+
+/// Full record of a winning ticket
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct WinningTicketInfo {
+    pub round_id: u64,
+    pub ticket_number: u64,
+    pub owner: AccountOwner,
+    pub prize_amount: Amount,
+    /// The prize was paid out (directly, or claimed from escrow)
+    pub claimed: bool,
+    pub source_chain_id: Option<String>,
+    /// None for winners drawn before pools were recorded
+    pub pool: Option<WinnerPool>,
+    /// Undelivered prizes still held for the ticket's claimants
+    pub escrowed: Vec<EscrowEntry>,
+    /// None for winners drawn before draw audits were recorded
+    pub draw: Option<DrawAudit>,
+}
+
+/// Result of recomputing a draw from its audit record
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct WinVerification {
+    pub round_id: u64,
+    pub ticket_number: u64,
+    /// Owner recorded for the winning ticket
+    pub stored_owner: Option<AccountOwner>,
+    /// Ticket the audited seed selects (None without an audit record)
+    pub expected_ticket: Option<u64>,
+    /// Current owner of the expected ticket
+    pub expected_owner: Option<AccountOwner>,
+    /// The seed selects this ticket and its owner matches the stored winner
+    pub verified: bool,
+}
+
+/// Proof that a winning ticket is part of a completed round's winner Merkle root.
+/// The leaf is SHA-256 of (ticket_number, owner, prize_amount); pairs are hashed in sorted order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub root: Vec<u8>,
+    pub proof: Vec<Vec<u8>>,
+    pub leaf: Vec<u8>,
+}
+
+/// CSV export of a round (UTF-8, RFC 4180)
+/// Columns: ticket_number,owner,amount_paid,is_winner,prize_amount,claimed,source_chain_id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundExport {
+    pub round_id: u64,
+    pub csv_bytes: Vec<u8>,
+}
+
+/// Total prize won by one owner in a round
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct OwnerPayout {
+    pub owner: AccountOwner,
+    pub total_prize: Amount,
+}
+
+/// Canonical record of a completed round. Every collection is sorted, so the
+/// same round always serializes to the same bytes.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RoundArchive {
+    pub round: LotteryRound,
+    /// Every buyer, sorted
+    pub participants: Vec<AccountOwner>,
+    /// Ticket ranges, sorted by first ticket
+    pub tickets: Vec<TicketPurchaseInfo>,
+    /// Winning tickets, sorted by ticket number
+    pub winners: Vec<LotteryWinnerInfo>,
+    /// Prize total per winner, sorted by owner
+    pub payouts: Vec<OwnerPayout>,
+    /// Awarded and delivered prize totals
+    pub payout_summary: RoundPayoutSummary,
+}
+
+/// Every configuration register of the lottery-rounds app, for export and import between chains
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct LotteryRoundsConfig {
+    pub lottery_app_id: Option<String>,
+    pub purchase_allowlist_enabled: bool,
+    pub duplicate_ticket_resolution: DuplicateResolution,
+    pub target_prize_pool: Option<Amount>,
+    pub min_prize_pool_to_close: Option<Amount>,
+    /// Lowest threshold first
+    pub fee_tiers: Vec<FeeTier>,
+    pub default_fee_bps: u16,
+    pub ticket_price_floor: Option<Amount>,
+    pub ticket_price_ceiling: Option<Amount>,
+    /// None means DEFAULT_MAX_WINNER_PCT_BPS
+    pub max_winner_pct_bps: Option<u16>,
+    pub one_win_per_address: bool,
+}
+
+/// A single metrics counter for GraphQL queries
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MetricEntry {
+    pub name: String,
+    pub count: u64,
 }
 
 // ========================================
@@ -83,6 +407,31 @@ pub enum RoundStatus {
     Complete, // All winners drawn
 }
 
+impl RoundStatus {
+    /// Key used for this status in the `rounds_by_status` index
+    pub fn index_key(self) -> u8 {
+        match self {
+            RoundStatus::Active => 0,
+            RoundStatus::Closed => 1,
+            RoundStatus::Complete => 2,
+        }
+    }
+}
+
+/// Filter of a round list query; the creation bounds are exclusive timestamps in micros
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, InputObject)]
+pub struct RoundFilter {
+    pub status: Option<RoundStatus>,
+    pub created_after: Option<u64>,
+    pub created_before: Option<u64>,
+}
+
+impl RoundFilter {
+    pub fn created_range(&self) -> CreatedRange {
+        CreatedRange { after: self.created_after, before: self.created_before }
+    }
+}
+
 /// Winner pool identifier
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
 pub enum WinnerPool {
@@ -93,8 +442,34 @@ pub enum WinnerPool {
     Complete, // All winners drawn
 }
 
+/// How a drawn ticket sold to more than one owner (parallel cross-chain purchases) is paid out
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
+pub enum DuplicateResolution {
+    #[default]
+    FirstComeFirstServed, // Prize goes to the owner recorded for the ticket
+    SplitProportionally,  // Prize is divided equally among all claimants
+    Refund,               // Each claimant gets the ticket price back instead of the prize
+}
+
+/// What closed a lottery round
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
+pub enum RoundCloseTrigger {
+    Manual,    // CloseRound operation
+    TicketCap, // Ticket limit reached
+    PrizeCap,  // Prize pool reached the configured target
+}
+
+/// How a lottery round was settled when it closed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
+pub enum RoundCloseMode {
+    #[default]
+    Normal,                  // Winners are drawn from the four pools
+    PartialRefundLowTickets, // Too few tickets for a winner in every pool; ticket holders are refunded
+}
+
 /// A lottery round
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
 pub struct LotteryRound {
     pub id: u64,
     pub created_at: u64,
@@ -117,10 +492,40 @@ pub struct LotteryRound {
     pub pool2_winners_drawn: u64,
     pub pool3_winners_drawn: u64,
     pub pool4_winners_drawn: u64,
+    
+    /// What closed the round (None while active)
+    pub triggered_by: Option<RoundCloseTrigger>,
+    pub close_mode: RoundCloseMode,
+    /// Price changes after `ticket_price`, the base price
+    pub price_schedule: Vec<PriceStep>,
+    /// Price a purchase pays at the time the round was read
+    pub current_ticket_price: Amount,
+    /// Owners who bought tickets in the round, however many purchases each made
+    pub unique_buyers: u64,
+}
+
+#[ComplexObject]
+impl LotteryRound {
+    async fn ticket_price_attos(&self) -> String {
+        amount_attos(self.ticket_price)
+    }
+
+    async fn ticket_price_display(&self) -> String {
+        amount_display(self.ticket_price)
+    }
+
+    async fn prize_pool_attos(&self) -> String {
+        amount_attos(self.prize_pool)
+    }
+
+    async fn prize_pool_display(&self) -> String {
+        amount_display(self.prize_pool)
+    }
 }
 
 /// A user's ticket purchase
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
 pub struct TicketPurchase {
     pub owner: AccountOwner,
     pub first_ticket: u64,
@@ -130,8 +535,20 @@ pub struct TicketPurchase {
     pub source_chain_id: Option<String>,
 }
 
+#[ComplexObject]
+impl TicketPurchase {
+    async fn amount_paid_attos(&self) -> String {
+        amount_attos(self.amount_paid)
+    }
+
+    async fn amount_paid_display(&self) -> String {
+        amount_display(self.amount_paid)
+    }
+}
+
 /// Ticket purchase info for queries
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
 pub struct TicketPurchaseInfo {
     pub owner: AccountOwner,
     pub first_ticket: u64,
@@ -141,8 +558,20 @@ pub struct TicketPurchaseInfo {
     pub source_chain_id: Option<String>,
 }
 
+#[ComplexObject]
+impl TicketPurchaseInfo {
+    async fn amount_paid_attos(&self) -> String {
+        amount_attos(self.amount_paid)
+    }
+
+    async fn amount_paid_display(&self) -> String {
+        amount_display(self.amount_paid)
+    }
+}
+
 /// Winner information
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
 pub struct LotteryWinnerInfo {
     pub ticket_number: u64,
     pub owner: AccountOwner,
@@ -151,6 +580,63 @@ pub struct LotteryWinnerInfo {
     pub source_chain_id: Option<String>,
 }
 
+#[ComplexObject]
+impl LotteryWinnerInfo {
+    async fn prize_amount_attos(&self) -> String {
+        amount_attos(self.prize_amount)
+    }
+
+    async fn prize_amount_display(&self) -> String {
+        amount_display(self.prize_amount)
+    }
+}
+
+/// A round's winners drawn from one pool, with the pool's share of the prize
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct PoolWinners {
+    pub pool: WinnerPool,
+    pub prize_percentage: u64,
+    pub winners: Vec<LotteryWinnerInfo>,
+}
+
+/// One winner pool of the active round, projected from its current sales
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct PoolSchedule {
+    pub pool: WinnerPool,
+    /// Share of the sold tickets that win in this pool
+    pub ticket_percentage: u64,
+    /// Winning tickets if the round closed now
+    pub winner_count: u64,
+    pub prize_percentage: u64,
+    /// The pool's share of the current prize pool
+    pub prize_allocation: Amount,
+    pub prize_per_winner: Amount,
+}
+
+/// Reward structure of the active round, Pool1 to Pool4
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct PrizeSchedule {
+    pub round_id: u64,
+    pub total_tickets_sold: u64,
+    pub prize_pool: Amount,
+    pub pools: Vec<PoolSchedule>,
+}
+
+/// Prizes of a round that have been drawn but not yet delivered
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct UnclaimedPrizes {
+    pub round_id: u64,
+    pub unclaimed_winner_count: u64,
+    pub total_unclaimed_amount: Amount,
+}
+
+/// House fee charged on purchases once a round's prize pool holds `min_pool_tokens` whole tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct FeeTier {
+    pub min_pool_tokens: u64,
+    pub fee_bps: u16,
+}
+
 pub struct LotteryRoundsAbi;
 
 impl ContractAbi for LotteryRoundsAbi {
@@ -166,12 +652,20 @@ impl ServiceAbi for LotteryRoundsAbi {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct LotteryRoundsParameters {
     pub native_app_id: ::linera_sdk::linera_base_types::ApplicationId,
+    /// Account allowed to run admin operations (rotate with TransferAdminRole)
+    pub admin: AccountOwner,
+    /// Winners drawn automatically when a round closes (None = draw manually with GenerateWinner)
+    #[serde(default)]
+    pub draws_per_close: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum LotteryRoundsOperation {
     // Round management
-    CreateRound { ticket_price: Amount },
+    /// `ticket_price` applies until the first `(effective_from, price)` entry of
+    /// `price_schedule` takes effect (empty for a fixed price; ascending, at most
+    /// MAX_PRICE_SCHEDULE_ENTRIES entries)
+    CreateRound { ticket_price: Amount, price_schedule: Vec<(u64, Amount)> },
     CloseRound,
     GenerateWinner { round_id: u64 },
     
@@ -182,17 +676,127 @@ pub enum LotteryRoundsOperation {
         ticket_price: Amount,
         source_chain_id: Option<String>,
     },
+    /// Give some of the signer's tickets to `to` (at most MAX_TICKETS_PER_TRANSFER) while the
+    /// round is active or still drawing winners; tickets that already won cannot be given
+    TransferTickets { round_id: u64, to: AccountOwner, ticket_numbers: Vec<u64> },
     
     // Configuration
     SetLotteryAppId { lottery_app_id: String },
+    /// Replace the whole configuration (creator chain only). Nothing is applied
+    /// unless the lottery app id, fees and limits in `config` are all valid.
+    ImportConfig { config: LotteryRoundsConfig },
+    SetDuplicateResolution { resolution: DuplicateResolution },
+    /// Close the active round as soon as its prize pool reaches `amount` (None disables)
+    SetTargetPrizePool { amount: Option<Amount> },
+    /// Refuse CloseRound while the active round's prize pool is below `amount` (None disables).
+    /// Rounds that reach it still close under the usual winner budget and low-ticket rules.
+    SetMinPrizePoolToClose { amount: Option<Amount> },
+    /// Refuse CreateRound below this ticket price (None = any positive price)
+    SetTicketPriceFloor { floor: Option<Amount> },
+    /// Refuse CreateRound above this ticket price (None = no ceiling)
+    SetTicketPriceCeiling { ceiling: Option<Amount> },
+    /// Refuse to close rounds whose winners would exceed `bps` of the tickets sold
+    /// (MIN_MAX_WINNER_PCT_BPS to 10000; defaults to DEFAULT_MAX_WINNER_PCT_BPS)
+    SetMaxWinnerPctBps { bps: u16 },
+    /// When enabled, an address wins at most one prize per round; draws skip tickets of owners
+    /// who already won, and fail once no other owner holds a ticket
+    SetOneWinPerAddress { enabled: bool },
+    /// Charge `fee_bps` on purchases into rounds whose prize pool holds at least
+    /// `min_pool_tokens` whole tokens (replaces the tier at that threshold; at most MAX_HOUSE_FEE_BPS)
+    AddFeeTier { min_pool_tokens: u64, fee_bps: u16 },
+    /// Remove the fee tier at `min_pool_tokens`
+    RemoveFeeTier { min_pool_tokens: u64 },
+    /// House fee charged when no fee tier applies (0 by default)
+    SetDefaultFeeBps { fee_bps: u16 },
+    
+    // Purchase allowlist (private/VIP rounds)
+    EnableAllowlist,
+    DisableAllowlist,
+    AddToAllowlist { addresses: Vec<AccountOwner> },
+    RemoveFromAllowlist { address: AccountOwner },
 
     // Queries
     GetActiveRound,
+    /// Get only the ID of the active round (None when no round is active)
+    GetActiveRoundId,
+    /// Get the status of the current round (the active one, else the latest one while
+    /// its winners are drawn) without its full details, for polling clients
+    GetCurrentRoundStatus,
+    /// Get the winner pool being drawn in the current round and its remaining winners
+    GetCurrentWinnerPool,
     GetRound { id: u64 },
     GetAllRounds,
+    /// Get up to `limit` rounds in a status, newest first
+    GetRoundsByStatus { status: RoundStatus, limit: u64 },
     GetRoundTicketPurchases { round_id: u64 },
     GetUserTickets { round_id: u64, owner: AccountOwner },
+    /// Get up to `limit` (at most MAX_TICKET_NUMBERS_PAGE) of an owner's ticket numbers above
+    /// `after`, ascending; pass the last number returned as `after` to get the next page
+    GetOwnerTicketNumbers { round_id: u64, owner: AccountOwner, after: Option<u64>, limit: u64 },
     GetRoundWinners { round_id: u64 },
+    /// Get a round's winners whose ticket is in `start_ticket..=end_ticket` (at most
+    /// MAX_WINNER_TICKET_RANGE tickets), by ticket number
+    GetWinnersByTicketRange { round_id: u64, start_ticket: u64, end_ticket: u64 },
+    /// Get a round's winners grouped by the pool they were drawn from (Pool1 to Pool4)
+    GetRoundWinnersByPool { round_id: u64 },
+    GetAllowlistStatus { owner: AccountOwner },
+    VerifyPoolIntegrity { round_id: u64 },
+    /// Get how many of a round's winners have been drawn, for progress bars during settlement
+    GetDrawProgress { round_id: u64 },
+    ExportRoundCSV { round_id: u64 },
+    /// Export a completed round as a BCS-serialized `RoundArchive`
+    ExportRound { round_id: u64 },
+    GetUserParticipation { owner: AccountOwner },
+    GetPlayerTotals { owner: AccountOwner },
+    /// Quote how many tickets `amount` buys in the active round (read-only)
+    QuotePurchase { amount: Amount },
+    /// Preview the ticket count and remainder of `amount` in the active round (read-only)
+    PreviewTicketCount { amount: Amount },
+    /// Get the winner pools of the active round as they stand with the current sales
+    GetPrizeSchedule,
+    /// Get every round that still has undelivered prizes
+    GetRoundsWithUnclaimedPrizes,
+    /// Get how much of a round's prize pool has been awarded, paid and left unclaimed
+    GetRoundPayoutSummary { round_id: u64 },
+    /// Get the sum of all undelivered prizes
+    GetTotalUnclaimedPrizesAmount,
+    /// Get every configuration register
+    GetConfig,
+    /// Prove that a ticket is among the winners committed to by a completed round's Merkle root
+    ProveMembershipOfWinner { round_id: u64, ticket_number: u64 },
+    
+    // Prize escrow
+    /// Deliver `owner`'s escrowed prize for a winning ticket (before it expires). Admin only:
+    /// lottery-app pays prizes from the signer, and the admin's account funds the prizes
+    /// escrow holds back, as it does the reclaimed escrow WithdrawFees sends.
+    ClaimEscrowedPrize { round_id: u64, ticket_number: u64, owner: AccountOwner },
+    /// Move expired escrowed prizes of a ticket to the reclaimed balance (anyone, after expires_at)
+    ExpireEscrow { round_id: u64, ticket_number: u64 },
+    /// Send the reclaimed escrow balance to a recipient (creator chain only)
+    WithdrawFees { recipient: AccountOwner, destination_chain: Option<String> },
+    
+    // Responsible gaming
+    /// Set (or clear with None) the signer's own per-round spend limit
+    SetMySpendLimit { per_round: Option<Amount> },
+    
+    // Notifications
+    /// Set the signer's round notification preferences (both flags off unsubscribes)
+    SetNotificationPreferences { prefs: NotificationPreferences },
+    /// Send every round's state to observer chain `chain_id` when it closes (admin only,
+    /// at most MAX_SYNC_TARGETS chains)
+    AddSyncTarget { chain_id: String },
+    /// Stop replicating rounds to `chain_id` (admin only)
+    RemoveSyncTarget { chain_id: String },
+    
+    // Metrics
+    ResetMetrics,
+    /// Get up to `limit` (at most MAX_OPERATION_LOG_PAGE) operation log entries from
+    /// position `offset`, oldest first
+    GetOperationLog { offset: u64, limit: u64 },
+    
+    // Administration
+    /// Hand the admin role to another account (admin only)
+    TransferAdminRole { new_admin: AccountOwner },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -203,8 +807,10 @@ pub enum LotteryRoundsResponse {
     LotteryRound(Option<LotteryRound>),
     LotteryRounds(Vec<LotteryRound>),
     TicketPurchase(TicketPurchase),
+    TicketNumbers(Vec<u64>),
     TicketPurchases(Vec<TicketPurchaseInfo>),
     LotteryWinners(Vec<LotteryWinnerInfo>),
+    WinnersByPool(Vec<PoolWinners>),
     WinnerGenerated {
         round_id: u64,
         ticket_number: u64,
@@ -213,9 +819,48 @@ pub enum LotteryRoundsResponse {
         new_round_created: bool,
         source_chain_id: Option<String>,
     },
+    AllowlistStatus {
+        enabled: bool,
+        allowlisted: bool,
+    },
+    PoolIntegrity(PoolIntegrity),
+    DrawProgress(DrawProgress),
+    RoundExport(RoundExport),
+    /// BCS-serialized `RoundArchive`
+    Archive(Vec<u8>),
+    UserParticipation(Vec<UserRoundParticipation>),
+    PlayerTotals(Option<PlayerTotals>),
+    /// Amount delivered, expired or withdrawn by an escrow operation
+    EscrowAmount(Amount),
+    MerkleProof(MerkleProof),
+    Config(LotteryRoundsConfig),
+    RoundsWithUnclaimedPrizes(Vec<UnclaimedPrizes>),
+    TotalUnclaimed(Amount),
+    RoundPayoutSummary(RoundPayoutSummary),
+    PurchaseQuote(Result<PurchaseQuote, QuoteError>),
+    TicketCountPreview(Result<TicketCountPreview, QuoteError>),
+    /// None when there is no active round
+    PrizeSchedule(Option<PrizeSchedule>),
+    ActiveRoundId(Option<u64>),
+    /// `time_since_last_change` is measured from the later of the round's creation and closing
+    CurrentStatus {
+        round_id: Option<u64>,
+        status: Option<RoundStatus>,
+        tickets_sold: u64,
+        time_since_last_change: u64,
+    },
+    CurrentPool { round_id: u64, pool: WinnerPool, remaining: u64 },
+    OperationLog(Vec<OperationLogEntry>),
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub enum LotteryRoundsMessage {
     Notify,
+    /// A round stopped accepting tickets
+    RoundClosed { round_id: u64 },
+    /// A winning ticket was drawn
+    WinnerDrawn { round_id: u64, ticket_number: u64, owner: AccountOwner, prize_amount: Amount },
+    /// A round's state as it closed, for read-only observer chains, with the number of
+    /// winning tickets each pool holds
+    RoundStateSync { round: LotteryRound, ticket_count_by_pool: Vec<(WinnerPool, u64)> },
 }
@@ -6,16 +6,42 @@
 mod state;
 
 use linera_sdk::{
-    linera_base_types::{AccountOwner, ChainId, WithContractAbi},
+    linera_base_types::{AccountOwner, Amount, ApplicationId, ChainId, WithContractAbi},
     views::{RootView, View},
     Contract, ContractRuntime,
 };
 use lottery_abi::{
     LotteryAppAbi, LotteryAppOperation, LotteryAppResponse, LotteryAppMessage as Message,
-    LotteryAppParameters, LotteryRoundsAbi, LotteryRoundsOperation, LotteryRoundsResponse,
+    LotteryAppParameters, LotteryRoundsOperation, LotteryRoundsResponse, PrizeNotice, TransferNotice,
+    validate_memo,
 };
 use self::state::LotteryAppState;
 
+/// Metrics counter name for an operation
+fn operation_metric(operation: &LotteryAppOperation) -> &'static str {
+    match operation {
+        LotteryAppOperation::Transfer { .. } => "operation.Transfer",
+        LotteryAppOperation::Claim { .. } => "operation.Claim",
+        LotteryAppOperation::SendPrize { .. } => "operation.SendPrize",
+        LotteryAppOperation::SendWinnerPrize { .. } => "operation.SendWinnerPrize",
+        LotteryAppOperation::GetConfig => "operation.GetConfig",
+        LotteryAppOperation::ResetMetrics => "operation.ResetMetrics",
+        LotteryAppOperation::GetChainMetrics => "operation.GetChainMetrics",
+        LotteryAppOperation::GetTopChains { .. } => "operation.GetTopChains",
+        LotteryAppOperation::SetNativeAppId { .. } => "operation.SetNativeAppId",
+        LotteryAppOperation::SetLotteryRoundsAppId { .. } => "operation.SetLotteryRoundsAppId",
+    }
+}
+
+/// Metrics counter name for a received message
+fn message_metric(message: &Message) -> &'static str {
+    match message {
+        Message::Notify => "message.Notify",
+        Message::TransferForTickets { .. } => "message.TransferForTickets",
+        Message::PrizeWon { .. } => "message.PrizeWon",
+    }
+}
+
 pub struct LotteryAppContract {
     state: LotteryAppState,
     runtime: ContractRuntime<Self>,
@@ -41,18 +67,24 @@ impl Contract for LotteryAppContract {
     }
 
     async fn instantiate(&mut self, _arg: Self::InstantiationArgument) {
-        // Validate params access
-        let _ = self.runtime.application_parameters();
+        // Peer app ids left out of the parameters are set later with SetNativeAppId / SetLotteryRoundsAppId
+        let params = self.runtime.application_parameters();
+        self.state.native_app_id.set(params.native_app_id.map(|app_id| app_id.with_abi()));
+        self.state.lottery_rounds_app_id.set(params.lottery_rounds_app_id.map(|app_id| app_id.with_abi()));
         self.state.initialized.set(true);
     }
 
     async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response {
+        self.state.increment_metric(operation_metric(&operation)).await
+            .expect("Failed to update metrics");
+        
         match operation {
             LotteryAppOperation::Transfer {
                 owner,
                 amount,
                 target_account,
                 purchase_tickets,
+                memo,
             } => {
                 eprintln!("LotteryApp::Transfer - owner: {:?}, amount: {:?}, target: {:?}, purchase_tickets: {}", 
                     owner, amount, target_account, purchase_tickets);
@@ -61,11 +93,17 @@ impl Contract for LotteryAppContract {
                     .check_account_permission(owner)
                     .expect("Permission for Transfer operation");
 
-                let params = self.runtime.application_parameters();
-                eprintln!("LotteryApp::Transfer - native_app_id from params: {:?}", params.native_app_id);
-                
-                let native_app_id = params.native_app_id.with_abi::<native::NativeAbi>();
-                let lottery_rounds_app_id = params.lottery_rounds_app_id.with_abi::<LotteryRoundsAbi>();
+                // A same-chain transfer to oneself moves no tokens, so it must not pay for tickets
+                if purchase_tickets && target_account.owner == owner && target_account.chain_id == self.runtime.chain_id() {
+                    panic!("Self-transfers cannot purchase tickets");
+                }
+                if let Err(e) = validate_memo(memo.as_deref()) {
+                    panic!("Invalid transfer memo: {}", e);
+                }
+
+                let native_app_id = self.state.native_app()
+                    .unwrap_or_else(|e| panic!("Failed to transfer: {}", e));
+                eprintln!("LotteryApp::Transfer - native_app_id: {:?}", native_app_id);
 
                 eprintln!("LotteryApp::Transfer - Calling native::Transfer...");
                 
@@ -77,6 +115,7 @@ impl Contract for LotteryAppContract {
                         owner,
                         amount,
                         target_account,
+                        memo: memo.clone(),
                     },
                 );
                 
@@ -87,6 +126,8 @@ impl Contract for LotteryAppContract {
                     eprintln!("LotteryApp::Transfer - Calling lottery-rounds::PurchaseTickets...");
                     if target_account.chain_id == self.runtime.chain_id() {
                         // Same chain - call lottery-rounds directly
+                        let lottery_rounds_app_id = self.state.lottery_rounds_app()
+                            .unwrap_or_else(|e| panic!("Failed to transfer: {}", e));
                         let rounds_response: LotteryRoundsResponse = self.runtime.call_application(
                             true,
                             lottery_rounds_app_id,
@@ -105,11 +146,14 @@ impl Contract for LotteryAppContract {
                             amount,
                             source_chain_id: self.runtime.chain_id(),
                             source_owner: owner,
+                            memo,
                         };
                         self.runtime
                             .prepare_message(message)
                             .with_authentication()
                             .send_to(target_account.chain_id);
+                        self.state.increment_metric("messages_sent").await
+                            .expect("Failed to update metrics");
                         eprintln!("LotteryApp::Transfer - Cross-chain message sent");
                     }
                 }
@@ -128,9 +172,8 @@ impl Contract for LotteryAppContract {
                     .check_account_permission(source_account.owner)
                     .expect("Permission for Claim operation");
 
-                let params = self.runtime.application_parameters();
-                let native_app_id = params.native_app_id.with_abi::<native::NativeAbi>();
-                let lottery_rounds_app_id = params.lottery_rounds_app_id.with_abi::<LotteryRoundsAbi>();
+                let native_app_id = self.state.native_app()
+                    .unwrap_or_else(|e| panic!("Failed to claim: {}", e));
 
                 // Step 1: Call Native app to claim tokens
                 let _native_response: native::NativeResponse = self.runtime.call_application(
@@ -145,6 +188,8 @@ impl Contract for LotteryAppContract {
 
                 // Step 2: If purchase_tickets, register tickets in lottery-rounds
                 if purchase_tickets {
+                    let lottery_rounds_app_id = self.state.lottery_rounds_app()
+                        .unwrap_or_else(|e| panic!("Failed to claim: {}", e));
                     let _rounds_response: LotteryRoundsResponse = self.runtime.call_application(
                         true,
                         lottery_rounds_app_id,
@@ -162,54 +207,101 @@ impl Contract for LotteryAppContract {
 
             LotteryAppOperation::SendPrize { recipient, amount, source_chain_id } => {
                 // Called by lottery-rounds to distribute prize
-                let params = self.runtime.application_parameters();
-                let native_app_id = params.native_app_id.with_abi::<native::NativeAbi>();
+                self.send_prize(recipient, amount, source_chain_id.as_deref());
+                LotteryAppResponse::Ok
+            }
 
-                let target_chain = if let Some(source_chain_id_str) = &source_chain_id {
-                    source_chain_id_str.parse::<ChainId>().unwrap_or_else(|_| self.runtime.chain_id())
+            LotteryAppOperation::SendWinnerPrize { recipient, amount, source_chain_id, round_id, ticket_number } => {
+                // Called by lottery-rounds for a drawn winner; the notice follows the payout
+                let target_chain = self.send_prize(recipient, amount, source_chain_id.as_deref());
+                if target_chain == self.runtime.chain_id() {
+                    self.state.record_prize(PrizeNotice { owner: recipient, round_id, ticket_number, amount })
+                        .unwrap_or_else(|e| panic!("Failed to record prize: {}", e));
                 } else {
-                    self.runtime.chain_id()
-                };
-
-                let target_account = linera_sdk::abis::fungible::Account {
-                    chain_id: target_chain,
-                    owner: recipient,
-                };
-
-                let payer = self.runtime.authenticated_signer()
-                    .expect("Authentication required for prize distribution");
+                    self.runtime
+                        .prepare_message(Message::PrizeWon { owner: recipient, round_id, ticket_number, amount })
+                        .with_authentication()
+                        .send_to(target_chain);
+                }
+                LotteryAppResponse::Ok
+            }
+            
+            LotteryAppOperation::GetConfig => {
+                LotteryAppResponse::Config(self.state.config())
+            }
+            
+            LotteryAppOperation::SetNativeAppId { native_app_id } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("SetNativeAppId can only be executed on the application creator chain");
+                }
+                match native_app_id.parse::<ApplicationId>() {
+                    Ok(app_id) => self.state.native_app_id.set(Some(app_id.with_abi())),
+                    Err(e) => panic!("Failed to parse Native ApplicationId: {:?}", e),
+                }
+                LotteryAppResponse::Ok
+            }
+            
+            LotteryAppOperation::SetLotteryRoundsAppId { lottery_rounds_app_id } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("SetLotteryRoundsAppId can only be executed on the application creator chain");
+                }
+                match lottery_rounds_app_id.parse::<ApplicationId>() {
+                    Ok(app_id) => self.state.lottery_rounds_app_id.set(Some(app_id.with_abi())),
+                    Err(e) => panic!("Failed to parse LotteryRounds ApplicationId: {:?}", e),
+                }
+                LotteryAppResponse::Ok
+            }
+            
+            LotteryAppOperation::ResetMetrics => {
+                self.state.reset_metrics();
+                LotteryAppResponse::Ok
+            }
 
-                let _native_response: native::NativeResponse = self.runtime.call_application(
-                    true,
-                    native_app_id,
-                    &native::NativeOperation::Transfer {
-                        owner: payer,
-                        amount,
-                        target_account,
-                    },
-                );
+            LotteryAppOperation::GetChainMetrics => {
+                match self.state.get_chain_metrics().await {
+                    Ok(metrics) => LotteryAppResponse::ChainMetrics(metrics),
+                    Err(e) => panic!("Failed to get chain metrics: {}", e),
+                }
+            }
 
-                LotteryAppResponse::Ok
+            LotteryAppOperation::GetTopChains { n } => {
+                match self.state.top_chains(n).await {
+                    Ok(metrics) => LotteryAppResponse::ChainMetrics(metrics),
+                    Err(e) => panic!("Failed to get top chains: {}", e),
+                }
             }
         }
     }
 
     async fn execute_message(&mut self, message: Self::Message) {
+        self.state.increment_metric(message_metric(&message)).await
+            .expect("Failed to update metrics");
+        
         match message {
             Message::Notify => {
                 eprintln!("LotteryApp::execute_message - Notify received");
             }
-            Message::TransferForTickets { owner: _, amount, source_chain_id, source_owner } => {
+            Message::TransferForTickets { owner, amount, source_chain_id, source_owner, memo } => {
                 eprintln!("==== LotteryApp::execute_message - TransferForTickets ====");
                 eprintln!("  source_owner: {:?}", source_owner);
                 eprintln!("  source_chain: {:?}", source_chain_id);
                 eprintln!("  amount: {:?}", amount);
                 eprintln!("  current chain: {:?}", self.runtime.chain_id());
                 
-                let params = self.runtime.application_parameters();
-                eprintln!("  lottery_rounds_app_id from params: {:?}", params.lottery_rounds_app_id);
+                // The memo is only recorded for the recipient, it never affects the purchase
+                if let Some(memo) = memo {
+                    self.state.record_transfer(TransferNotice {
+                        recipient: owner,
+                        source_chain_id: source_chain_id.to_string(),
+                        source_owner,
+                        amount,
+                        memo,
+                    }).expect("Failed to record transfer");
+                }
                 
-                let lottery_rounds_app_id = params.lottery_rounds_app_id.with_abi::<LotteryRoundsAbi>();
+                let lottery_rounds_app_id = self.state.lottery_rounds_app()
+                    .unwrap_or_else(|e| panic!("Failed to purchase tickets: {}", e));
+                eprintln!("  lottery_rounds_app_id: {:?}", lottery_rounds_app_id);
 
                 eprintln!("  Calling lottery-rounds::PurchaseTickets...");
 
@@ -226,8 +318,16 @@ impl Contract for LotteryAppContract {
                 );
                 
                 eprintln!("  rounds_response: {:?}", rounds_response);
+
+                let timestamp = self.runtime.system_time().micros();
+                self.state.record_chain_purchase(source_chain_id, source_owner, amount, timestamp).await
+                    .expect("Failed to update chain metrics");
                 eprintln!("==== LotteryApp::execute_message - Complete ====");
             }
+            Message::PrizeWon { owner, round_id, ticket_number, amount } => {
+                self.state.record_prize(PrizeNotice { owner, round_id, ticket_number, amount })
+                    .expect("Failed to record prize");
+            }
         }
     }
 
@@ -235,3 +335,274 @@ impl Contract for LotteryAppContract {
         self.state.save().await.expect("Failed to save state");
     }
 }
+
+impl LotteryAppContract {
+    /// Pay `amount` from the signer to `recipient` on their chain (this chain when
+    /// `source_chain_id` is missing or unparsable), returning the chain paid to
+    fn send_prize(&mut self, recipient: AccountOwner, amount: Amount, source_chain_id: Option<&str>) -> ChainId {
+        let native_app_id = self.state.native_app()
+            .unwrap_or_else(|e| panic!("Failed to send prize: {}", e));
+
+        let target_chain = if let Some(source_chain_id_str) = source_chain_id {
+            source_chain_id_str.parse::<ChainId>().unwrap_or_else(|_| self.runtime.chain_id())
+        } else {
+            self.runtime.chain_id()
+        };
+
+        let target_account = linera_sdk::abis::fungible::Account {
+            chain_id: target_chain,
+            owner: recipient,
+        };
+
+        let payer = self.runtime.authenticated_signer()
+            .expect("Authentication required for prize distribution");
+
+        let _native_response: native::NativeResponse = self.runtime.call_application(
+            true,
+            native_app_id,
+            &native::NativeOperation::Transfer {
+                owner: payer,
+                amount,
+                target_account,
+                memo: None,
+            },
+        );
+
+        target_chain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use futures::FutureExt as _;
+    use linera_sdk::{
+        abis::fungible::Account,
+        linera_base_types::{AccountOwner, Amount, ApplicationId, ChainId, CryptoHash, Timestamp},
+        util::BlockingWait,
+        views::View,
+        Contract, ContractRuntime,
+    };
+    use lottery_abi::{
+        LotteryAppMessage, LotteryAppOperation, LotteryAppParameters, LotteryAppResponse, LotteryRoundsOperation,
+        LotteryRoundsResponse, PrizeNotice,
+    };
+
+    use super::{LotteryAppContract, LotteryAppState};
+
+    fn app_id(name: &str) -> ApplicationId {
+        ApplicationId::new(CryptoHash::test_hash(name))
+    }
+
+    /// Creates a contract signed by `owner` that records every ticket purchase sent to lottery-rounds
+    fn create_contract(owner: AccountOwner) -> (LotteryAppContract, Arc<Mutex<Vec<LotteryRoundsOperation>>>) {
+        create_contract_with(owner, LotteryAppParameters {
+            native_app_id: Some(app_id("native")),
+            lottery_rounds_app_id: Some(app_id("lottery-rounds")),
+        })
+    }
+
+    /// Like `create_contract`, but instantiated with the given parameters
+    fn create_contract_with(
+        owner: AccountOwner,
+        parameters: LotteryAppParameters,
+    ) -> (LotteryAppContract, Arc<Mutex<Vec<LotteryRoundsOperation>>>) {
+        let purchases = Arc::new(Mutex::new(Vec::new()));
+        let recorder = purchases.clone();
+        let runtime = ContractRuntime::new()
+            .with_application_parameters(parameters)
+            .with_chain_id(ChainId(CryptoHash::test_hash("lottery")))
+            .with_application_creator_chain_id(ChainId(CryptoHash::test_hash("lottery")))
+            .with_authenticated_signer(owner)
+            .with_system_time(Timestamp::from(1_000))
+            .with_call_application_handler(move |_authenticated, application_id, operation| {
+                if application_id == app_id("lottery-rounds") {
+                    recorder.lock().unwrap().push(bcs::from_bytes(&operation).unwrap());
+                    bcs::to_bytes(&LotteryRoundsResponse::Ok).unwrap()
+                } else {
+                    bcs::to_bytes(&native::NativeResponse::Ok).unwrap()
+                }
+            });
+        let state = LotteryAppState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to load state");
+        let mut contract = LotteryAppContract { state, runtime };
+        contract.instantiate(()).now_or_never().unwrap();
+        (contract, purchases)
+    }
+
+    fn ticket_transfer(owner: AccountOwner, target_owner: AccountOwner) -> LotteryAppOperation {
+        LotteryAppOperation::Transfer {
+            owner,
+            amount: Amount::from_tokens(5),
+            target_account: Account { chain_id: ChainId(CryptoHash::test_hash("lottery")), owner: target_owner },
+            purchase_tickets: true,
+            memo: None,
+        }
+    }
+
+    #[test]
+    fn transfer_to_pool_purchases_tickets_once() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let pool = AccountOwner::from(CryptoHash::test_hash("pool"));
+        let (mut contract, purchases) = create_contract(alice);
+
+        contract.execute_operation(ticket_transfer(alice, pool)).now_or_never().unwrap();
+
+        assert_eq!(purchases.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Self-transfers cannot purchase tickets")]
+    fn self_transfer_does_not_purchase_tickets() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let (mut contract, _purchases) = create_contract(alice);
+
+        contract.execute_operation(ticket_transfer(alice, alice)).now_or_never().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid transfer memo")]
+    fn oversized_memo_is_rejected() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let pool = AccountOwner::from(CryptoHash::test_hash("pool"));
+        let (mut contract, _purchases) = create_contract(alice);
+
+        let mut transfer = ticket_transfer(alice, pool);
+        if let LotteryAppOperation::Transfer { memo, .. } = &mut transfer {
+            *memo = Some("m".repeat(65));
+        }
+        contract.execute_operation(transfer).now_or_never().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to transfer: NOT_CONFIGURED")]
+    fn transfer_fails_until_the_native_app_id_is_set() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let pool = AccountOwner::from(CryptoHash::test_hash("pool"));
+        let (mut contract, _purchases) = create_contract_with(alice, LotteryAppParameters::default());
+
+        contract.execute_operation(ticket_transfer(alice, pool)).now_or_never().unwrap();
+    }
+
+    #[test]
+    fn app_ids_can_be_wired_after_a_parameter_free_deployment() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let pool = AccountOwner::from(CryptoHash::test_hash("pool"));
+        let (mut contract, purchases) = create_contract_with(alice, LotteryAppParameters::default());
+        assert!(!contract.state.configuration_complete());
+
+        contract.execute_operation(LotteryAppOperation::SetNativeAppId {
+            native_app_id: app_id("native").to_string(),
+        }).now_or_never().unwrap();
+        assert!(!contract.state.configuration_complete());
+        contract.execute_operation(LotteryAppOperation::SetLotteryRoundsAppId {
+            lottery_rounds_app_id: app_id("lottery-rounds").to_string(),
+        }).now_or_never().unwrap();
+        assert!(contract.state.configuration_complete());
+
+        let LotteryAppResponse::Config(config) =
+            contract.execute_operation(LotteryAppOperation::GetConfig).now_or_never().unwrap()
+        else {
+            panic!("GetConfig should return the configuration");
+        };
+        assert_eq!(config.lottery_rounds_app_id, Some(app_id("lottery-rounds").to_string()));
+
+        contract.execute_operation(ticket_transfer(alice, pool)).now_or_never().unwrap();
+        assert_eq!(purchases.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn cross_chain_purchases_are_aggregated_per_source_chain() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let pool = AccountOwner::from(CryptoHash::test_hash("pool"));
+        let busy_chain = ChainId(CryptoHash::test_hash("busy"));
+        let quiet_chain = ChainId(CryptoHash::test_hash("quiet"));
+        let (mut contract, purchases) = create_contract(pool);
+
+        for (source_chain_id, source_owner, tokens) in [
+            (busy_chain, alice, 3),
+            (busy_chain, alice, 4),
+            (busy_chain, bob, 5),
+            (quiet_chain, alice, 2),
+        ] {
+            contract.execute_message(LotteryAppMessage::TransferForTickets {
+                owner: pool,
+                amount: Amount::from_tokens(tokens),
+                source_chain_id,
+                source_owner,
+                memo: None,
+            }).now_or_never().unwrap();
+        }
+        assert_eq!(purchases.lock().unwrap().len(), 4);
+
+        let LotteryAppResponse::ChainMetrics(metrics) =
+            contract.execute_operation(LotteryAppOperation::GetChainMetrics).now_or_never().unwrap()
+        else {
+            panic!("GetChainMetrics should return chain metrics");
+        };
+        assert_eq!(metrics.len(), 2);
+        let busy = metrics.iter().find(|entry| entry.chain_id == busy_chain).unwrap();
+        assert_eq!((busy.total_purchases, busy.total_amount, busy.unique_buyers), (3, Amount::from_tokens(12), 2));
+        assert_eq!(busy.last_activity, 1_000);
+        let quiet = metrics.iter().find(|entry| entry.chain_id == quiet_chain).unwrap();
+        assert_eq!((quiet.total_purchases, quiet.total_amount, quiet.unique_buyers), (1, Amount::from_tokens(2), 1));
+
+        let LotteryAppResponse::ChainMetrics(top) =
+            contract.execute_operation(LotteryAppOperation::GetTopChains { n: 1 }).now_or_never().unwrap()
+        else {
+            panic!("GetTopChains should return chain metrics");
+        };
+        assert_eq!(top.iter().map(|entry| entry.chain_id).collect::<Vec<_>>(), vec![busy_chain]);
+    }
+
+    #[test]
+    fn prize_notices_reach_the_winners_chain() {
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let alice_chain = ChainId(CryptoHash::test_hash("alice-chain"));
+        let (mut lottery_chain, _purchases) = create_contract(admin);
+        let (mut winner_chain, _purchases) = create_contract(alice);
+
+        lottery_chain.execute_operation(LotteryAppOperation::SendWinnerPrize {
+            recipient: alice,
+            amount: Amount::from_tokens(7),
+            source_chain_id: Some(alice_chain.to_string()),
+            round_id: 3,
+            ticket_number: 42,
+        }).now_or_never().unwrap();
+        let (owner, round_id, ticket_number, amount) = {
+            let messages = lottery_chain.runtime.created_send_message_requests();
+            assert_eq!(messages.len(), 1);
+            assert_eq!(messages[0].destination, alice_chain);
+            let LotteryAppMessage::PrizeWon { owner, round_id, ticket_number, amount } = messages[0].message else {
+                panic!("Expected a PrizeWon message");
+            };
+            (owner, round_id, ticket_number, amount)
+        };
+        assert_eq!(amount, Amount::from_tokens(7));
+        assert!(lottery_chain.state.prizes_won(alice).now_or_never().unwrap().unwrap().is_empty());
+
+        // The notice is delivered to the lottery app on the winner's chain
+        winner_chain.execute_message(LotteryAppMessage::PrizeWon { owner, round_id, ticket_number, amount })
+            .now_or_never()
+            .unwrap();
+        let prizes = winner_chain.state.prizes_won(alice).now_or_never().unwrap().unwrap();
+        assert_eq!(prizes, vec![PrizeNotice { owner: alice, round_id: 3, ticket_number: 42, amount: Amount::from_tokens(7) }]);
+
+        // A winner on the lottery chain gets the same record without a message
+        lottery_chain.execute_operation(LotteryAppOperation::SendWinnerPrize {
+            recipient: bob,
+            amount: Amount::from_tokens(2),
+            source_chain_id: None,
+            round_id: 3,
+            ticket_number: 8,
+        }).now_or_never().unwrap();
+        assert_eq!(lottery_chain.runtime.created_send_message_requests().len(), 1);
+        let prizes = lottery_chain.state.prizes_won(bob).now_or_never().unwrap().unwrap();
+        assert_eq!(prizes, vec![PrizeNotice { owner: bob, round_id: 3, ticket_number: 8, amount: Amount::from_tokens(2) }]);
+    }
+}
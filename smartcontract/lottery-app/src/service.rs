@@ -12,7 +12,7 @@ use linera_sdk::{
     views::View,
     Service, ServiceRuntime,
 };
-use lottery_abi::{LotteryAppAbi, LotteryAppParameters, LotteryAppOperation};
+use lottery_abi::{ChainMetrics, LotteryAppAbi, LotteryAppConfig, LotteryAppParameters, LotteryAppOperation, MetricEntry, PrizeNotice, TransferNotice};
 use self::state::LotteryAppState;
 
 /// Input type for fungible account
@@ -50,7 +50,6 @@ impl Service for LotteryAppService {
         let schema = Schema::build(
             QueryRoot {
                 state: self.state.clone(),
-                runtime: self.runtime.clone(),
             },
             MutationRoot {
                 runtime: self.runtime.clone(),
@@ -64,7 +63,6 @@ impl Service for LotteryAppService {
 
 struct QueryRoot {
     state: Arc<LotteryAppState>,
-    runtime: Arc<ServiceRuntime<LotteryAppService>>,
 }
 
 #[Object]
@@ -74,16 +72,57 @@ impl QueryRoot {
         *self.state.initialized.get()
     }
     
-    /// Get the configured Native app ID
-    async fn native_app_id(&self) -> String {
-        let params = self.runtime.application_parameters();
-        format!("{}", params.native_app_id)
+    /// Get the configured Native app ID, null until set
+    async fn native_app_id(&self) -> Option<String> {
+        self.state.config().native_app_id
+    }
+    
+    /// Get the configured Lottery Rounds app ID, null until set
+    async fn lottery_rounds_app_id(&self) -> Option<String> {
+        self.state.config().lottery_rounds_app_id
+    }
+    
+    /// Whether both peer app ids are set, so every operation can run
+    async fn configuration_complete(&self) -> bool {
+        self.state.configuration_complete()
+    }
+    
+    /// Get the app's configuration
+    async fn config(&self) -> LotteryAppConfig {
+        self.state.config()
+    }
+    
+    /// Get operation/message counters sorted by name
+    async fn metrics(&self) -> Vec<MetricEntry> {
+        self.state.get_metrics().await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, count)| MetricEntry { name, count })
+            .collect()
+    }
+    
+    /// Ticket transfers with a memo received by `owner`, newest first
+    async fn incoming_transfers(&self, owner: AccountOwner, limit: usize) -> Vec<TransferNotice> {
+        self.state.incoming_transfers(owner, limit).await
+            .unwrap_or_default()
     }
     
-    /// Get the configured Lottery Rounds app ID
-    async fn lottery_rounds_app_id(&self) -> String {
-        let params = self.runtime.application_parameters();
-        format!("{}", params.lottery_rounds_app_id)
+    /// Lottery prizes won by `owner`, newest first
+    async fn my_prizes(&self, owner: AccountOwner) -> Vec<PrizeNotice> {
+        self.state.prizes_won(owner).await
+            .unwrap_or_default()
+    }
+    
+    /// Cross-chain ticket purchase traffic of every source chain
+    async fn chain_metrics(&self) -> Vec<ChainMetrics> {
+        self.state.get_chain_metrics().await
+            .unwrap_or_default()
+    }
+    
+    /// The `n` source chains with the highest purchase volume, highest first
+    async fn top_chains(&self, n: u64) -> Vec<ChainMetrics> {
+        self.state.top_chains(n).await
+            .unwrap_or_default()
     }
     
     /// Get version
@@ -106,6 +145,7 @@ impl MutationRoot {
         amount: String,
         target_account: AccountInput,
         purchase_tickets: bool,
+        memo: Option<String>,
     ) -> String {
         let fungible_account = linera_sdk::abis::fungible::Account {
             chain_id: target_account.chain_id,
@@ -117,6 +157,7 @@ impl MutationRoot {
             amount: amount.parse::<Amount>().unwrap_or_default(),
             target_account: fungible_account,
             purchase_tickets,
+            memo,
         });
         
         if purchase_tickets {
@@ -173,4 +214,24 @@ impl MutationRoot {
         
         "SendPrize operation scheduled".to_string()
     }
+    
+    /// Zero all operation/message counters
+    async fn reset_metrics(&self) -> String {
+        self.runtime.schedule_operation(&LotteryAppOperation::ResetMetrics);
+        "ResetMetrics operation scheduled".to_string()
+    }
+    
+    /// Set the Native app ID (creator chain only)
+    async fn set_native_app_id(&self, native_app_id: String) -> String {
+        self.runtime.schedule_operation(&LotteryAppOperation::SetNativeAppId { native_app_id: native_app_id.clone() });
+        format!("SetNativeAppId operation scheduled with ID: {}", native_app_id)
+    }
+    
+    /// Set the Lottery Rounds app ID (creator chain only)
+    async fn set_lottery_rounds_app_id(&self, lottery_rounds_app_id: String) -> String {
+        self.runtime.schedule_operation(&LotteryAppOperation::SetLotteryRoundsAppId {
+            lottery_rounds_app_id: lottery_rounds_app_id.clone(),
+        });
+        format!("SetLotteryRoundsAppId operation scheduled with ID: {}", lottery_rounds_app_id)
+    }
 }
@@ -1,7 +1,10 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use linera_sdk::views::{linera_views, RegisterView, RootView, ViewStorageContext};
+use linera_sdk::linera_base_types::{AccountOwner, Amount, ApplicationId, ChainId};
+use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, View, ViewError, ViewStorageContext};
+use lottery_abi::{ChainMetrics, LotteryAppConfig, LotteryRoundsAbi, PrizeNotice, TransferNotice, NOT_CONFIGURED};
+use native::NativeAbi;
 
 /// The application state for Lottery App (minimal state, mostly a wrapper).
 #[derive(RootView)]
@@ -9,4 +12,188 @@ use linera_sdk::views::{linera_views, RegisterView, RootView, ViewStorageContext
 pub struct LotteryAppState {
     /// Placeholder - lottery app is mostly stateless, delegates to native and lottery-rounds
     pub initialized: RegisterView<bool>,
+    /// Native app that moves the tokens, from the parameters or SetNativeAppId
+    pub native_app_id: RegisterView<Option<ApplicationId<NativeAbi>>>,
+    /// Lottery Rounds app that registers tickets, from the parameters or SetLotteryRoundsAppId
+    pub lottery_rounds_app_id: RegisterView<Option<ApplicationId<LotteryRoundsAbi>>>,
+    /// Operation/message counters keyed by metric name
+    pub metrics: MapView<String, u64>,
+    /// Received ticket transfers that carried a memo, keyed by (recipient, arrival sequence)
+    pub transfer_inbox: MapView<(AccountOwner, u64), TransferNotice>,
+    /// Sequence number of the next inbox entry
+    pub next_transfer_seq: RegisterView<u64>,
+    /// Cross-chain ticket purchase traffic keyed by source chain
+    pub chain_metrics: MapView<ChainId, ChainMetrics>,
+    /// Source owners already counted in their chain's unique_buyers
+    pub seen_buyers: MapView<(ChainId, AccountOwner), bool>,
+    /// Prizes won by owners of this chain, keyed by (owner, arrival sequence)
+    pub prize_inbox: MapView<(AccountOwner, u64), PrizeNotice>,
+    /// Sequence number of the next prize inbox entry
+    pub next_prize_seq: RegisterView<u64>,
+}
+
+#[allow(dead_code)]
+impl LotteryAppState {
+    /// The Native app id, or a NOT_CONFIGURED error if it was never set
+    pub fn native_app(&self) -> Result<ApplicationId<NativeAbi>, String> {
+        self.native_app_id.get()
+            .ok_or_else(|| format!("{}: Native app ID not set - run SetNativeAppId first", NOT_CONFIGURED))
+    }
+    
+    /// The Lottery Rounds app id, or a NOT_CONFIGURED error if it was never set
+    pub fn lottery_rounds_app(&self) -> Result<ApplicationId<LotteryRoundsAbi>, String> {
+        self.lottery_rounds_app_id.get()
+            .ok_or_else(|| format!("{}: Lottery Rounds app ID not set - run SetLotteryRoundsAppId first", NOT_CONFIGURED))
+    }
+    
+    /// Whether both peer app ids are set
+    pub fn configuration_complete(&self) -> bool {
+        self.native_app_id.get().is_some() && self.lottery_rounds_app_id.get().is_some()
+    }
+    
+    /// The peer app ids as strings, None where unset
+    pub fn config(&self) -> LotteryAppConfig {
+        LotteryAppConfig {
+            native_app_id: self.native_app_id.get().map(|app_id| app_id.forget_abi().to_string()),
+            lottery_rounds_app_id: self.lottery_rounds_app_id.get().map(|app_id| app_id.forget_abi().to_string()),
+        }
+    }
+    
+    /// Increment a metrics counter (single read + insert)
+    pub async fn increment_metric(&mut self, name: &str) -> Result<(), String> {
+        let count = self.metrics.get(name).await
+            .map_err(|e: ViewError| format!("Failed to get metric: {:?}", e))?
+            .unwrap_or(0);
+        self.metrics.insert(name, count + 1)
+            .map_err(|e: ViewError| format!("Failed to update metric: {:?}", e))
+    }
+    
+    /// Get all metrics counters sorted by name
+    pub async fn get_metrics(&self) -> Result<Vec<(String, u64)>, String> {
+        let names = self.metrics.indices().await
+            .map_err(|e: ViewError| format!("Failed to get metric indices: {:?}", e))?;
+        
+        let mut metrics = Vec::with_capacity(names.len());
+        for name in names {
+            if let Some(count) = self.metrics.get(&name).await
+                .map_err(|e: ViewError| format!("Failed to get metric: {:?}", e))? {
+                metrics.push((name, count));
+            }
+        }
+        metrics.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(metrics)
+    }
+    
+    /// Zero all metrics counters
+    pub fn reset_metrics(&mut self) {
+        self.metrics.clear();
+    }
+    
+    /// Store a received transfer notice in the recipient's inbox
+    pub fn record_transfer(&mut self, notice: TransferNotice) -> Result<(), String> {
+        let seq = *self.next_transfer_seq.get();
+        self.transfer_inbox.insert(&(notice.recipient, seq), notice)
+            .map_err(|e: ViewError| format!("Failed to record transfer: {:?}", e))?;
+        self.next_transfer_seq.set(seq + 1);
+        Ok(())
+    }
+    
+    /// Count a ticket purchase of `amount` by `buyer` from `chain_id` at `timestamp`
+    pub async fn record_chain_purchase(&mut self, chain_id: ChainId, buyer: AccountOwner, amount: Amount, timestamp: u64) -> Result<(), String> {
+        let mut metrics = self.chain_metrics.get(&chain_id).await
+            .map_err(|e: ViewError| format!("Failed to get chain metrics: {:?}", e))?
+            .unwrap_or(ChainMetrics {
+                chain_id,
+                total_purchases: 0,
+                total_amount: Amount::ZERO,
+                unique_buyers: 0,
+                last_activity: 0,
+            });
+        let seen = self.seen_buyers.contains_key(&(chain_id, buyer)).await
+            .map_err(|e: ViewError| format!("Failed to get seen buyers: {:?}", e))?;
+        if !seen {
+            self.seen_buyers.insert(&(chain_id, buyer), true)
+                .map_err(|e: ViewError| format!("Failed to record buyer: {:?}", e))?;
+            metrics.unique_buyers += 1;
+        }
+        metrics.total_purchases += 1;
+        metrics.total_amount = metrics.total_amount.saturating_add(amount);
+        metrics.last_activity = timestamp;
+        self.chain_metrics.insert(&chain_id, metrics)
+            .map_err(|e: ViewError| format!("Failed to update chain metrics: {:?}", e))
+    }
+    
+    /// Purchase metrics of every source chain
+    pub async fn get_chain_metrics(&self) -> Result<Vec<ChainMetrics>, String> {
+        let chains = self.chain_metrics.indices().await
+            .map_err(|e: ViewError| format!("Failed to get chain metrics indices: {:?}", e))?;
+        
+        let mut metrics = Vec::with_capacity(chains.len());
+        for chain_id in chains {
+            if let Some(entry) = self.chain_metrics.get(&chain_id).await
+                .map_err(|e: ViewError| format!("Failed to get chain metrics: {:?}", e))? {
+                metrics.push(entry);
+            }
+        }
+        Ok(metrics)
+    }
+    
+    /// The `n` source chains with the highest total_amount, highest first
+    pub async fn top_chains(&self, n: u64) -> Result<Vec<ChainMetrics>, String> {
+        let mut metrics = self.get_chain_metrics().await?;
+        metrics.sort_by(|a, b| b.total_amount.cmp(&a.total_amount).then(a.chain_id.cmp(&b.chain_id)));
+        metrics.truncate(usize::try_from(n).unwrap_or(usize::MAX));
+        Ok(metrics)
+    }
+    
+    /// Up to `limit` of an owner's received transfers, newest first
+    pub async fn incoming_transfers(&self, owner: AccountOwner, limit: usize) -> Result<Vec<TransferNotice>, String> {
+        let keys = self.transfer_inbox.indices().await
+            .map_err(|e: ViewError| format!("Failed to get transfer inbox: {:?}", e))?;
+        
+        let mut seqs: Vec<u64> = keys.into_iter()
+            .filter(|(recipient, _)| *recipient == owner)
+            .map(|(_, seq)| seq)
+            .collect();
+        seqs.sort_unstable_by(|a, b| b.cmp(a));
+        
+        let mut notices = Vec::new();
+        for seq in seqs.into_iter().take(limit) {
+            if let Some(notice) = self.transfer_inbox.get(&(owner, seq)).await
+                .map_err(|e: ViewError| format!("Failed to get transfer: {:?}", e))? {
+                notices.push(notice);
+            }
+        }
+        Ok(notices)
+    }
+    
+    /// Store a prize notice in the winner's inbox
+    pub fn record_prize(&mut self, notice: PrizeNotice) -> Result<(), String> {
+        let seq = *self.next_prize_seq.get();
+        self.prize_inbox.insert(&(notice.owner, seq), notice)
+            .map_err(|e: ViewError| format!("Failed to record prize: {:?}", e))?;
+        self.next_prize_seq.set(seq + 1);
+        Ok(())
+    }
+    
+    /// Every prize an owner was notified of, newest first
+    pub async fn prizes_won(&self, owner: AccountOwner) -> Result<Vec<PrizeNotice>, String> {
+        let keys = self.prize_inbox.indices().await
+            .map_err(|e: ViewError| format!("Failed to get prize inbox: {:?}", e))?;
+        
+        let mut seqs: Vec<u64> = keys.into_iter()
+            .filter(|(winner, _)| *winner == owner)
+            .map(|(_, seq)| seq)
+            .collect();
+        seqs.sort_unstable_by(|a, b| b.cmp(a));
+        
+        let mut notices = Vec::with_capacity(seqs.len());
+        for seq in seqs {
+            if let Some(notice) = self.prize_inbox.get(&(owner, seq)).await
+                .map_err(|e: ViewError| format!("Failed to get prize: {:?}", e))? {
+                notices.push(notice);
+            }
+        }
+        Ok(notices)
+    }
 }
@@ -6,69 +6,39 @@
 mod state;
 
 use linera_sdk::{
-    linera_base_types::{Amount, ApplicationId, WithContractAbi},
+    linera_base_types::{AccountOwner, Amount, ApplicationId, ChainId, WithContractAbi},
     views::{RootView, View},
     Contract, ContractRuntime,
 };
 use lottery_abi::{
     LotteryRoundsAbi, LotteryRoundsOperation, LotteryRoundsResponse, LotteryRoundsMessage as Message,
-    LotteryRound as LibLotteryRound, RoundStatus as LibRoundStatus, WinnerPool as LibWinnerPool,
+    LotteryRound as LibLotteryRound,
     TicketPurchase as LibTicketPurchase, TicketPurchaseInfo as LibTicketPurchaseInfo,
-    LotteryWinnerInfo as LibLotteryWinnerInfo,
-    LotteryAppAbi, LotteryAppOperation, LotteryAppResponse,
+    LotteryWinnerInfo as LibLotteryWinnerInfo, PoolWinners, RoundCloseMode, RoundExport, TicketCountPreview, UnclaimedPrizes,
+    LotteryAppAbi, LotteryAppOperation, LotteryAppResponse, game_results_stream, GameKind, GameResult, OperationLogEntry,
 };
-use self::state::{LotteryRoundsState, LotteryRound, RoundStatus, WinnerPool, TicketPurchase};
+use self::state::{pool_prize_percentage, LotteryRoundsState, LotteryRound, RoundStatus, WinnerPool, TicketPurchase};
 
 
 // Conversion functions between lib types and state types
-fn round_status_to_lib(status: RoundStatus) -> LibRoundStatus {
-    match status {
-        RoundStatus::Active => LibRoundStatus::Active,
-        RoundStatus::Closed => LibRoundStatus::Closed,
-        RoundStatus::Complete => LibRoundStatus::Complete,
-    }
+fn lottery_round_option_to_lib(round: Option<LotteryRound>, now: u64) -> Option<LibLotteryRound> {
+    round.map(|round| round.to_lib(now))
 }
 
-fn winner_pool_to_lib(pool: WinnerPool) -> LibWinnerPool {
-    match pool {
-        WinnerPool::Pool1 => LibWinnerPool::Pool1,
-        WinnerPool::Pool2 => LibWinnerPool::Pool2,
-        WinnerPool::Pool3 => LibWinnerPool::Pool3,
-        WinnerPool::Pool4 => LibWinnerPool::Pool4,
-        WinnerPool::Complete => LibWinnerPool::Complete,
-    }
+fn lottery_rounds_to_lib(rounds: Vec<LotteryRound>, now: u64) -> Vec<LibLotteryRound> {
+    rounds.iter().map(|round| round.to_lib(now)).collect()
 }
 
-fn lottery_round_to_lib(round: LotteryRound) -> LibLotteryRound {
-    LibLotteryRound {
-        id: round.id,
-        created_at: round.created_at,
-        closed_at: round.closed_at,
-        status: round_status_to_lib(round.status),
-        ticket_price: round.ticket_price,
-        total_tickets_sold: round.total_tickets_sold,
-        next_ticket_number: round.next_ticket_number,
-        prize_pool: round.prize_pool,
-        current_winner_pool: winner_pool_to_lib(round.current_winner_pool),
-        pool1_count: round.pool1_count,
-        pool2_count: round.pool2_count,
-        pool3_count: round.pool3_count,
-        pool4_count: round.pool4_count,
-        pool1_winners_drawn: round.pool1_winners_drawn,
-        pool2_winners_drawn: round.pool2_winners_drawn,
-        pool3_winners_drawn: round.pool3_winners_drawn,
-        pool4_winners_drawn: round.pool4_winners_drawn,
+fn winner_to_lib((ticket_number, owner, prize_amount, claimed, source_chain_id): (u64, AccountOwner, Amount, bool, Option<String>)) -> LibLotteryWinnerInfo {
+    LibLotteryWinnerInfo {
+        ticket_number,
+        owner,
+        prize_amount,
+        claimed,
+        source_chain_id,
     }
 }
 
-fn lottery_round_option_to_lib(round: Option<LotteryRound>) -> Option<LibLotteryRound> {
-    round.map(lottery_round_to_lib)
-}
-
-fn lottery_rounds_to_lib(rounds: Vec<LotteryRound>) -> Vec<LibLotteryRound> {
-    rounds.into_iter().map(lottery_round_to_lib).collect()
-}
-
 fn ticket_purchase_to_lib(purchase: TicketPurchase) -> LibTicketPurchase {
     LibTicketPurchase {
         owner: purchase.owner,
@@ -80,6 +50,113 @@ fn ticket_purchase_to_lib(purchase: TicketPurchase) -> LibTicketPurchase {
     }
 }
 
+fn unclaimed_prizes_to_lib((round_id, unclaimed_winner_count, total_unclaimed_amount): (u64, u64, Amount)) -> UnclaimedPrizes {
+    UnclaimedPrizes { round_id, unclaimed_winner_count, total_unclaimed_amount }
+}
+
+/// Whether a prize can be sent to `chain_id` (None means this chain)
+fn is_deliverable(chain_id: Option<&str>) -> bool {
+    chain_id.map_or(true, |chain_id| chain_id.parse::<ChainId>().is_ok())
+}
+
+/// Offset between the seeds of consecutive draws in one block (a prime, so seeds
+/// taken modulo the ticket count do not line up)
+const DRAW_SEED_STRIDE: u64 = 1_000_003;
+
+/// Metrics counter name for an operation
+fn operation_metric(operation: &LotteryRoundsOperation) -> &'static str {
+    match operation {
+        LotteryRoundsOperation::CreateRound { .. } => "operation.CreateRound",
+        LotteryRoundsOperation::CloseRound => "operation.CloseRound",
+        LotteryRoundsOperation::GenerateWinner { .. } => "operation.GenerateWinner",
+        LotteryRoundsOperation::PurchaseTickets { .. } => "operation.PurchaseTickets",
+        LotteryRoundsOperation::TransferTickets { .. } => "operation.TransferTickets",
+        LotteryRoundsOperation::SetLotteryAppId { .. } => "operation.SetLotteryAppId",
+        LotteryRoundsOperation::ImportConfig { .. } => "operation.ImportConfig",
+        LotteryRoundsOperation::SetDuplicateResolution { .. } => "operation.SetDuplicateResolution",
+        LotteryRoundsOperation::SetTargetPrizePool { .. } => "operation.SetTargetPrizePool",
+        LotteryRoundsOperation::SetMinPrizePoolToClose { .. } => "operation.SetMinPrizePoolToClose",
+        LotteryRoundsOperation::SetTicketPriceFloor { .. } => "operation.SetTicketPriceFloor",
+        LotteryRoundsOperation::SetTicketPriceCeiling { .. } => "operation.SetTicketPriceCeiling",
+        LotteryRoundsOperation::SetMaxWinnerPctBps { .. } => "operation.SetMaxWinnerPctBps",
+        LotteryRoundsOperation::SetOneWinPerAddress { .. } => "operation.SetOneWinPerAddress",
+        LotteryRoundsOperation::AddFeeTier { .. } => "operation.AddFeeTier",
+        LotteryRoundsOperation::RemoveFeeTier { .. } => "operation.RemoveFeeTier",
+        LotteryRoundsOperation::SetDefaultFeeBps { .. } => "operation.SetDefaultFeeBps",
+        LotteryRoundsOperation::EnableAllowlist => "operation.EnableAllowlist",
+        LotteryRoundsOperation::DisableAllowlist => "operation.DisableAllowlist",
+        LotteryRoundsOperation::AddToAllowlist { .. } => "operation.AddToAllowlist",
+        LotteryRoundsOperation::RemoveFromAllowlist { .. } => "operation.RemoveFromAllowlist",
+        LotteryRoundsOperation::GetActiveRound => "operation.GetActiveRound",
+        LotteryRoundsOperation::GetActiveRoundId => "operation.GetActiveRoundId",
+        LotteryRoundsOperation::GetCurrentRoundStatus => "operation.GetCurrentRoundStatus",
+        LotteryRoundsOperation::GetCurrentWinnerPool => "operation.GetCurrentWinnerPool",
+        LotteryRoundsOperation::GetRound { .. } => "operation.GetRound",
+        LotteryRoundsOperation::GetAllRounds => "operation.GetAllRounds",
+        LotteryRoundsOperation::GetRoundsByStatus { .. } => "operation.GetRoundsByStatus",
+        LotteryRoundsOperation::GetRoundTicketPurchases { .. } => "operation.GetRoundTicketPurchases",
+        LotteryRoundsOperation::GetUserTickets { .. } => "operation.GetUserTickets",
+        LotteryRoundsOperation::GetOwnerTicketNumbers { .. } => "operation.GetOwnerTicketNumbers",
+        LotteryRoundsOperation::GetRoundWinners { .. } => "operation.GetRoundWinners",
+        LotteryRoundsOperation::GetWinnersByTicketRange { .. } => "operation.GetWinnersByTicketRange",
+        LotteryRoundsOperation::GetRoundWinnersByPool { .. } => "operation.GetRoundWinnersByPool",
+        LotteryRoundsOperation::GetAllowlistStatus { .. } => "operation.GetAllowlistStatus",
+        LotteryRoundsOperation::VerifyPoolIntegrity { .. } => "operation.VerifyPoolIntegrity",
+        LotteryRoundsOperation::GetDrawProgress { .. } => "operation.GetDrawProgress",
+        LotteryRoundsOperation::ExportRoundCSV { .. } => "operation.ExportRoundCSV",
+        LotteryRoundsOperation::ExportRound { .. } => "operation.ExportRound",
+        LotteryRoundsOperation::GetUserParticipation { .. } => "operation.GetUserParticipation",
+        LotteryRoundsOperation::GetPlayerTotals { .. } => "operation.GetPlayerTotals",
+        LotteryRoundsOperation::QuotePurchase { .. } => "operation.QuotePurchase",
+        LotteryRoundsOperation::PreviewTicketCount { .. } => "operation.PreviewTicketCount",
+        LotteryRoundsOperation::GetPrizeSchedule => "operation.GetPrizeSchedule",
+        LotteryRoundsOperation::GetRoundsWithUnclaimedPrizes => "operation.GetRoundsWithUnclaimedPrizes",
+        LotteryRoundsOperation::GetRoundPayoutSummary { .. } => "operation.GetRoundPayoutSummary",
+        LotteryRoundsOperation::GetTotalUnclaimedPrizesAmount => "operation.GetTotalUnclaimedPrizesAmount",
+        LotteryRoundsOperation::GetConfig => "operation.GetConfig",
+        LotteryRoundsOperation::ProveMembershipOfWinner { .. } => "operation.ProveMembershipOfWinner",
+        LotteryRoundsOperation::SetMySpendLimit { .. } => "operation.SetMySpendLimit",
+        LotteryRoundsOperation::SetNotificationPreferences { .. } => "operation.SetNotificationPreferences",
+        LotteryRoundsOperation::AddSyncTarget { .. } => "operation.AddSyncTarget",
+        LotteryRoundsOperation::RemoveSyncTarget { .. } => "operation.RemoveSyncTarget",
+        LotteryRoundsOperation::ClaimEscrowedPrize { .. } => "operation.ClaimEscrowedPrize",
+        LotteryRoundsOperation::ExpireEscrow { .. } => "operation.ExpireEscrow",
+        LotteryRoundsOperation::WithdrawFees { .. } => "operation.WithdrawFees",
+        LotteryRoundsOperation::ResetMetrics => "operation.ResetMetrics",
+        LotteryRoundsOperation::GetOperationLog { .. } => "operation.GetOperationLog",
+        LotteryRoundsOperation::TransferAdminRole { .. } => "operation.TransferAdminRole",
+    }
+}
+
+/// Whether an operation changes app configuration and needs the admin as signer
+fn requires_admin(operation: &LotteryRoundsOperation) -> bool {
+    matches!(
+        operation,
+        LotteryRoundsOperation::SetLotteryAppId { .. }
+            | LotteryRoundsOperation::ImportConfig { .. }
+            | LotteryRoundsOperation::SetDuplicateResolution { .. }
+            | LotteryRoundsOperation::SetTargetPrizePool { .. }
+            | LotteryRoundsOperation::SetMinPrizePoolToClose { .. }
+            | LotteryRoundsOperation::SetTicketPriceFloor { .. }
+            | LotteryRoundsOperation::SetTicketPriceCeiling { .. }
+            | LotteryRoundsOperation::SetMaxWinnerPctBps { .. }
+            | LotteryRoundsOperation::SetOneWinPerAddress { .. }
+            | LotteryRoundsOperation::AddFeeTier { .. }
+            | LotteryRoundsOperation::RemoveFeeTier { .. }
+            | LotteryRoundsOperation::SetDefaultFeeBps { .. }
+            | LotteryRoundsOperation::EnableAllowlist
+            | LotteryRoundsOperation::DisableAllowlist
+            | LotteryRoundsOperation::AddToAllowlist { .. }
+            | LotteryRoundsOperation::RemoveFromAllowlist { .. }
+            | LotteryRoundsOperation::WithdrawFees { .. }
+            | LotteryRoundsOperation::ClaimEscrowedPrize { .. }
+            | LotteryRoundsOperation::AddSyncTarget { .. }
+            | LotteryRoundsOperation::RemoveSyncTarget { .. }
+            | LotteryRoundsOperation::ResetMetrics
+            | LotteryRoundsOperation::TransferAdminRole { .. }
+    )
+}
+
 pub struct LotteryRoundsContract {
     state: LotteryRoundsState,
     runtime: ContractRuntime<Self>,
@@ -95,7 +172,7 @@ impl Contract for LotteryRoundsContract {
     type Message = Message;
     type Parameters = lottery_abi::LotteryRoundsParameters;
     type InstantiationArgument = ();
-    type EventValue = ();
+    type EventValue = GameResult;
 
     async fn load(runtime: ContractRuntime<Self>) -> Self {
         let state = LotteryRoundsState::load(runtime.root_view_storage_context())
@@ -105,11 +182,20 @@ impl Contract for LotteryRoundsContract {
     }
 
     async fn instantiate(&mut self, _arg: Self::InstantiationArgument) {
-        // Validate params access
-        let _ = self.runtime.application_parameters();
+        let params = self.runtime.application_parameters();
+        self.state.admin.set(Some(params.admin));
     }
 
     async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response {
+        self.state.increment_metric(operation_metric(&operation)).await
+            .expect("Failed to update metrics");
+        
+        if requires_admin(&operation) {
+            if let Err(e) = self.state.check_admin(self.runtime.authenticated_signer()) {
+                panic!("Failed to authorize {}: {}", operation_metric(&operation), e);
+            }
+        }
+        
         match operation {
             LotteryRoundsOperation::SetLotteryAppId { lottery_app_id } => {
                 match lottery_app_id.parse::<ApplicationId>() {
@@ -121,12 +207,140 @@ impl Contract for LotteryRoundsContract {
                 }
                 LotteryRoundsResponse::Ok
             }
+            
+            LotteryRoundsOperation::ImportConfig { config } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("ImportConfig can only be executed on the application creator chain");
+                }
+                match self.state.import_config(config) {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to import config: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::GetConfig => {
+                match self.state.get_config().await {
+                    Ok(config) => LotteryRoundsResponse::Config(config),
+                    Err(e) => panic!("Failed to get config: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::ResetMetrics => {
+                self.state.reset_metrics();
+                LotteryRoundsResponse::Ok
+            }
+            
+            LotteryRoundsOperation::GetOperationLog { offset, limit } => {
+                match self.state.get_operation_log(offset, limit).await {
+                    Ok(entries) => LotteryRoundsResponse::OperationLog(entries),
+                    Err(e) => panic!("Failed to get operation log: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::TransferAdminRole { new_admin } => {
+                self.state.admin.set(Some(new_admin));
+                LotteryRoundsResponse::Ok
+            }
+            
+            LotteryRoundsOperation::SetDuplicateResolution { resolution } => {
+                self.state.duplicate_ticket_resolution.set(resolution);
+                LotteryRoundsResponse::Ok
+            }
+            
+            LotteryRoundsOperation::SetTargetPrizePool { amount } => {
+                match self.state.set_target_prize_pool(amount) {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set target prize pool: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::SetMinPrizePoolToClose { amount } => {
+                match self.state.set_min_prize_pool_to_close(amount) {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set minimum prize pool to close: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::SetTicketPriceFloor { floor } => {
+                match self.state.set_ticket_price_floor(floor) {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set ticket price floor: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::SetTicketPriceCeiling { ceiling } => {
+                match self.state.set_ticket_price_ceiling(ceiling) {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set ticket price ceiling: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::SetMaxWinnerPctBps { bps } => {
+                match self.state.set_max_winner_pct_bps(bps) {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set winner share cap: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::SetOneWinPerAddress { enabled } => {
+                self.state.one_win_per_address.set(enabled);
+                LotteryRoundsResponse::Ok
+            }
+            
+            LotteryRoundsOperation::AddFeeTier { min_pool_tokens, fee_bps } => {
+                match self.state.add_fee_tier(min_pool_tokens, fee_bps) {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to add fee tier: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::RemoveFeeTier { min_pool_tokens } => {
+                match self.state.remove_fee_tier(min_pool_tokens).await {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to remove fee tier: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::SetDefaultFeeBps { fee_bps } => {
+                match self.state.set_default_fee_bps(fee_bps) {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set default fee: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::EnableAllowlist => {
+                self.state.set_allowlist_enabled(true);
+                LotteryRoundsResponse::Ok
+            }
+            
+            LotteryRoundsOperation::DisableAllowlist => {
+                self.state.set_allowlist_enabled(false);
+                LotteryRoundsResponse::Ok
+            }
+            
+            LotteryRoundsOperation::AddToAllowlist { addresses } => {
+                match self.state.add_to_allowlist(addresses) {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to add to allowlist: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::RemoveFromAllowlist { address } => {
+                match self.state.remove_from_allowlist(address) {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to remove from allowlist: {}", e),
+                }
+            }
 
 
-            LotteryRoundsOperation::CreateRound { ticket_price } => {
+            LotteryRoundsOperation::CreateRound { ticket_price, price_schedule } => {
                 let timestamp = self.runtime.system_time().micros();
-                match self.state.create_lottery_round(ticket_price, timestamp).await {
-                    Ok(round_id) => LotteryRoundsResponse::RoundId(round_id),
+                match self.state.create_lottery_round(ticket_price, price_schedule, timestamp).await {
+                    Ok(round_id) => {
+                        let actor = self.signer_or_chain();
+                        self.log_operation("create_round", actor, format!("round={} ticket_price={}", round_id, ticket_price));
+                        LotteryRoundsResponse::RoundId(round_id)
+                    }
                     Err(e) => panic!("Failed to create lottery round: {}", e),
                 }
             }
@@ -134,67 +348,41 @@ impl Contract for LotteryRoundsContract {
             LotteryRoundsOperation::CloseRound => {
                 let timestamp = self.runtime.system_time().micros();
                 match self.state.close_lottery_round(timestamp).await {
-                    Ok(round_id) => LotteryRoundsResponse::RoundId(round_id),
+                    Ok(round_id) => {
+                        self.round_closed(round_id).await;
+                        LotteryRoundsResponse::RoundId(round_id)
+                    }
                     Err(e) => panic!("Failed to close lottery round: {}", e),
                 }
             }
             
-            LotteryRoundsOperation::GenerateWinner { round_id } => {
-                // Generate VRF value automatically from timestamp + block height
-                let timestamp = self.runtime.system_time().micros();
-                let block_height = self.runtime.block_height();
-                let vrf_value = timestamp.wrapping_add(block_height.into());
-                
-                eprintln!("GenerateWinner: round_id={}, vrf_value={} (timestamp={}, block={})", 
-                    round_id, vrf_value, timestamp, block_height);
-                
-                // Get default ticket price for new rounds
-                let default_ticket_price = self.state.get_current_ticket_price().await
-                    .unwrap_or(Amount::from_tokens(1));
-                
-                // Generate one winner using VRF
-                match self.state.generate_winner(vrf_value, round_id, timestamp, default_ticket_price).await {
-                    Ok((round_id, ticket_number, owner, prize_amount, new_round_created, source_chain_id)) => {
-                        // Get lottery app ID from state (set via SetLotteryAppId operation)
-                        let lottery_app_id = self.state.lottery_app_id.get()
-                            .expect("Lottery app ID not set - run SetLotteryAppId first");
-                        
-                        // Call lottery-app to send prize
-                        if prize_amount > Amount::ZERO {
-                            let _response: LotteryAppResponse = self.runtime.call_application(
-                                true, // authenticated
-                                lottery_app_id,
-                                &LotteryAppOperation::SendPrize {
-                                    recipient: owner.clone(),
-                                    amount: prize_amount,
-                                    source_chain_id: source_chain_id.clone(),
-                                },
-                            );
-                            
-                            // Mark prize as claimed
-                            if let Err(e) = self.state.mark_prize_claimed(round_id, ticket_number).await {
-                                eprintln!("Failed to mark prize as claimed: {}", e);
-                            }
-                        }
+            LotteryRoundsOperation::GenerateWinner { round_id } => self.draw_winner(round_id, 0).await,
 
-                        
-                        LotteryRoundsResponse::WinnerGenerated {
-                            round_id,
-                            ticket_number,
-                            owner,
-                            prize_amount,
-                            new_round_created,
-                            source_chain_id,
+            LotteryRoundsOperation::PurchaseTickets { owner, amount, ticket_price, source_chain_id } => {
+                let timestamp = self.runtime.system_time().micros();
+                let active_round = *self.state.active_round.get();
+                match self.state.purchase_tickets(owner, amount, ticket_price, source_chain_id, timestamp).await {
+                    Ok(purchase) => {
+                        self.log_operation("purchase", owner, format!(
+                            "round={} amount={} tickets={}",
+                            active_round.unwrap_or_default(), amount, purchase.total_tickets,
+                        ));
+                        // The purchase may have reached the target prize pool and closed the round
+                        if let (Some(round_id), None) = (active_round, *self.state.active_round.get()) {
+                            self.round_closed(round_id).await;
                         }
+                        LotteryRoundsResponse::TicketPurchase(ticket_purchase_to_lib(purchase))
                     }
-                    Err(e) => panic!("Failed to generate winner: {}", e),
+                    Err(e) => panic!("Failed to purchase tickets: {}", e),
                 }
             }
-
-            LotteryRoundsOperation::PurchaseTickets { owner, amount, ticket_price, source_chain_id } => {
-                match self.state.purchase_tickets(owner, amount, ticket_price, source_chain_id).await {
-                    Ok(purchase) => LotteryRoundsResponse::TicketPurchase(ticket_purchase_to_lib(purchase)),
-                    Err(e) => panic!("Failed to purchase tickets: {}", e),
+            
+            LotteryRoundsOperation::TransferTickets { round_id, to, ticket_numbers } => {
+                let owner = self.runtime.authenticated_signer()
+                    .expect("TransferTickets requires an authenticated signer");
+                match self.state.transfer_tickets(round_id, owner, to, &ticket_numbers).await {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to transfer tickets: {}", e),
                 }
             }
 
@@ -203,7 +391,7 @@ impl Contract for LotteryRoundsContract {
                 match self.state.get_active_round().await {
                     Ok(Some(round_id)) => {
                         match self.state.get_round(round_id).await {
-                            Ok(Some(round)) => LotteryRoundsResponse::LotteryRound(lottery_round_option_to_lib(Some(round))),
+                            Ok(Some(round)) => LotteryRoundsResponse::LotteryRound(lottery_round_option_to_lib(Some(round), self.runtime.system_time().micros())),
                             Ok(None) => LotteryRoundsResponse::LotteryRound(None),
                             Err(e) => panic!("Failed to get round: {}", e),
                         }
@@ -213,9 +401,38 @@ impl Contract for LotteryRoundsContract {
                 }
             }
             
+            LotteryRoundsOperation::GetActiveRoundId => LotteryRoundsResponse::ActiveRoundId(*self.state.active_round.get()),
+            
+            LotteryRoundsOperation::GetCurrentRoundStatus => {
+                let timestamp = self.runtime.system_time().micros();
+                match self.state.current_round().await {
+                    Ok(round) => LotteryRoundsResponse::CurrentStatus {
+                        round_id: round.as_ref().map(|round| round.id),
+                        status: round.as_ref().map(|round| round.status),
+                        tickets_sold: round.as_ref().map_or(0, |round| round.total_tickets_sold),
+                        time_since_last_change: round.as_ref().map_or(0, |round| {
+                            timestamp.saturating_sub(round.created_at.max(round.closed_at.unwrap_or(0)))
+                        }),
+                    },
+                    Err(e) => panic!("Failed to get current round: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::GetCurrentWinnerPool => {
+                match self.state.current_round().await {
+                    Ok(Some(round)) => LotteryRoundsResponse::CurrentPool {
+                        round_id: round.id,
+                        pool: round.current_winner_pool,
+                        remaining: round.remaining_in_current_pool(),
+                    },
+                    Ok(None) => panic!("Failed to get current winner pool: no round exists yet"),
+                    Err(e) => panic!("Failed to get current round: {}", e),
+                }
+            }
+            
             LotteryRoundsOperation::GetRound { id } => {
                 match self.state.get_round(id).await {
-                    Ok(Some(round)) => LotteryRoundsResponse::LotteryRound(lottery_round_option_to_lib(Some(round))),
+                    Ok(Some(round)) => LotteryRoundsResponse::LotteryRound(lottery_round_option_to_lib(Some(round), self.runtime.system_time().micros())),
                     Ok(None) => LotteryRoundsResponse::LotteryRound(None),
                     Err(e) => panic!("Failed to get round: {}", e),
                 }
@@ -223,11 +440,18 @@ impl Contract for LotteryRoundsContract {
             
             LotteryRoundsOperation::GetAllRounds => {
                 match self.state.get_all_rounds().await {
-                    Ok(rounds) => LotteryRoundsResponse::LotteryRounds(lottery_rounds_to_lib(rounds)),
+                    Ok(rounds) => LotteryRoundsResponse::LotteryRounds(lottery_rounds_to_lib(rounds, self.runtime.system_time().micros())),
                     Err(e) => panic!("Failed to get all rounds: {}", e),
                 }
             }
             
+            LotteryRoundsOperation::GetRoundsByStatus { status, limit } => {
+                match self.state.get_rounds_by_status(status, limit).await {
+                    Ok(rounds) => LotteryRoundsResponse::LotteryRounds(lottery_rounds_to_lib(rounds, self.runtime.system_time().micros())),
+                    Err(e) => panic!("Failed to get rounds by status: {}", e),
+                }
+            }
+            
             LotteryRoundsOperation::GetRoundTicketPurchases { round_id } => {
                 match self.state.get_round_ticket_purchases(round_id).await {
                     Ok(purchases) => {
@@ -255,32 +479,2294 @@ impl Contract for LotteryRoundsContract {
                 }
             }
             
+            LotteryRoundsOperation::GetOwnerTicketNumbers { round_id, owner, after, limit } => {
+                match self.state.get_owner_ticket_numbers(round_id, owner, after, limit).await {
+                    Ok(ticket_numbers) => LotteryRoundsResponse::TicketNumbers(ticket_numbers),
+                    Err(e) => panic!("Failed to get owner ticket numbers: {}", e),
+                }
+            }
+            
             LotteryRoundsOperation::GetRoundWinners { round_id } => {
                 match self.state.get_round_winners(round_id).await {
-                    Ok(winners) => {
-                        let winner_info: Vec<_> = winners.into_iter().map(|(ticket_number, owner, prize, claimed, source_chain_id)| {
-                            LibLotteryWinnerInfo {
-                                ticket_number,
-                                owner,
-                                prize_amount: prize,
-                                claimed,
-                                source_chain_id,
-                            }
-                        }).collect();
-                        LotteryRoundsResponse::LotteryWinners(winner_info)
-                    },
+                    Ok(winners) => LotteryRoundsResponse::LotteryWinners(winners.into_iter().map(winner_to_lib).collect()),
                     Err(e) => panic!("Failed to get round winners: {}", e),
                 }
             }
+            
+            LotteryRoundsOperation::GetWinnersByTicketRange { round_id, start_ticket, end_ticket } => {
+                match self.state.get_winners_by_ticket_range(round_id, start_ticket, end_ticket).await {
+                    Ok(winners) => LotteryRoundsResponse::LotteryWinners(winners.into_iter().map(winner_to_lib).collect()),
+                    Err(e) => panic!("Failed to get winners by ticket range: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::GetRoundWinnersByPool { round_id } => {
+                match self.state.get_round_winners_by_pool(round_id).await {
+                    Ok(groups) => LotteryRoundsResponse::WinnersByPool(groups.into_iter().map(|(pool, winners)| PoolWinners {
+                        pool,
+                        prize_percentage: pool_prize_percentage(pool) as u64,
+                        winners: winners.into_iter().map(winner_to_lib).collect(),
+                    }).collect()),
+                    Err(e) => panic!("Failed to get round winners by pool: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::GetAllowlistStatus { owner } => {
+                match self.state.is_allowlisted(&owner).await {
+                    Ok(allowlisted) => LotteryRoundsResponse::AllowlistStatus {
+                        enabled: *self.state.purchase_allowlist_enabled.get(),
+                        allowlisted,
+                    },
+                    Err(e) => panic!("Failed to get allowlist status: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::VerifyPoolIntegrity { round_id } => {
+                match self.state.verify_pool_integrity(round_id).await {
+                    Ok(integrity) => LotteryRoundsResponse::PoolIntegrity(integrity),
+                    Err(e) => panic!("Failed to verify pool integrity: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::GetDrawProgress { round_id } => {
+                match self.state.draw_progress(round_id).await {
+                    Ok(progress) => LotteryRoundsResponse::DrawProgress(progress),
+                    Err(e) => panic!("Failed to get draw progress: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::ExportRoundCSV { round_id } => {
+                match self.state.export_round_csv(round_id).await {
+                    Ok(csv_bytes) => LotteryRoundsResponse::RoundExport(RoundExport { round_id, csv_bytes }),
+                    Err(e) => panic!("Failed to export round: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::ExportRound { round_id } => {
+                match self.state.round_archive(round_id).await {
+                    Ok(archive) => LotteryRoundsResponse::Archive(
+                        linera_sdk::bcs::to_bytes(&archive).expect("Round archive is serializable"),
+                    ),
+                    Err(e) => panic!("Failed to archive round: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::GetUserParticipation { owner } => {
+                match self.state.get_user_participation(owner).await {
+                    Ok(participation) => LotteryRoundsResponse::UserParticipation(participation),
+                    Err(e) => panic!("Failed to get user participation: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::GetPlayerTotals { owner } => {
+                match self.state.get_player_totals(owner).await {
+                    Ok(totals) => LotteryRoundsResponse::PlayerTotals(totals),
+                    Err(e) => panic!("Failed to get player totals: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::QuotePurchase { amount } => {
+                let timestamp = self.runtime.system_time().micros();
+                match self.state.quote_purchase(amount, timestamp).await {
+                    Ok(quote) => LotteryRoundsResponse::PurchaseQuote(quote),
+                    Err(e) => panic!("Failed to quote purchase: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::PreviewTicketCount { amount } => {
+                let timestamp = self.runtime.system_time().micros();
+                match self.state.quote_purchase(amount, timestamp).await {
+                    Ok(quote) => LotteryRoundsResponse::TicketCountPreview(quote.map(|quote| TicketCountPreview {
+                        ticket_count: quote.ticket_count,
+                        remainder: quote.remainder,
+                    })),
+                    Err(e) => panic!("Failed to preview ticket count: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::GetPrizeSchedule => {
+                match self.state.get_prize_schedule().await {
+                    Ok(schedule) => LotteryRoundsResponse::PrizeSchedule(schedule),
+                    Err(e) => panic!("Failed to get prize schedule: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::GetRoundsWithUnclaimedPrizes => {
+                match self.state.get_rounds_with_unclaimed_prizes().await {
+                    Ok(rounds) => LotteryRoundsResponse::RoundsWithUnclaimedPrizes(
+                        rounds.into_iter().map(unclaimed_prizes_to_lib).collect(),
+                    ),
+                    Err(e) => panic!("Failed to get rounds with unclaimed prizes: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::GetRoundPayoutSummary { round_id } => {
+                match self.state.round_payout_summary(round_id).await {
+                    Ok(summary) => LotteryRoundsResponse::RoundPayoutSummary(summary),
+                    Err(e) => panic!("Failed to get round payout summary: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::GetTotalUnclaimedPrizesAmount => {
+                match self.state.get_total_unclaimed_prizes_amount().await {
+                    Ok(amount) => LotteryRoundsResponse::TotalUnclaimed(amount),
+                    Err(e) => panic!("Failed to get total unclaimed prizes: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::ProveMembershipOfWinner { round_id, ticket_number } => {
+                match self.state.prove_winner(round_id, ticket_number).await {
+                    Ok(proof) => LotteryRoundsResponse::MerkleProof(proof),
+                    Err(e) => panic!("Failed to prove winner: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::ClaimEscrowedPrize { round_id, ticket_number, owner } => {
+                // The admin signs, so the prize is paid from its account rather than the winner's
+                let now = self.runtime.system_time().micros();
+                let entry = match self.state.take_escrowed_prize(round_id, ticket_number, owner, now).await {
+                    Ok(entry) => entry,
+                    Err(e) => panic!("Failed to claim escrowed prize: {}", e),
+                };
+                let lottery_app_id = self.state.lottery_app_id.get()
+                    .expect("Lottery app ID not set - run SetLotteryAppId first");
+                
+                // An unreachable destination falls back to this chain
+                let source_chain_id = Some(entry.destination_chain)
+                    .filter(|chain_id| is_deliverable(Some(chain_id)));
+                // Low-ticket refunds are escrowed against a ticket that never won
+                let is_winner = self.state.winning_tickets.contains_key(&(round_id, ticket_number)).await
+                    .expect("Failed to check winning ticket");
+                let send = if is_winner {
+                    LotteryAppOperation::SendWinnerPrize {
+                        recipient: owner,
+                        amount: entry.amount,
+                        source_chain_id,
+                        round_id,
+                        ticket_number,
+                    }
+                } else {
+                    LotteryAppOperation::SendPrize {
+                        recipient: owner,
+                        amount: entry.amount,
+                        source_chain_id,
+                    }
+                };
+                let _response: LotteryAppResponse = self.runtime.call_application(true, lottery_app_id, &send);
+                
+                let remaining = self.state.get_escrowed_prizes(round_id, ticket_number).await
+                    .expect("Failed to get escrow");
+                if is_winner && remaining.is_empty() {
+                    if let Err(e) = self.state.mark_prize_claimed(round_id, ticket_number).await {
+                        eprintln!("Failed to mark prize as claimed: {}", e);
+                    }
+                }
+                LotteryRoundsResponse::EscrowAmount(entry.amount)
+            }
+            
+            LotteryRoundsOperation::ExpireEscrow { round_id, ticket_number } => {
+                let now = self.runtime.system_time().micros();
+                match self.state.expire_escrow(round_id, ticket_number, now).await {
+                    Ok(amount) => LotteryRoundsResponse::EscrowAmount(amount),
+                    Err(e) => panic!("Failed to expire escrow: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::WithdrawFees { recipient, destination_chain } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("WithdrawFees can only be executed on the application creator chain");
+                }
+                let amount = self.state.take_reclaimed_escrow();
+                if amount == Amount::ZERO {
+                    panic!("No reclaimed escrow to withdraw");
+                }
+                let lottery_app_id = self.state.lottery_app_id.get()
+                    .expect("Lottery app ID not set - run SetLotteryAppId first");
+                let _response: LotteryAppResponse = self.runtime.call_application(
+                    true,
+                    lottery_app_id,
+                    &LotteryAppOperation::SendPrize {
+                        recipient,
+                        amount,
+                        source_chain_id: destination_chain,
+                    },
+                );
+                LotteryRoundsResponse::EscrowAmount(amount)
+            }
+            
+            LotteryRoundsOperation::SetMySpendLimit { per_round } => {
+                // Only the owner can set their own limit
+                let owner = self.runtime.authenticated_signer()
+                    .expect("SetMySpendLimit requires an authenticated signer");
+                match self.state.set_spend_limit(owner, per_round) {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set spend limit: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::SetNotificationPreferences { prefs } => {
+                let owner = self.runtime.authenticated_signer()
+                    .expect("SetNotificationPreferences requires an authenticated signer");
+                match self.state.set_notification_preferences(owner, prefs).await {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set notification preferences: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::AddSyncTarget { chain_id } => {
+                match self.state.add_sync_target(chain_id).await {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to add sync target: {}", e),
+                }
+            }
+            
+            LotteryRoundsOperation::RemoveSyncTarget { chain_id } => {
+                match self.state.remove_sync_target(&chain_id).await {
+                    Ok(()) => LotteryRoundsResponse::Ok,
+                    Err(e) => panic!("Failed to remove sync target: {}", e),
+                }
+            }
         }
     }
 
-    async fn execute_message(&mut self, _message: Self::Message) {
+    async fn execute_message(&mut self, message: Self::Message) {
         // No messages expected for LotteryRounds app currently
         // All communication happens via cross-app calls (operations)
+        match message {
+            Message::Notify => {
+                self.state.increment_metric("message.Notify").await
+                    .expect("Failed to update metrics");
+            }
+            // Round notifications are only consumed by subscribers watching their chain
+            Message::RoundClosed { .. } => {
+                self.state.increment_metric("message.RoundClosed").await
+                    .expect("Failed to update metrics");
+            }
+            Message::WinnerDrawn { .. } => {
+                self.state.increment_metric("message.WinnerDrawn").await
+                    .expect("Failed to update metrics");
+            }
+            // Observer chains keep a read-only copy of each round the lottery chain closes
+            Message::RoundStateSync { round, .. } => {
+                self.state.increment_metric("message.RoundStateSync").await
+                    .expect("Failed to update metrics");
+                self.state.store_synced_round(round)
+                    .expect("Failed to store synced round");
+            }
+        }
     }
 
     async fn store(mut self) {
         self.state.save().await.expect("Failed to save state");
     }
 }
+
+impl LotteryRoundsContract {
+    /// Follow-up work once a round stops accepting tickets: notify subscribers,
+    /// replicate the round to observer chains and run the configured automatic draws
+    async fn round_closed(&mut self, round_id: u64) {
+        let actor = self.signer_or_chain();
+        self.log_operation("close_round", actor, format!("round={}", round_id));
+        self.notify_subscribers(true, Message::RoundClosed { round_id }).await;
+        let round = self.state.get_round(round_id).await
+            .expect("Failed to get round")
+            .expect("Closed round not found");
+        self.sync_round_state(&round).await;
+        if round.close_mode == RoundCloseMode::PartialRefundLowTickets {
+            self.log_next_round(actor).await;
+            self.pay_low_ticket_refunds(&round).await;
+        } else if let Some(draws) = self.runtime.application_parameters().draws_per_close {
+            self.draw_winners(round_id, draws).await;
+        }
+    }
+    
+    /// Send the refunds of a round closed with too few tickets, escrowing undeliverable ones
+    /// against the recipient's first ticket
+    async fn pay_low_ticket_refunds(&mut self, round: &LotteryRound) {
+        let refunds = self.state.low_ticket_refunds(round).await
+            .expect("Failed to compute low ticket refunds");
+        let lottery_app_id = *self.state.lottery_app_id.get();
+        let timestamp = self.runtime.system_time().micros();
+        for (recipient, amount, recipient_chain_id) in refunds {
+            if amount == Amount::ZERO {
+                continue;
+            }
+            match lottery_app_id {
+                Some(lottery_app_id) if is_deliverable(recipient_chain_id.as_deref()) => {
+                    let _response: LotteryAppResponse = self.runtime.call_application(
+                        true, // authenticated
+                        lottery_app_id,
+                        &LotteryAppOperation::SendPrize {
+                            recipient,
+                            amount,
+                            source_chain_id: recipient_chain_id,
+                        },
+                    );
+                }
+                _ => {
+                    let first_ticket = self.state.ticket_purchases.get(&(round.id, recipient)).await
+                        .expect("Failed to get ticket purchase")
+                        .expect("Refunded owner has no ticket purchase")
+                        .first_ticket;
+                    let destination_chain = recipient_chain_id
+                        .unwrap_or_else(|| self.runtime.chain_id().to_string());
+                    self.state.escrow_prize(round.id, first_ticket, recipient, amount, destination_chain, timestamp).await
+                        .expect("Failed to escrow refund");
+                }
+            }
+        }
+    }
+    
+    /// Send `message` once to every chain with a subscriber for round close (or,
+    /// with `on_close` false, winner drawn) notifications
+    async fn notify_subscribers(&mut self, on_close: bool, message: Message) {
+        let destinations = self.state.notification_destinations(on_close).await
+            .expect("Failed to get notification subscribers");
+        let current_chain = self.runtime.chain_id();
+        let chains: std::collections::BTreeSet<ChainId> = destinations
+            .into_iter()
+            .map(|chain_id| chain_id.and_then(|chain_id| chain_id.parse().ok()).unwrap_or(current_chain))
+            .collect();
+        for chain_id in chains {
+            self.runtime
+                .prepare_message(message.clone())
+                .with_authentication()
+                .send_to(chain_id);
+        }
+    }
+    
+    /// The operation's signer, AccountOwner::CHAIN when it is unsigned
+    fn signer_or_chain(&mut self) -> AccountOwner {
+        self.runtime.authenticated_signer().unwrap_or(AccountOwner::CHAIN)
+    }
+    
+    /// Append an entry for a state-changing operation to the operation log
+    fn log_operation(&mut self, op_type: &str, actor: AccountOwner, data: String) {
+        let entry = OperationLogEntry {
+            op_type: op_type.to_string(),
+            actor,
+            timestamp: self.runtime.system_time().micros(),
+            block_height: self.runtime.block_height().0,
+            data,
+        };
+        self.state.record_operation(entry);
+    }
+    
+    /// Log the creation of the round a completed round opened automatically
+    async fn log_next_round(&mut self, actor: AccountOwner) {
+        let Some(round_id) = *self.state.active_round.get() else {
+            return;
+        };
+        let round = self.state.get_round(round_id).await
+            .expect("Failed to get round")
+            .expect("Active round not found");
+        self.log_operation("create_round", actor, format!("round={} ticket_price={}", round_id, round.ticket_price));
+    }
+    
+    /// Send the state of a closed round to every sync target chain
+    async fn sync_round_state(&mut self, round: &LotteryRound) {
+        let targets = self.state.sync_targets().await
+            .expect("Failed to get sync targets");
+        let now = self.runtime.system_time().micros();
+        let message = Message::RoundStateSync {
+            round: round.to_lib(now),
+            ticket_count_by_pool: round.pool_counts(),
+        };
+        for (_, chain_id) in targets {
+            let chain_id: ChainId = chain_id.parse()
+                .expect("Sync targets are validated when added");
+            self.runtime
+                .prepare_message(message.clone())
+                .with_authentication()
+                .send_to(chain_id);
+        }
+    }
+    
+    /// Draw up to `draws` winners of a closed round, stopping early once every pool is drawn
+    async fn draw_winners(&mut self, round_id: u64, draws: u64) {
+        for draw in 0..draws {
+            let round = self.state.get_round(round_id).await
+                .expect("Failed to get round")
+                .expect("Closed round not found");
+            if round.status != RoundStatus::Closed {
+                break;
+            }
+            self.draw_winner(round_id, draw).await;
+        }
+    }
+
+    /// Draw the next winner of a closed round and send (or escrow) its prize.
+    /// `draw` distinguishes the seeds of several draws made in the same block.
+    async fn draw_winner(&mut self, round_id: u64, draw: u64) -> LotteryRoundsResponse {
+        // Generate VRF value automatically from timestamp + block height
+        let timestamp = self.runtime.system_time().micros();
+        let block_height = self.runtime.block_height();
+        let vrf_value = timestamp.wrapping_add(block_height.into()).wrapping_add(draw.wrapping_mul(DRAW_SEED_STRIDE));
+        
+        eprintln!("GenerateWinner: round_id={}, vrf_value={} (timestamp={}, block={}, draw={})", 
+            round_id, vrf_value, timestamp, block_height, draw);
+        
+        // Get default ticket price for new rounds
+        let default_ticket_price = self.state.get_current_ticket_price().await
+            .unwrap_or(Amount::from_tokens(1));
+        
+        // Generate one winner using VRF
+        match self.state.generate_winner(vrf_value, round_id, timestamp, default_ticket_price).await {
+            Ok((round_id, ticket_number, owner, prize_amount, new_round_created, source_chain_id, payouts)) => {
+                let actor = self.signer_or_chain();
+                self.log_operation("winner", actor, format!("round={} ticket={} prize={}", round_id, ticket_number, prize_amount));
+                
+                // Get lottery app ID from state (set via SetLotteryAppId operation)
+                let lottery_app_id = *self.state.lottery_app_id.get();
+                
+                // Call lottery-app to send prize (one payout per claimant of the ticket).
+                // Prizes that cannot be delivered are held in escrow until the admin delivers them.
+                if prize_amount > Amount::ZERO {
+                    let mut escrowed = false;
+                    for (recipient, amount, recipient_chain_id) in payouts {
+                        if amount == Amount::ZERO {
+                            continue;
+                        }
+                        match lottery_app_id {
+                            Some(lottery_app_id) if is_deliverable(recipient_chain_id.as_deref()) => {
+                                let _response: LotteryAppResponse = self.runtime.call_application(
+                                    true, // authenticated
+                                    lottery_app_id,
+                                    &LotteryAppOperation::SendWinnerPrize {
+                                        recipient,
+                                        amount,
+                                        source_chain_id: recipient_chain_id,
+                                        round_id,
+                                        ticket_number,
+                                    },
+                                );
+                            }
+                            _ => {
+                                let destination_chain = recipient_chain_id
+                                    .unwrap_or_else(|| self.runtime.chain_id().to_string());
+                                self.state.escrow_prize(round_id, ticket_number, recipient, amount, destination_chain, timestamp).await
+                                    .expect("Failed to escrow prize");
+                                escrowed = true;
+                            }
+                        }
+                    }
+                    
+                    // Mark prize as claimed (escrowed prizes are marked when claimed)
+                    if !escrowed {
+                        if let Err(e) = self.state.mark_prize_claimed(round_id, ticket_number).await {
+                            eprintln!("Failed to mark prize as claimed: {}", e);
+                        }
+                    }
+                }
+
+                self.notify_subscribers(false, Message::WinnerDrawn { round_id, ticket_number, owner, prize_amount }).await;
+                
+                // The last draw of a round settles it and opens the next one
+                if new_round_created {
+                    self.log_next_round(actor).await;
+                    let winners = self.state.get_round_winners(round_id).await
+                        .expect("Failed to get round winners");
+                    let result = GameResult::round_settled(GameKind::Lottery, round_id, winners.iter().map(|(_, _, prize, ..)| *prize));
+                    self.runtime.emit(game_results_stream(), &result);
+                }
+                
+                LotteryRoundsResponse::WinnerGenerated {
+                    round_id,
+                    ticket_number,
+                    owner,
+                    prize_amount,
+                    new_round_created,
+                    source_chain_id,
+                }
+            }
+            Err(e) => panic!("Failed to generate winner: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use futures::FutureExt as _;
+    use linera_sdk::{
+        linera_base_types::{AccountOwner, Amount, ApplicationId, BlockHeight, ChainId, CryptoHash, Timestamp},
+        util::BlockingWait,
+        views::View,
+        Contract, ContractRuntime,
+    };
+    use lottery_abi::{
+        DuplicateResolution, FeeTier, LotteryAppOperation, LotteryAppResponse, LotteryRoundsConfig, LotteryRoundsMessage, LotteryRoundsOperation,
+        LotteryRoundsParameters, LotteryRoundsResponse, NotificationPreferences, PendingKind, QuoteError, RoundArchive, RoundCloseMode, RoundCloseTrigger, RoundStatus, UnclaimedPrizes, WinnerPool,
+        ESCROW_DURATION_MICROS,
+    };
+    use lottery_rounds::merkle;
+    use winner_feed::Game;
+
+    use super::{LotteryRoundsContract, LotteryRoundsState, TicketPurchase};
+
+    fn create_runtime() -> ContractRuntime<LotteryRoundsContract> {
+        create_runtime_with_draws(None)
+    }
+
+    fn create_runtime_with_draws(draws_per_close: Option<u64>) -> ContractRuntime<LotteryRoundsContract> {
+        let parameters = LotteryRoundsParameters {
+            native_app_id: ApplicationId::new(CryptoHash::test_hash("native")),
+            admin: AccountOwner::from(CryptoHash::test_hash("admin")),
+            draws_per_close,
+        };
+        ContractRuntime::new()
+            .with_application_parameters(parameters)
+            .with_system_time(Timestamp::from(1_000))
+    }
+
+    fn create_contract() -> LotteryRoundsContract {
+        load_contract(create_runtime())
+    }
+
+    /// Creates a contract with a lottery app that records every prize sent
+    fn create_contract_with_prize_recorder() -> (LotteryRoundsContract, Arc<Mutex<Vec<LotteryAppOperation>>>) {
+        prize_recorder_contract(create_runtime())
+    }
+
+    fn prize_recorder_contract(
+        runtime: ContractRuntime<LotteryRoundsContract>,
+    ) -> (LotteryRoundsContract, Arc<Mutex<Vec<LotteryAppOperation>>>) {
+        let prizes = Arc::new(Mutex::new(Vec::new()));
+        let recorder = prizes.clone();
+        let chain = ChainId(CryptoHash::test_hash("lottery"));
+        let runtime = runtime
+            .with_chain_id(chain)
+            .with_application_creator_chain_id(chain)
+            .with_block_height(BlockHeight(0))
+            .with_call_application_handler(move |_authenticated, _application_id, operation| {
+                recorder.lock().unwrap().push(bcs::from_bytes(&operation).unwrap());
+                bcs::to_bytes(&LotteryAppResponse::Ok).unwrap()
+            });
+        let mut contract = load_contract(runtime);
+        let lottery_app_id = ApplicationId::new(CryptoHash::test_hash("lottery-app"));
+        execute(&mut contract, LotteryRoundsOperation::SetLotteryAppId { lottery_app_id: lottery_app_id.to_string() });
+        (contract, prizes)
+    }
+
+    fn load_contract(runtime: ContractRuntime<LotteryRoundsContract>) -> LotteryRoundsContract {
+        let state = LotteryRoundsState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to load state");
+        LotteryRoundsContract { state, runtime }
+    }
+
+    fn execute(contract: &mut LotteryRoundsContract, operation: LotteryRoundsOperation) -> LotteryRoundsResponse {
+        contract
+            .execute_operation(operation)
+            .now_or_never()
+            .expect("Execution should not await anything")
+    }
+
+    fn purchase(contract: &mut LotteryRoundsContract, owner: AccountOwner) -> Result<u64, String> {
+        contract.state
+            .purchase_tickets(owner, Amount::from_tokens(2), Amount::ONE, None, 1_000)
+            .now_or_never()
+            .unwrap()
+            .map(|purchase| purchase.total_tickets)
+    }
+
+    fn purchase_tickets_for(contract: &mut LotteryRoundsContract, owner: AccountOwner, tickets: u128) {
+        contract.state
+            .purchase_tickets(owner, Amount::from_tokens(tickets), Amount::ONE, None, 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+    }
+
+    #[test]
+    fn allowlist_gates_ticket_purchases() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        execute(&mut contract, LotteryRoundsOperation::EnableAllowlist);
+        assert!(purchase(&mut contract, alice).is_err());
+
+        execute(&mut contract, LotteryRoundsOperation::AddToAllowlist { addresses: vec![alice] });
+        assert_eq!(purchase(&mut contract, alice), Ok(2));
+        assert!(purchase(&mut contract, bob).is_err());
+        assert!(matches!(
+            execute(&mut contract, LotteryRoundsOperation::GetAllowlistStatus { owner: alice }),
+            LotteryRoundsResponse::AllowlistStatus { enabled: true, allowlisted: true }
+        ));
+
+        execute(&mut contract, LotteryRoundsOperation::RemoveFromAllowlist { address: alice });
+        assert!(purchase(&mut contract, alice).is_err());
+
+        execute(&mut contract, LotteryRoundsOperation::DisableAllowlist);
+        assert_eq!(purchase(&mut contract, bob), Ok(2));
+    }
+
+    /// Creates a contract whose admin was set from the application parameters
+    fn create_instantiated_contract() -> LotteryRoundsContract {
+        let mut contract = create_contract();
+        contract.instantiate(()).now_or_never().expect("Instantiation should not await anything");
+        contract
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the admin")]
+    fn non_admin_cannot_change_config() {
+        let mut contract = create_instantiated_contract();
+        contract.runtime.set_authenticated_signer(Some(AccountOwner::from(CryptoHash::test_hash("mallory"))));
+        execute(&mut contract, LotteryRoundsOperation::EnableAllowlist);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the admin")]
+    fn old_admin_is_rejected_after_transfer() {
+        let mut contract = create_instantiated_contract();
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        let new_admin = AccountOwner::from(CryptoHash::test_hash("new-admin"));
+        contract.runtime.set_authenticated_signer(Some(admin));
+        execute(&mut contract, LotteryRoundsOperation::TransferAdminRole { new_admin });
+
+        contract.runtime.set_authenticated_signer(Some(new_admin));
+        execute(&mut contract, LotteryRoundsOperation::EnableAllowlist);
+        assert!(*contract.state.purchase_allowlist_enabled.get());
+
+        contract.runtime.set_authenticated_signer(Some(admin));
+        execute(&mut contract, LotteryRoundsOperation::DisableAllowlist);
+    }
+
+    #[test]
+    fn active_round_id_tracks_the_active_round() {
+        let mut contract = create_contract();
+        assert!(matches!(
+            execute(&mut contract, LotteryRoundsOperation::GetActiveRoundId),
+            LotteryRoundsResponse::ActiveRoundId(None)
+        ));
+
+        let LotteryRoundsResponse::RoundId(round_id) =
+            execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() })
+        else {
+            panic!("CreateRound should return a round ID");
+        };
+        assert!(matches!(
+            execute(&mut contract, LotteryRoundsOperation::GetActiveRoundId),
+            LotteryRoundsResponse::ActiveRoundId(Some(id)) if id == round_id
+        ));
+    }
+
+    #[test]
+    fn cleanup_removes_every_ticket_purchase_of_the_round() {
+        let mut contract = create_contract();
+        let LotteryRoundsResponse::RoundId(round_id) =
+            execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() })
+        else {
+            panic!("CreateRound should return a round ID");
+        };
+        let buyers: Vec<AccountOwner> = ["alice", "bob", "carol", "dave", "erin"]
+            .into_iter()
+            .map(|name| AccountOwner::from(CryptoHash::test_hash(name)))
+            .collect();
+        for buyer in &buyers {
+            assert_eq!(purchase(&mut contract, *buyer), Ok(2));
+        }
+        assert_eq!(purchase(&mut contract, buyers[0]), Ok(2));
+        assert_eq!(contract.state.round_participants.get(&round_id).now_or_never().unwrap().unwrap(), Some(buyers.clone()));
+
+        // Creating the round that pushes this one out of the history triggers its cleanup
+        for _ in 0..5 {
+            contract.state.create_lottery_round(Amount::ONE, Vec::new(), 2_000).now_or_never().unwrap().unwrap();
+        }
+
+        assert!(contract.state.get_round_ticket_purchases(round_id).now_or_never().unwrap().unwrap().is_empty());
+        for buyer in &buyers {
+            assert!(contract.state.ticket_purchases.get(&(round_id, *buyer)).now_or_never().unwrap().unwrap().is_none());
+            assert!(contract.state.round_spend.get(&(round_id, *buyer)).now_or_never().unwrap().unwrap().is_none());
+        }
+        assert!(contract.state.round_participants.get(&round_id).now_or_never().unwrap().unwrap().is_none());
+    }
+
+    /// Sells tickets 1..=14 to alice, injects bob as a second owner of the same
+    /// tickets (as a parallel chain would), closes the round and draws one winner
+    fn draw_duplicated_ticket(resolution: DuplicateResolution) -> (AccountOwner, AccountOwner, Vec<(AccountOwner, Amount, Option<String>)>) {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+
+        execute(&mut contract, LotteryRoundsOperation::SetDuplicateResolution { resolution });
+        let LotteryRoundsResponse::RoundId(round_id) =
+            execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() })
+        else {
+            panic!("CreateRound should return a round ID");
+        };
+        contract.state
+            .purchase_tickets(alice, Amount::from_tokens(14), Amount::ONE, None, 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        contract.state.ticket_purchases.insert(&(round_id, bob), TicketPurchase {
+            owner: bob,
+            first_ticket: 1,
+            last_ticket: 14,
+            total_tickets: 14,
+            amount_paid: Amount::from_tokens(14),
+            source_chain_id: Some("bob-chain".to_string()),
+            ranges: vec![(1, 14)],
+        }).unwrap();
+
+        contract.state.close_lottery_round(2_000).now_or_never().unwrap().unwrap();
+        let claimants = contract.state.duplicate_tickets.get(&(round_id, 1)).now_or_never().unwrap().unwrap();
+        assert_eq!(claimants.map(|owners| owners.len()), Some(2));
+
+        let (_, _, owner, prize_amount, _, _, payouts) = contract.state
+            .generate_winner(0, round_id, 3_000, Amount::ONE)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert_eq!(owner, alice);
+        let total = payouts.iter().fold(Amount::ZERO, |total, (_, amount, _)| total.saturating_add(*amount));
+        assert_eq!(total, prize_amount);
+        (alice, bob, payouts)
+    }
+
+    #[test]
+    fn duplicate_ticket_first_come_first_served() {
+        let (alice, _, payouts) = draw_duplicated_ticket(DuplicateResolution::FirstComeFirstServed);
+        assert_eq!(payouts.len(), 1);
+        assert_eq!(payouts[0].0, alice);
+    }
+
+    #[test]
+    fn duplicate_ticket_split_proportionally() {
+        let (alice, bob, payouts) = draw_duplicated_ticket(DuplicateResolution::SplitProportionally);
+        assert_eq!(payouts.len(), 2);
+        assert!(payouts.iter().any(|(owner, _, chain)| *owner == alice && chain.is_none()));
+        assert!(payouts.iter().any(|(owner, _, chain)| *owner == bob && chain.as_deref() == Some("bob-chain")));
+        // Pool 1 pays 20% of the 14 token prize pool to its single winner, shared by both claimants
+        assert!(payouts.iter().all(|(_, amount, _)| *amount == Amount::from_millis(1_400)));
+    }
+
+    #[test]
+    fn duplicate_ticket_refund() {
+        let (_, _, payouts) = draw_duplicated_ticket(DuplicateResolution::Refund);
+        assert_eq!(payouts.len(), 2);
+        assert!(payouts.iter().all(|(_, amount, _)| *amount == Amount::ONE));
+    }
+
+    #[test]
+    fn csv_export_has_one_row_per_ticket() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+
+        let LotteryRoundsResponse::RoundId(round_id) =
+            execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() })
+        else {
+            panic!("CreateRound should return a round ID");
+        };
+        purchase(&mut contract, alice).unwrap();
+        purchase(&mut contract, bob).unwrap();
+
+        let LotteryRoundsResponse::RoundExport(export) =
+            execute(&mut contract, LotteryRoundsOperation::ExportRoundCSV { round_id })
+        else {
+            panic!("ExportRoundCSV should return a round export");
+        };
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        let csv = String::from_utf8(export.csv_bytes).unwrap();
+        let rows: Vec<&str> = csv.split_terminator("\r\n").collect();
+
+        assert_eq!(rows[0], "ticket_number,owner,amount_paid,is_winner,prize_amount,claimed,source_chain_id");
+        assert_eq!(rows.len() as u64 - 1, round.total_tickets_sold);
+        assert!(rows[1].starts_with(&format!("1,{},", alice)));
+    }
+
+    /// Completes a round of 8 tickets for alice and 6 for bob and exports its archive
+    fn archived_round_bytes() -> Vec<u8> {
+        let (mut contract, _prizes) = prize_recorder_contract(create_runtime_with_draws(Some(4)));
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        for (name, tokens) in [("alice", 8), ("bob", 6)] {
+            contract.state
+                .purchase_tickets(AccountOwner::from(CryptoHash::test_hash(name)), Amount::from_tokens(tokens), Amount::ONE, None, 1_000)
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+        }
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+
+        let LotteryRoundsResponse::Archive(bytes) = execute(&mut contract, LotteryRoundsOperation::ExportRound { round_id }) else {
+            panic!("ExportRound should return an archive");
+        };
+        bytes
+    }
+
+    #[test]
+    fn round_archive_bytes_are_stable() {
+        let bytes = archived_round_bytes();
+        assert_eq!(bytes, archived_round_bytes());
+
+        let archive: RoundArchive = bcs::from_bytes(&bytes).unwrap();
+        assert_eq!(archive.round.status, RoundStatus::Complete);
+        assert_eq!(archive.participants.len(), 2);
+        assert!(archive.participants.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(archive.tickets.iter().map(|purchase| purchase.first_ticket).collect::<Vec<_>>(), vec![1, 9]);
+        assert_eq!(archive.winners.len(), 4);
+        assert!(archive.winners.windows(2).all(|pair| pair[0].ticket_number < pair[1].ticket_number));
+        assert!(archive.payouts.windows(2).all(|pair| pair[0].owner < pair[1].owner));
+        let won = archive.winners.iter().fold(Amount::ZERO, |total, winner| total.saturating_add(winner.prize_amount));
+        let paid = archive.payouts.iter().fold(Amount::ZERO, |total, payout| total.saturating_add(payout.total_prize));
+        assert_eq!(won, paid);
+    }
+
+    #[test]
+    fn round_archive_requires_a_completed_round() {
+        let mut contract = create_contract();
+        let LotteryRoundsResponse::RoundId(round_id) =
+            execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() })
+        else {
+            panic!("CreateRound should return a round ID");
+        };
+        let err = contract.state.round_archive(round_id).now_or_never().unwrap().unwrap_err();
+        assert_eq!(err, "Only completed rounds can be archived");
+    }
+
+    #[test]
+    fn user_participation_across_rounds() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+
+        // Round 1: alice holds every ticket, so the first draw is hers
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        contract.state
+            .purchase_tickets(alice, Amount::from_tokens(14), Amount::ONE, None, 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let LotteryRoundsResponse::RoundId(first_round) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+        contract.state.generate_winner(0, first_round, 2_000, Amount::ONE).now_or_never().unwrap().unwrap();
+
+        // Round 2: alice buys tickets but nothing is drawn yet
+        let LotteryRoundsResponse::RoundId(second_round) =
+            execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() })
+        else {
+            panic!("CreateRound should return a round ID");
+        };
+        purchase(&mut contract, alice).unwrap();
+
+        let LotteryRoundsResponse::UserParticipation(participation) =
+            execute(&mut contract, LotteryRoundsOperation::GetUserParticipation { owner: alice })
+        else {
+            panic!("GetUserParticipation should return participation");
+        };
+        assert_eq!(participation.len(), 2);
+        let first = participation.iter().find(|entry| entry.round_id == first_round).unwrap();
+        assert!(first.won);
+        assert!(first.total_prize > Amount::ZERO);
+        let second = participation.iter().find(|entry| entry.round_id == second_round).unwrap();
+        assert!(!second.won);
+        assert_eq!(second.total_tickets, 2);
+    }
+
+    #[test]
+    fn player_totals_and_spend_limit() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        contract.runtime.set_authenticated_signer(Some(alice));
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        execute(&mut contract, LotteryRoundsOperation::SetMySpendLimit { per_round: Some(Amount::from_tokens(3)) });
+        purchase(&mut contract, alice).unwrap();
+        // A second 2-token purchase would bring the round spend to 4 tokens
+        assert!(purchase(&mut contract, alice).is_err());
+
+        execute(&mut contract, LotteryRoundsOperation::SetMySpendLimit { per_round: None });
+        purchase(&mut contract, alice).unwrap();
+
+        let LotteryRoundsResponse::PlayerTotals(Some(totals)) =
+            execute(&mut contract, LotteryRoundsOperation::GetPlayerTotals { owner: alice })
+        else {
+            panic!("GetPlayerTotals should return totals");
+        };
+        assert_eq!(totals.total_spent, Amount::from_tokens(4));
+        assert_eq!(totals.tickets_bought, 4);
+        assert_eq!(totals.rounds_played, 1);
+        assert_eq!(totals.last_purchase_at, 1_000);
+
+        // Alice holds every ticket, so the first prize is credited to her once the
+        // round has enough tickets to draw winners
+        purchase_tickets_for(&mut contract, alice, 10);
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+        let (_, _, _, prize_amount, _, _, _) = contract.state
+            .generate_winner(0, round_id, 2_000, Amount::ONE)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let totals = contract.state.get_player_totals(alice).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(totals.total_won, prize_amount);
+    }
+
+    #[test]
+    fn operation_log_records_state_changes_in_order() {
+        let (mut contract, _prizes) = create_contract_with_prize_recorder();
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        contract.runtime.set_authenticated_signer(Some(admin));
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        for owner in [alice, bob] {
+            execute(&mut contract, LotteryRoundsOperation::PurchaseTickets {
+                owner,
+                amount: Amount::from_tokens(10),
+                ticket_price: Amount::ONE,
+                source_chain_id: None,
+            });
+        }
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+        let LotteryRoundsResponse::WinnerGenerated { ticket_number, prize_amount, new_round_created: false, .. } =
+            execute(&mut contract, LotteryRoundsOperation::GenerateWinner { round_id })
+        else {
+            panic!("GenerateWinner should draw the first of several winners");
+        };
+
+        let log = |contract: &mut LotteryRoundsContract, offset, limit| {
+            match execute(contract, LotteryRoundsOperation::GetOperationLog { offset, limit }) {
+                LotteryRoundsResponse::OperationLog(entries) => entries,
+                other => panic!("Unexpected response {:?}", other),
+            }
+        };
+        let entries = log(&mut contract, 0, 100);
+        let summary: Vec<(&str, AccountOwner)> = entries.iter().map(|entry| (entry.op_type.as_str(), entry.actor)).collect();
+        assert_eq!(summary, vec![
+            ("create_round", admin),
+            ("purchase", alice),
+            ("purchase", bob),
+            ("close_round", admin),
+            ("winner", admin),
+        ]);
+        assert!(entries.iter().all(|entry| entry.timestamp == 1_000 && entry.block_height == 0));
+        assert_eq!(entries[1].data, format!("round={} amount={} tickets=10", round_id, Amount::from_tokens(10)));
+        assert_eq!(entries[4].data, format!("round={} ticket={} prize={}", round_id, ticket_number, prize_amount));
+
+        // Pages are taken from the log positions
+        assert_eq!(log(&mut contract, 1, 2), entries[1..3].to_vec());
+        assert_eq!(log(&mut contract, 4, 10), entries[4..].to_vec());
+        assert!(log(&mut contract, 5, 10).is_empty());
+    }
+
+    #[test]
+    fn repeat_purchases_count_one_unique_buyer() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let carol = AccountOwner::from(CryptoHash::test_hash("carol"));
+        let LotteryRoundsResponse::RoundId(round_id) =
+            execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() })
+        else {
+            panic!("CreateRound should return a round ID");
+        };
+        for owner in [alice, alice, alice, bob] {
+            purchase(&mut contract, owner).unwrap();
+        }
+        // Receiving a gift does not make Carol a buyer
+        contract.runtime.set_authenticated_signer(Some(alice));
+        execute(&mut contract, LotteryRoundsOperation::TransferTickets { round_id, to: carol, ticket_numbers: vec![1] });
+
+        let LotteryRoundsResponse::LotteryRound(Some(round)) = execute(&mut contract, LotteryRoundsOperation::GetRound { id: round_id }) else {
+            panic!("GetRound should return the round");
+        };
+        assert_eq!((round.total_tickets_sold, round.unique_buyers), (8, 2));
+    }
+
+    #[test]
+    fn gifting_tickets_splits_the_givers_range() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let LotteryRoundsResponse::RoundId(round_id) =
+            execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() })
+        else {
+            panic!("CreateRound should return a round ID");
+        };
+        purchase_tickets_for(&mut contract, alice, 10);
+
+        contract.runtime.set_authenticated_signer(Some(alice));
+        execute(&mut contract, LotteryRoundsOperation::TransferTickets { round_id, to: bob, ticket_numbers: vec![6, 4, 5] });
+
+        let held = |contract: &LotteryRoundsContract, owner| {
+            contract.state.get_user_tickets(round_id, owner).now_or_never().unwrap().unwrap().unwrap()
+        };
+        let (alice_tickets, bob_tickets) = (held(&contract, alice), held(&contract, bob));
+        assert_eq!(alice_tickets.ranges, vec![(1, 3), (7, 10)]);
+        assert_eq!((alice_tickets.first_ticket, alice_tickets.last_ticket, alice_tickets.total_tickets), (1, 10, 7));
+        assert_eq!(alice_tickets.amount_paid, Amount::from_tokens(7));
+        assert_eq!(bob_tickets.ranges, vec![(4, 6)]);
+        assert_eq!(bob_tickets.amount_paid, Amount::from_tokens(3));
+        for ticket_number in 4..=6 {
+            let owner = contract.state.ticket_to_owner.get(&(round_id, ticket_number)).now_or_never().unwrap().unwrap();
+            assert_eq!(owner, Some(bob));
+        }
+
+        // Gifting ticket 7 back joins Bob's range; Alice can no longer give away ticket 5
+        execute(&mut contract, LotteryRoundsOperation::TransferTickets { round_id, to: bob, ticket_numbers: vec![7] });
+        assert_eq!(held(&contract, bob).ranges, vec![(4, 7)]);
+        let err = contract.state.transfer_tickets(round_id, alice, bob, &[5]).now_or_never().unwrap().unwrap_err();
+        assert!(err.contains("is not held by"), "{}", err);
+    }
+
+    #[test]
+    fn undeliverable_prize_is_escrowed_and_claimed() {
+        let (mut contract, prizes) = create_contract_with_prize_recorder();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        contract.runtime.set_authenticated_signer(Some(alice));
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        contract.state
+            .purchase_tickets(alice, Amount::from_tokens(14), Amount::ONE, Some("unreachable".to_string()), 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+        let LotteryRoundsResponse::WinnerGenerated { ticket_number, prize_amount, .. } =
+            execute(&mut contract, LotteryRoundsOperation::GenerateWinner { round_id })
+        else {
+            panic!("GenerateWinner should return the winner");
+        };
+
+        // Nothing was sent; the prize waits in escrow
+        assert!(prizes.lock().unwrap().is_empty());
+        let entries = contract.state.get_escrowed_prizes(round_id, ticket_number).now_or_never().unwrap().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].amount, prize_amount);
+        assert_eq!(entries[0].destination_chain, "unreachable");
+        let feed = contract.state.winner_feed.recent(10).now_or_never().unwrap().unwrap();
+        assert_eq!(feed.len(), 1);
+        assert_eq!((feed[0].game, feed[0].owner, feed[0].amount), (Game::Lottery, alice, prize_amount));
+        assert!(contract.state.expire_escrow(round_id, ticket_number, 2_000).now_or_never().unwrap().is_err());
+
+        // The admin signs the delivery, so lottery-app pays it from the admin's account
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        contract.state.admin.set(Some(admin));
+        contract.runtime.set_authenticated_signer(Some(admin));
+        let response = execute(&mut contract, LotteryRoundsOperation::ClaimEscrowedPrize { round_id, ticket_number, owner: alice });
+        assert!(matches!(response, LotteryRoundsResponse::EscrowAmount(amount) if amount == prize_amount));
+        assert!(matches!(
+            prizes.lock().unwrap().as_slice(),
+            [LotteryAppOperation::SendWinnerPrize { recipient, amount, source_chain_id: None, round_id: sent_round, ticket_number: sent_ticket }]
+                if *recipient == alice && *amount == prize_amount && *sent_round == round_id && *sent_ticket == ticket_number
+        ));
+        assert!(contract.state.get_escrowed_prizes(round_id, ticket_number).now_or_never().unwrap().unwrap().is_empty());
+        let winners = contract.state.get_round_winners(round_id).now_or_never().unwrap().unwrap();
+        assert!(winners.iter().all(|(_, _, _, claimed, _)| *claimed));
+    }
+
+    #[test]
+    fn escrowed_refund_is_claimed_as_a_plain_prize() {
+        let (mut contract, prizes) = create_contract_with_prize_recorder();
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        contract.state
+            .escrow_prize(1, 3, bob, Amount::from_tokens(2), "unreachable".to_string(), 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        contract.state.admin.set(Some(admin));
+        contract.runtime.set_authenticated_signer(Some(admin));
+        execute(&mut contract, LotteryRoundsOperation::ClaimEscrowedPrize { round_id: 1, ticket_number: 3, owner: bob });
+        assert!(matches!(
+            prizes.lock().unwrap().as_slice(),
+            [LotteryAppOperation::SendPrize { recipient, amount, source_chain_id: None }]
+                if *recipient == bob && *amount == Amount::from_tokens(2)
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the admin")]
+    fn winners_cannot_pay_their_own_escrowed_prize() {
+        let (mut contract, _prizes) = create_contract_with_prize_recorder();
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        contract.state.admin.set(Some(AccountOwner::from(CryptoHash::test_hash("admin"))));
+        contract.state
+            .escrow_prize(1, 3, bob, Amount::from_tokens(2), "unreachable".to_string(), 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        contract.runtime.set_authenticated_signer(Some(bob));
+        execute(&mut contract, LotteryRoundsOperation::ClaimEscrowedPrize { round_id: 1, ticket_number: 3, owner: bob });
+    }
+
+    #[test]
+    fn subscribers_are_notified_on_their_chains() {
+        let (mut contract, _prizes) = create_contract_with_prize_recorder();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let alice_chain = ChainId(CryptoHash::test_hash("alice-chain"));
+        let bob_chain = ChainId(CryptoHash::test_hash("bob-chain"));
+        for (owner, chain_id, on_close) in [(alice, alice_chain, true), (bob, bob_chain, false)] {
+            contract.runtime.set_authenticated_signer(Some(owner));
+            execute(&mut contract, LotteryRoundsOperation::SetNotificationPreferences {
+                prefs: NotificationPreferences {
+                    notify_on_round_close: on_close,
+                    notify_on_winner_drawn: !on_close,
+                    destination_chain_id: Some(chain_id.to_string()),
+                },
+            });
+        }
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        contract.state.purchase_tickets(alice, Amount::from_tokens(14), Amount::ONE, None, 1_000).now_or_never().unwrap().unwrap();
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+        {
+            let messages = contract.runtime.created_send_message_requests();
+            assert_eq!(messages.len(), 1);
+            assert_eq!(messages[0].destination, alice_chain);
+            assert!(matches!(messages[0].message, LotteryRoundsMessage::RoundClosed { round_id: id } if id == round_id));
+        }
+
+        execute(&mut contract, LotteryRoundsOperation::GenerateWinner { round_id });
+        let messages = contract.runtime.created_send_message_requests();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].destination, bob_chain);
+        assert!(matches!(messages[1].message, LotteryRoundsMessage::WinnerDrawn { owner, .. } if owner == alice));
+    }
+
+    #[test]
+    fn closed_rounds_are_synced_to_observer_chains() {
+        let (mut contract, _prizes) = create_contract_with_prize_recorder();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let observer_chain = ChainId(CryptoHash::test_hash("observer"));
+        execute(&mut contract, LotteryRoundsOperation::AddSyncTarget { chain_id: observer_chain.to_string() });
+        assert!(contract.state.add_sync_target(observer_chain.to_string()).now_or_never().unwrap().is_err());
+        assert!(contract.state.add_sync_target("not-a-chain".to_string()).now_or_never().unwrap().is_err());
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        purchase_tickets_for(&mut contract, alice, 20);
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        let (synced, ticket_count_by_pool) = {
+            let messages = contract.runtime.created_send_message_requests();
+            assert_eq!(messages.len(), 1);
+            assert_eq!(messages[0].destination, observer_chain);
+            let LotteryRoundsMessage::RoundStateSync { round, ticket_count_by_pool } = messages[0].message.clone() else {
+                panic!("Expected a RoundStateSync message");
+            };
+            (round, ticket_count_by_pool)
+        };
+
+        // The message carries the whole round as it closed, including its pool sizes
+        let expected = round.to_lib(contract.runtime.system_time().micros());
+        assert_eq!(bcs::to_bytes(&synced).unwrap(), bcs::to_bytes(&expected).unwrap());
+        assert_eq!((synced.id, synced.status, synced.total_tickets_sold), (round_id, RoundStatus::Closed, 20));
+        assert_eq!(ticket_count_by_pool, round.pool_counts());
+        let counted: u64 = ticket_count_by_pool.iter().map(|(_, count)| count).sum();
+        assert_eq!(counted, round.pool1_count + round.pool2_count + round.pool3_count + round.pool4_count);
+        assert!(counted > 0);
+
+        // The observer keeps a read-only copy
+        let mut observer = create_contract();
+        observer.execute_message(LotteryRoundsMessage::RoundStateSync { round: synced, ticket_count_by_pool })
+            .now_or_never()
+            .expect("Execution should not await anything");
+        let stored = observer.state.get_synced_rounds().now_or_never().unwrap().unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(bcs::to_bytes(&stored[0]).unwrap(), bcs::to_bytes(&expected).unwrap());
+
+        execute(&mut contract, LotteryRoundsOperation::RemoveSyncTarget { chain_id: observer_chain.to_string() });
+        assert!(contract.state.sync_targets().now_or_never().unwrap().unwrap().is_empty());
+    }
+
+    #[test]
+    fn notification_subscribers_are_capped() {
+        let mut contract = create_contract();
+        let prefs = NotificationPreferences { notify_on_round_close: true, ..NotificationPreferences::default() };
+        for i in 0..lottery_abi::MAX_NOTIFICATION_SUBSCRIBERS {
+            let owner = AccountOwner::from(CryptoHash::test_hash(format!("subscriber-{}", i)));
+            contract.state.set_notification_preferences(owner, prefs.clone()).now_or_never().unwrap().unwrap();
+        }
+        let late = AccountOwner::from(CryptoHash::test_hash("late"));
+        assert!(contract.state.set_notification_preferences(late, prefs.clone()).now_or_never().unwrap().is_err());
+
+        // Existing subscribers can still update, and unsubscribing frees a slot
+        let first = AccountOwner::from(CryptoHash::test_hash("subscriber-0"));
+        contract.state.set_notification_preferences(first, NotificationPreferences::default()).now_or_never().unwrap().unwrap();
+        contract.state.set_notification_preferences(late, prefs).now_or_never().unwrap().unwrap();
+    }
+
+    #[test]
+    fn prize_schedule_projects_the_active_round() {
+        let mut contract = create_contract();
+        assert!(matches!(
+            execute(&mut contract, LotteryRoundsOperation::GetPrizeSchedule),
+            LotteryRoundsResponse::PrizeSchedule(None)
+        ));
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        contract.state
+            .purchase_tickets(AccountOwner::from(CryptoHash::test_hash("alice")), Amount::from_tokens(20), Amount::ONE, None, 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let LotteryRoundsResponse::PrizeSchedule(Some(schedule)) = execute(&mut contract, LotteryRoundsOperation::GetPrizeSchedule) else {
+            panic!("GetPrizeSchedule should return the active round's schedule");
+        };
+        assert_eq!(schedule.total_tickets_sold, 20);
+        assert_eq!(schedule.prize_pool, Amount::from_tokens(20));
+        let pools: Vec<_> = schedule.pools.iter()
+            .map(|pool| (pool.pool, pool.ticket_percentage, pool.winner_count, pool.prize_percentage, pool.prize_allocation))
+            .collect();
+        // 20 tickets: 15% = 3, 7% = 1, 5% = 1 and 3% rounds down to 0 but every pool gets a winner
+        assert_eq!(pools, vec![
+            (WinnerPool::Pool1, 15, 3, 20, Amount::from_tokens(4)),
+            (WinnerPool::Pool2, 7, 1, 25, Amount::from_tokens(5)),
+            (WinnerPool::Pool3, 5, 1, 30, Amount::from_tokens(6)),
+            (WinnerPool::Pool4, 3, 1, 25, Amount::from_tokens(5)),
+        ]);
+        assert_eq!(schedule.pools[0].prize_per_winner, Amount::from_attos(u128::from(Amount::from_tokens(4)) / 3));
+    }
+
+    #[test]
+    fn quote_matches_the_following_purchase() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let amount = Amount::from_millis(7_500);
+        assert!(matches!(
+            execute(&mut contract, LotteryRoundsOperation::QuotePurchase { amount }),
+            LotteryRoundsResponse::PurchaseQuote(Err(QuoteError::NoActiveRound))
+        ));
+
+        let price = Amount::from_tokens(2);
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: price, price_schedule: Vec::new() }) else {
+            panic!("CreateRound should return the round id");
+        };
+        let LotteryRoundsResponse::PurchaseQuote(Ok(quote)) = execute(&mut contract, LotteryRoundsOperation::QuotePurchase { amount }) else {
+            panic!("QuotePurchase should quote the active round");
+        };
+        assert_eq!((quote.round_id, quote.ticket_price, quote.ticket_count), (round_id, price, 3));
+        assert_eq!((quote.total_cost, quote.remainder), (Amount::from_tokens(6), Amount::from_millis(1_500)));
+
+        // Quoting changed nothing; the real purchase gets what was quoted
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.total_tickets_sold, 0);
+        let purchase = contract.state.purchase_tickets(alice, amount, price, None, 1_000).now_or_never().unwrap().unwrap();
+        assert_eq!(purchase.total_tickets, quote.ticket_count);
+    }
+
+    #[test]
+    fn ticket_count_preview_reports_the_remainder() {
+        let mut contract = create_contract();
+        let amount = Amount::from_millis(3_250);
+        assert!(matches!(
+            execute(&mut contract, LotteryRoundsOperation::PreviewTicketCount { amount }),
+            LotteryRoundsResponse::TicketCountPreview(Err(QuoteError::NoActiveRound))
+        ));
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        let LotteryRoundsResponse::TicketCountPreview(Ok(preview)) =
+            execute(&mut contract, LotteryRoundsOperation::PreviewTicketCount { amount })
+        else {
+            panic!("PreviewTicketCount should preview the active round");
+        };
+        assert_eq!(preview.ticket_count, 3);
+        assert_eq!(preview.remainder, Amount::from_millis(250));
+    }
+
+    #[test]
+    fn purchases_pay_the_price_scheduled_at_purchase_time() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let schedule = vec![(5_000, Amount::from_tokens(2))];
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CreateRound {
+            ticket_price: Amount::ONE,
+            price_schedule: schedule.clone(),
+        }) else {
+            panic!("CreateRound should return the round id");
+        };
+
+        // Just before the boundary the base price applies, from it on the scheduled one
+        let amount = Amount::from_tokens(4);
+        let before = contract.state.purchase_tickets(alice, amount, Amount::ONE, None, 4_999).now_or_never().unwrap().unwrap();
+        let after = contract.state.purchase_tickets(bob, amount, Amount::ONE, None, 5_000).now_or_never().unwrap().unwrap();
+        assert_eq!((before.first_ticket, before.last_ticket), (1, 4));
+        assert_eq!((after.first_ticket, after.last_ticket), (5, 6));
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!((round.total_tickets_sold, round.prize_pool), (6, Amount::from_tokens(8)));
+
+        contract.runtime.set_system_time(Timestamp::from(5_000));
+        let LotteryRoundsResponse::PurchaseQuote(Ok(quote)) = execute(&mut contract, LotteryRoundsOperation::QuotePurchase { amount }) else {
+            panic!("QuotePurchase should quote the active round");
+        };
+        assert_eq!((quote.ticket_price, quote.ticket_count), (Amount::from_tokens(2), 2));
+        assert_eq!(quote.price_schedule.len(), 1);
+        assert_eq!((quote.price_schedule[0].effective_from, quote.price_schedule[0].ticket_price), schedule[0]);
+        let LotteryRoundsResponse::LotteryRound(Some(active)) = execute(&mut contract, LotteryRoundsOperation::GetActiveRound) else {
+            panic!("GetActiveRound should return the round");
+        };
+        assert_eq!((active.ticket_price, active.current_ticket_price), (Amount::ONE, Amount::from_tokens(2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to create lottery round: Ticket price must be greater than zero")]
+    fn zero_ticket_price_is_rejected_at_creation() {
+        let mut contract = create_contract();
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ZERO, price_schedule: Vec::new() });
+    }
+
+    #[test]
+    fn ticket_prices_outside_the_bounds_are_rejected_at_creation() {
+        let mut contract = create_contract();
+        execute(&mut contract, LotteryRoundsOperation::SetTicketPriceFloor { floor: Some(Amount::ONE) });
+        execute(&mut contract, LotteryRoundsOperation::SetTicketPriceCeiling { ceiling: Some(Amount::from_tokens(10)) });
+        assert!(contract.state.set_ticket_price_ceiling(Some(Amount::from_millis(500))).is_err());
+
+        let create = |contract: &mut LotteryRoundsContract, ticket_price, price_schedule| {
+            contract.state.create_lottery_round(ticket_price, price_schedule, 1_000).now_or_never().unwrap()
+        };
+        let err = create(&mut contract, Amount::from_millis(500), Vec::new()).unwrap_err();
+        assert!(err.contains("below the floor"), "{}", err);
+        let err = create(&mut contract, Amount::ONE, vec![(5_000, Amount::from_tokens(11))]).unwrap_err();
+        assert!(err.contains("above the ceiling"), "{}", err);
+        assert!(create(&mut contract, Amount::from_tokens(10), Vec::new()).is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "Price schedule timestamps must be ascending")]
+    fn unordered_price_schedule_is_rejected() {
+        let mut contract = create_contract();
+        execute(&mut contract, LotteryRoundsOperation::CreateRound {
+            ticket_price: Amount::ONE,
+            price_schedule: vec![(5_000, Amount::from_tokens(2)), (4_000, Amount::from_tokens(3))],
+        });
+    }
+
+    #[test]
+    fn unclaimed_prizes_are_tracked_per_round() {
+        let (mut contract, _prizes) = create_contract_with_prize_recorder();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let mut expected = Vec::new();
+        for _ in 0..2 {
+            execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+            contract.state
+                .purchase_tickets(alice, Amount::from_tokens(14), Amount::ONE, Some("unreachable".to_string()), 1_000)
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+            let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+                panic!("CloseRound should return a round ID");
+            };
+            let LotteryRoundsResponse::WinnerGenerated { prize_amount, .. } =
+                execute(&mut contract, LotteryRoundsOperation::GenerateWinner { round_id })
+            else {
+                panic!("GenerateWinner should return the winner");
+            };
+            expected.push(UnclaimedPrizes { round_id, unclaimed_winner_count: 1, total_unclaimed_amount: prize_amount });
+        }
+
+        let LotteryRoundsResponse::RoundsWithUnclaimedPrizes(rounds) =
+            execute(&mut contract, LotteryRoundsOperation::GetRoundsWithUnclaimedPrizes)
+        else {
+            panic!("GetRoundsWithUnclaimedPrizes should return rounds");
+        };
+        assert_eq!(rounds, expected);
+        let total = expected[0].total_unclaimed_amount.saturating_add(expected[1].total_unclaimed_amount);
+        assert!(matches!(
+            execute(&mut contract, LotteryRoundsOperation::GetTotalUnclaimedPrizesAmount),
+            LotteryRoundsResponse::TotalUnclaimed(amount) if amount == total
+        ));
+
+        // Delivering a prize removes its round from the overview
+        let (ticket_number, ..) = contract.state.get_round_winners(expected[0].round_id).now_or_never().unwrap().unwrap()[0];
+        contract.state.mark_prize_claimed(expected[0].round_id, ticket_number).now_or_never().unwrap().unwrap();
+        let rounds = contract.state.get_rounds_with_unclaimed_prizes().now_or_never().unwrap().unwrap();
+        assert_eq!(rounds, vec![(expected[1].round_id, 1, expected[1].total_unclaimed_amount)]);
+    }
+
+    #[test]
+    fn payout_summary_splits_awarded_prizes_into_paid_and_unclaimed() {
+        let (mut contract, _prizes) = create_contract_with_prize_recorder();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        contract.state
+            .purchase_tickets(alice, Amount::from_tokens(20), Amount::ONE, Some("unreachable".to_string()), 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+        let mut awarded = Vec::new();
+        for _ in 0..2 {
+            let LotteryRoundsResponse::WinnerGenerated { prize_amount, .. } =
+                execute(&mut contract, LotteryRoundsOperation::GenerateWinner { round_id })
+            else {
+                panic!("GenerateWinner should return the winner");
+            };
+            awarded.push(prize_amount);
+        }
+        let (ticket_number, _, paid, ..) = contract.state.get_round_winners(round_id).now_or_never().unwrap().unwrap()[0];
+        contract.state.mark_prize_claimed(round_id, ticket_number).now_or_never().unwrap().unwrap();
+
+        let LotteryRoundsResponse::RoundPayoutSummary(summary) =
+            execute(&mut contract, LotteryRoundsOperation::GetRoundPayoutSummary { round_id })
+        else {
+            panic!("GetRoundPayoutSummary should return the summary");
+        };
+        let total_awarded = awarded[0].saturating_add(awarded[1]);
+        assert_eq!(summary.prize_pool, Amount::from_tokens(20));
+        assert_eq!(summary.total_awarded, total_awarded);
+        assert_eq!(summary.total_paid, paid);
+        assert_eq!(summary.total_unclaimed, total_awarded.saturating_sub(paid));
+        assert_eq!(summary.winners_drawn, 2);
+        assert!(summary.winners_remaining > 0);
+    }
+
+    #[test]
+    fn expired_escrow_is_reclaimed_and_withdrawn() {
+        let (mut contract, prizes) = create_contract_with_prize_recorder();
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let treasury = AccountOwner::from(CryptoHash::test_hash("treasury"));
+        contract.state
+            .escrow_prize(1, 7, bob, Amount::from_tokens(3), "unreachable".to_string(), 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let expires_at = 1_000 + ESCROW_DURATION_MICROS;
+        contract.runtime.set_system_time(Timestamp::from(expires_at));
+        assert!(contract.state.take_escrowed_prize(1, 7, bob, expires_at).now_or_never().unwrap().is_err());
+
+        let response = execute(&mut contract, LotteryRoundsOperation::ExpireEscrow { round_id: 1, ticket_number: 7 });
+        assert!(matches!(response, LotteryRoundsResponse::EscrowAmount(amount) if amount == Amount::from_tokens(3)));
+        assert_eq!(*contract.state.reclaimed_escrow.get(), Amount::from_tokens(3));
+
+        execute(&mut contract, LotteryRoundsOperation::WithdrawFees { recipient: treasury, destination_chain: None });
+        assert_eq!(*contract.state.reclaimed_escrow.get(), Amount::ZERO);
+        assert!(matches!(
+            prizes.lock().unwrap().as_slice(),
+            [LotteryAppOperation::SendPrize { recipient, .. }] if *recipient == treasury
+        ));
+    }
+
+    #[test]
+    fn pending_items_list_escrowed_payouts() {
+        let mut contract = create_contract();
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let carol = AccountOwner::from(CryptoHash::test_hash("carol"));
+        let bob_chain = ChainId(CryptoHash::test_hash("bob-chain")).to_string();
+        for (ticket_number, owner, destination_chain) in [(3, bob, bob_chain.clone()), (7, carol, "unbob_chain".to_string())] {
+            contract.state
+                .escrow_prize(1, ticket_number, owner, Amount::from_tokens(2), destination_chain, 1_000)
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+        }
+        let pending = contract.state.pending_items(10).now_or_never().unwrap().unwrap();
+        assert_eq!(pending.iter().map(|item| (item.kind, item.owner)).collect::<Vec<_>>(), vec![
+            (PendingKind::EscrowedPrize, bob),
+            (PendingKind::EscrowedPrize, carol),
+        ]);
+        assert_eq!(contract.state.pending_items(1).now_or_never().unwrap().unwrap().len(), 1);
+        assert_eq!(pending[0].source_chain_id, Some(bob_chain));
+    }
+
+    #[test]
+    fn rounds_by_status_uses_index() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        purchase_tickets_for(&mut contract, alice, 7);
+        purchase_tickets_for(&mut contract, bob, 7);
+        execute(&mut contract, LotteryRoundsOperation::CloseRound);
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+
+        let ids = |response: LotteryRoundsResponse| match response {
+            LotteryRoundsResponse::LotteryRounds(rounds) => rounds.into_iter().map(|round| round.id).collect::<Vec<_>>(),
+            other => panic!("Unexpected response {:?}", other),
+        };
+        let closed = execute(&mut contract, LotteryRoundsOperation::GetRoundsByStatus { status: RoundStatus::Closed, limit: 10 });
+        assert_eq!(ids(closed), vec![1]);
+        let active = execute(&mut contract, LotteryRoundsOperation::GetRoundsByStatus { status: RoundStatus::Active, limit: 10 });
+        assert_eq!(ids(active), vec![2]);
+        let complete = execute(&mut contract, LotteryRoundsOperation::GetRoundsByStatus { status: RoundStatus::Complete, limit: 10 });
+        assert!(ids(complete).is_empty());
+    }
+
+    #[test]
+    fn low_ticket_rounds_close_with_a_partial_refund() {
+        let close_with = |tickets: u128| {
+            let mut contract = create_contract();
+            execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+            purchase_tickets_for(&mut contract, AccountOwner::from(CryptoHash::test_hash("alice")), tickets);
+            let round_id = contract.state.close_lottery_round(2_000).now_or_never().unwrap().unwrap();
+            contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap()
+        };
+
+        // Below 14 tickets the 30% winner budget cannot cover one winner per pool
+        for tickets in 1..=7 {
+            let round = close_with(tickets);
+            assert_eq!(round.close_mode, RoundCloseMode::PartialRefundLowTickets, "{} tickets", tickets);
+            assert_eq!(round.status, super::RoundStatus::Complete);
+            assert_eq!([round.pool1_count, round.pool2_count, round.pool3_count, round.pool4_count], [0; 4]);
+        }
+        assert_eq!(close_with(13).close_mode, RoundCloseMode::PartialRefundLowTickets);
+        let round = close_with(14);
+        assert_eq!(round.close_mode, RoundCloseMode::Normal);
+        assert_eq!([round.pool1_count, round.pool2_count, round.pool3_count, round.pool4_count], [1, 1, 1, 1]);
+    }
+
+    #[test]
+    fn winner_budget_is_handed_out_from_pool4_down() {
+        use super::state::allocate_winner_pools;
+
+        assert_eq!(allocate_winner_pools(0), (RoundCloseMode::PartialRefundLowTickets, [0; 4]));
+        // 4 winners: Pool1 asks for 2 (15% of 14) but only 1 is left after Pools 4 to 2
+        assert_eq!(allocate_winner_pools(14), (RoundCloseMode::Normal, [1, 1, 1, 1]));
+        assert_eq!(allocate_winner_pools(20), (RoundCloseMode::Normal, [3, 1, 1, 1]));
+        assert_eq!(allocate_winner_pools(100), (RoundCloseMode::Normal, [15, 7, 5, 3]));
+        for tickets in 14..=1_000u64 {
+            let (_, counts) = allocate_winner_pools(tickets);
+            assert!(counts.iter().all(|count| *count >= 1));
+            assert!(counts.iter().sum::<u64>() * 100 <= tickets * 30, "{} tickets", tickets);
+        }
+    }
+
+    #[test]
+    fn partial_refund_returns_70_percent_by_tickets_held() {
+        let (mut contract, prizes) = create_contract_with_prize_recorder();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        purchase_tickets_for(&mut contract, alice, 3);
+        purchase_tickets_for(&mut contract, bob, 1);
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+
+        // 70% of the 4 token pool: 2.1 tokens for alice's 3 tickets, 0.7 for bob's one
+        let mut refunds: Vec<(AccountOwner, Amount)> = prizes.lock().unwrap().iter()
+            .map(|operation| match operation {
+                LotteryAppOperation::SendPrize { recipient, amount, .. } => (*recipient, *amount),
+                other => panic!("Unexpected operation {:?}", other),
+            })
+            .collect();
+        refunds.sort();
+        let mut expected = vec![(alice, Amount::from_millis(2_100)), (bob, Amount::from_millis(700))];
+        expected.sort();
+        assert_eq!(refunds, expected);
+        // The rest is withdrawable with the fees, and the next round is open
+        assert_eq!(*contract.state.reclaimed_escrow.get(), Amount::from_millis(1_200));
+        assert!(contract.state.get_round_winners(round_id).now_or_never().unwrap().unwrap().is_empty());
+        assert_eq!(contract.state.get_active_round().now_or_never().unwrap().unwrap(), Some(round_id + 1));
+    }
+
+    #[test]
+    fn draws_per_close_settles_the_round() {
+        // 14 tickets: one winner in each of the four pools
+        let (mut contract, prizes) = prize_recorder_contract(create_runtime_with_draws(Some(4)));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        contract.state
+            .purchase_tickets(alice, Amount::from_tokens(14), Amount::ONE, None, 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.status, super::RoundStatus::Complete);
+        let winners = contract.state.get_round_winners(round_id).now_or_never().unwrap().unwrap();
+        assert_eq!(winners.len(), 4);
+        assert!(winners.iter().all(|(_, _, _, claimed, _)| *claimed));
+        assert_eq!(prizes.lock().unwrap().len(), 4);
+        assert_eq!(contract.state.get_active_round().now_or_never().unwrap().unwrap(), Some(round_id + 1));
+    }
+
+    #[test]
+    fn completed_round_commits_to_its_winners() {
+        let (mut contract, _prizes) = prize_recorder_contract(create_runtime_with_draws(Some(4)));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        contract.state
+            .purchase_tickets(alice, Amount::from_tokens(14), Amount::ONE, None, 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+
+        let winners = contract.state.get_round_winners(round_id).now_or_never().unwrap().unwrap();
+        assert_eq!(winners.len(), 4);
+        for (ticket_number, owner, prize, _, _) in &winners {
+            let LotteryRoundsResponse::MerkleProof(proof) =
+                execute(&mut contract, LotteryRoundsOperation::ProveMembershipOfWinner { round_id, ticket_number: *ticket_number })
+            else {
+                panic!("ProveMembershipOfWinner should return a proof");
+            };
+            let root: [u8; 32] = proof.root.try_into().unwrap();
+            let leaf: [u8; 32] = proof.leaf.try_into().unwrap();
+            let siblings: Vec<[u8; 32]> = proof.proof.into_iter().map(|node| node.try_into().unwrap()).collect();
+            assert_eq!(leaf, merkle::winner_leaf(*ticket_number, owner, *prize));
+            assert!(merkle::verify_proof(&root, &leaf, &siblings));
+        }
+
+        let losing_ticket = (1..=14).find(|ticket| winners.iter().all(|(winner, _, _, _, _)| winner != ticket)).unwrap();
+        assert!(contract.state.prove_winner(round_id, losing_ticket).now_or_never().unwrap().is_err());
+    }
+
+    #[test]
+    fn round_winners_are_ordered_by_ticket_number() {
+        let (mut contract, _prizes) = prize_recorder_contract(create_runtime_with_draws(Some(4)));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        contract.state
+            .purchase_tickets(alice, Amount::from_tokens(14), Amount::ONE, None, 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+
+        let ticket_numbers = |contract: &mut LotteryRoundsContract| {
+            let LotteryRoundsResponse::LotteryWinners(winners) =
+                execute(contract, LotteryRoundsOperation::GetRoundWinners { round_id })
+            else {
+                panic!("GetRoundWinners should return the winners");
+            };
+            winners.into_iter().map(|winner| winner.ticket_number).collect::<Vec<_>>()
+        };
+        let first = ticket_numbers(&mut contract);
+        assert_eq!(first.len(), 4);
+        assert!(first.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(ticket_numbers(&mut contract), first);
+    }
+
+    #[test]
+    fn owner_ticket_numbers_expand_every_batch_page_by_page() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+
+        let LotteryRoundsResponse::RoundId(round_id) =
+            execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() })
+        else {
+            panic!("CreateRound should return a round ID");
+        };
+        for (owner, tokens) in [(alice, 3), (bob, 2), (alice, 4)] {
+            contract.state
+                .purchase_tickets(owner, Amount::from_tokens(tokens), Amount::ONE, None, 1_000)
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+        }
+
+        let mut page = |owner, after, limit| {
+            let LotteryRoundsResponse::TicketNumbers(ticket_numbers) =
+                execute(&mut contract, LotteryRoundsOperation::GetOwnerTicketNumbers { round_id, owner, after, limit })
+            else {
+                panic!("GetOwnerTicketNumbers should return ticket numbers");
+            };
+            ticket_numbers
+        };
+        assert_eq!(page(alice, None, 10), vec![1, 2, 3, 6, 7, 8, 9]);
+        assert_eq!(page(alice, None, 4), vec![1, 2, 3, 6]);
+        assert_eq!(page(alice, Some(6), 4), vec![7, 8, 9]);
+        assert_eq!(page(alice, Some(9), 4), Vec::<u64>::new());
+        assert_eq!(page(bob, None, 10), vec![4, 5]);
+    }
+
+    #[test]
+    fn winners_by_ticket_range_only_returns_tickets_in_the_range() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let round_id = 1;
+        for ticket_number in (5..=100).step_by(5) {
+            contract.state.winning_tickets
+                .insert(&(round_id, ticket_number), super::state::WinningTicket(alice, Amount::ONE, false, None, Some(WinnerPool::Pool1)))
+                .unwrap();
+        }
+        // A winner of another round with a ticket in the range is not included
+        contract.state.winning_tickets
+            .insert(&(round_id + 1, 7), super::state::WinningTicket(alice, Amount::ONE, false, None, Some(WinnerPool::Pool1)))
+            .unwrap();
+
+        let LotteryRoundsResponse::LotteryWinners(winners) = execute(&mut contract, LotteryRoundsOperation::GetWinnersByTicketRange {
+            round_id,
+            start_ticket: 1,
+            end_ticket: 50,
+        }) else {
+            panic!("GetWinnersByTicketRange should return the winners");
+        };
+        assert_eq!(
+            winners.iter().map(|winner| winner.ticket_number).collect::<Vec<_>>(),
+            (5..=50).step_by(5).collect::<Vec<_>>(),
+        );
+
+        let too_wide = contract.state
+            .get_winners_by_ticket_range(round_id, 1, lottery_abi::MAX_WINNER_TICKET_RANGE + 1)
+            .now_or_never()
+            .unwrap();
+        assert!(too_wide.is_err());
+    }
+
+    #[test]
+    fn every_draw_recomputes_from_its_audit_record() {
+        let (mut contract, _prizes) = prize_recorder_contract(create_runtime_with_draws(Some(4)));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        for owner in [alice, bob] {
+            contract.state
+                .purchase_tickets(owner, Amount::from_tokens(7), Amount::ONE, None, 1_000)
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+        }
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+
+        let winners = contract.state.get_round_winners(round_id).now_or_never().unwrap().unwrap();
+        assert_eq!(winners.len(), 4);
+        for (ticket_number, owner, ..) in &winners {
+            let verification = contract.state.verify_win(round_id, *ticket_number).now_or_never().unwrap().unwrap();
+            assert!(verification.verified, "ticket {} should verify", ticket_number);
+            assert_eq!(verification.expected_owner, Some(*owner));
+        }
+
+        // A reassigned ticket no longer matches the stored winner
+        let (ticket_number, ..) = winners[0];
+        let mallory = AccountOwner::from(CryptoHash::test_hash("mallory"));
+        contract.state.ticket_to_owner.insert(&(round_id, ticket_number), mallory).unwrap();
+        let verification = contract.state.verify_win(round_id, ticket_number).now_or_never().unwrap().unwrap();
+        assert!(!verification.verified);
+        assert_eq!(verification.expected_owner, Some(mallory));
+
+        // Tickets that did not win have nothing to recompute
+        let losing_ticket = (1..=14).find(|ticket| winners.iter().all(|(won, ..)| won != ticket)).unwrap();
+        let verification = contract.state.verify_win(round_id, losing_ticket).now_or_never().unwrap().unwrap();
+        assert!(!verification.verified);
+        assert_eq!(verification.stored_owner, None);
+        assert_eq!(verification.expected_ticket, None);
+    }
+
+    #[test]
+    fn draw_skips_tickets_without_an_owner() {
+        let (mut contract, _prizes) = create_contract_with_prize_recorder();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        contract.state
+            .purchase_tickets(alice, Amount::from_tokens(14), Amount::ONE, None, 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+        // Only the last ticket still has an owner
+        for ticket_number in 1..14 {
+            contract.state.ticket_to_owner.remove(&(round_id, ticket_number)).unwrap();
+        }
+
+        let LotteryRoundsResponse::WinnerGenerated { owner, ticket_number, .. } =
+            execute(&mut contract, LotteryRoundsOperation::GenerateWinner { round_id })
+        else {
+            panic!("GenerateWinner should return the winner");
+        };
+        assert_eq!((owner, ticket_number), (alice, 14));
+        assert!(contract.state.verify_win(round_id, ticket_number).now_or_never().unwrap().unwrap().verified);
+    }
+
+    #[test]
+    fn one_win_per_address_skips_previous_winners() {
+        let (mut contract, _prizes) = create_contract_with_prize_recorder();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        execute(&mut contract, LotteryRoundsOperation::SetOneWinPerAddress { enabled: true });
+
+        // 15 tickets give one winner in each pool; alice holds all but the last
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        for (owner, tokens) in [(alice, 14), (bob, 1)] {
+            contract.state
+                .purchase_tickets(owner, Amount::from_tokens(tokens), Amount::ONE, None, 1_000)
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+        }
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+
+        let LotteryRoundsResponse::WinnerGenerated { owner: first, .. } =
+            execute(&mut contract, LotteryRoundsOperation::GenerateWinner { round_id })
+        else {
+            panic!("GenerateWinner should return the winner");
+        };
+        assert_eq!(first, alice);
+        let LotteryRoundsResponse::WinnerGenerated { owner: second, ticket_number, .. } =
+            execute(&mut contract, LotteryRoundsOperation::GenerateWinner { round_id })
+        else {
+            panic!("GenerateWinner should return the winner");
+        };
+        assert_eq!((second, ticket_number), (bob, 15));
+        // The skipped tickets are part of the audit, so the draw still verifies
+        assert!(contract.state.verify_win(round_id, ticket_number).now_or_never().unwrap().unwrap().verified);
+
+        // Pool3 needs a third address
+        let err = contract.state
+            .generate_winner(7, round_id, 2_000, Amount::ONE)
+            .now_or_never()
+            .unwrap()
+            .unwrap_err();
+        assert!(err.starts_with("No eligible ticket left"), "{}", err);
+        let winners = contract.state.get_round_winners(round_id).now_or_never().unwrap().unwrap();
+        assert_eq!(winners.len(), 2);
+    }
+
+    #[test]
+    fn draw_progress_tracks_settlement() {
+        let (mut contract, _prizes) = create_contract_with_prize_recorder();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        contract.state.purchase_tickets(alice, Amount::from_tokens(15), Amount::ONE, None, 1_000).now_or_never().unwrap().unwrap();
+        let progress = |contract: &mut LotteryRoundsContract, round_id| {
+            let LotteryRoundsResponse::DrawProgress(progress) = execute(contract, LotteryRoundsOperation::GetDrawProgress { round_id }) else {
+                panic!("GetDrawProgress should return the progress");
+            };
+            (progress.winners_expected, progress.winners_drawn, progress.completion_bps)
+        };
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+        assert_eq!(progress(&mut contract, round_id), (4, 0, 0));
+
+        // Mid-draw: one of the four pools has its winner
+        execute(&mut contract, LotteryRoundsOperation::GenerateWinner { round_id });
+        assert_eq!(progress(&mut contract, round_id), (4, 1, 2_500));
+
+        for _ in 0..3 {
+            execute(&mut contract, LotteryRoundsOperation::GenerateWinner { round_id });
+        }
+        assert_eq!(progress(&mut contract, round_id), (4, 4, 10_000));
+    }
+
+    #[test]
+    fn current_status_follows_the_round_lifecycle() {
+        let (mut contract, _prizes) = create_contract_with_prize_recorder();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let status = |contract: &mut LotteryRoundsContract| {
+            let LotteryRoundsResponse::CurrentStatus { round_id, status, tickets_sold, time_since_last_change } =
+                execute(contract, LotteryRoundsOperation::GetCurrentRoundStatus)
+            else {
+                panic!("GetCurrentRoundStatus should return the status");
+            };
+            (round_id, status, tickets_sold, time_since_last_change)
+        };
+        let pool = |contract: &mut LotteryRoundsContract| {
+            let LotteryRoundsResponse::CurrentPool { round_id, pool, remaining } =
+                execute(contract, LotteryRoundsOperation::GetCurrentWinnerPool)
+            else {
+                panic!("GetCurrentWinnerPool should return the pool");
+            };
+            (round_id, pool, remaining)
+        };
+        assert_eq!(status(&mut contract), (None, None, 0, 0));
+
+        // Active: created at 1000
+        let LotteryRoundsResponse::RoundId(round_id) =
+            execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() })
+        else {
+            panic!("CreateRound should return a round ID");
+        };
+        contract.state.purchase_tickets(alice, Amount::from_tokens(15), Amount::ONE, None, 1_200).now_or_never().unwrap().unwrap();
+        contract.runtime.set_system_time(Timestamp::from(1_500));
+        assert_eq!(status(&mut contract), (Some(round_id), Some(RoundStatus::Active), 15, 500));
+
+        // Closed: no active round, the closed one is reported while it is drawn
+        contract.runtime.set_system_time(Timestamp::from(2_000));
+        execute(&mut contract, LotteryRoundsOperation::CloseRound);
+        contract.runtime.set_system_time(Timestamp::from(2_600));
+        assert_eq!(status(&mut contract), (Some(round_id), Some(RoundStatus::Closed), 15, 600));
+        assert_eq!(pool(&mut contract), (round_id, WinnerPool::Pool1, 1));
+        execute(&mut contract, LotteryRoundsOperation::GenerateWinner { round_id });
+        assert_eq!(pool(&mut contract), (round_id, WinnerPool::Pool2, 1));
+
+        // Complete: the next round takes over
+        for _ in 0..3 {
+            execute(&mut contract, LotteryRoundsOperation::GenerateWinner { round_id });
+        }
+        assert_eq!(status(&mut contract), (Some(round_id + 1), Some(RoundStatus::Active), 0, 0));
+        assert_eq!(pool(&mut contract), (round_id + 1, WinnerPool::Pool1, 0));
+    }
+
+    #[test]
+    fn winners_are_grouped_by_pool() {
+        // 14 tickets: one winner in each pool, each taking its pool's whole share of 14 tokens
+        let (mut contract, _prizes) = prize_recorder_contract(create_runtime_with_draws(Some(4)));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        contract.state
+            .purchase_tickets(alice, Amount::from_tokens(14), Amount::ONE, None, 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+
+        let LotteryRoundsResponse::WinnersByPool(groups) =
+            execute(&mut contract, LotteryRoundsOperation::GetRoundWinnersByPool { round_id })
+        else {
+            panic!("GetRoundWinnersByPool should return grouped winners");
+        };
+        let summary: Vec<_> = groups.iter()
+            .map(|group| (group.pool, group.prize_percentage, group.winners.iter().map(|winner| winner.prize_amount).collect::<Vec<_>>()))
+            .collect();
+        assert_eq!(summary, vec![
+            (WinnerPool::Pool1, 20, vec![Amount::from_millis(2_800)]),
+            (WinnerPool::Pool2, 25, vec![Amount::from_millis(3_500)]),
+            (WinnerPool::Pool3, 30, vec![Amount::from_millis(4_200)]),
+            (WinnerPool::Pool4, 25, vec![Amount::from_millis(3_500)]),
+        ]);
+    }
+
+    #[test]
+    fn config_round_trips_and_rejects_bad_app_ids() {
+        let (mut staging, _prizes) = create_contract_with_prize_recorder();
+        execute(&mut staging, LotteryRoundsOperation::EnableAllowlist);
+        execute(&mut staging, LotteryRoundsOperation::SetDuplicateResolution { resolution: DuplicateResolution::Refund });
+        execute(&mut staging, LotteryRoundsOperation::SetTargetPrizePool { amount: Some(Amount::from_tokens(500)) });
+        execute(&mut staging, LotteryRoundsOperation::SetMinPrizePoolToClose { amount: Some(Amount::from_tokens(10)) });
+        execute(&mut staging, LotteryRoundsOperation::AddFeeTier { min_pool_tokens: 100, fee_bps: 200 });
+        execute(&mut staging, LotteryRoundsOperation::AddFeeTier { min_pool_tokens: 10, fee_bps: 100 });
+        execute(&mut staging, LotteryRoundsOperation::SetDefaultFeeBps { fee_bps: 50 });
+        execute(&mut staging, LotteryRoundsOperation::SetTicketPriceFloor { floor: Some(Amount::from_millis(100)) });
+        execute(&mut staging, LotteryRoundsOperation::SetTicketPriceCeiling { ceiling: Some(Amount::from_tokens(10)) });
+        execute(&mut staging, LotteryRoundsOperation::SetMaxWinnerPctBps { bps: 3_500 });
+        execute(&mut staging, LotteryRoundsOperation::SetOneWinPerAddress { enabled: true });
+        let LotteryRoundsResponse::Config(config) = execute(&mut staging, LotteryRoundsOperation::GetConfig) else {
+            panic!("GetConfig should return the config");
+        };
+        // Every register differs from a fresh app's, so the round trip below covers all of them
+        let (fresh, _prizes) = prize_recorder_contract(create_runtime());
+        let defaults = fresh.state.get_config().now_or_never().unwrap().unwrap();
+        assert_ne!(config.lottery_app_id, defaults.lottery_app_id);
+        assert_ne!(config.purchase_allowlist_enabled, defaults.purchase_allowlist_enabled);
+        assert_ne!(config.duplicate_ticket_resolution, defaults.duplicate_ticket_resolution);
+        assert_ne!(config.target_prize_pool, defaults.target_prize_pool);
+        assert_ne!(config.min_prize_pool_to_close, defaults.min_prize_pool_to_close);
+        assert_eq!(config.fee_tiers, vec![FeeTier { min_pool_tokens: 10, fee_bps: 100 }, FeeTier { min_pool_tokens: 100, fee_bps: 200 }]);
+        assert_ne!(config.default_fee_bps, defaults.default_fee_bps);
+        assert_ne!(config.ticket_price_floor, defaults.ticket_price_floor);
+        assert_ne!(config.ticket_price_ceiling, defaults.ticket_price_ceiling);
+        assert_ne!(config.max_winner_pct_bps, defaults.max_winner_pct_bps);
+        assert_ne!(config.one_win_per_address, defaults.one_win_per_address);
+
+        let (mut production, _prizes) = prize_recorder_contract(create_runtime());
+        execute(&mut production, LotteryRoundsOperation::SetLotteryAppId {
+            lottery_app_id: ApplicationId::new(CryptoHash::test_hash("old-lottery-app")).to_string(),
+        });
+        execute(&mut production, LotteryRoundsOperation::AddFeeTier { min_pool_tokens: 1_000, fee_bps: 300 });
+        let before = production.state.get_config().now_or_never().unwrap().unwrap();
+        let invalid = LotteryRoundsConfig { lottery_app_id: Some("not-an-app-id".to_string()), ..config.clone() };
+        assert!(production.state.import_config(invalid).is_err());
+        let invalid = LotteryRoundsConfig { ticket_price_floor: Some(Amount::from_tokens(20)), ..config.clone() };
+        assert!(production.state.import_config(invalid).is_err());
+        assert_eq!(production.state.get_config().now_or_never().unwrap().unwrap(), before);
+
+        // Imported fee tiers replace the existing ones
+        execute(&mut production, LotteryRoundsOperation::ImportConfig { config: config.clone() });
+        assert_eq!(production.state.get_config().now_or_never().unwrap().unwrap(), config);
+    }
+
+    #[test]
+    fn reaching_target_prize_pool_closes_round_mid_purchase() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let price = Amount::from_tokens(5);
+        execute(&mut contract, LotteryRoundsOperation::SetTargetPrizePool { amount: Some(Amount::from_tokens(100)) });
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: price, price_schedule: Vec::new() }) else {
+            panic!("CreateRound should return the round id");
+        };
+
+        let buy = |owner, tokens| LotteryRoundsOperation::PurchaseTickets {
+            owner,
+            amount: Amount::from_tokens(tokens),
+            ticket_price: price,
+            source_chain_id: None,
+        };
+        execute(&mut contract, buy(alice, 60));
+        assert_eq!(contract.state.get_active_round().now_or_never().unwrap().unwrap(), Some(round_id));
+
+        // 60 + 50 crosses the 100 token target within this purchase
+        execute(&mut contract, buy(bob, 50));
+        assert_eq!(contract.state.get_active_round().now_or_never().unwrap().unwrap(), None);
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.status, super::RoundStatus::Closed);
+        assert_eq!(round.triggered_by, Some(RoundCloseTrigger::PrizeCap));
+        assert_eq!(round.prize_pool, Amount::from_tokens(110));
+    }
+
+    #[test]
+    fn batch_loaded_rounds_match_sequential_loads() {
+        let mut contract = create_contract();
+        let LotteryRoundsResponse::RoundId(first) = execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() }) else {
+            panic!("CreateRound should return the round id");
+        };
+        let template = contract.state.get_round(first).now_or_never().unwrap().unwrap().unwrap();
+        for round_id in first + 1..first + 20 {
+            let mut round = template.clone();
+            round.id = round_id;
+            round.ticket_price = Amount::from_tokens(round_id as u128);
+            contract.state.rounds.insert(&round_id, round).unwrap();
+        }
+
+        let ids: Vec<u64> = (first..first + 20).rev().chain([999]).collect();
+        let batch = contract.state.get_rounds_batch(&ids).now_or_never().unwrap().unwrap();
+        let sequential: Vec<_> = ids.iter()
+            .map(|round_id| contract.state.get_round(*round_id).now_or_never().unwrap().unwrap())
+            .collect();
+        assert_eq!(batch.len(), 21);
+        assert!(batch[20].is_none());
+        for (batched, loaded) in batch.iter().zip(&sequential) {
+            assert_eq!(batched.as_ref().map(|round| (round.id, round.ticket_price)), loaded.as_ref().map(|round| (round.id, round.ticket_price)));
+        }
+        assert_eq!(contract.state.get_all_rounds().now_or_never().unwrap().unwrap().len(), 20);
+    }
+
+    #[test]
+    fn round_below_minimum_prize_pool_cannot_close() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        execute(&mut contract, LotteryRoundsOperation::SetMinPrizePoolToClose { amount: Some(Amount::from_tokens(20)) });
+        execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() });
+        contract.state
+            .purchase_tickets(alice, Amount::from_tokens(19), Amount::ONE, None, 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let err = contract.state.close_lottery_round(2_000).now_or_never().unwrap().unwrap_err();
+        assert!(err.starts_with("Prize pool of"), "{}", err);
+
+        contract.state
+            .purchase_tickets(alice, Amount::ONE, Amount::ONE, None, 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CloseRound) else {
+            panic!("CloseRound should return a round ID");
+        };
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.status, super::RoundStatus::Closed);
+        assert_eq!(round.prize_pool, Amount::from_tokens(20));
+    }
+
+    #[test]
+    fn house_fee_follows_the_prize_pool_tier() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        execute(&mut contract, LotteryRoundsOperation::AddFeeTier { min_pool_tokens: 100, fee_bps: 200 });
+        execute(&mut contract, LotteryRoundsOperation::AddFeeTier { min_pool_tokens: 1_000, fee_bps: 100 });
+        execute(&mut contract, LotteryRoundsOperation::SetDefaultFeeBps { fee_bps: 500 });
+        let fee_bps = |contract: &LotteryRoundsContract, tokens| {
+            contract.state.fee_bps_for_pool(Amount::from_tokens(tokens)).now_or_never().unwrap().unwrap()
+        };
+        assert_eq!(fee_bps(&contract, 50), 500);
+        assert_eq!(fee_bps(&contract, 500), 200);
+        assert_eq!(fee_bps(&contract, 1_500), 100);
+
+        let LotteryRoundsResponse::RoundId(round_id) = execute(&mut contract, LotteryRoundsOperation::CreateRound { ticket_price: Amount::ONE, price_schedule: Vec::new() }) else {
+            panic!("CreateRound should return the round id");
+        };
+        let mut round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        round.prize_pool = Amount::from_tokens(500);
+        contract.state.rounds.insert(&round_id, round).unwrap();
+        purchase_tickets_for(&mut contract, alice, 100);
+        // 2% of 100 tokens stays with the house
+        let mut round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.prize_pool, Amount::from_tokens(598));
+        assert_eq!(*contract.state.reclaimed_escrow.get(), Amount::from_tokens(2));
+
+        round.prize_pool = Amount::from_tokens(1_500);
+        contract.state.rounds.insert(&round_id, round).unwrap();
+        purchase_tickets_for(&mut contract, alice, 100);
+        let pool = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap().prize_pool;
+        assert_eq!(pool, Amount::from_tokens(1_599));
+        assert_eq!(*contract.state.reclaimed_escrow.get(), Amount::from_tokens(3));
+    }
+
+    #[test]
+    fn statuses_and_pools_stored_in_the_former_state_layout_still_load() {
+        // Copies of the enums the state module used to define
+        #[derive(serde::Serialize)]
+        enum OldRoundStatus { Active, Closed, Complete }
+        #[derive(serde::Serialize)]
+        enum OldWinnerPool { Pool1, Pool2, Pool3, Pool4, Complete }
+
+        let statuses = [
+            (OldRoundStatus::Active, RoundStatus::Active),
+            (OldRoundStatus::Closed, RoundStatus::Closed),
+            (OldRoundStatus::Complete, RoundStatus::Complete),
+        ];
+        for (old, status) in statuses {
+            let bytes = bcs::to_bytes(&old).unwrap();
+            assert_eq!(bcs::from_bytes::<super::state::RoundStatus>(&bytes).unwrap(), status);
+        }
+        let pools = [
+            (OldWinnerPool::Pool1, WinnerPool::Pool1),
+            (OldWinnerPool::Pool2, WinnerPool::Pool2),
+            (OldWinnerPool::Pool3, WinnerPool::Pool3),
+            (OldWinnerPool::Pool4, WinnerPool::Pool4),
+            (OldWinnerPool::Complete, WinnerPool::Complete),
+        ];
+        for (old, pool) in pools {
+            let bytes = bcs::to_bytes(&old).unwrap();
+            assert_eq!(bcs::from_bytes::<super::state::WinnerPool>(&bytes).unwrap(), pool);
+        }
+
+        // Winning ticket entries stored before the pool was recorded load without one
+        let owner = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bytes = bcs::to_bytes(&(owner, Amount::ONE, true, Some("chain".to_string()))).unwrap();
+        let entry: super::state::WinningTicket = bcs::from_bytes(&bytes).unwrap();
+        assert_eq!(entry, super::state::WinningTicket(owner, Amount::ONE, true, Some("chain".to_string()), None));
+        let entry = super::state::WinningTicket(owner, Amount::ONE, false, None, Some(WinnerPool::Pool3));
+        let bytes = bcs::to_bytes(&entry).unwrap();
+        assert_eq!(bcs::from_bytes::<super::state::WinningTicket>(&bytes).unwrap(), entry);
+
+        // The status index keeps its keys
+        let keys: Vec<u8> = [RoundStatus::Active, RoundStatus::Closed, RoundStatus::Complete].into_iter().map(RoundStatus::index_key).collect();
+        assert_eq!(keys, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn rounds_stored_before_the_appended_fields_still_load() {
+        // Copies of the round types the state module used to define
+        #[derive(serde::Serialize)]
+        #[allow(dead_code)]
+        enum OldRoundStatus { Active, Closed, Complete }
+        #[derive(serde::Serialize)]
+        #[allow(dead_code)]
+        enum OldWinnerPool { Pool1, Pool2, Pool3, Pool4, Complete }
+        #[derive(serde::Serialize)]
+        struct OldLotteryRound {
+            id: u64,
+            created_at: u64,
+            closed_at: Option<u64>,
+            status: OldRoundStatus,
+            ticket_price: Amount,
+            total_tickets_sold: u64,
+            next_ticket_number: u64,
+            prize_pool: Amount,
+            current_winner_pool: OldWinnerPool,
+            pool1_count: u64,
+            pool2_count: u64,
+            pool3_count: u64,
+            pool4_count: u64,
+            pool1_winners_drawn: u64,
+            pool2_winners_drawn: u64,
+            pool3_winners_drawn: u64,
+            pool4_winners_drawn: u64,
+        }
+
+        let old = OldLotteryRound {
+            id: 3,
+            created_at: 100,
+            closed_at: Some(200),
+            status: OldRoundStatus::Closed,
+            ticket_price: Amount::ONE,
+            total_tickets_sold: 40,
+            next_ticket_number: 41,
+            prize_pool: Amount::from_tokens(40),
+            current_winner_pool: OldWinnerPool::Pool2,
+            pool1_count: 6,
+            pool2_count: 3,
+            pool3_count: 2,
+            pool4_count: 1,
+            pool1_winners_drawn: 6,
+            pool2_winners_drawn: 1,
+            pool3_winners_drawn: 0,
+            pool4_winners_drawn: 0,
+        };
+        let bytes = bcs::to_bytes(&old).unwrap();
+        let round: super::state::LotteryRound = bcs::from_bytes(&bytes).unwrap();
+
+        assert_eq!((round.id, round.created_at, round.closed_at), (3, 100, Some(200)));
+        assert_eq!(round.status, RoundStatus::Closed);
+        assert_eq!((round.ticket_price, round.prize_pool), (Amount::ONE, Amount::from_tokens(40)));
+        assert_eq!((round.total_tickets_sold, round.next_ticket_number), (40, 41));
+        assert_eq!(round.current_winner_pool, WinnerPool::Pool2);
+        assert_eq!(round.pool_counts().into_iter().map(|(_, count)| count).collect::<Vec<_>>(), vec![6, 3, 2, 1]);
+        assert_eq!(round.remaining_in_current_pool(), 2);
+        assert_eq!(round.triggered_by, None);
+        assert_eq!(round.close_mode, RoundCloseMode::Normal);
+        assert!(round.price_schedule.is_empty());
+        assert_eq!(round.ticket_price_at(1_000), Amount::ONE);
+        assert_eq!(round.unique_buyers, 0);
+
+        // Rewriting the round keeps the original bytes as its prefix
+        let rewritten = bcs::to_bytes(&round).unwrap();
+        assert_eq!(&rewritten[..bytes.len()], &bytes[..]);
+    }
+
+    #[test]
+    fn purchases_stored_before_ticket_ranges_still_load() {
+        #[derive(serde::Serialize)]
+        struct OldTicketPurchase {
+            owner: AccountOwner,
+            first_ticket: u64,
+            last_ticket: u64,
+            total_tickets: u64,
+            amount_paid: Amount,
+            source_chain_id: Option<String>,
+        }
+
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let old = OldTicketPurchase {
+            owner: alice,
+            first_ticket: 5,
+            last_ticket: 8,
+            total_tickets: 4,
+            amount_paid: Amount::from_tokens(4),
+            source_chain_id: Some("remote".to_string()),
+        };
+        let purchase: super::state::TicketPurchase = bcs::from_bytes(&bcs::to_bytes(&old).unwrap()).unwrap();
+
+        assert_eq!(purchase.owner, alice);
+        assert_eq!((purchase.first_ticket, purchase.last_ticket, purchase.total_tickets), (5, 8, 4));
+        assert_eq!(purchase.amount_paid, Amount::from_tokens(4));
+        assert_eq!(purchase.source_chain_id.as_deref(), Some("remote"));
+        // Each stored purchase was a single batch, so it spans one range
+        assert_eq!(purchase.ranges, vec![(5, 8)]);
+        assert_eq!(purchase.tickets().collect::<Vec<_>>(), vec![5, 6, 7, 8]);
+    }
+}
@@ -3,6 +3,8 @@
 
 /*! Lottery Rounds Application - Re-exports from lottery-abi */
 
+pub mod merkle;
+
 // Re-export everything from lottery-abi for this crate
 pub use lottery_abi::{
     // Lottery Rounds types
@@ -0,0 +1,102 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! SHA-256 Merkle tree over a round's winning tickets
+ *
+ * Nodes are stored in a flat array with the root at index 0 and the children of
+ * node `i` at `2i + 1` and `2i + 2`; the leaves occupy the last `n` slots. Pairs are
+ * hashed in sorted order, so a proof is just the list of sibling hashes.
+ */
+
+use linera_sdk::linera_base_types::{AccountOwner, Amount};
+use sha2::{Digest, Sha256};
+
+/// Leaf for a winning ticket: SHA-256 of (ticket_number, owner, prize_amount)
+pub fn winner_leaf(ticket_number: u64, owner: &AccountOwner, prize_amount: Amount) -> [u8; 32] {
+    let owner_bytes = linera_sdk::bcs::to_bytes(owner).expect("AccountOwner is serializable");
+    let mut hasher = Sha256::new();
+    hasher.update(ticket_number.to_be_bytes());
+    hasher.update(owner_bytes);
+    hasher.update(u128::from(prize_amount).to_be_bytes());
+    hasher.finalize().into()
+}
+
+fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut hasher = Sha256::new();
+    hasher.update(first);
+    hasher.update(second);
+    hasher.finalize().into()
+}
+
+/// Build the tree over `leaves` (which must not be empty); the root is `tree[0]`.
+/// Leaf `i` is stored at `tree.len() - 1 - i`.
+pub fn build_tree(leaves: Vec<[u8; 32]>) -> Vec<[u8; 32]> {
+    assert!(!leaves.is_empty(), "Cannot build a Merkle tree without leaves");
+    let size = 2 * leaves.len() - 1;
+    let mut tree = vec![[0u8; 32]; size];
+    for (i, leaf) in leaves.into_iter().enumerate() {
+        tree[size - 1 - i] = leaf;
+    }
+    for i in (0..size / 2).rev() {
+        tree[i] = hash_pair(&tree[2 * i + 1], &tree[2 * i + 2]);
+    }
+    tree
+}
+
+/// Sibling hashes from leaf `leaf_index` up to (excluding) the root
+pub fn generate_proof(tree: &[[u8; 32]], leaf_index: usize) -> Vec<[u8; 32]> {
+    let mut index = tree.len() - 1 - leaf_index;
+    let mut proof = Vec::new();
+    while index > 0 {
+        let sibling = if index % 2 == 1 { index + 1 } else { index - 1 };
+        proof.push(tree[sibling]);
+        index = (index - 1) / 2;
+    }
+    proof
+}
+
+/// Check that `leaf` is in the tree with the given `root`
+pub fn verify_proof(root: &[u8; 32], leaf: &[u8; 32], proof: &[[u8; 32]]) -> bool {
+    let computed = proof.iter().fold(*leaf, |node, sibling| hash_pair(&node, sibling));
+    computed == *root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_tree, generate_proof, verify_proof};
+
+    fn leaves(count: u8) -> Vec<[u8; 32]> {
+        (0..count).map(|i| [i; 32]).collect()
+    }
+
+    #[test]
+    fn test_single_leaf_is_the_root() {
+        let tree = build_tree(leaves(1));
+        assert_eq!(tree, vec![[0; 32]]);
+        let proof = generate_proof(&tree, 0);
+        assert!(proof.is_empty());
+        assert!(verify_proof(&tree[0], &[0; 32], &proof));
+        assert!(!verify_proof(&tree[0], &[1; 32], &proof));
+    }
+
+    #[test]
+    fn test_every_leaf_of_eight_is_provable() {
+        let tree = build_tree(leaves(8));
+        assert_eq!(tree.len(), 15);
+        for (i, leaf) in leaves(8).iter().enumerate() {
+            let proof = generate_proof(&tree, i);
+            assert_eq!(proof.len(), 3);
+            assert!(verify_proof(&tree[0], leaf, &proof));
+        }
+    }
+
+    #[test]
+    fn test_unbalanced_tree_and_foreign_leaf() {
+        let tree = build_tree(leaves(5));
+        for (i, leaf) in leaves(5).iter().enumerate() {
+            assert!(verify_proof(&tree[0], leaf, &generate_proof(&tree, i)));
+        }
+        assert!(!verify_proof(&tree[0], &[9; 32], &generate_proof(&tree, 0)));
+    }
+}
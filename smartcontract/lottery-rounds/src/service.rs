@@ -6,7 +6,8 @@
 mod state;
 
 use std::sync::Arc;
-use async_graphql::{EmptySubscription, Object, Request, Response, Schema};
+use base64::Engine as _;
+use async_graphql::{EmptySubscription, InputObject, Object, Request, Response, Schema};
 use linera_sdk::{
     linera_base_types::{AccountOwner, Amount, WithServiceAbi},
     views::View,
@@ -14,9 +15,19 @@ use linera_sdk::{
 };
 use lottery_abi::{
     LotteryRoundsAbi, LotteryRound, RoundStatus, WinnerPool, TicketPurchaseInfo, LotteryWinnerInfo,
-    LotteryRoundsParameters, LotteryRoundsOperation,
+    LotteryRoundsParameters, LotteryRoundsOperation, DuplicateResolution, MetricEntry,
+    LotteryRoundsConfig, DrawProgress, NotificationPreferences, PoolIntegrity, PoolWinners, PrizeSchedule, PurchaseQuote, RoundArchive, FeeTier, RoundPayoutSummary, UnclaimedPrizes, UserRoundParticipation, PlayerTotals, EscrowEntry,
+    WinningTicketInfo, WinVerification, OperationLogEntry, PendingItem, RoundFilter, SortOrder,
 };
-use self::state::{LotteryRoundsState, LotteryRound as StateLotteryRound, RoundStatus as StateRoundStatus, WinnerPool as StateWinnerPool};
+use winner_feed::FeedEntry;
+use self::state::{pool_prize_percentage, LotteryRoundsState, WinningTicket};
+
+/// Input type for a price schedule entry
+#[derive(InputObject, Clone)]
+pub struct PriceStepInput {
+    pub effective_from: u64,
+    pub ticket_price: Amount,
+}
 
 pub struct LotteryRoundsService {
     state: Arc<LotteryRoundsState>,
@@ -63,74 +74,71 @@ struct QueryRoot {
     runtime: Arc<ServiceRuntime<LotteryRoundsService>>,
 }
 
-// Conversion functions
-fn convert_round_status(status: StateRoundStatus) -> RoundStatus {
-    match status {
-        StateRoundStatus::Active => RoundStatus::Active,
-        StateRoundStatus::Closed => RoundStatus::Closed,
-        StateRoundStatus::Complete => RoundStatus::Complete,
+#[Object]
+impl QueryRoot {
+    /// Get the ID of the active lottery round
+    async fn active_round_id(&self) -> Option<u64> {
+        *self.state.active_round.get()
     }
-}
 
-fn convert_winner_pool(pool: StateWinnerPool) -> WinnerPool {
-    match pool {
-        StateWinnerPool::Pool1 => WinnerPool::Pool1,
-        StateWinnerPool::Pool2 => WinnerPool::Pool2,
-        StateWinnerPool::Pool3 => WinnerPool::Pool3,
-        StateWinnerPool::Pool4 => WinnerPool::Pool4,
-        StateWinnerPool::Complete => WinnerPool::Complete,
+    /// Status of the active round, else of the latest round while it is drawn
+    async fn current_round_status(&self) -> Option<RoundStatus> {
+        let round = self.state.current_round().await.ok()??;
+        Some(round.status)
     }
-}
 
-fn convert_round(round: StateLotteryRound) -> LotteryRound {
-    LotteryRound {
-        id: round.id,
-        created_at: round.created_at,
-        closed_at: round.closed_at,
-        status: convert_round_status(round.status),
-        ticket_price: round.ticket_price,
-        total_tickets_sold: round.total_tickets_sold,
-        next_ticket_number: round.next_ticket_number,
-        prize_pool: round.prize_pool,
-        current_winner_pool: convert_winner_pool(round.current_winner_pool),
-        pool1_count: round.pool1_count,
-        pool2_count: round.pool2_count,
-        pool3_count: round.pool3_count,
-        pool4_count: round.pool4_count,
-        pool1_winners_drawn: round.pool1_winners_drawn,
-        pool2_winners_drawn: round.pool2_winners_drawn,
-        pool3_winners_drawn: round.pool3_winners_drawn,
-        pool4_winners_drawn: round.pool4_winners_drawn,
+    /// Winner pool being drawn in the current round
+    async fn current_winner_pool(&self) -> Option<WinnerPool> {
+        let round = self.state.current_round().await.ok()??;
+        Some(round.current_winner_pool)
     }
-}
 
-#[Object]
-impl QueryRoot {
     /// Get the active lottery round
     async fn active_round(&self) -> Option<LotteryRound> {
         let round_id = self.state.get_active_round().await.ok()??;
         let round = self.state.get_round(round_id).await.ok()??;
-        Some(convert_round(round))
+        Some(round.to_lib(self.runtime.system_time().micros()))
     }
 
     /// Get a specific round by ID
     async fn round(&self, id: u64) -> Option<LotteryRound> {
         let round = self.state.get_round(id).await.ok()??;
-        Some(convert_round(round))
+        Some(round.to_lib(self.runtime.system_time().micros()))
     }
 
     /// Get all rounds
     async fn all_rounds(&self) -> Vec<LotteryRound> {
+        let now = self.runtime.system_time().micros();
         self.state.get_all_rounds().await
             .unwrap_or_default()
             .into_iter()
-            .map(convert_round)
+            .map(|round| round.to_lib(now))
             .collect()
     }
 
-    /// Get ticket purchases for a round
-    async fn round_ticket_purchases(&self, round_id: u64) -> Vec<TicketPurchaseInfo> {
-        self.state.get_round_ticket_purchases(round_id).await
+    /// Get up to `limit` rounds in a status, newest first
+    async fn rounds_by_status(&self, status: RoundStatus, limit: u64) -> Vec<LotteryRound> {
+        let now = self.runtime.system_time().micros();
+        self.state.get_rounds_by_status(status, limit).await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|round| round.to_lib(now))
+            .collect()
+    }
+
+    /// Up to `limit` rounds matching `filter` (all rounds when omitted), by ID in `order` (ascending by default)
+    async fn rounds(&self, filter: Option<RoundFilter>, order: Option<SortOrder>, limit: u64) -> Vec<LotteryRound> {
+        let now = self.runtime.system_time().micros();
+        self.state.filter_rounds(&filter.unwrap_or_default(), order.unwrap_or_default(), limit).await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|round| round.to_lib(now))
+            .collect()
+    }
+
+    /// Get ticket purchases for a round, sorted by first ticket when `order` is given
+    async fn round_ticket_purchases(&self, round_id: u64, order: Option<SortOrder>) -> Vec<TicketPurchaseInfo> {
+        let mut purchases: Vec<TicketPurchaseInfo> = self.state.get_round_ticket_purchases(round_id).await
             .unwrap_or_default()
             .into_iter()
             .map(|(owner, purchase)| TicketPurchaseInfo {
@@ -141,7 +149,11 @@ impl QueryRoot {
                 amount_paid: purchase.amount_paid,
                 source_chain_id: purchase.source_chain_id,
             })
-            .collect()
+            .collect();
+        if let Some(order) = order {
+            order.sort_by_key(&mut purchases, |purchase| purchase.first_ticket);
+        }
+        purchases
     }
 
     /// Get user tickets for a round
@@ -157,9 +169,108 @@ impl QueryRoot {
         })
     }
 
-    /// Get winners for a round
-    async fn round_winners(&self, round_id: u64) -> Vec<LotteryWinnerInfo> {
-        self.state.get_round_winners(round_id).await
+    /// Up to `limit` of an owner's ticket numbers above `after`, ascending (pages of at most 1000)
+    async fn owner_ticket_numbers(&self, round_id: u64, owner: AccountOwner, after: Option<u64>, limit: u64) -> Vec<u64> {
+        self.state.get_owner_ticket_numbers(round_id, owner, after, limit).await
+            .unwrap_or_default()
+    }
+
+    /// Get a user's participation across all retained rounds
+    async fn user_participation(&self, owner: AccountOwner) -> Vec<UserRoundParticipation> {
+        self.state.get_user_participation(owner).await.unwrap_or_default()
+    }
+    
+    /// Get a player's lifetime spend and winnings
+    async fn player_totals(&self, owner: AccountOwner) -> Option<PlayerTotals> {
+        self.state.get_player_totals(owner).await.ok().flatten()
+    }
+    
+    /// Quote how many tickets `amount` buys in the active round
+    async fn purchase_quote(&self, amount: String) -> async_graphql::Result<PurchaseQuote> {
+        let amount = amount.parse::<Amount>()
+            .map_err(|e| async_graphql::Error::new(format!("Invalid amount {}: {}", amount, e)))?;
+        Ok(self.state.quote_purchase(amount, self.runtime.system_time().micros()).await??)
+    }
+    
+    /// Winner pools of the active round as they stand with the current sales
+    async fn prize_schedule(&self) -> Option<PrizeSchedule> {
+        self.state.get_prize_schedule().await.ok().flatten()
+    }
+    
+    /// Get every round that still has undelivered prizes
+    async fn rounds_with_unclaimed_prizes(&self) -> Vec<UnclaimedPrizes> {
+        self.state.get_rounds_with_unclaimed_prizes().await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(round_id, unclaimed_winner_count, total_unclaimed_amount)| UnclaimedPrizes {
+                round_id,
+                unclaimed_winner_count,
+                total_unclaimed_amount,
+            })
+            .collect()
+    }
+    
+    /// Awarded, paid and unclaimed prize totals of a round
+    async fn round_payout_summary(&self, round_id: u64) -> Option<RoundPayoutSummary> {
+        self.state.round_payout_summary(round_id).await.ok()
+    }
+    
+    /// Get the sum of all undelivered prizes
+    async fn total_unclaimed_prizes_amount(&self) -> Amount {
+        self.state.get_total_unclaimed_prizes_amount().await.unwrap_or(Amount::ZERO)
+    }
+    
+    /// Get an owner's round notification preferences
+    async fn notification_preferences(&self, owner: AccountOwner) -> Option<NotificationPreferences> {
+        self.state.notification_subscriptions.get(&owner).await.ok().flatten()
+    }
+    
+    /// Observer chains closed rounds are replicated to
+    async fn sync_targets(&self) -> Vec<String> {
+        self.state.sync_targets().await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(_, chain_id)| chain_id)
+            .collect()
+    }
+    
+    /// Rounds replicated from the lottery chain (populated on observer chains)
+    async fn synced_rounds(&self) -> Vec<LotteryRound> {
+        self.state.get_synced_rounds().await.unwrap_or_default()
+    }
+    
+    /// A round replicated from the lottery chain, by ID
+    async fn synced_round(&self, round_id: u64) -> Option<LotteryRound> {
+        self.state.synced_rounds.get(&round_id).await.ok().flatten()
+    }
+    
+    /// Get a player's self-imposed per-round spend limit
+    async fn spend_limit(&self, owner: AccountOwner) -> Option<Amount> {
+        self.state.spend_limits.get(&owner).await.ok().flatten()
+    }
+    
+    /// Get winners for a round by ticket number, sorted by prize when `order` is given
+    async fn round_winners(&self, round_id: u64, order: Option<SortOrder>) -> Vec<LotteryWinnerInfo> {
+        let mut winners: Vec<LotteryWinnerInfo> = self.state.get_round_winners(round_id).await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(ticket_number, owner, prize_amount, claimed, source_chain_id)| LotteryWinnerInfo {
+                ticket_number,
+                owner,
+                prize_amount,
+                claimed,
+                source_chain_id,
+            })
+            .collect();
+        if let Some(order) = order {
+            order.sort_by_key(&mut winners, |winner| winner.prize_amount);
+        }
+        winners
+    }
+    
+    /// Get a round's winners whose ticket is in `start..=end` (at most 1000 tickets)
+    async fn winners_by_ticket_range(&self, round_id: u64, start: u64, end: u64) -> Vec<LotteryWinnerInfo> {
+        self.state.get_winners_by_ticket_range(round_id, start, end).await
             .unwrap_or_default()
             .into_iter()
             .map(|(ticket_number, owner, prize_amount, claimed, source_chain_id)| LotteryWinnerInfo {
@@ -172,6 +283,174 @@ impl QueryRoot {
             .collect()
     }
     
+    /// Get winners for a round grouped by pool, with each pool's share of the prize
+    async fn round_winners_by_pool(&self, round_id: u64) -> Vec<PoolWinners> {
+        self.state.get_round_winners_by_pool(round_id).await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(pool, winners)| PoolWinners {
+                pool,
+                prize_percentage: pool_prize_percentage(pool) as u64,
+                winners: winners.into_iter()
+                    .map(|(ticket_number, owner, prize_amount, claimed, source_chain_id)| LotteryWinnerInfo {
+                        ticket_number,
+                        owner,
+                        prize_amount,
+                        claimed,
+                        source_chain_id,
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+    
+    /// Get the owner of a ticket
+    async fn ticket_owner(&self, round_id: u64, ticket_number: u64) -> Option<AccountOwner> {
+        self.state.ticket_to_owner.get(&(round_id, ticket_number)).await.ok().flatten()
+    }
+    
+    /// Get the full record of a winning ticket, including its draw audit
+    async fn winning_ticket(&self, round_id: u64, ticket_number: u64) -> Option<WinningTicketInfo> {
+        let WinningTicket(owner, prize_amount, claimed, source_chain_id, pool) =
+            self.state.winning_tickets.get(&(round_id, ticket_number)).await.ok().flatten()?;
+        Some(WinningTicketInfo {
+            round_id,
+            ticket_number,
+            owner,
+            prize_amount,
+            claimed,
+            source_chain_id,
+            pool,
+            escrowed: self.state.get_escrowed_prizes(round_id, ticket_number).await.unwrap_or_default(),
+            draw: self.state.draw_audits.get(&(round_id, ticket_number)).await.ok().flatten(),
+        })
+    }
+    
+    /// Recompute a draw from its audit record and check the stored winner
+    async fn verify_win(&self, round_id: u64, ticket_number: u64) -> Option<WinVerification> {
+        self.state.verify_win(round_id, ticket_number).await.ok()
+    }
+    
+    /// Get the escrowed (undelivered) prizes of a winning ticket
+    async fn escrowed_prizes(&self, round_id: u64, ticket_number: u64) -> Vec<EscrowEntry> {
+        self.state.get_escrowed_prizes(round_id, ticket_number).await.unwrap_or_default()
+    }
+    
+    /// Payouts still waiting to reach their owner (at most MAX_PENDING_ITEMS)
+    async fn pending_items(&self, limit: u64) -> Vec<PendingItem> {
+        self.state.pending_items(limit).await.unwrap_or_default()
+    }
+    
+    /// Expired escrowed prizes available to WithdrawFees
+    async fn reclaimed_escrow(&self) -> Amount {
+        *self.state.reclaimed_escrow.get()
+    }
+    
+    /// Get the most recent lottery winners, newest first
+    async fn recent_winners(&self, limit: u64) -> Vec<FeedEntry> {
+        self.state.winner_feed.recent(limit).await.unwrap_or_default()
+    }
+    
+    /// Compare the sum of the four pool allocations with a round's prize pool
+    async fn pool_integrity(&self, round_id: u64) -> Option<PoolIntegrity> {
+        self.state.verify_pool_integrity(round_id).await.ok()
+    }
+    
+    /// Winners drawn so far out of those a round's pools expect
+    async fn draw_progress(&self, round_id: u64) -> Option<DrawProgress> {
+        self.state.draw_progress(round_id).await.ok()
+    }
+    
+    /// Export every ticket of a round as base64-encoded CSV
+    async fn round_export_csv(&self, round_id: u64) -> Option<String> {
+        let csv_bytes = self.state.export_round_csv(round_id).await.ok()?;
+        Some(base64::engine::general_purpose::STANDARD.encode(csv_bytes))
+    }
+    
+    /// Canonical archive of a completed round (the same data ExportRound serializes)
+    async fn round_archive(&self, round_id: u64) -> Option<RoundArchive> {
+        self.state.round_archive(round_id).await.ok()
+    }
+    
+    /// How drawn tickets with more than one owner are paid out
+    async fn duplicate_ticket_resolution(&self) -> DuplicateResolution {
+        *self.state.duplicate_ticket_resolution.get()
+    }
+    
+    /// Get every configuration register
+    async fn config(&self) -> Option<LotteryRoundsConfig> {
+        self.state.get_config().await.ok()
+    }
+    
+    /// Prize pool at which the active round closes automatically
+    async fn target_prize_pool(&self) -> Option<Amount> {
+        *self.state.target_prize_pool.get()
+    }
+    
+    /// Prize pool the active round needs before it may be closed
+    async fn min_prize_pool_to_close(&self) -> Option<Amount> {
+        *self.state.min_prize_pool_to_close.get()
+    }
+    
+    /// Lowest ticket price a new round may have
+    async fn ticket_price_floor(&self) -> Option<Amount> {
+        *self.state.ticket_price_floor.get()
+    }
+    
+    /// Highest ticket price a new round may have
+    async fn ticket_price_ceiling(&self) -> Option<Amount> {
+        *self.state.ticket_price_ceiling.get()
+    }
+    
+    /// Largest share of a round's tickets that may win, in basis points
+    async fn max_winner_pct_bps(&self) -> u16 {
+        self.state.max_winner_pct_bps()
+    }
+    
+    /// Whether an address can win at most one prize per round
+    async fn one_win_per_address(&self) -> bool {
+        *self.state.one_win_per_address.get()
+    }
+    
+    /// House fee tiers, lowest threshold first
+    async fn fee_tiers(&self) -> Vec<FeeTier> {
+        self.state.get_fee_tiers().await.unwrap_or_default()
+    }
+    
+    /// House fee in basis points when no fee tier applies
+    async fn default_fee_bps(&self) -> u16 {
+        *self.state.default_fee_bps.get()
+    }
+    
+    /// Account allowed to run admin operations
+    async fn admin(&self) -> Option<AccountOwner> {
+        *self.state.admin.get()
+    }
+    
+    /// Whether the purchase allowlist is enabled
+    async fn allowlist_enabled(&self) -> bool {
+        *self.state.purchase_allowlist_enabled.get()
+    }
+    
+    /// Check whether an owner is on the purchase allowlist
+    async fn is_allowlisted(&self, owner: AccountOwner) -> bool {
+        self.state.is_allowlisted(&owner).await.unwrap_or(false)
+    }
+    
+    /// Up to `limit` operation log entries from position `offset`, oldest first
+    async fn operation_log(&self, offset: u64, limit: u64) -> Vec<OperationLogEntry> {
+        self.state.get_operation_log(offset, limit).await.unwrap_or_default()
+    }
+    
+    /// Get operation/message counters sorted by name
+    async fn metrics(&self) -> Vec<MetricEntry> {
+        self.state.get_metrics().await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, count)| MetricEntry { name, count })
+            .collect()
+    }
+    
     /// Get the configured Native app ID
     async fn native_app_id(&self) -> String {
         let params = self.runtime.application_parameters();
@@ -190,10 +469,14 @@ struct MutationRoot {
 
 #[Object]
 impl MutationRoot {
-    /// Create a new lottery round with the specified ticket price
-    async fn create_round(&self, ticket_price: String) -> String {
+    /// Create a new lottery round with the specified ticket price, optionally changing
+    /// to each `price_schedule` price from its `effective_from` time (microseconds)
+    async fn create_round(&self, ticket_price: String, price_schedule: Option<Vec<PriceStepInput>>) -> String {
         self.runtime.schedule_operation(&LotteryRoundsOperation::CreateRound {
             ticket_price: ticket_price.parse::<Amount>().unwrap_or_default(),
+            price_schedule: price_schedule.unwrap_or_default().into_iter()
+                .map(|step| (step.effective_from, step.ticket_price))
+                .collect(),
         });
         "CreateRound operation scheduled".to_string()
     }
@@ -212,6 +495,13 @@ impl MutationRoot {
         format!("GenerateWinner operation scheduled for round {}", round_id)
     }
     
+    /// Give some of the signer's tickets in a round to another owner
+    async fn gift_tickets(&self, round_id: u64, to: AccountOwner, ticket_numbers: Vec<u64>) -> String {
+        let count = ticket_numbers.len();
+        self.runtime.schedule_operation(&LotteryRoundsOperation::TransferTickets { round_id, to, ticket_numbers });
+        format!("TransferTickets operation scheduled: {} tickets of round {} to {}", count, round_id, to)
+    }
+    
     /// Set the Lottery App ID for cross-app calls
     async fn set_lottery_app_id(&self, lottery_app_id: String) -> String {
         self.runtime.schedule_operation(&LotteryRoundsOperation::SetLotteryAppId {
@@ -219,4 +509,154 @@ impl MutationRoot {
         });
         format!("SetLotteryAppId operation scheduled: {}", lottery_app_id)
     }
+    
+    /// Set how drawn tickets with more than one owner are paid out
+    async fn set_duplicate_resolution(&self, resolution: DuplicateResolution) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::SetDuplicateResolution { resolution });
+        format!("SetDuplicateResolution operation scheduled: {:?}", resolution)
+    }
+    
+    /// Close the active round once its prize pool reaches `amount` (null disables)
+    async fn set_target_prize_pool(&self, amount: Option<Amount>) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::SetTargetPrizePool { amount });
+        format!("SetTargetPrizePool operation scheduled: {:?}", amount)
+    }
+    
+    /// Refuse to close the active round until its prize pool reaches `amount` (null disables)
+    async fn set_min_prize_pool_to_close(&self, amount: Option<Amount>) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::SetMinPrizePoolToClose { amount });
+        format!("SetMinPrizePoolToClose operation scheduled: {:?}", amount)
+    }
+    
+    /// Refuse new rounds priced below `floor` (null allows any positive price)
+    async fn set_ticket_price_floor(&self, floor: Option<Amount>) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::SetTicketPriceFloor { floor });
+        format!("SetTicketPriceFloor operation scheduled: {:?}", floor)
+    }
+    
+    /// Refuse new rounds priced above `ceiling` (null removes the ceiling)
+    async fn set_ticket_price_ceiling(&self, ceiling: Option<Amount>) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::SetTicketPriceCeiling { ceiling });
+        format!("SetTicketPriceCeiling operation scheduled: {:?}", ceiling)
+    }
+    
+    /// Refuse to close rounds whose winners would exceed `bps` of the tickets sold
+    async fn set_max_winner_pct_bps(&self, bps: u16) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::SetMaxWinnerPctBps { bps });
+        format!("SetMaxWinnerPctBps operation scheduled: {} bps", bps)
+    }
+    
+    /// Let an address win at most one prize per round
+    async fn set_one_win_per_address(&self, enabled: bool) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::SetOneWinPerAddress { enabled });
+        format!("SetOneWinPerAddress operation scheduled: {}", enabled)
+    }
+    
+    /// Charge `fee_bps` on purchases into rounds whose prize pool holds at least `min_pool_tokens`
+    async fn add_fee_tier(&self, min_pool_tokens: u64, fee_bps: u16) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::AddFeeTier { min_pool_tokens, fee_bps });
+        format!("AddFeeTier operation scheduled: {} bps from {} tokens", fee_bps, min_pool_tokens)
+    }
+    
+    /// Remove the fee tier at `min_pool_tokens`
+    async fn remove_fee_tier(&self, min_pool_tokens: u64) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::RemoveFeeTier { min_pool_tokens });
+        format!("RemoveFeeTier operation scheduled: {} tokens", min_pool_tokens)
+    }
+    
+    /// House fee charged when no fee tier applies
+    async fn set_default_fee_bps(&self, fee_bps: u16) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::SetDefaultFeeBps { fee_bps });
+        format!("SetDefaultFeeBps operation scheduled: {} bps", fee_bps)
+    }
+    
+    /// Get alerts when rounds close and/or winners are drawn (both false unsubscribes)
+    async fn set_notification_preferences(
+        &self,
+        notify_on_round_close: bool,
+        notify_on_winner_drawn: bool,
+        destination_chain_id: Option<String>,
+    ) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::SetNotificationPreferences {
+            prefs: NotificationPreferences { notify_on_round_close, notify_on_winner_drawn, destination_chain_id },
+        });
+        "SetNotificationPreferences operation scheduled".to_string()
+    }
+    
+    /// Replicate every round that closes to observer chain `chain_id` (admin only)
+    async fn add_sync_target(&self, chain_id: String) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::AddSyncTarget { chain_id: chain_id.clone() });
+        format!("AddSyncTarget operation scheduled: {}", chain_id)
+    }
+    
+    /// Stop replicating rounds to `chain_id` (admin only)
+    async fn remove_sync_target(&self, chain_id: String) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::RemoveSyncTarget { chain_id: chain_id.clone() });
+        format!("RemoveSyncTarget operation scheduled: {}", chain_id)
+    }
+    
+    /// Zero all operation/message counters
+    async fn reset_metrics(&self) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::ResetMetrics);
+        "ResetMetrics operation scheduled".to_string()
+    }
+    
+    /// Hand the admin role to another account (admin only)
+    async fn transfer_admin_role(&self, new_admin: AccountOwner) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::TransferAdminRole { new_admin });
+        format!("TransferAdminRole operation scheduled: {}", new_admin)
+    }
+    
+    /// Set (or clear) your own per-round spend limit
+    async fn set_my_spend_limit(&self, per_round: Option<String>) -> String {
+        let per_round = per_round.map(|limit| limit.parse::<Amount>().unwrap_or_default());
+        self.runtime.schedule_operation(&LotteryRoundsOperation::SetMySpendLimit { per_round });
+        match per_round {
+            Some(limit) => format!("SetMySpendLimit operation scheduled: {}", limit),
+            None => "SetMySpendLimit operation scheduled: no limit".to_string(),
+        }
+    }
+    
+    /// Deliver a winner's escrowed prize for a winning ticket, paid from the admin's account (admin only)
+    async fn claim_escrowed_prize(&self, round_id: u64, ticket_number: u64, owner: AccountOwner) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::ClaimEscrowedPrize { round_id, ticket_number, owner });
+        format!("ClaimEscrowedPrize operation scheduled for ticket {} of round {}", ticket_number, round_id)
+    }
+    
+    /// Reclaim expired escrowed prizes of a winning ticket
+    async fn expire_escrow(&self, round_id: u64, ticket_number: u64) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::ExpireEscrow { round_id, ticket_number });
+        format!("ExpireEscrow operation scheduled for ticket {} of round {}", ticket_number, round_id)
+    }
+    
+    /// Send the reclaimed escrow balance to a recipient (creator chain only)
+    async fn withdraw_fees(&self, recipient: AccountOwner, destination_chain: Option<String>) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::WithdrawFees { recipient, destination_chain });
+        "WithdrawFees operation scheduled".to_string()
+    }
+    
+    /// Enable the purchase allowlist (only approved owners can buy tickets)
+    async fn enable_allowlist(&self) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::EnableAllowlist);
+        "EnableAllowlist operation scheduled".to_string()
+    }
+    
+    /// Disable the purchase allowlist (anyone can buy tickets)
+    async fn disable_allowlist(&self) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::DisableAllowlist);
+        "DisableAllowlist operation scheduled".to_string()
+    }
+    
+    /// Approve owners to purchase tickets
+    async fn add_to_allowlist(&self, addresses: Vec<AccountOwner>) -> String {
+        let count = addresses.len();
+        self.runtime.schedule_operation(&LotteryRoundsOperation::AddToAllowlist { addresses });
+        format!("AddToAllowlist operation scheduled for {} addresses", count)
+    }
+    
+    /// Revoke an owner's purchase approval
+    async fn remove_from_allowlist(&self, address: AccountOwner) -> String {
+        self.runtime.schedule_operation(&LotteryRoundsOperation::RemoveFromAllowlist { address });
+        "RemoveFromAllowlist operation scheduled".to_string()
+    }
 }
@@ -1,30 +1,128 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext, ViewError};
-use linera_sdk::linera_base_types::{AccountOwner, Amount, ApplicationId};
+use linera_sdk::views::{linera_views, LogView, MapView, RegisterView, RootView, View, ViewStorageContext, ViewError};
+use linera_sdk::linera_base_types::{AccountOwner, Amount, ApplicationId, ChainId};
 use serde::{Deserialize, Serialize};
 use async_graphql::SimpleObject;
 use num_bigint::BigUint;
 use num_traits::cast::ToPrimitive;
+use view_batch::BatchLoader;
+use winner_feed::{FeedEntry, Game, WinnerFeed};
+use lottery_abi::{
+    checked_percentage, csv::CsvWriter, tickets_for_amount, TicketCountError, validate_price_schedule, DrawAudit, DuplicateResolution, NotificationPreferences, PurchaseQuote, QuoteError, EscrowEntry, LotteryRoundsConfig, LotteryWinnerInfo, MerkleProof, OwnerPayout,
+    DrawProgress, FeeTier, PayoutTotals, PendingItem, PendingKind, PlayerTotals, PoolIntegrity, PoolSchedule, PrizeSchedule, RoundArchive, RoundCloseMode, RoundCloseTrigger, RoundFilter, RoundPayoutSummary, SortOrder, TicketPurchaseInfo, UserRoundParticipation, WinVerification,
+    DEFAULT_MAX_WINNER_PCT_BPS, ESCROW_DURATION_MICROS, MAX_HOUSE_FEE_BPS, MAX_NOTIFICATION_SUBSCRIBERS, MAX_PENDING_ITEMS, MAX_SYNC_TARGETS, MAX_TICKET_NUMBERS_PAGE, MAX_TICKETS_PER_TRANSFER, MAX_WINNER_TICKET_RANGE,
+    MAX_OPERATION_LOG_PAGE, MIN_MAX_WINNER_PCT_BPS, OperationLogEntry,
+};
+use lottery_rounds::merkle;
+// Stored in their ABI layout; serde output matches the former state-local copies
+pub use lottery_abi::{RoundStatus, WinnerPool};
 
-/// Calculate prize amount for a specific winner pool
-/// Returns the portion of prize pool allocated to this pool
-fn calculate_prize_for_pool(prize_pool: Amount, pool: WinnerPool) -> Amount {
-    let prize_u128: u128 = u128::from(prize_pool);
-    
-    let percentage = match pool {
+/// Percentage of the prize pool allocated to a winner pool
+pub fn pool_prize_percentage(pool: WinnerPool) -> u128 {
+    match pool {
         WinnerPool::Pool1 => 20, // 15% of tickets get 20% of prize
         WinnerPool::Pool2 => 25, // 7% of tickets get 25% of prize
         WinnerPool::Pool3 => 30, // 5% of tickets get 30% of prize
         WinnerPool::Pool4 => 25, // 3% of tickets get 25% of prize
         WinnerPool::Complete => 0,
-    };
+    }
+}
+
+/// Percentage of the sold tickets that win in a winner pool
+pub fn pool_ticket_percentage(pool: WinnerPool) -> u64 {
+    match pool {
+        WinnerPool::Pool1 => 15,
+        WinnerPool::Pool2 => 7,
+        WinnerPool::Pool3 => 5,
+        WinnerPool::Pool4 => 3,
+        WinnerPool::Complete => 0,
+    }
+}
+
+/// Share of the sold tickets that may win across all pools, in basis points
+const WINNER_BUDGET_BPS: u32 = 3_000;
+
+/// Share of the prize pool refunded to ticket holders when a round closes with too few tickets
+const LOW_TICKET_REFUND_PERCENTAGE: u128 = 70;
+
+/// Close mode and number of winners in each pool (Pool1 to Pool4) for a round that sold
+/// `total_tickets`. The 30% winner budget is handed out Pool4 first (highest prize per
+/// winner); each pool asks for its ticket percentage, and at least one winner. A budget
+/// below one winner per pool closes the round with a partial refund and no winners.
+pub fn allocate_winner_pools(total_tickets: u64) -> (RoundCloseMode, [u64; 4]) {
+    let mut budget = checked_percentage(total_tickets, WINNER_BUDGET_BPS);
+    if budget < 4 {
+        return (RoundCloseMode::PartialRefundLowTickets, [0; 4]);
+    }
+    let mut counts = [0; 4];
+    for (index, pool) in [WinnerPool::Pool1, WinnerPool::Pool2, WinnerPool::Pool3, WinnerPool::Pool4].into_iter().enumerate().rev() {
+        let wanted = checked_percentage(total_tickets, pool_ticket_percentage(pool) as u32 * 100).max(1);
+        counts[index] = wanted.min(budget);
+        budget -= counts[index];
+    }
+    (RoundCloseMode::Normal, counts)
+}
+
+/// Number of winners in each pool (Pool1 to Pool4) for a round that sold `total_tickets`
+pub fn winner_pool_counts(total_tickets: u64) -> [u64; 4] {
+    allocate_winner_pools(total_tickets).1
+}
+
+/// Smallest ticket count from `total_tickets` upwards whose winners stay within `max_bps`
+/// of the tickets (None if `max_bps` is below what the pools always draw)
+pub fn min_tickets_for_winner_share(total_tickets: u64, max_bps: u16) -> Option<u64> {
+    // From 34 tickets on every pool has winners by percentage alone, 30% in total
+    (total_tickets..=total_tickets.max(34)).find(|&tickets| winner_share_allowed(tickets, max_bps))
+}
+
+/// Whether the winners of a round selling `total_tickets` stay within `max_bps` of the tickets
+fn winner_share_allowed(total_tickets: u64, max_bps: u16) -> bool {
+    let winners: u64 = winner_pool_counts(total_tickets).iter().sum();
+    u128::from(winners) * 10_000 <= u128::from(total_tickets) * u128::from(max_bps)
+}
+
+/// Pick the winning ticket for `vrf_value` among tickets 1..=total_tickets_sold, skipping
+/// tickets that already won. Deterministic, so a draw can be recomputed from its DrawAudit.
+pub fn select_winning_ticket(vrf_value: u64, total_tickets_sold: u64, previous_winners: &std::collections::HashSet<u64>) -> Option<u64> {
+    if total_tickets_sold == 0 {
+        return None;
+    }
+    (0..total_tickets_sold.saturating_mul(2))
+        .map(|attempt| (vrf_value.wrapping_add(attempt) % total_tickets_sold) + 1) // Tickets are 1-indexed
+        .find(|ticket| !previous_winners.contains(ticket))
+}
+
+/// Calculate prize amount for a specific winner pool
+/// Returns the portion of prize pool allocated to this pool
+fn calculate_prize_for_pool(prize_pool: Amount, pool: WinnerPool) -> Amount {
+    let prize_u128: u128 = u128::from(prize_pool);
+    
+    let percentage = pool_prize_percentage(pool);
     
     let pool_prize = (prize_u128 * percentage) / 100;
     Amount::from_attos(pool_prize)
 }
 
+/// Sum of the allocations of all four winner pools
+/// Pool percentages add up to 100%, so this equals `prize_pool` minus integer-division dust
+fn total_pool_allocation(prize_pool: Amount) -> Amount {
+    let allocated = [WinnerPool::Pool1, WinnerPool::Pool2, WinnerPool::Pool3, WinnerPool::Pool4]
+        .into_iter()
+        .fold(Amount::ZERO, |total, pool| total.saturating_add(calculate_prize_for_pool(prize_pool, pool)));
+    debug_assert!(allocated <= prize_pool, "Pool allocations exceed the prize pool");
+    allocated
+}
+
+/// `bps` basis points of `amount`, rounded down
+fn house_fee(amount: Amount, bps: u16) -> Amount {
+    let attos = u128::from(amount);
+    let bps = u128::from(bps);
+    // Split so the multiplication cannot overflow
+    Amount::from_attos(attos / 10_000 * bps + attos % 10_000 * bps / 10_000)
+}
+
 /// Calculate individual prize per winner in a pool
 /// Returns prize_for_pool / number_of_winners_in_pool
 fn calculate_prize_per_winner(prize_pool: Amount, pool: WinnerPool, winners_in_pool: u64) -> Amount {
@@ -59,66 +157,345 @@ pub struct LotteryRoundsState {
     pub ticket_purchases: MapView<(u64, AccountOwner), TicketPurchase>,
     /// Mapping from ticket number to owner
     pub ticket_to_owner: MapView<(u64, u64), AccountOwner>,
-    /// Winning tickets with prize info: (round_id, ticket_number) -> (owner, prize_amount, claimed, source_chain_id)
-    pub winning_tickets: MapView<(u64, u64), (AccountOwner, Amount, bool, Option<String>)>,
+    /// Winning tickets with prize info: (round_id, ticket_number) -> (owner, prize_amount, claimed, source_chain_id, pool)
+    pub winning_tickets: MapView<(u64, u64), WinningTicket>,
     /// Lottery App ID for cross-app calls (stored as string, parsed at runtime)
     pub lottery_app_id: RegisterView<Option<ApplicationId<lottery_abi::LotteryAppAbi>>>,
+    /// Account allowed to run admin operations (None = anyone, for apps created before admins existed)
+    pub admin: RegisterView<Option<AccountOwner>>,
+    /// When enabled, only allowlisted owners can purchase tickets
+    pub purchase_allowlist_enabled: RegisterView<bool>,
+    /// Owners approved to purchase tickets while the allowlist is enabled
+    pub purchase_allowlist: MapView<AccountOwner, bool>,
+    /// How drawn tickets with more than one owner are paid out
+    pub duplicate_ticket_resolution: RegisterView<DuplicateResolution>,
+    /// Tickets sold to more than one owner: (round_id, ticket_number) -> claimants (built on close)
+    pub duplicate_tickets: MapView<(u64, u64), Vec<AccountOwner>>,
+    /// Operation/message counters keyed by metric name
+    pub metrics: MapView<String, u64>,
+    /// Lifetime spend and winnings per player
+    pub player_totals: MapView<AccountOwner, PlayerTotals>,
+    /// Self-imposed per-round spend limits
+    pub spend_limits: MapView<AccountOwner, Amount>,
+    /// Amount spent per round and player (for spend limit enforcement)
+    pub round_spend: MapView<(u64, AccountOwner), Amount>,
+    /// Undeliverable prizes: (round_id, ticket_number) -> one entry per claimant
+    pub escrow: MapView<(u64, u64), Vec<EscrowEntry>>,
+    /// House fees, expired escrowed prizes and the unrefunded share of low-ticket rounds, withdrawable via WithdrawFees
+    pub reclaimed_escrow: RegisterView<Amount>,
+    /// Recent winners shown on the homepage ticker
+    pub winner_feed: WinnerFeed<ViewStorageContext>,
+    /// Secondary index of rounds by status: (status key, round_id)
+    pub rounds_by_status: MapView<(u8, u64), ()>,
+    /// Merkle root over the winning tickets of each completed round
+    pub winner_merkle_root: MapView<u64, [u8; 32]>,
+    /// Close the active round once its prize pool reaches this amount
+    pub target_prize_pool: RegisterView<Option<Amount>>,
+    /// CloseRound is refused while the active round's prize pool is below this amount
+    pub min_prize_pool_to_close: RegisterView<Option<Amount>>,
+    /// Lowest ticket price a new round may have (None = any positive price)
+    pub ticket_price_floor: RegisterView<Option<Amount>>,
+    /// Highest ticket price a new round may have (None = no ceiling)
+    pub ticket_price_ceiling: RegisterView<Option<Amount>>,
+    /// Largest share of tickets that may win, in basis points (None = DEFAULT_MAX_WINNER_PCT_BPS)
+    pub max_winner_pct_bps: RegisterView<Option<u16>>,
+    /// House fee tiers: minimum prize pool in whole tokens -> fee in basis points
+    pub fee_tiers: MapView<u64, u16>,
+    /// House fee in basis points when no fee tier applies
+    pub default_fee_bps: RegisterView<u16>,
+    /// Undelivered prizes per round: round_id -> (unclaimed winner count, total unclaimed amount)
+    pub rounds_with_unclaimed: MapView<u64, (u64, Amount)>,
+    /// Awarded and delivered prize totals per round
+    pub round_payouts: MapView<u64, PayoutTotals>,
+    /// Round notification preferences per subscribed owner
+    pub notification_subscriptions: MapView<AccountOwner, NotificationPreferences>,
+    /// Owners who bought tickets in each round, for per-owner cleanup
+    pub round_participants: MapView<u64, Vec<AccountOwner>>,
+    /// Seed inputs of each draw: (round_id, ticket_number) -> audit record
+    pub draw_audits: MapView<(u64, u64), DrawAudit>,
+    /// Skip tickets of owners who already won the round when drawing
+    pub one_win_per_address: RegisterView<bool>,
+    /// Owners who won a prize in each round: (round_id, owner) -> has_won
+    pub existing_winners_by_owner: MapView<(u64, AccountOwner), bool>,
+    /// Observer chains sent each round's state when it closes: index -> chain_id
+    pub sync_target_chains: MapView<u64, String>,
+    /// Rounds replicated from the lottery chain (on observer chains), by round ID
+    pub synced_rounds: MapView<u64, lottery_abi::LotteryRound>,
+    /// Every round creation, purchase, close and winner draw, in execution order
+    pub operation_log: LogView<OperationLogEntry>,
 }
 
 
-/// A lottery round
-#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
-pub struct LotteryRound {
-    pub id: u64,
-    pub created_at: u64,
-    pub closed_at: Option<u64>,
-    pub status: RoundStatus,
-    pub ticket_price: Amount,
-    pub total_tickets_sold: u64,
-    pub next_ticket_number: u64,
-    pub prize_pool: Amount,
-    pub current_winner_pool: WinnerPool,
-    
-    // Winner pool sizes (calculated when round closes)
-    pub pool1_count: u64,  // 15% of tickets
-    pub pool2_count: u64,  // 7% of tickets
-    pub pool3_count: u64,  // 5% of tickets
-    pub pool4_count: u64,  // 3% of tickets
-    
-    // Winner pool progress
-    pub pool1_winners_drawn: u64,
-    pub pool2_winners_drawn: u64,
-    pub pool3_winners_drawn: u64,
-    pub pool4_winners_drawn: u64,
+/// A winning ticket: (owner, prize_amount, claimed, source_chain_id, pool).
+/// Entries written before the pool was recorded read it as None.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WinningTicket(
+    pub AccountOwner,
+    pub Amount,
+    pub bool,
+    pub Option<String>,
+    #[serde(default, deserialize_with = "lottery_abi::stored_fields::default_if_missing")]
+    pub Option<WinnerPool>,
+);
+
+/// A lottery round as the state keeps it: the ABI round, stored in the layout of StoredLotteryRound.
+/// `current_ticket_price` is not stored and holds the base price until to_lib prices the round.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "StoredLotteryRound", into = "StoredLotteryRound")]
+pub struct LotteryRound(lottery_abi::LotteryRound);
+
+impl std::ops::Deref for LotteryRound {
+    type Target = lottery_abi::LotteryRound;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for LotteryRound {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Serialized form of LotteryRound: the original fields in their original order, then
+/// every later field. Values written before a trailing field existed read it as its default.
+#[derive(Serialize, Deserialize)]
+struct StoredLotteryRound {
+    id: u64,
+    created_at: u64,
+    closed_at: Option<u64>,
+    status: RoundStatus,
+    ticket_price: Amount,
+    total_tickets_sold: u64,
+    next_ticket_number: u64,
+    prize_pool: Amount,
+    current_winner_pool: WinnerPool,
+    pool1_count: u64,
+    pool2_count: u64,
+    pool3_count: u64,
+    pool4_count: u64,
+    pool1_winners_drawn: u64,
+    pool2_winners_drawn: u64,
+    pool3_winners_drawn: u64,
+    pool4_winners_drawn: u64,
+    #[serde(default, deserialize_with = "lottery_abi::stored_fields::default_if_missing")]
+    triggered_by: Option<RoundCloseTrigger>,
+    #[serde(default, deserialize_with = "lottery_abi::stored_fields::default_if_missing")]
+    close_mode: RoundCloseMode,
+    #[serde(default, deserialize_with = "lottery_abi::stored_fields::default_if_missing")]
+    price_schedule: Vec<(u64, Amount)>,
+    #[serde(default, deserialize_with = "lottery_abi::stored_fields::default_if_missing")]
+    unique_buyers: u64,
+}
+
+impl From<StoredLotteryRound> for LotteryRound {
+    fn from(stored: StoredLotteryRound) -> Self {
+        LotteryRound(lottery_abi::LotteryRound {
+            id: stored.id,
+            created_at: stored.created_at,
+            closed_at: stored.closed_at,
+            status: stored.status,
+            ticket_price: stored.ticket_price,
+            total_tickets_sold: stored.total_tickets_sold,
+            next_ticket_number: stored.next_ticket_number,
+            prize_pool: stored.prize_pool,
+            current_winner_pool: stored.current_winner_pool,
+            pool1_count: stored.pool1_count,
+            pool2_count: stored.pool2_count,
+            pool3_count: stored.pool3_count,
+            pool4_count: stored.pool4_count,
+            pool1_winners_drawn: stored.pool1_winners_drawn,
+            pool2_winners_drawn: stored.pool2_winners_drawn,
+            pool3_winners_drawn: stored.pool3_winners_drawn,
+            pool4_winners_drawn: stored.pool4_winners_drawn,
+            triggered_by: stored.triggered_by,
+            close_mode: stored.close_mode,
+            price_schedule: stored.price_schedule.into_iter()
+                .map(|(effective_from, ticket_price)| lottery_abi::PriceStep { effective_from, ticket_price })
+                .collect(),
+            current_ticket_price: stored.ticket_price,
+            unique_buyers: stored.unique_buyers,
+        })
+    }
 }
 
-/// Status of a lottery round
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
-pub enum RoundStatus {
-    Active,   // Accepting ticket purchases
-    Closed,   // Not accepting purchases, drawing winners
-    Complete, // All winners drawn
+impl From<LotteryRound> for StoredLotteryRound {
+    fn from(LotteryRound(round): LotteryRound) -> Self {
+        StoredLotteryRound {
+            id: round.id,
+            created_at: round.created_at,
+            closed_at: round.closed_at,
+            status: round.status,
+            ticket_price: round.ticket_price,
+            total_tickets_sold: round.total_tickets_sold,
+            next_ticket_number: round.next_ticket_number,
+            prize_pool: round.prize_pool,
+            current_winner_pool: round.current_winner_pool,
+            pool1_count: round.pool1_count,
+            pool2_count: round.pool2_count,
+            pool3_count: round.pool3_count,
+            pool4_count: round.pool4_count,
+            pool1_winners_drawn: round.pool1_winners_drawn,
+            pool2_winners_drawn: round.pool2_winners_drawn,
+            pool3_winners_drawn: round.pool3_winners_drawn,
+            pool4_winners_drawn: round.pool4_winners_drawn,
+            triggered_by: round.triggered_by,
+            close_mode: round.close_mode,
+            price_schedule: round.price_schedule.into_iter()
+                .map(|step| (step.effective_from, step.ticket_price))
+                .collect(),
+            unique_buyers: round.unique_buyers,
+        }
+    }
 }
 
-/// Winner pool identifier
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
-pub enum WinnerPool {
-    Pool1,    // 15% of tickets, 20% of prize
-    Pool2,    // 7% of tickets, 25% of prize
-    Pool3,    // 5% of tickets, 30% of prize
-    Pool4,    // 3% of tickets, 25% of prize
-    Complete, // All winners drawn
+impl LotteryRound {
+    /// Ticket price a purchase at `timestamp` pays
+    pub fn ticket_price_at(&self, timestamp: u64) -> Amount {
+        let schedule: Vec<(u64, Amount)> = self.price_schedule.iter()
+            .map(|step| (step.effective_from, step.ticket_price))
+            .collect();
+        lottery_abi::effective_ticket_price(self.ticket_price, &schedule, timestamp)
+    }
+
+    /// Winners of `current_winner_pool` still to be drawn (zero once Complete)
+    pub fn remaining_in_current_pool(&self) -> u64 {
+        match self.current_winner_pool {
+            WinnerPool::Pool1 => self.pool1_count.saturating_sub(self.pool1_winners_drawn),
+            WinnerPool::Pool2 => self.pool2_count.saturating_sub(self.pool2_winners_drawn),
+            WinnerPool::Pool3 => self.pool3_count.saturating_sub(self.pool3_winners_drawn),
+            WinnerPool::Pool4 => self.pool4_count.saturating_sub(self.pool4_winners_drawn),
+            WinnerPool::Complete => 0,
+        }
+    }
+
+    /// Number of winning tickets in each of the four pools
+    pub fn pool_counts(&self) -> Vec<(WinnerPool, u64)> {
+        vec![
+            (WinnerPool::Pool1, self.pool1_count),
+            (WinnerPool::Pool2, self.pool2_count),
+            (WinnerPool::Pool3, self.pool3_count),
+            (WinnerPool::Pool4, self.pool4_count),
+        ]
+    }
+
+    /// The library representation of this round, priced at `now`
+    pub fn to_lib(&self, now: u64) -> lottery_abi::LotteryRound {
+        lottery_abi::LotteryRound {
+            current_ticket_price: self.ticket_price_at(now),
+            ..self.0.clone()
+        }
+    }
 }
 
-/// A user's ticket purchase
+/// A user's tickets in a round: every batch they bought plus the tickets gifted to them
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[serde(from = "StoredTicketPurchase", into = "StoredTicketPurchase")]
 pub struct TicketPurchase {
     pub owner: AccountOwner,
+    /// Lowest ticket number held
     pub first_ticket: u64,
+    /// Highest ticket number held
     pub last_ticket: u64,
     pub total_tickets: u64,
     pub amount_paid: Amount,
     pub source_chain_id: Option<String>,
+    /// Held tickets as ascending, non-adjacent (first, last) ranges
+    #[graphql(skip)]
+    pub ranges: Vec<(u64, u64)>,
+}
+
+/// Serialized form of TicketPurchase: the original fields, then the held ranges. A purchase
+/// stored before ranges existed was one batch, so it reads as the single range it spans.
+#[derive(Serialize, Deserialize)]
+struct StoredTicketPurchase {
+    owner: AccountOwner,
+    first_ticket: u64,
+    last_ticket: u64,
+    total_tickets: u64,
+    amount_paid: Amount,
+    source_chain_id: Option<String>,
+    #[serde(default, deserialize_with = "lottery_abi::stored_fields::default_if_missing")]
+    ranges: Vec<(u64, u64)>,
+}
+
+impl From<StoredTicketPurchase> for TicketPurchase {
+    fn from(stored: StoredTicketPurchase) -> Self {
+        let ranges = if stored.ranges.is_empty() && stored.total_tickets > 0 {
+            vec![(stored.first_ticket, stored.last_ticket)]
+        } else {
+            stored.ranges
+        };
+        TicketPurchase {
+            owner: stored.owner,
+            first_ticket: stored.first_ticket,
+            last_ticket: stored.last_ticket,
+            total_tickets: stored.total_tickets,
+            amount_paid: stored.amount_paid,
+            source_chain_id: stored.source_chain_id,
+            ranges,
+        }
+    }
+}
+
+impl From<TicketPurchase> for StoredTicketPurchase {
+    fn from(purchase: TicketPurchase) -> Self {
+        StoredTicketPurchase {
+            owner: purchase.owner,
+            first_ticket: purchase.first_ticket,
+            last_ticket: purchase.last_ticket,
+            total_tickets: purchase.total_tickets,
+            amount_paid: purchase.amount_paid,
+            source_chain_id: purchase.source_chain_id,
+            ranges: purchase.ranges,
+        }
+    }
+}
+
+impl TicketPurchase {
+    /// Every ticket number held, ascending
+    pub fn tickets(&self) -> impl Iterator<Item = u64> + '_ {
+        self.ranges.iter().flat_map(|&(first, last)| first..=last)
+    }
+    
+    /// Add tickets `first..=last`, merging them with the ranges they touch
+    pub fn add_range(&mut self, first: u64, last: u64) {
+        self.ranges.push((first, last));
+        self.ranges.sort_unstable();
+        let mut merged: Vec<(u64, u64)> = Vec::with_capacity(self.ranges.len());
+        for (first, last) in self.ranges.drain(..) {
+            match merged.last_mut() {
+                Some((_, previous_last)) if first <= previous_last.saturating_add(1) => {
+                    *previous_last = (*previous_last).max(last);
+                }
+                _ => merged.push((first, last)),
+            }
+        }
+        self.ranges = merged;
+        self.refresh_span();
+    }
+    
+    /// Remove one ticket, splitting the range that holds it
+    pub fn remove_ticket(&mut self, ticket_number: u64) {
+        let Some(index) = self.ranges.iter().position(|&(first, last)| (first..=last).contains(&ticket_number)) else {
+            return;
+        };
+        let (first, last) = self.ranges.remove(index);
+        if ticket_number < last {
+            self.ranges.insert(index, (ticket_number + 1, last));
+        }
+        if ticket_number > first {
+            self.ranges.insert(index, (first, ticket_number - 1));
+        }
+        self.refresh_span();
+    }
+    
+    fn refresh_span(&mut self) {
+        self.first_ticket = self.ranges.first().map_or(0, |&(first, _)| first);
+        self.last_ticket = self.ranges.last().map_or(0, |&(_, last)| last);
+        self.total_tickets = self.ranges.iter().map(|&(first, last)| last - first + 1).sum();
+    }
 }
 
 /// Maximum number of rounds to keep in history
@@ -127,6 +504,46 @@ const MAX_HISTORY_ROUNDS: u64 = 5;
 #[allow(dead_code)]
 
 impl LotteryRoundsState {
+    /// Increment a metrics counter (single read + insert)
+    pub async fn increment_metric(&mut self, name: &str) -> Result<(), String> {
+        let count = self.metrics.get(name).await
+            .map_err(|e: ViewError| format!("Failed to get metric: {:?}", e))?
+            .unwrap_or(0);
+        self.metrics.insert(name, count + 1)
+            .map_err(|e: ViewError| format!("Failed to update metric: {:?}", e))
+    }
+    
+    /// Get all metrics counters sorted by name
+    pub async fn get_metrics(&self) -> Result<Vec<(String, u64)>, String> {
+        let names = self.metrics.indices().await
+            .map_err(|e: ViewError| format!("Failed to get metric indices: {:?}", e))?;
+        
+        let mut metrics = Vec::with_capacity(names.len());
+        for name in names {
+            if let Some(count) = self.metrics.get(&name).await
+                .map_err(|e: ViewError| format!("Failed to get metric: {:?}", e))? {
+                metrics.push((name, count));
+            }
+        }
+        metrics.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(metrics)
+    }
+    
+    /// Zero all metrics counters
+    pub fn reset_metrics(&mut self) {
+        self.metrics.clear();
+    }
+    
+    /// Check that `signer` may run admin operations, i.e. no admin is set or it is the admin
+    pub fn check_admin(&self, signer: Option<AccountOwner>) -> Result<(), String> {
+        match *self.admin.get() {
+            Some(admin) if signer != Some(admin) => {
+                Err(format!("Only the admin {} can run this operation (signer: {:?})", admin, signer))
+            }
+            _ => Ok(()),
+        }
+    }
+    
     /// Cleanup old round data (tickets, winners, round itself)
     async fn cleanup_old_round(&mut self, round_id: u64) -> Result<(), String> {
         eprintln!("Cleaning up old round: {}", round_id);
@@ -144,12 +561,29 @@ impl LotteryRoundsState {
             // We need to iterate through potential winning tickets
             for ticket_num in 1..=total_tickets {
                 let _ = self.winning_tickets.remove(&(round_id, ticket_num));
+                let _ = self.draw_audits.remove(&(round_id, ticket_num));
             }
+            
+            // Remove duplicate ticket records for this round
+            for ticket_num in 1..=total_tickets {
+                let _ = self.duplicate_tickets.remove(&(round_id, ticket_num));
+            }
+            
+            let _ = self.rounds_by_status.remove(&(round.status.index_key(), round_id));
+            let _ = self.winner_merkle_root.remove(&round_id);
         }
+        let _ = self.rounds_with_unclaimed.remove(&round_id);
+        let _ = self.round_payouts.remove(&round_id);
         
-        // Remove ticket purchases for this round - we need to collect keys first
-        // Since we can't easily iterate MapView with compound keys, we'll just remove the round
-        // Note: ticket_purchases keyed by (round_id, AccountOwner) - can't easily iterate
+        // Remove per-owner purchase and spend records via the round's participant list
+        if let Ok(Some(participants)) = self.round_participants.get(&round_id).await {
+            for owner in participants {
+                let _ = self.ticket_purchases.remove(&(round_id, owner));
+                let _ = self.round_spend.remove(&(round_id, owner));
+                let _ = self.existing_winners_by_owner.remove(&(round_id, owner));
+            }
+        }
+        let _ = self.round_participants.remove(&round_id);
         
         // Remove the round itself
         self.rounds.remove(&round_id)
@@ -160,7 +594,13 @@ impl LotteryRoundsState {
     }
     
     /// Creates a new lottery round with specified ticket price
-    pub async fn create_lottery_round(&mut self, ticket_price: Amount, timestamp: u64) -> Result<u64, String> {
+    pub async fn create_lottery_round(&mut self, ticket_price: Amount, price_schedule: Vec<(u64, Amount)>, timestamp: u64) -> Result<u64, String> {
+        validate_price_schedule(&price_schedule)?;
+        self.check_ticket_price(ticket_price)?;
+        for (_, price) in &price_schedule {
+            self.check_ticket_price(*price)?;
+        }
+        
         let round_id = *self.round_counter.get() + 1;
         self.round_counter.set(round_id);
         
@@ -171,7 +611,7 @@ impl LotteryRoundsState {
             let _ = self.cleanup_old_round(oldest_round_id).await;
         }
         
-        let round = LotteryRound {
+        let round = LotteryRound(lottery_abi::LotteryRound {
             id: round_id,
             created_at: timestamp,
             closed_at: None,
@@ -189,18 +629,293 @@ impl LotteryRoundsState {
             pool2_winners_drawn: 0,
             pool3_winners_drawn: 0,
             pool4_winners_drawn: 0,
-        };
+            triggered_by: None,
+            close_mode: RoundCloseMode::Normal,
+            price_schedule: price_schedule.into_iter()
+                .map(|(effective_from, ticket_price)| lottery_abi::PriceStep { effective_from, ticket_price })
+                .collect(),
+            current_ticket_price: ticket_price,
+            unique_buyers: 0,
+        });
         
         self.rounds.insert(&round_id, round)
             .map_err(|e: ViewError| format!("Failed to insert round: {:?}", e))?;
+        self.reindex_round_status(round_id, None, Some(RoundStatus::Active))?;
         self.active_round.set(Some(round_id));
         
         Ok(round_id)
     }
     
+    /// Snapshot of every configuration register
+    pub async fn get_config(&self) -> Result<LotteryRoundsConfig, String> {
+        Ok(LotteryRoundsConfig {
+            lottery_app_id: self.lottery_app_id.get().map(|app_id| app_id.forget_abi().to_string()),
+            purchase_allowlist_enabled: *self.purchase_allowlist_enabled.get(),
+            duplicate_ticket_resolution: *self.duplicate_ticket_resolution.get(),
+            target_prize_pool: *self.target_prize_pool.get(),
+            min_prize_pool_to_close: *self.min_prize_pool_to_close.get(),
+            fee_tiers: self.get_fee_tiers().await?,
+            default_fee_bps: *self.default_fee_bps.get(),
+            ticket_price_floor: *self.ticket_price_floor.get(),
+            ticket_price_ceiling: *self.ticket_price_ceiling.get(),
+            max_winner_pct_bps: *self.max_winner_pct_bps.get(),
+            one_win_per_address: *self.one_win_per_address.get(),
+        })
+    }
+    
+    /// Apply a full configuration, validating all of it before changing anything
+    pub fn import_config(&mut self, config: LotteryRoundsConfig) -> Result<(), String> {
+        let lottery_app_id = config.lottery_app_id.as_deref()
+            .map(|app_id| app_id.parse::<ApplicationId>().map_err(|e| format!("Invalid application ID {}: {:?}", app_id, e)))
+            .transpose()?;
+        if config.target_prize_pool == Some(Amount::ZERO) {
+            return Err("Target prize pool must be greater than zero".to_string());
+        }
+        if config.min_prize_pool_to_close == Some(Amount::ZERO) {
+            return Err("Minimum prize pool to close must be greater than zero".to_string());
+        }
+        if config.fee_tiers.iter().map(|tier| tier.fee_bps).chain([config.default_fee_bps]).any(|fee_bps| fee_bps > MAX_HOUSE_FEE_BPS) {
+            return Err(format!("House fee cannot exceed {} bps", MAX_HOUSE_FEE_BPS));
+        }
+        if config.ticket_price_floor == Some(Amount::ZERO) {
+            return Err("Ticket price floor must be greater than zero".to_string());
+        }
+        if config.ticket_price_ceiling == Some(Amount::ZERO) {
+            return Err("Ticket price ceiling must be greater than zero".to_string());
+        }
+        if let (Some(floor), Some(ceiling)) = (config.ticket_price_floor, config.ticket_price_ceiling) {
+            if floor > ceiling {
+                return Err(format!("Ticket price floor {} is above the ceiling of {}", floor, ceiling));
+            }
+        }
+        if let Some(bps) = config.max_winner_pct_bps {
+            if !(MIN_MAX_WINNER_PCT_BPS..=10_000).contains(&bps) {
+                return Err(format!("Winner share cap must be between {} and 10000 bps", MIN_MAX_WINNER_PCT_BPS));
+            }
+        }
+        
+        self.lottery_app_id.set(lottery_app_id.map(|app_id| app_id.with_abi()));
+        self.purchase_allowlist_enabled.set(config.purchase_allowlist_enabled);
+        self.duplicate_ticket_resolution.set(config.duplicate_ticket_resolution);
+        self.target_prize_pool.set(config.target_prize_pool);
+        self.min_prize_pool_to_close.set(config.min_prize_pool_to_close);
+        self.fee_tiers.clear();
+        for tier in config.fee_tiers {
+            self.fee_tiers.insert(&tier.min_pool_tokens, tier.fee_bps)
+                .map_err(|e: ViewError| format!("Failed to add fee tier: {:?}", e))?;
+        }
+        self.default_fee_bps.set(config.default_fee_bps);
+        self.ticket_price_floor.set(config.ticket_price_floor);
+        self.ticket_price_ceiling.set(config.ticket_price_ceiling);
+        self.max_winner_pct_bps.set(config.max_winner_pct_bps);
+        self.one_win_per_address.set(config.one_win_per_address);
+        Ok(())
+    }
+    
+    /// Check `price` is positive and within the configured floor and ceiling
+    pub fn check_ticket_price(&self, price: Amount) -> Result<(), String> {
+        if price.is_zero() {
+            return Err("Ticket price must be greater than zero".to_string());
+        }
+        if let Some(floor) = *self.ticket_price_floor.get() {
+            if price < floor {
+                return Err(format!("Ticket price {} is below the floor of {}", price, floor));
+            }
+        }
+        if let Some(ceiling) = *self.ticket_price_ceiling.get() {
+            if price > ceiling {
+                return Err(format!("Ticket price {} is above the ceiling of {}", price, ceiling));
+            }
+        }
+        Ok(())
+    }
+    
+    /// `price` moved into the configured range, for rounds opened automatically
+    fn bounded_ticket_price(&self, price: Amount) -> Amount {
+        let price = self.ticket_price_floor.get().map_or(price, |floor| price.max(floor));
+        self.ticket_price_ceiling.get().map_or(price, |ceiling| price.min(ceiling))
+    }
+    
+    /// Set (or clear) the lowest ticket price a new round may have
+    pub fn set_ticket_price_floor(&mut self, floor: Option<Amount>) -> Result<(), String> {
+        if floor == Some(Amount::ZERO) {
+            return Err("Ticket price floor must be greater than zero".to_string());
+        }
+        if let (Some(floor), Some(ceiling)) = (floor, *self.ticket_price_ceiling.get()) {
+            if floor > ceiling {
+                return Err(format!("Ticket price floor {} is above the ceiling of {}", floor, ceiling));
+            }
+        }
+        self.ticket_price_floor.set(floor);
+        Ok(())
+    }
+    
+    /// Set (or clear) the highest ticket price a new round may have
+    pub fn set_ticket_price_ceiling(&mut self, ceiling: Option<Amount>) -> Result<(), String> {
+        if ceiling == Some(Amount::ZERO) {
+            return Err("Ticket price ceiling must be greater than zero".to_string());
+        }
+        if let (Some(floor), Some(ceiling)) = (*self.ticket_price_floor.get(), ceiling) {
+            if floor > ceiling {
+                return Err(format!("Ticket price ceiling {} is below the floor of {}", ceiling, floor));
+            }
+        }
+        self.ticket_price_ceiling.set(ceiling);
+        Ok(())
+    }
+    
+    /// Set (or clear) the prize pool the active round needs before it may be closed
+    pub fn set_min_prize_pool_to_close(&mut self, amount: Option<Amount>) -> Result<(), String> {
+        if amount == Some(Amount::ZERO) {
+            return Err("Minimum prize pool to close must be greater than zero".to_string());
+        }
+        self.min_prize_pool_to_close.set(amount);
+        Ok(())
+    }
+    
+    /// Set (or clear) the prize pool at which the active round closes automatically
+    pub fn set_target_prize_pool(&mut self, amount: Option<Amount>) -> Result<(), String> {
+        if amount == Some(Amount::ZERO) {
+            return Err("Target prize pool must be greater than zero".to_string());
+        }
+        self.target_prize_pool.set(amount);
+        Ok(())
+    }
+    
+    /// Largest share of a round's tickets that may win, in basis points
+    pub fn max_winner_pct_bps(&self) -> u16 {
+        self.max_winner_pct_bps.get().unwrap_or(DEFAULT_MAX_WINNER_PCT_BPS)
+    }
+    
+    /// Set the largest share of a round's tickets that may win
+    pub fn set_max_winner_pct_bps(&mut self, bps: u16) -> Result<(), String> {
+        if !(MIN_MAX_WINNER_PCT_BPS..=10_000).contains(&bps) {
+            return Err(format!("Winner share cap must be between {} and 10000 bps", MIN_MAX_WINNER_PCT_BPS));
+        }
+        self.max_winner_pct_bps.set(Some(bps));
+        Ok(())
+    }
+    
+    /// Check that closing with `total_tickets` keeps the winners within the cap
+    pub fn check_winner_share(&self, total_tickets: u64) -> Result<(), String> {
+        let max_bps = self.max_winner_pct_bps();
+        if winner_share_allowed(total_tickets, max_bps) {
+            return Ok(());
+        }
+        let winners: u64 = winner_pool_counts(total_tickets).iter().sum();
+        let needed = min_tickets_for_winner_share(total_tickets, max_bps)
+            .map(|tickets| format!("; {} tickets are needed", tickets))
+            .unwrap_or_default();
+        Err(format!(
+            "Closing with {} tickets would draw {} winners, above the cap of {} bps{}",
+            total_tickets, winners, max_bps, needed
+        ))
+    }
+    
+    /// Add or replace the fee tier at `min_pool_tokens`
+    pub fn add_fee_tier(&mut self, min_pool_tokens: u64, fee_bps: u16) -> Result<(), String> {
+        if fee_bps > MAX_HOUSE_FEE_BPS {
+            return Err(format!("House fee cannot exceed {} bps", MAX_HOUSE_FEE_BPS));
+        }
+        self.fee_tiers.insert(&min_pool_tokens, fee_bps)
+            .map_err(|e: ViewError| format!("Failed to add fee tier: {:?}", e))
+    }
+    
+    /// Remove the fee tier at `min_pool_tokens`
+    pub async fn remove_fee_tier(&mut self, min_pool_tokens: u64) -> Result<(), String> {
+        if !self.fee_tiers.contains_key(&min_pool_tokens).await
+            .map_err(|e: ViewError| format!("Failed to get fee tier: {:?}", e))? {
+            return Err(format!("No fee tier at {} tokens", min_pool_tokens));
+        }
+        self.fee_tiers.remove(&min_pool_tokens)
+            .map_err(|e: ViewError| format!("Failed to remove fee tier: {:?}", e))
+    }
+    
+    /// Set the house fee charged when no fee tier applies
+    pub fn set_default_fee_bps(&mut self, fee_bps: u16) -> Result<(), String> {
+        if fee_bps > MAX_HOUSE_FEE_BPS {
+            return Err(format!("House fee cannot exceed {} bps", MAX_HOUSE_FEE_BPS));
+        }
+        self.default_fee_bps.set(fee_bps);
+        Ok(())
+    }
+    
+    /// All fee tiers, lowest threshold first
+    pub async fn get_fee_tiers(&self) -> Result<Vec<FeeTier>, String> {
+        let thresholds = self.fee_tiers.indices().await
+            .map_err(|e: ViewError| format!("Failed to get fee tier indices: {:?}", e))?;
+        
+        let mut tiers = Vec::with_capacity(thresholds.len());
+        for min_pool_tokens in thresholds {
+            if let Some(fee_bps) = self.fee_tiers.get(&min_pool_tokens).await
+                .map_err(|e: ViewError| format!("Failed to get fee tier: {:?}", e))? {
+                tiers.push(FeeTier { min_pool_tokens, fee_bps });
+            }
+        }
+        tiers.sort_by_key(|tier| tier.min_pool_tokens);
+        Ok(tiers)
+    }
+    
+    /// House fee for a purchase into a round holding `prize_pool`: the fee of the highest tier
+    /// whose threshold the pool's whole tokens reach, or the default fee below every tier
+    pub async fn fee_bps_for_pool(&self, prize_pool: Amount) -> Result<u16, String> {
+        let pool_tokens = u64::try_from(u128::from(prize_pool) / u128::from(Amount::ONE)).unwrap_or(u64::MAX);
+        Ok(self.get_fee_tiers().await?
+            .into_iter()
+            .rev()
+            .find(|tier| pool_tokens >= tier.min_pool_tokens)
+            .map_or(*self.default_fee_bps.get(), |tier| tier.fee_bps))
+    }
+    
+    /// Enable or disable the purchase allowlist
+    pub fn set_allowlist_enabled(&mut self, enabled: bool) {
+        self.purchase_allowlist_enabled.set(enabled);
+    }
+    
+    /// Approve owners to purchase tickets while the allowlist is enabled
+    pub fn add_to_allowlist(&mut self, addresses: Vec<AccountOwner>) -> Result<(), String> {
+        for address in addresses {
+            self.purchase_allowlist.insert(&address, true)
+                .map_err(|e: ViewError| format!("Failed to add to allowlist: {:?}", e))?;
+        }
+        Ok(())
+    }
+    
+    /// Revoke an owner's purchase approval
+    pub fn remove_from_allowlist(&mut self, address: AccountOwner) -> Result<(), String> {
+        self.purchase_allowlist.remove(&address)
+            .map_err(|e: ViewError| format!("Failed to remove from allowlist: {:?}", e))
+    }
+    
+    /// Check whether an owner is on the purchase allowlist
+    pub async fn is_allowlisted(&self, owner: &AccountOwner) -> Result<bool, String> {
+        self.purchase_allowlist.contains_key(owner).await
+            .map_err(|e: ViewError| format!("Failed to check allowlist: {:?}", e))
+    }
+    
+    /// Quote a purchase of `amount` in the active round without changing anything
+    pub async fn quote_purchase(&self, amount: Amount, timestamp: u64) -> Result<Result<PurchaseQuote, QuoteError>, String> {
+        let round = match *self.active_round.get() {
+            Some(round_id) => self.rounds.get(&round_id).await
+                .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))?,
+            None => None,
+        };
+        Ok(match round {
+            Some(round) if round.status == RoundStatus::Active => {
+                lottery_abi::quote_purchase(round.id, round.ticket_price_at(timestamp), amount)
+                    .map(|quote| PurchaseQuote { price_schedule: round.price_schedule.clone(), ..quote })
+            }
+            _ => Err(QuoteError::NoActiveRound),
+        })
+    }
+    
     /// Purchase tickets in the active round
     /// Called by lottery-app via cross-app call
-    pub async fn purchase_tickets(&mut self, owner: AccountOwner, amount: Amount, ticket_price: Amount, source_chain_id: Option<String>) -> Result<TicketPurchase, String> {
+    pub async fn purchase_tickets(&mut self, owner: AccountOwner, amount: Amount, ticket_price: Amount, source_chain_id: Option<String>, timestamp: u64) -> Result<TicketPurchase, String> {
+        if *self.purchase_allowlist_enabled.get() && !self.is_allowlisted(&owner).await? {
+            return Err(format!("Owner {} is not allowlisted for ticket purchases", owner));
+        }
+        
         let round_id_opt = self.active_round.get();
         
         if let Some(round_id) = *round_id_opt {
@@ -213,31 +928,56 @@ impl LotteryRoundsState {
                 return Err("No active round accepting ticket purchases".to_string());
             }
             
-            // Use the round's ticket price, not the parameter (for backwards compatibility)
-            let actual_ticket_price = round.ticket_price;
+            // Use the round's price at purchase time, not the parameter (for backwards compatibility)
+            let actual_ticket_price = round.ticket_price_at(timestamp);
             eprintln!("purchase_tickets: amount={:?}, param_ticket_price={:?}, round_ticket_price={:?}", 
                 amount, ticket_price, actual_ticket_price);
             
             // Calculate number of tickets using ROUND's ticket price
-            let amount_u128 = u128::from(amount);
-            let ticket_price_u128 = u128::from(actual_ticket_price);
+            let ticket_count_u64 = tickets_for_amount(amount, actual_ticket_price)
+                .map_err(|e| match e {
+                    TicketCountError::ZeroTicketPrice => "Invalid ticket price in round".to_string(),
+                    e => e.to_string(),
+                })?;
+            eprintln!("purchase_tickets: calculated ticket_count={}", ticket_count_u64);
             
-            if ticket_price_u128 == 0 {
-                return Err("Invalid ticket price in round".to_string());
+            if ticket_count_u64 == 0 {
+                return Err(format!("Amount {:?} too small to purchase any tickets at price {:?}", amount, actual_ticket_price));
             }
             
-            let ticket_count = amount_u128 / ticket_price_u128;
-            eprintln!("purchase_tickets: calculated ticket_count={}", ticket_count);
-            
-            if ticket_count == 0 {
-                return Err(format!("Amount {:?} too small to purchase any tickets at price {:?}", amount, actual_ticket_price));
+            // Enforce the owner's self-imposed spend limit for this round
+            let spent_this_round = self.round_spend.get(&(round_id, owner)).await
+                .map_err(|e: ViewError| format!("Failed to get round spend: {:?}", e))?
+                .unwrap_or(Amount::ZERO);
+            let spent_after = spent_this_round.saturating_add(amount);
+            if let Some(limit) = self.spend_limits.get(&owner).await
+                .map_err(|e: ViewError| format!("Failed to get spend limit: {:?}", e))? {
+                if spent_after > limit {
+                    return Err(format!("Purchase of {} exceeds spend limit {} for this round (already spent {})", amount, limit, spent_this_round));
+                }
             }
+            self.round_spend.insert(&(round_id, owner), spent_after)
+                .map_err(|e: ViewError| format!("Failed to record round spend: {:?}", e))?;
             
-            let ticket_count_u64 = ticket_count as u64;
+            // Update lifetime totals
+            let mut totals = self.player_totals.get(&owner).await
+                .map_err(|e: ViewError| format!("Failed to get player totals: {:?}", e))?
+                .unwrap_or_default();
+            if spent_this_round.is_zero() {
+                totals.rounds_played += 1;
+                round.unique_buyers += 1;
+            }
+            totals.total_spent = totals.total_spent.saturating_add(amount);
+            totals.tickets_bought += ticket_count_u64;
+            totals.last_purchase_at = timestamp;
+            self.player_totals.insert(&owner, totals)
+                .map_err(|e: ViewError| format!("Failed to update player totals: {:?}", e))?;
             
             // Assign ticket numbers
             let first_ticket = round.next_ticket_number;
-            let last_ticket = first_ticket + ticket_count_u64 - 1;
+            let next_ticket_number = first_ticket.checked_add(ticket_count_u64)
+                .ok_or("Ticket numbers for this round are exhausted")?;
+            let last_ticket = next_ticket_number - 1;
             
             // Record the purchase
             let purchase = TicketPurchase {
@@ -247,34 +987,158 @@ impl LotteryRoundsState {
                 total_tickets: ticket_count_u64,
                 amount_paid: amount,
                 source_chain_id: source_chain_id.clone(),
+                ranges: vec![(first_ticket, last_ticket)],
             };
             
-            // Store ticket purchase
-            self.ticket_purchases.insert(&(round_id, owner.clone()), purchase.clone())
+            // Add the batch to the owner's earlier tickets in this round
+            let mut held = self.ticket_purchases.get(&(round_id, owner)).await
+                .map_err(|e: ViewError| format!("Failed to get ticket purchase: {:?}", e))?
+                .unwrap_or_else(|| TicketPurchase { amount_paid: Amount::ZERO, ranges: Vec::new(), ..purchase.clone() });
+            held.add_range(first_ticket, last_ticket);
+            held.amount_paid = held.amount_paid.saturating_add(amount);
+            self.ticket_purchases.insert(&(round_id, owner.clone()), held)
                 .map_err(|e: ViewError| format!("Failed to record purchase: {:?}", e))?;
             
+            let mut participants = self.round_participants.get(&round_id).await
+                .map_err(|e: ViewError| format!("Failed to get round participants: {:?}", e))?
+                .unwrap_or_default();
+            if !participants.contains(&owner) {
+                participants.push(owner);
+                self.round_participants.insert(&round_id, participants)
+                    .map_err(|e: ViewError| format!("Failed to record round participant: {:?}", e))?;
+            }
+            
             // Map each ticket to owner
             for ticket_num in first_ticket..=last_ticket {
                 self.ticket_to_owner.insert(&(round_id, ticket_num), owner.clone())
                     .map_err(|e: ViewError| format!("Failed to map ticket to owner: {:?}", e))?;
             }
             
+            // The house fee depends on the pool before this purchase and is kept out of it
+            let fee = house_fee(amount, self.fee_bps_for_pool(round.prize_pool).await?);
+            self.reclaimed_escrow.set(self.reclaimed_escrow.get().saturating_add(fee));
+            
             // Update round
-            round.next_ticket_number = last_ticket + 1;
+            round.next_ticket_number = next_ticket_number;
             round.total_tickets_sold += ticket_count_u64;
-            round.prize_pool = round.prize_pool.saturating_add(amount);
+            round.prize_pool = round.prize_pool.saturating_add(amount.saturating_sub(fee));
+            
+            // Close as soon as the target prize is reached, provided the round can be drawn
+            let prize_cap_reached = self.target_prize_pool.get()
+                .is_some_and(|target| round.prize_pool >= target)
+                && allocate_winner_pools(round.total_tickets_sold).0 == RoundCloseMode::Normal
+                && self.check_winner_share(round.total_tickets_sold).is_ok();
             
             self.rounds.insert(&round_id, round)
                 .map_err(|e: ViewError| format!("Failed to update round: {:?}", e))?;
             
+            if prize_cap_reached {
+                self.close_round(timestamp, RoundCloseTrigger::PrizeCap).await?;
+            }
+            
             Ok(purchase)
         } else {
             Err("No active round".to_string())
         }
     }
     
+    /// Give `ticket_numbers` of round `round_id` from `from` to `to` while winners are still
+    /// to be drawn. Tickets that already won cannot move. The price paid moves with the
+    /// tickets, pro rata, so both owners keep their price per ticket.
+    pub async fn transfer_tickets(&mut self, round_id: u64, from: AccountOwner, to: AccountOwner, ticket_numbers: &[u64]) -> Result<(), String> {
+        if ticket_numbers.is_empty() {
+            return Err("No tickets to transfer".to_string());
+        }
+        if ticket_numbers.len() as u64 > MAX_TICKETS_PER_TRANSFER {
+            return Err(format!("At most {} tickets can be transferred at once", MAX_TICKETS_PER_TRANSFER));
+        }
+        if from == to {
+            return Err("Tickets cannot be transferred to their own owner".to_string());
+        }
+        let round = self.get_round(round_id).await?.ok_or("Round not found")?;
+        if round.status == RoundStatus::Complete {
+            return Err("Every winner of this round has been drawn".to_string());
+        }
+        
+        let mut tickets = std::collections::BTreeSet::new();
+        for &ticket_number in ticket_numbers {
+            if !tickets.insert(ticket_number) {
+                return Err(format!("Ticket {} is listed more than once", ticket_number));
+            }
+            let owner = self.ticket_to_owner.get(&(round_id, ticket_number)).await
+                .map_err(|e: ViewError| format!("Failed to get ticket owner: {:?}", e))?;
+            if owner != Some(from) {
+                return Err(format!("Ticket {} of round {} is not held by {}", ticket_number, round_id, from));
+            }
+            if self.winning_tickets.contains_key(&(round_id, ticket_number)).await
+                .map_err(|e: ViewError| format!("Failed to check winning ticket: {:?}", e))? {
+                return Err(format!("Ticket {} has already won", ticket_number));
+            }
+        }
+        
+        let mut sender = self.ticket_purchases.get(&(round_id, from)).await
+            .map_err(|e: ViewError| format!("Failed to get ticket purchase: {:?}", e))?
+            .ok_or_else(|| format!("{} has no tickets in round {}", from, round_id))?;
+        let mut recipient = self.ticket_purchases.get(&(round_id, to)).await
+            .map_err(|e: ViewError| format!("Failed to get ticket purchase: {:?}", e))?
+            .unwrap_or_else(|| TicketPurchase {
+                owner: to,
+                first_ticket: 0,
+                last_ticket: 0,
+                total_tickets: 0,
+                amount_paid: Amount::ZERO,
+                source_chain_id: None,
+                ranges: Vec::new(),
+            });
+        
+        // Split so the multiplication cannot overflow
+        let (paid, held, moved) = (u128::from(sender.amount_paid), u128::from(sender.total_tickets.max(1)), tickets.len() as u128);
+        let paid_moved = Amount::from_attos(paid / held * moved + paid % held * moved / held);
+        for ticket_number in tickets {
+            self.ticket_to_owner.insert(&(round_id, ticket_number), to)
+                .map_err(|e: ViewError| format!("Failed to map ticket to owner: {:?}", e))?;
+            sender.remove_ticket(ticket_number);
+            recipient.add_range(ticket_number, ticket_number);
+        }
+        sender.amount_paid = sender.amount_paid.saturating_sub(paid_moved);
+        recipient.amount_paid = recipient.amount_paid.saturating_add(paid_moved);
+        
+        if sender.total_tickets == 0 {
+            self.ticket_purchases.remove(&(round_id, from))
+                .map_err(|e: ViewError| format!("Failed to remove ticket purchase: {:?}", e))?;
+        } else {
+            self.ticket_purchases.insert(&(round_id, from), sender)
+                .map_err(|e: ViewError| format!("Failed to update ticket purchase: {:?}", e))?;
+        }
+        self.ticket_purchases.insert(&(round_id, to), recipient)
+            .map_err(|e: ViewError| format!("Failed to update ticket purchase: {:?}", e))?;
+        
+        let mut participants = self.round_participants.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round participants: {:?}", e))?
+            .unwrap_or_default();
+        if !participants.contains(&to) {
+            participants.push(to);
+            self.round_participants.insert(&round_id, participants)
+                .map_err(|e: ViewError| format!("Failed to record round participant: {:?}", e))?;
+        }
+        Ok(())
+    }
+    
     /// Close the active lottery round and calculate winner pools
     pub async fn close_lottery_round(&mut self, timestamp: u64) -> Result<u64, String> {
+        if let (Some(minimum), Some(round_id)) = (*self.min_prize_pool_to_close.get(), *self.active_round.get()) {
+            let round = self.get_round(round_id).await?.ok_or("Active round not found")?;
+            if round.prize_pool < minimum {
+                return Err(format!(
+                    "Prize pool of {} is below the {} needed to close the round",
+                    round.prize_pool, minimum
+                ));
+            }
+        }
+        self.close_round(timestamp, RoundCloseTrigger::Manual).await
+    }
+    
+    async fn close_round(&mut self, timestamp: u64, trigger: RoundCloseTrigger) -> Result<u64, String> {
         let round_id_opt = self.active_round.get();
         
         if let Some(round_id) = *round_id_opt {
@@ -287,38 +1151,46 @@ impl LotteryRoundsState {
                 return Err("Round is not active".to_string());
             }
             
-            if round.total_tickets_sold < 4 {
-                return Err("Cannot close round with fewer than 4 tickets sold".to_string());
+            if round.total_tickets_sold == 0 {
+                return Err("Cannot close round with no tickets sold".to_string());
             }
             
-            // Calculate winner pool sizes
-            // 30% of tickets are winners: 15% + 7% + 5% + 3% = 30%
-            let total_tickets = round.total_tickets_sold;
-            round.pool1_count = (total_tickets * 15) / 100; // 15% of tickets
-            round.pool2_count = (total_tickets * 7) / 100;  // 7% of tickets
-            round.pool3_count = (total_tickets * 5) / 100;  // 5% of tickets
-            round.pool4_count = (total_tickets * 3) / 100;  // 3% of tickets
-            
-            // Ensure at least 1 winner in each pool if there are tickets
-            if round.pool1_count == 0 && total_tickets > 0 {
-                round.pool1_count = 1;
-            }
-            if round.pool2_count == 0 && total_tickets > 1 {
-                round.pool2_count = 1;
-            }
-            if round.pool3_count == 0 && total_tickets > 2 {
-                round.pool3_count = 1;
+            // Calculate winner pool sizes from the 30% winner budget
+            let (close_mode, counts) = allocate_winner_pools(round.total_tickets_sold);
+            if close_mode == RoundCloseMode::Normal {
+                self.check_winner_share(round.total_tickets_sold)?;
             }
-            if round.pool4_count == 0 && total_tickets > 3 {
-                round.pool4_count = 1;
+            [round.pool1_count, round.pool2_count, round.pool3_count, round.pool4_count] = counts;
+            
+            round.closed_at = Some(timestamp);
+            round.triggered_by = Some(trigger);
+            round.close_mode = close_mode;
+            
+            self.record_duplicate_tickets(round_id).await?;
+            
+            if close_mode == RoundCloseMode::PartialRefundLowTickets {
+                // Nothing to draw: the round completes now and the next one opens at the same price
+                round.status = RoundStatus::Complete;
+                round.current_winner_pool = WinnerPool::Complete;
+                let refunded = self.low_ticket_refunds(&round).await?.iter()
+                    .fold(Amount::ZERO, |total, (_, amount, _)| total.saturating_add(*amount));
+                self.reclaimed_escrow.set(self.reclaimed_escrow.get().saturating_add(round.prize_pool.saturating_sub(refunded)));
+                // Schedules hold absolute times, so the next round keeps the price reached, not the schedule
+                let ticket_price = self.bounded_ticket_price(round.ticket_price_at(timestamp));
+                self.rounds.insert(&round_id, round)
+                    .map_err(|e: ViewError| format!("Failed to update round: {:?}", e))?;
+                self.reindex_round_status(round_id, Some(RoundStatus::Active), Some(RoundStatus::Complete))?;
+                self.active_round.set(None);
+                self.create_lottery_round(ticket_price, Vec::new(), timestamp).await?;
+                return Ok(round_id);
             }
             
             round.status = RoundStatus::Closed;
-            round.closed_at = Some(timestamp);
             round.current_winner_pool = WinnerPool::Pool1;
             
             self.rounds.insert(&round_id, round)
                 .map_err(|e: ViewError| format!("Failed to update round: {:?}", e))?;
+            self.reindex_round_status(round_id, Some(RoundStatus::Active), Some(RoundStatus::Closed))?;
             self.active_round.set(None);
             
             Ok(round_id)
@@ -327,9 +1199,89 @@ impl LotteryRoundsState {
         }
     }
     
+    /// Refunds of a round closed with too few tickets: 70% of the prize pool, split between
+    /// ticket holders by the number of tickets they bought (rounding dust is not refunded)
+    /// Returns: Vec<(recipient, amount, source_chain_id)>
+    pub async fn low_ticket_refunds(&self, round: &LotteryRound) -> Result<Vec<(AccountOwner, Amount, Option<String>)>, String> {
+        let purchases = self.get_round_ticket_purchases(round.id).await?;
+        let total_tickets: u128 = purchases.iter().map(|(_, purchase)| u128::from(purchase.total_tickets)).sum();
+        if total_tickets == 0 {
+            return Ok(Vec::new());
+        }
+        let refund_pool = u128::from(round.prize_pool) / 100 * LOW_TICKET_REFUND_PERCENTAGE
+            + u128::from(round.prize_pool) % 100 * LOW_TICKET_REFUND_PERCENTAGE / 100;
+        Ok(purchases.into_iter()
+            .map(|(owner, purchase)| {
+                let tickets = u128::from(purchase.total_tickets);
+                // Split so the multiplication cannot overflow
+                let share = refund_pool / total_tickets * tickets + refund_pool % total_tickets * tickets / total_tickets;
+                (owner, Amount::from_attos(share), purchase.source_chain_id)
+            })
+            .collect())
+    }
+    
+    /// Scan all purchases of a round and record every ticket that was sold to more than one owner
+    async fn record_duplicate_tickets(&mut self, round_id: u64) -> Result<(), String> {
+        let mut ticket_owners: std::collections::BTreeMap<u64, Vec<AccountOwner>> = std::collections::BTreeMap::new();
+        for (owner, purchase) in self.get_round_ticket_purchases(round_id).await? {
+            for ticket_num in purchase.tickets() {
+                ticket_owners.entry(ticket_num).or_default().push(owner);
+            }
+        }
+        
+        for (ticket_num, owners) in ticket_owners {
+            if owners.len() > 1 {
+                eprintln!("Ticket {} in round {} has {} owners", ticket_num, round_id, owners.len());
+                self.duplicate_tickets.insert(&(round_id, ticket_num), owners)
+                    .map_err(|e: ViewError| format!("Failed to record duplicate ticket: {:?}", e))?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Work out who gets paid for a drawn ticket, applying the configured
+    /// duplicate resolution when the ticket has more than one claimant
+    /// Returns: Vec<(recipient, amount, source_chain_id)>
+    async fn ticket_payouts(&self, round: &LotteryRound, ticket_number: u64, owner: AccountOwner, prize_amount: Amount, source_chain_id: Option<String>) -> Result<Vec<(AccountOwner, Amount, Option<String>)>, String> {
+        let claimants = self.duplicate_tickets.get(&(round.id, ticket_number)).await
+            .map_err(|e: ViewError| format!("Failed to get duplicate ticket: {:?}", e))?
+            .unwrap_or_default();
+        
+        if claimants.len() < 2 {
+            return Ok(vec![(owner, prize_amount, source_chain_id)]);
+        }
+        
+        let amounts = match *self.duplicate_ticket_resolution.get() {
+            DuplicateResolution::FirstComeFirstServed => {
+                return Ok(vec![(owner, prize_amount, source_chain_id)]);
+            }
+            DuplicateResolution::SplitProportionally => {
+                // Every claimant holds the same ticket, so each gets an equal share;
+                // the first claimant also receives the rounding remainder
+                let prize_u128 = u128::from(prize_amount);
+                let share = prize_u128 / claimants.len() as u128;
+                let remainder = prize_u128 - share * claimants.len() as u128;
+                (0..claimants.len())
+                    .map(|i| Amount::from_attos(if i == 0 { share + remainder } else { share }))
+                    .collect::<Vec<_>>()
+            }
+            DuplicateResolution::Refund => vec![round.ticket_price; claimants.len()],
+        };
+        
+        let mut payouts = Vec::with_capacity(claimants.len());
+        for (claimant, amount) in claimants.into_iter().zip(amounts) {
+            let claimant_chain_id = self.ticket_purchases.get(&(round.id, claimant)).await
+                .map_err(|e: ViewError| format!("Failed to get ticket purchase: {:?}", e))?
+                .and_then(|purchase| purchase.source_chain_id);
+            payouts.push((claimant, amount, claimant_chain_id));
+        }
+        Ok(payouts)
+    }
+    
     /// Generate one winner using VRF and prepare for prize distribution
-    /// Returns: (round_id, ticket_number, owner, prize_amount, new_round_created, source_chain_id)
-    pub async fn generate_winner(&mut self, vrf_value: u64, round_id: u64, current_timestamp: u64, default_ticket_price: Amount) -> Result<(u64, u64, AccountOwner, Amount, bool, Option<String>), String> {
+    /// Returns: (round_id, ticket_number, owner, prize_amount, new_round_created, source_chain_id, payouts)
+    /// where payouts lists every (recipient, amount, source_chain_id) to pay for the drawn ticket
+    pub async fn generate_winner(&mut self, vrf_value: u64, round_id: u64, current_timestamp: u64, default_ticket_price: Amount) -> Result<(u64, u64, AccountOwner, Amount, bool, Option<String>, Vec<(AccountOwner, Amount, Option<String>)>), String> {
         let mut round = self.rounds.get(&round_id).await
             .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))?
             .ok_or("Round not found")?
@@ -360,32 +1312,42 @@ impl LotteryRoundsState {
             .map(|(_, ticket)| ticket)
             .collect::<std::collections::HashSet<_>>();
         
-        // Select a random ticket that hasn't won yet
-        let mut attempts = 0u64;
-        let max_attempts = round.total_tickets_sold * 2;
-        let selected_ticket;
-        
-        loop {
-            if attempts >= max_attempts {
-                return Err("Failed to find unique winning ticket after many attempts".to_string());
-            }
-            
-            // Generate pseudo-random ticket number using VRF value + attempts
-            let seed = vrf_value.wrapping_add(attempts);
-            let ticket = (seed % round.total_tickets_sold) + 1; // Tickets are 1-indexed
-            
-            if !existing_winners.contains(&ticket) {
-                selected_ticket = ticket;
-                break;
+        // Select a random ticket that hasn't won yet. A ticket left without an owner (by cleanup
+        // or a gap) and, with one win per address, a ticket whose owner already won are excluded
+        // too, and the selection moves on to the next attempt.
+        let one_win_per_address = *self.one_win_per_address.get();
+        let mut excluded = existing_winners;
+        let (selected_ticket, owner) = loop {
+            let Some(ticket) = select_winning_ticket(vrf_value, round.total_tickets_sold, &excluded) else {
+                return Err(if one_win_per_address {
+                    "No eligible ticket left: every remaining ticket belongs to an address that already won this round".to_string()
+                } else {
+                    "Failed to find unique winning ticket after many attempts".to_string()
+                });
+            };
+            let Some(owner) = self.ticket_to_owner.get(&(round_id, ticket)).await
+                .map_err(|e: ViewError| format!("Failed to get ticket owner: {:?}", e))? else {
+                eprintln!("Ticket {} of round {} has no owner, drawing another", ticket, round_id);
+                excluded.insert(ticket);
+                continue;
+            };
+            let already_won = one_win_per_address && self.existing_winners_by_owner.contains_key(&(round_id, owner)).await
+                .map_err(|e: ViewError| format!("Failed to check previous winners: {:?}", e))?;
+            if !already_won {
+                break (ticket, owner);
             }
-            
-            attempts += 1;
-        }
+            excluded.insert(ticket);
+        };
         
-        // Get ticket owner
-        let owner = self.ticket_to_owner.get(&(round_id, selected_ticket)).await
-            .map_err(|e: ViewError| format!("Failed to get ticket owner: {:?}", e))?
-            .ok_or("Ticket has no owner")?;
+        let mut previous_winners: Vec<u64> = excluded.into_iter().collect();
+        previous_winners.sort_unstable();
+        self.draw_audits.insert(&(round_id, selected_ticket), DrawAudit {
+            vrf_value,
+            total_tickets_sold: round.total_tickets_sold,
+            previous_winners,
+            drawn_at: current_timestamp,
+        })
+            .map_err(|e: ViewError| format!("Failed to record draw audit: {:?}", e))?;
         
         // Get source chain ID from ticket purchase
         let source_chain_id = self.ticket_purchases.get(&(round_id, owner.clone())).await
@@ -393,11 +1355,51 @@ impl LotteryRoundsState {
             .and_then(|purchase| purchase.source_chain_id.clone());
         
         // Calculate prize for this winner
-        let prize_amount = calculate_prize_per_winner(round.prize_pool, pool, winners_count);
+        let winner_prize = calculate_prize_per_winner(round.prize_pool, pool, winners_count);
+        
+        // Apply the duplicate resolution if this ticket was sold more than once
+        let payouts = self.ticket_payouts(&round, selected_ticket, owner, winner_prize, source_chain_id.clone()).await?;
+        let prize_amount = payouts.iter().fold(Amount::ZERO, |total, (_, amount, _)| total.saturating_add(*amount));
+        
+        // Credit lifetime winnings to everyone paid for this ticket and announce them
+        for (recipient, amount, recipient_chain_id) in &payouts {
+            if !amount.is_zero() {
+                self.winner_feed.append(FeedEntry {
+                    game: Game::Lottery,
+                    owner: *recipient,
+                    amount: *amount,
+                    round_id,
+                    timestamp: current_timestamp,
+                    chain_id: recipient_chain_id.clone(),
+                })?;
+            }
+
+            let mut totals = self.player_totals.get(recipient).await
+                .map_err(|e: ViewError| format!("Failed to get player totals: {:?}", e))?
+                .unwrap_or_default();
+            totals.total_won = totals.total_won.saturating_add(*amount);
+            self.player_totals.insert(recipient, totals)
+                .map_err(|e: ViewError| format!("Failed to update player totals: {:?}", e))?;
+            self.existing_winners_by_owner.insert(&(round_id, *recipient), true)
+                .map_err(|e: ViewError| format!("Failed to record round winner: {:?}", e))?;
+        }
+        self.existing_winners_by_owner.insert(&(round_id, owner), true)
+            .map_err(|e: ViewError| format!("Failed to record round winner: {:?}", e))?;
         
         // Record winning ticket
-        self.winning_tickets.insert(&(round_id, selected_ticket), (owner.clone(), prize_amount, false, source_chain_id.clone()))
+        self.winning_tickets.insert(&(round_id, selected_ticket), WinningTicket(owner.clone(), prize_amount, false, source_chain_id.clone(), Some(pool)))
             .map_err(|e: ViewError| format!("Failed to record winning ticket: {:?}", e))?;
+        let (count, amount) = self.rounds_with_unclaimed.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get unclaimed prizes: {:?}", e))?
+            .unwrap_or((0, Amount::ZERO));
+        self.rounds_with_unclaimed.insert(&round_id, (count + 1, amount.saturating_add(prize_amount)))
+            .map_err(|e: ViewError| format!("Failed to update unclaimed prizes: {:?}", e))?;
+        let mut totals = self.round_payouts.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round payouts: {:?}", e))?
+            .unwrap_or_default();
+        totals.award(prize_amount);
+        self.round_payouts.insert(&round_id, totals)
+            .map_err(|e: ViewError| format!("Failed to update round payouts: {:?}", e))?;
         
         // Update round progress
         match pool {
@@ -434,7 +1436,11 @@ impl LotteryRoundsState {
             
             // If all pools complete, automatically create new round
             if round.current_winner_pool == WinnerPool::Complete {
-                let new_round_id = self.create_lottery_round(default_ticket_price, current_timestamp).await?;
+                self.reindex_round_status(round_id, Some(RoundStatus::Closed), Some(RoundStatus::Complete))?;
+                let (leaves, _) = self.winner_leaves(round_id, None).await?;
+                self.winner_merkle_root.insert(&round_id, merkle::build_tree(leaves)[0])
+                    .map_err(|e: ViewError| format!("Failed to store winner Merkle root: {:?}", e))?;
+                let new_round_id = self.create_lottery_round(self.bounded_ticket_price(default_ticket_price), Vec::new(), current_timestamp).await?;
                 new_round_created = true;
                 eprintln!("All winners drawn for round {}. Created new round {} at timestamp {}", round_id, new_round_id, current_timestamp);
             }
@@ -444,7 +1450,67 @@ impl LotteryRoundsState {
         self.rounds.insert(&round_id, round)
             .map_err(|e: ViewError| format!("Failed to update round: {:?}", e))?;
         
-        Ok((round_id, selected_ticket, owner, prize_amount, new_round_created, source_chain_id))
+        Ok((round_id, selected_ticket, owner, prize_amount, new_round_created, source_chain_id, payouts))
+    }
+    
+    /// Sorted Merkle leaves of a round's winning tickets, and the position of `ticket_number`'s leaf
+    async fn winner_leaves(&self, round_id: u64, ticket_number: Option<u64>) -> Result<(Vec<[u8; 32]>, Option<usize>), String> {
+        let winners = self.get_round_winners(round_id).await?;
+        let mut leaves: Vec<[u8; 32]> = winners.iter()
+            .map(|(ticket, owner, prize, _, _)| merkle::winner_leaf(*ticket, owner, *prize))
+            .collect();
+        leaves.sort();
+        let position = ticket_number
+            .and_then(|ticket| winners.iter().find(|(winner, _, _, _, _)| *winner == ticket))
+            .map(|(ticket, owner, prize, _, _)| merkle::winner_leaf(*ticket, owner, *prize))
+            .and_then(|leaf| leaves.iter().position(|candidate| *candidate == leaf));
+        Ok((leaves, position))
+    }
+    
+    /// Build a proof that `ticket_number` is one of a completed round's winners
+    pub async fn prove_winner(&self, round_id: u64, ticket_number: u64) -> Result<MerkleProof, String> {
+        let root = self.winner_merkle_root.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get winner Merkle root: {:?}", e))?
+            .ok_or("Round has no winner Merkle root (not complete)")?;
+        let (leaves, position) = self.winner_leaves(round_id, Some(ticket_number)).await?;
+        let index = position.ok_or_else(|| format!("Ticket {} is not a winner of round {}", ticket_number, round_id))?;
+        let tree = merkle::build_tree(leaves);
+        if tree[0] != root {
+            return Err("Winning tickets no longer match the stored Merkle root".to_string());
+        }
+        Ok(MerkleProof {
+            root: root.to_vec(),
+            proof: merkle::generate_proof(&tree, index).into_iter().map(|node| node.to_vec()).collect(),
+            leaf: tree[tree.len() - 1 - index].to_vec(),
+        })
+    }
+    
+    /// Recompute a winning ticket's draw from its audit record and compare owners
+    pub async fn verify_win(&self, round_id: u64, ticket_number: u64) -> Result<WinVerification, String> {
+        let stored_owner = self.winning_tickets.get(&(round_id, ticket_number)).await
+            .map_err(|e: ViewError| format!("Failed to get winning ticket: {:?}", e))?
+            .map(|WinningTicket(owner, ..)| owner);
+        let audit = self.draw_audits.get(&(round_id, ticket_number)).await
+            .map_err(|e: ViewError| format!("Failed to get draw audit: {:?}", e))?;
+        
+        let expected_ticket = audit.and_then(|audit| {
+            let previous_winners = audit.previous_winners.into_iter().collect();
+            select_winning_ticket(audit.vrf_value, audit.total_tickets_sold, &previous_winners)
+        });
+        let expected_owner = match expected_ticket {
+            Some(ticket) => self.ticket_to_owner.get(&(round_id, ticket)).await
+                .map_err(|e: ViewError| format!("Failed to get ticket owner: {:?}", e))?,
+            None => None,
+        };
+        
+        Ok(WinVerification {
+            round_id,
+            ticket_number,
+            verified: expected_ticket == Some(ticket_number) && stored_owner.is_some() && expected_owner == stored_owner,
+            stored_owner,
+            expected_ticket,
+            expected_owner,
+        })
     }
     
     /// Mark winning ticket as claimed
@@ -452,13 +1518,161 @@ impl LotteryRoundsState {
         let winning_info = self.winning_tickets.get(&(round_id, ticket_number)).await
             .map_err(|e: ViewError| format!("Failed to get winning ticket: {:?}", e))?
             .ok_or("Winning ticket not found")?;
+        if winning_info.2 {
+            return Ok(());
+        }
         
-        self.winning_tickets.insert(&(round_id, ticket_number), (winning_info.0, winning_info.1, true, winning_info.3))
+        self.winning_tickets.insert(&(round_id, ticket_number), WinningTicket(winning_info.0, winning_info.1, true, winning_info.3, winning_info.4))
             .map_err(|e: ViewError| format!("Failed to update winning ticket: {:?}", e))?;
         
+        if let Some((count, amount)) = self.rounds_with_unclaimed.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get unclaimed prizes: {:?}", e))? {
+            if count <= 1 {
+                self.rounds_with_unclaimed.remove(&round_id)
+                    .map_err(|e: ViewError| format!("Failed to update unclaimed prizes: {:?}", e))?;
+            } else {
+                self.rounds_with_unclaimed.insert(&round_id, (count - 1, amount.saturating_sub(winning_info.1)))
+                    .map_err(|e: ViewError| format!("Failed to update unclaimed prizes: {:?}", e))?;
+            }
+        }
+        let mut payouts = self.round_payouts.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round payouts: {:?}", e))?
+            .unwrap_or_default();
+        payouts.pay(winning_info.1);
+        self.round_payouts.insert(&round_id, payouts)
+            .map_err(|e: ViewError| format!("Failed to update round payouts: {:?}", e))?;
+        
         Ok(())
     }
     
+    /// Rounds with undelivered prizes, oldest first
+    /// Returns: Vec<(round_id, unclaimed_winner_count, total_unclaimed_amount)>
+    pub async fn get_rounds_with_unclaimed_prizes(&self) -> Result<Vec<(u64, u64, Amount)>, String> {
+        let round_ids = self.rounds_with_unclaimed.indices().await
+            .map_err(|e: ViewError| format!("Failed to get unclaimed prize indices: {:?}", e))?;
+        
+        let mut rounds = Vec::with_capacity(round_ids.len());
+        for round_id in round_ids {
+            if let Some((count, amount)) = self.rounds_with_unclaimed.get(&round_id).await
+                .map_err(|e: ViewError| format!("Failed to get unclaimed prizes: {:?}", e))? {
+                rounds.push((round_id, count, amount));
+            }
+        }
+        Ok(rounds)
+    }
+    
+    /// Awarded, paid and unclaimed prize totals of a round, from its running totals
+    pub async fn round_payout_summary(&self, round_id: u64) -> Result<RoundPayoutSummary, String> {
+        let round = self.rounds.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))?
+            .ok_or("Round not found")?;
+        let payouts = self.round_payouts.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round payouts: {:?}", e))?
+            .unwrap_or_default();
+        
+        let winners_total = round.pool1_count + round.pool2_count + round.pool3_count + round.pool4_count;
+        let winners_drawn = round.pool1_winners_drawn + round.pool2_winners_drawn + round.pool3_winners_drawn + round.pool4_winners_drawn;
+        Ok(payouts.summary(round.prize_pool, total_pool_allocation(round.prize_pool), winners_drawn, winners_total))
+    }
+    
+    /// Sum of all undelivered prizes
+    pub async fn get_total_unclaimed_prizes_amount(&self) -> Result<Amount, String> {
+        Ok(self.get_rounds_with_unclaimed_prizes().await?
+            .into_iter()
+            .fold(Amount::ZERO, |total, (_, _, amount)| total.saturating_add(amount)))
+    }
+    
+    /// Hold a prize in escrow for `owner` until ESCROW_DURATION_MICROS after `now`
+    pub async fn escrow_prize(&mut self, round_id: u64, ticket_number: u64, owner: AccountOwner, amount: Amount, destination_chain: String, now: u64) -> Result<(), String> {
+        let mut entries = self.get_escrowed_prizes(round_id, ticket_number).await?;
+        entries.push(EscrowEntry {
+            owner,
+            amount,
+            destination_chain,
+            created_at: now,
+            expires_at: now.saturating_add(ESCROW_DURATION_MICROS),
+        });
+        self.escrow.insert(&(round_id, ticket_number), entries)
+            .map_err(|e: ViewError| format!("Failed to escrow prize: {:?}", e))
+    }
+    
+    /// Get the escrowed prizes of a winning ticket
+    pub async fn get_escrowed_prizes(&self, round_id: u64, ticket_number: u64) -> Result<Vec<EscrowEntry>, String> {
+        Ok(self.escrow.get(&(round_id, ticket_number)).await
+            .map_err(|e: ViewError| format!("Failed to get escrow: {:?}", e))?
+            .unwrap_or_default())
+    }
+    
+    /// Remove and return `owner`'s unexpired escrowed prize for a ticket
+    pub async fn take_escrowed_prize(&mut self, round_id: u64, ticket_number: u64, owner: AccountOwner, now: u64) -> Result<EscrowEntry, String> {
+        let mut entries = self.get_escrowed_prizes(round_id, ticket_number).await?;
+        let position = entries.iter().position(|entry| entry.owner == owner)
+            .ok_or_else(|| format!("No escrowed prize for {} on ticket {} of round {}", owner, ticket_number, round_id))?;
+        if now >= entries[position].expires_at {
+            return Err("Escrowed prize has expired".to_string());
+        }
+        
+        let entry = entries.remove(position);
+        self.store_escrow(round_id, ticket_number, entries)?;
+        Ok(entry)
+    }
+    
+    /// Move every expired escrowed prize of a ticket to the reclaimed balance
+    pub async fn expire_escrow(&mut self, round_id: u64, ticket_number: u64, now: u64) -> Result<Amount, String> {
+        let (expired, remaining): (Vec<_>, Vec<_>) = self.get_escrowed_prizes(round_id, ticket_number).await?
+            .into_iter()
+            .partition(|entry| now >= entry.expires_at);
+        if expired.is_empty() {
+            return Err(format!("No expired escrow for ticket {} of round {}", ticket_number, round_id));
+        }
+        
+        let amount = expired.iter().fold(Amount::ZERO, |total, entry| total.saturating_add(entry.amount));
+        self.reclaimed_escrow.set(self.reclaimed_escrow.get().saturating_add(amount));
+        self.store_escrow(round_id, ticket_number, remaining)?;
+        Ok(amount)
+    }
+    
+    /// Escrowed payouts by round and ticket, at most `limit` (capped at MAX_PENDING_ITEMS)
+    pub async fn pending_items(&self, limit: u64) -> Result<Vec<PendingItem>, String> {
+        let limit = limit.min(MAX_PENDING_ITEMS) as usize;
+        let tickets = self.escrow.indices().await
+            .map_err(|e: ViewError| format!("Failed to get escrow indices: {:?}", e))?;
+        
+        let mut items = Vec::new();
+        for (round_id, ticket_number) in tickets {
+            for entry in self.get_escrowed_prizes(round_id, ticket_number).await? {
+                if items.len() == limit {
+                    return Ok(items);
+                }
+                items.push(PendingItem {
+                    kind: PendingKind::EscrowedPrize,
+                    owner: entry.owner,
+                    amount: entry.amount,
+                    source_chain_id: Some(entry.destination_chain),
+                    created_at: entry.created_at,
+                    retry_count: 0,
+                });
+            }
+        }
+        Ok(items)
+    }
+    
+    /// Take the whole reclaimed escrow balance
+    pub fn take_reclaimed_escrow(&mut self) -> Amount {
+        let amount = *self.reclaimed_escrow.get();
+        self.reclaimed_escrow.set(Amount::ZERO);
+        amount
+    }
+    
+    fn store_escrow(&mut self, round_id: u64, ticket_number: u64, entries: Vec<EscrowEntry>) -> Result<(), String> {
+        if entries.is_empty() {
+            self.escrow.remove(&(round_id, ticket_number))
+        } else {
+            self.escrow.insert(&(round_id, ticket_number), entries)
+        }
+        .map_err(|e: ViewError| format!("Failed to update escrow: {:?}", e))
+    }
+    
     /// Get lottery round by ID
     pub async fn get_round(&self, round_id: u64) -> Result<Option<LotteryRound>, String> {
         self.rounds.get(&round_id).await
@@ -469,12 +1683,66 @@ impl LotteryRoundsState {
     pub async fn get_all_rounds(&self) -> Result<Vec<LotteryRound>, String> {
         let indices = self.rounds.indices().await
             .map_err(|e: ViewError| format!("Failed to get round indices: {:?}", e))?;
+        Ok(self.get_rounds_batch(&indices).await?.into_iter().flatten().collect())
+    }
+    
+    /// Load several rounds at once, in the order of `ids` (None for unknown rounds)
+    pub async fn get_rounds_batch(&self, ids: &[u64]) -> Result<Vec<Option<LotteryRound>>, String> {
+        BatchLoader::new(&self.rounds).load(ids).await
+            .map_err(|e: ViewError| format!("Failed to get rounds: {:?}", e))
+    }
+    
+    /// Move a round between entries of the status index (None = not indexed)
+    fn reindex_round_status(&mut self, round_id: u64, previous: Option<RoundStatus>, current: Option<RoundStatus>) -> Result<(), String> {
+        if let Some(previous) = previous {
+            self.rounds_by_status.remove(&(previous.index_key(), round_id))
+                .map_err(|e: ViewError| format!("Failed to update round status index: {:?}", e))?;
+        }
+        if let Some(current) = current {
+            self.rounds_by_status.insert(&(current.index_key(), round_id), ())
+                .map_err(|e: ViewError| format!("Failed to update round status index: {:?}", e))?;
+        }
+        Ok(())
+    }
+    
+    /// Get up to `limit` rounds in `status`, newest first (uses the status index)
+    pub async fn get_rounds_by_status(&self, status: RoundStatus, limit: u64) -> Result<Vec<LotteryRound>, String> {
+        let filter = RoundFilter { status: Some(status), ..RoundFilter::default() };
+        self.filter_rounds(&filter, SortOrder::Desc, limit).await
+    }
+    
+    /// Up to `limit` rounds matching `filter`, by ID in `order`. A status filter reads
+    /// the status index instead of every round, and since IDs grow with creation time
+    /// the walk stops at the first round past the far creation bound.
+    pub async fn filter_rounds(&self, filter: &RoundFilter, order: SortOrder, limit: u64) -> Result<Vec<LotteryRound>, String> {
+        let mut round_ids: Vec<u64> = match filter.status {
+            Some(status) => {
+                let key = status.index_key();
+                self.rounds_by_status.indices().await
+                    .map_err(|e: ViewError| format!("Failed to get round status index: {:?}", e))?
+                    .into_iter()
+                    .filter(|(status_key, _)| *status_key == key)
+                    .map(|(_, round_id)| round_id)
+                    .collect()
+            }
+            None => self.rounds.indices().await
+                .map_err(|e: ViewError| format!("Failed to get round indices: {:?}", e))?,
+        };
+        order.sort(&mut round_ids);
         
-        let mut rounds = Vec::with_capacity(indices.len());
-        
-        for index in indices {
-            if let Some(round) = self.rounds.get(&index).await
-                .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))? {
+        let created = filter.created_range();
+        let mut rounds = Vec::new();
+        for round_id in round_ids {
+            if rounds.len() as u64 >= limit {
+                break;
+            }
+            let Some(round) = self.get_round(round_id).await? else {
+                continue;
+            };
+            if created.is_exhausted(round.created_at, order) {
+                break;
+            }
+            if created.contains(round.created_at) {
                 rounds.push(round);
             }
         }
@@ -486,23 +1754,27 @@ impl LotteryRoundsState {
         Ok(*self.active_round.get())
     }
     
+    /// The active round, or the latest round while none is active (it is being drawn)
+    pub async fn current_round(&self) -> Result<Option<LotteryRound>, String> {
+        let round_id = match *self.active_round.get() {
+            Some(round_id) => round_id,
+            None if *self.round_counter.get() > 0 => *self.round_counter.get(),
+            None => return Ok(None),
+        };
+        self.get_round(round_id).await
+    }
+    
     /// Get ticket purchases for a specific round
     pub async fn get_round_ticket_purchases(&self, round_id: u64) -> Result<Vec<(AccountOwner, TicketPurchase)>, String> {
-        let indices = self.ticket_purchases.indices().await
-            .map_err(|e: ViewError| format!("Failed to get ticket purchase indices: {:?}", e))?;
-        
-        let mut purchases = Vec::new();
-        
-        for (rid, owner) in indices {
-            if rid == round_id {
-                if let Some(purchase) = self.ticket_purchases.get(&(rid, owner.clone())).await
-                    .map_err(|e: ViewError| format!("Failed to get ticket purchase: {:?}", e))? {
-                    purchases.push((owner, purchase));
-                }
-            }
-        }
+        let keys: Vec<(u64, AccountOwner)> = self.ticket_purchases.indices().await
+            .map_err(|e: ViewError| format!("Failed to get ticket purchase indices: {:?}", e))?
+            .into_iter()
+            .filter(|(rid, _)| *rid == round_id)
+            .collect();
         
-        Ok(purchases)
+        let purchases = BatchLoader::new(&self.ticket_purchases).load_present(keys).await
+            .map_err(|e: ViewError| format!("Failed to get ticket purchases: {:?}", e))?;
+        Ok(purchases.into_iter().map(|((_, owner), purchase)| (owner, purchase)).collect())
     }
     
     /// Get user's tickets for a specific round
@@ -511,6 +1783,178 @@ impl LotteryRoundsState {
             .map_err(|e: ViewError| format!("Failed to get user tickets: {:?}", e))
     }
     
+    /// Up to `limit` (at most MAX_TICKET_NUMBERS_PAGE) of an owner's ticket numbers above `after`,
+    /// ascending; empty if the owner holds no tickets in the round
+    pub async fn get_owner_ticket_numbers(&self, round_id: u64, owner: AccountOwner, after: Option<u64>, limit: u64) -> Result<Vec<u64>, String> {
+        let Some(purchase) = self.get_user_tickets(round_id, owner).await? else {
+            return Ok(Vec::new());
+        };
+        let limit = usize::try_from(limit.min(MAX_TICKET_NUMBERS_PAGE)).unwrap_or(usize::MAX);
+        let first = after.map_or(0, |after| after.saturating_add(1));
+        Ok(purchase.ranges.iter()
+            .filter(|&&(_, last)| last >= first)
+            .flat_map(|&(range_first, last)| range_first.max(first)..=last)
+            .take(limit)
+            .collect())
+    }
+    
+    /// Get a player's lifetime totals
+    pub async fn get_player_totals(&self, owner: AccountOwner) -> Result<Option<PlayerTotals>, String> {
+        self.player_totals.get(&owner).await
+            .map_err(|e: ViewError| format!("Failed to get player totals: {:?}", e))
+    }
+    
+    /// Set or clear a player's own per-round spend limit
+    pub fn set_spend_limit(&mut self, owner: AccountOwner, per_round: Option<Amount>) -> Result<(), String> {
+        match per_round {
+            Some(limit) => self.spend_limits.insert(&owner, limit),
+            None => self.spend_limits.remove(&owner),
+        }
+        .map_err(|e: ViewError| format!("Failed to set spend limit: {:?}", e))
+    }
+    
+    /// Subscribe, update or (with both flags off) unsubscribe an owner's notifications
+    pub async fn set_notification_preferences(&mut self, owner: AccountOwner, prefs: NotificationPreferences) -> Result<(), String> {
+        if let Some(chain_id) = &prefs.destination_chain_id {
+            chain_id.parse::<ChainId>()
+                .map_err(|e| format!("Invalid destination chain ID {}: {:?}", chain_id, e))?;
+        }
+        if !prefs.notify_on_round_close && !prefs.notify_on_winner_drawn {
+            return self.notification_subscriptions.remove(&owner)
+                .map_err(|e: ViewError| format!("Failed to remove notification preferences: {:?}", e));
+        }
+        
+        let subscribed = self.notification_subscriptions.contains_key(&owner).await
+            .map_err(|e: ViewError| format!("Failed to check notification preferences: {:?}", e))?;
+        let subscribers = self.notification_subscriptions.count().await
+            .map_err(|e: ViewError| format!("Failed to count subscribers: {:?}", e))? as u64;
+        if !subscribed && subscribers >= MAX_NOTIFICATION_SUBSCRIBERS {
+            return Err(format!("Notification subscriptions are limited to {} owners", MAX_NOTIFICATION_SUBSCRIBERS));
+        }
+        self.notification_subscriptions.insert(&owner, prefs)
+            .map_err(|e: ViewError| format!("Failed to set notification preferences: {:?}", e))
+    }
+    
+    /// Destination chains of subscribers who want round close (or, with `on_close`
+    /// false, winner drawn) notifications; None stands for the lottery chain
+    pub async fn notification_destinations(&self, on_close: bool) -> Result<Vec<Option<String>>, String> {
+        let owners = self.notification_subscriptions.indices().await
+            .map_err(|e: ViewError| format!("Failed to get subscriber indices: {:?}", e))?;
+        
+        let mut destinations = Vec::new();
+        for owner in owners {
+            if let Some(prefs) = self.notification_subscriptions.get(&owner).await
+                .map_err(|e: ViewError| format!("Failed to get notification preferences: {:?}", e))? {
+                let wanted = if on_close { prefs.notify_on_round_close } else { prefs.notify_on_winner_drawn };
+                if wanted {
+                    destinations.push(prefs.destination_chain_id);
+                }
+            }
+        }
+        Ok(destinations)
+    }
+    
+    /// Append a state-changing operation to the operation log
+    pub fn record_operation(&mut self, entry: OperationLogEntry) {
+        self.operation_log.push(entry);
+    }
+    
+    /// Up to `limit` (at most MAX_OPERATION_LOG_PAGE) operation log entries from position `offset`
+    pub async fn get_operation_log(&self, offset: u64, limit: u64) -> Result<Vec<OperationLogEntry>, String> {
+        let count = self.operation_log.count();
+        let start = usize::try_from(offset).unwrap_or(usize::MAX).min(count);
+        let limit = usize::try_from(limit.min(MAX_OPERATION_LOG_PAGE)).unwrap_or(usize::MAX);
+        let end = start.saturating_add(limit).min(count);
+        self.operation_log.read(start..end).await
+            .map_err(|e: ViewError| format!("Failed to read operation log: {:?}", e))
+    }
+    
+    /// Observer chains that closed rounds are sent to, as (index, chain_id) in the order added
+    pub async fn sync_targets(&self) -> Result<Vec<(u64, String)>, String> {
+        let indices = self.sync_target_chains.indices().await
+            .map_err(|e: ViewError| format!("Failed to get sync target indices: {:?}", e))?;
+        BatchLoader::new(&self.sync_target_chains).load_present(indices).await
+            .map_err(|e: ViewError| format!("Failed to get sync targets: {:?}", e))
+    }
+    
+    /// Replicate every round that closes from now on to `chain_id`
+    pub async fn add_sync_target(&mut self, chain_id: String) -> Result<(), String> {
+        chain_id.parse::<ChainId>()
+            .map_err(|e| format!("Invalid sync target chain ID {}: {:?}", chain_id, e))?;
+        let targets = self.sync_targets().await?;
+        if targets.iter().any(|(_, target)| *target == chain_id) {
+            return Err(format!("Chain {} is already a sync target", chain_id));
+        }
+        if targets.len() as u64 >= MAX_SYNC_TARGETS {
+            return Err(format!("Rounds can be synced to at most {} chains", MAX_SYNC_TARGETS));
+        }
+        let index = targets.last().map_or(0, |(index, _)| index + 1);
+        self.sync_target_chains.insert(&index, chain_id)
+            .map_err(|e: ViewError| format!("Failed to add sync target: {:?}", e))
+    }
+    
+    /// Stop replicating rounds to `chain_id`
+    pub async fn remove_sync_target(&mut self, chain_id: &str) -> Result<(), String> {
+        let (index, _) = self.sync_targets().await?
+            .into_iter()
+            .find(|(_, target)| target == chain_id)
+            .ok_or_else(|| format!("Chain {} is not a sync target", chain_id))?;
+        self.sync_target_chains.remove(&index)
+            .map_err(|e: ViewError| format!("Failed to remove sync target: {:?}", e))
+    }
+    
+    /// Keep the latest state of a round replicated from the lottery chain
+    pub fn store_synced_round(&mut self, round: lottery_abi::LotteryRound) -> Result<(), String> {
+        let id = round.id;
+        self.synced_rounds.insert(&id, round)
+            .map_err(|e: ViewError| format!("Failed to store synced round: {:?}", e))
+    }
+    
+    /// Every replicated round, by ID
+    pub async fn get_synced_rounds(&self) -> Result<Vec<lottery_abi::LotteryRound>, String> {
+        let ids = self.synced_rounds.indices().await
+            .map_err(|e: ViewError| format!("Failed to get synced round indices: {:?}", e))?;
+        let rounds = BatchLoader::new(&self.synced_rounds).load_present(ids).await
+            .map_err(|e: ViewError| format!("Failed to get synced rounds: {:?}", e))?;
+        Ok(rounds.into_iter().map(|(_, round)| round).collect())
+    }
+    
+    /// Get a user's participation across all retained rounds (rounds removed by
+    /// the MAX_HISTORY_ROUNDS cleanup are not reported)
+    pub async fn get_user_participation(&self, owner: AccountOwner) -> Result<Vec<UserRoundParticipation>, String> {
+        // Sum the owner's prizes per round in a single pass over winning tickets
+        let mut prizes: std::collections::BTreeMap<u64, Amount> = std::collections::BTreeMap::new();
+        let winning_indices = self.winning_tickets.indices().await
+            .map_err(|e: ViewError| format!("Failed to get winning ticket indices: {:?}", e))?;
+        for (round_id, ticket_number) in winning_indices {
+            if let Some(WinningTicket(winner, prize, ..)) = self.winning_tickets.get(&(round_id, ticket_number)).await
+                .map_err(|e: ViewError| format!("Failed to get winning ticket: {:?}", e))? {
+                if winner == owner {
+                    let total = prizes.entry(round_id).or_insert(Amount::ZERO);
+                    *total = total.saturating_add(prize);
+                }
+            }
+        }
+        
+        let round_ids = self.rounds.indices().await
+            .map_err(|e: ViewError| format!("Failed to get round indices: {:?}", e))?;
+        
+        let mut participation = Vec::new();
+        for round_id in round_ids {
+            if let Some(purchase) = self.ticket_purchases.get(&(round_id, owner)).await
+                .map_err(|e: ViewError| format!("Failed to get ticket purchase: {:?}", e))? {
+                let total_prize = prizes.get(&round_id).copied();
+                participation.push(UserRoundParticipation {
+                    round_id,
+                    total_tickets: purchase.total_tickets,
+                    won: total_prize.is_some(),
+                    total_prize: total_prize.unwrap_or(Amount::ZERO),
+                });
+            }
+        }
+        Ok(participation)
+    }
+    
     /// Get all winning tickets for a round
     pub async fn get_round_winners(&self, round_id: u64) -> Result<Vec<(u64, AccountOwner, Amount, bool, Option<String>)>, String> {
         let indices = self.winning_tickets.indices().await
@@ -520,16 +1964,222 @@ impl LotteryRoundsState {
         
         for (rid, ticket_number) in indices {
             if rid == round_id {
-                if let Some((owner, prize, claimed, source_chain_id)) = self.winning_tickets.get(&(rid, ticket_number)).await
+                if let Some(WinningTicket(owner, prize, claimed, source_chain_id, _)) = self.winning_tickets.get(&(rid, ticket_number)).await
                     .map_err(|e: ViewError| format!("Failed to get winning ticket: {:?}", e))? {
                     winners.push((ticket_number, owner, prize, claimed, source_chain_id));
                 }
             }
         }
+        // Stable order for clients that diff repeated queries
+        winners.sort_by_key(|(ticket_number, ..)| *ticket_number);
         
         Ok(winners)
     }
     
+    /// Get a round's winners with a ticket in `start..=end`, looking up only the keys in the range
+    pub async fn get_winners_by_ticket_range(&self, round_id: u64, start: u64, end: u64) -> Result<Vec<(u64, AccountOwner, Amount, bool, Option<String>)>, String> {
+        if start > end {
+            return Err(format!("Ticket range start {} is after its end {}", start, end));
+        }
+        if end - start >= MAX_WINNER_TICKET_RANGE {
+            return Err(format!("Ticket range {}..={} spans more than {} tickets", start, end, MAX_WINNER_TICKET_RANGE));
+        }
+        
+        let keys: Vec<(u64, u64)> = (start..=end).map(|ticket_number| (round_id, ticket_number)).collect();
+        let winners = BatchLoader::new(&self.winning_tickets).load_present(keys).await
+            .map_err(|e: ViewError| format!("Failed to get winning tickets: {:?}", e))?;
+        Ok(winners.into_iter()
+            .map(|((_, ticket_number), WinningTicket(owner, prize, claimed, source_chain_id, _))| (ticket_number, owner, prize, claimed, source_chain_id))
+            .collect())
+    }
+    
+    /// Get winners for a round grouped by the pool they were drawn from, Pool1 to Pool4
+    pub async fn get_round_winners_by_pool(&self, round_id: u64) -> Result<Vec<(WinnerPool, Vec<(u64, AccountOwner, Amount, bool, Option<String>)>)>, String> {
+        let mut groups: Vec<_> = [WinnerPool::Pool1, WinnerPool::Pool2, WinnerPool::Pool3, WinnerPool::Pool4]
+            .into_iter()
+            .map(|pool| (pool, Vec::new()))
+            .collect();
+        
+        let indices = self.winning_tickets.indices().await
+            .map_err(|e: ViewError| format!("Failed to get winning ticket indices: {:?}", e))?;
+        for (rid, ticket_number) in indices {
+            if rid != round_id {
+                continue;
+            }
+            if let Some(WinningTicket(owner, prize, claimed, source_chain_id, pool)) = self.winning_tickets.get(&(rid, ticket_number)).await
+                .map_err(|e: ViewError| format!("Failed to get winning ticket: {:?}", e))? {
+                if let Some((_, winners)) = groups.iter_mut().find(|(group, _)| Some(*group) == pool) {
+                    winners.push((ticket_number, owner, prize, claimed, source_chain_id));
+                }
+            }
+        }
+        
+        Ok(groups)
+    }
+    
+    /// Compare the sum of the four pool allocations with a round's prize pool
+    pub async fn verify_pool_integrity(&self, round_id: u64) -> Result<PoolIntegrity, String> {
+        let round = self.rounds.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))?
+            .ok_or("Round not found")?;
+        
+        let allocated = total_pool_allocation(round.prize_pool);
+        if allocated > round.prize_pool {
+            return Err(format!("Pool allocations {} exceed prize pool {}", allocated, round.prize_pool));
+        }
+        
+        Ok(PoolIntegrity {
+            round_id,
+            prize_pool: round.prize_pool,
+            allocated,
+            dust: round.prize_pool.saturating_sub(allocated),
+        })
+    }
+    
+    /// Winners drawn so far out of those a round's pools expect
+    pub async fn draw_progress(&self, round_id: u64) -> Result<DrawProgress, String> {
+        let round = self.rounds.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))?
+            .ok_or("Round not found")?;
+        
+        let winners_expected = round.pool1_count + round.pool2_count + round.pool3_count + round.pool4_count;
+        let winners_drawn = round.pool1_winners_drawn + round.pool2_winners_drawn + round.pool3_winners_drawn + round.pool4_winners_drawn;
+        let completion_bps = if round.status == RoundStatus::Complete {
+            10_000
+        } else if winners_expected == 0 {
+            0
+        } else {
+            (u128::from(winners_drawn.min(winners_expected)) * 10_000 / u128::from(winners_expected)) as u64
+        };
+        
+        Ok(DrawProgress { round_id, winners_expected, winners_drawn, completion_bps })
+    }
+    
+    /// Export every ticket of a round as CSV bytes, one row per ticket
+    /// Columns: ticket_number,owner,amount_paid,is_winner,prize_amount,claimed,source_chain_id
+    pub async fn export_round_csv(&self, round_id: u64) -> Result<Vec<u8>, String> {
+        if self.get_round(round_id).await?.is_none() {
+            return Err("Round not found".to_string());
+        }
+        
+        let mut purchases = self.get_round_ticket_purchases(round_id).await?;
+        purchases.sort_by_key(|(_, purchase)| purchase.first_ticket);
+        
+        let mut writer = CsvWriter::new();
+        writer.write_record(["ticket_number", "owner", "amount_paid", "is_winner", "prize_amount", "claimed", "source_chain_id"]);
+        
+        for (owner, purchase) in purchases {
+            // Amount paid per ticket of this purchase
+            let amount_per_ticket = Amount::from_attos(u128::from(purchase.amount_paid) / u128::from(purchase.total_tickets.max(1)));
+            let source_chain_id = purchase.source_chain_id.clone().unwrap_or_default();
+            
+            for ticket_number in purchase.tickets() {
+                let winning_info = self.winning_tickets.get(&(round_id, ticket_number)).await
+                    .map_err(|e: ViewError| format!("Failed to get winning ticket: {:?}", e))?;
+                let (is_winner, prize_amount, claimed) = match winning_info {
+                    Some(WinningTicket(_, prize, claimed, ..)) => (true, prize, claimed),
+                    None => (false, Amount::ZERO, false),
+                };
+                
+                writer.write_record([
+                    ticket_number.to_string(),
+                    owner.to_string(),
+                    amount_per_ticket.to_string(),
+                    is_winner.to_string(),
+                    prize_amount.to_string(),
+                    claimed.to_string(),
+                    source_chain_id.clone(),
+                ]);
+            }
+        }
+        
+        Ok(writer.into_bytes())
+    }
+    
+    /// Winner pools of the active round as they would be if it closed now
+    pub async fn get_prize_schedule(&self) -> Result<Option<PrizeSchedule>, String> {
+        let Some(round_id) = *self.active_round.get() else {
+            return Ok(None);
+        };
+        let round = self.get_round(round_id).await?.ok_or("Active round not found")?;
+        
+        let pools = [WinnerPool::Pool1, WinnerPool::Pool2, WinnerPool::Pool3, WinnerPool::Pool4]
+            .into_iter()
+            .zip(winner_pool_counts(round.total_tickets_sold))
+            .map(|(pool, winner_count)| PoolSchedule {
+                pool,
+                ticket_percentage: pool_ticket_percentage(pool),
+                winner_count,
+                prize_percentage: pool_prize_percentage(pool) as u64,
+                prize_allocation: calculate_prize_for_pool(round.prize_pool, pool),
+                prize_per_winner: calculate_prize_per_winner(round.prize_pool, pool, winner_count),
+            })
+            .collect();
+        
+        Ok(Some(PrizeSchedule {
+            round_id,
+            total_tickets_sold: round.total_tickets_sold,
+            prize_pool: round.prize_pool,
+            pools,
+        }))
+    }
+    
+    /// Canonical archive of a completed round: ticket ranges, winners and payouts
+    pub async fn round_archive(&self, round_id: u64) -> Result<RoundArchive, String> {
+        let round = self.get_round(round_id).await?.ok_or("Round not found")?;
+        if round.status != RoundStatus::Complete {
+            return Err("Only completed rounds can be archived".to_string());
+        }
+        
+        let mut tickets: Vec<TicketPurchaseInfo> = self.get_round_ticket_purchases(round_id).await?
+            .into_iter()
+            .map(|(owner, purchase)| TicketPurchaseInfo {
+                owner,
+                first_ticket: purchase.first_ticket,
+                last_ticket: purchase.last_ticket,
+                total_tickets: purchase.total_tickets,
+                amount_paid: purchase.amount_paid,
+                source_chain_id: purchase.source_chain_id,
+            })
+            .collect();
+        tickets.sort_by_key(|purchase| (purchase.first_ticket, purchase.owner));
+        
+        let mut participants: Vec<AccountOwner> = tickets.iter().map(|purchase| purchase.owner).collect();
+        participants.sort();
+        participants.dedup();
+        
+        let mut winners: Vec<LotteryWinnerInfo> = self.get_round_winners(round_id).await?
+            .into_iter()
+            .map(|(ticket_number, owner, prize_amount, claimed, source_chain_id)| LotteryWinnerInfo {
+                ticket_number,
+                owner,
+                prize_amount,
+                claimed,
+                source_chain_id,
+            })
+            .collect();
+        winners.sort_by_key(|winner| winner.ticket_number);
+        
+        let mut payouts: Vec<OwnerPayout> = Vec::new();
+        for winner in &winners {
+            match payouts.iter_mut().find(|payout| payout.owner == winner.owner) {
+                Some(payout) => payout.total_prize = payout.total_prize.saturating_add(winner.prize_amount),
+                None => payouts.push(OwnerPayout { owner: winner.owner, total_prize: winner.prize_amount }),
+            }
+        }
+        payouts.sort_by_key(|payout| payout.owner);
+        
+        Ok(RoundArchive {
+            // Priced as of closing so the archive stays canonical
+            round: round.to_lib(round.closed_at.unwrap_or(round.created_at)),
+            participants,
+            tickets,
+            winners,
+            payouts,
+            payout_summary: self.round_payout_summary(round_id).await?,
+        })
+    }
+    
     /// Get the current round's ticket price
     pub async fn get_current_ticket_price(&self) -> Result<Amount, String> {
         if let Some(round_id) = *self.active_round.get() {
@@ -543,3 +2193,23 @@ impl LotteryRoundsState {
         Ok(Amount::from_tokens(1))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_allocation_leaves_dust() {
+        // 7 attos split 20/25/30/25% allocates 1 + 1 + 2 + 1 = 5 attos, leaving 2 attos of dust
+        let prize_pool = Amount::from_attos(7);
+        let allocated = total_pool_allocation(prize_pool);
+        assert_eq!(allocated, Amount::from_attos(5));
+        assert_eq!(prize_pool.saturating_sub(allocated), Amount::from_attos(2));
+    }
+
+    #[test]
+    fn test_pool_allocation_exact() {
+        let prize_pool = Amount::from_tokens(100);
+        assert_eq!(total_pool_allocation(prize_pool), prize_pool);
+    }
+}
@@ -6,11 +6,11 @@
 mod state;
 
 use linera_sdk::{
-    linera_base_types::{ApplicationId, ChainId, WithContractAbi},
+    linera_base_types::{Account, AccountOwner, Amount, ApplicationId, ChainId, WithContractAbi},
     views::{RootView, View},
     Contract, ContractRuntime,
 };
-use Winzareal::{Message, WinzaAbi, ExtendedOperation, ExtendedResponse, Prediction};
+use Winzareal::{validate_memo, Message, TransferNotice, VestingEntry, WinzaAbi, ExtendedOperation, ExtendedResponse, Prediction};
 use self::state::WinzaState;
 
 // Conversion function
@@ -18,6 +18,30 @@ fn to_rounds_prediction(pred: Prediction) -> rounds::Prediction {
     match pred {
         Prediction::Up => rounds::Prediction::Up,
         Prediction::Down => rounds::Prediction::Down,
+        Prediction::Flat => rounds::Prediction::Flat,
+    }
+}
+
+/// Metrics counter name for an operation
+fn operation_metric(operation: &ExtendedOperation) -> &'static str {
+    match operation {
+        ExtendedOperation::Balance { .. } => "operation.Balance",
+        ExtendedOperation::ChainBalance => "operation.ChainBalance",
+        ExtendedOperation::TickerSymbol => "operation.TickerSymbol",
+        ExtendedOperation::Transfer { .. } => "operation.Transfer",
+        ExtendedOperation::Claim { .. } => "operation.Claim",
+        ExtendedOperation::Withdraw => "operation.Withdraw",
+        ExtendedOperation::Mint { .. } => "operation.Mint",
+        ExtendedOperation::BulkMint { .. } => "operation.BulkMint",
+        ExtendedOperation::GetTotalMinted => "operation.GetTotalMinted",
+        ExtendedOperation::SetNativeAppId { .. } => "operation.SetNativeAppId",
+        ExtendedOperation::SetRoundsAppId { .. } => "operation.SetRoundsAppId",
+        ExtendedOperation::SendReward { .. } => "operation.SendReward",
+        ExtendedOperation::SendRewardVested { .. } => "operation.SendRewardVested",
+        ExtendedOperation::ClaimVested { .. } => "operation.ClaimVested",
+        ExtendedOperation::ResetMetrics => "operation.ResetMetrics",
+        ExtendedOperation::GetConfig => "operation.GetConfig",
+        ExtendedOperation::ImportConfig { .. } => "operation.ImportConfig",
     }
 }
 
@@ -46,11 +70,16 @@ impl Contract for WinzaContract {
     }
 
     async fn instantiate(&mut self, _arg: Self::InstantiationArgument) {
-        // Validation (optional)
-        let _ = self.runtime.application_parameters();
+        // Record the coordinated app IDs so bets are always routed to the Rounds app
+        let params = self.runtime.application_parameters();
+        self.state.native_app_id.set(Some(params.native_app_id.with_abi()));
+        self.state.rounds_app_id.set(Some(params.rounds_app_id.with_abi()));
     }
 
     async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response {
+        self.state.increment_metric(operation_metric(&operation)).await
+            .expect("Failed to update metrics");
+        
         match operation {
             // Winzareal-specific operations
             ExtendedOperation::SetNativeAppId { .. } => {
@@ -68,14 +97,19 @@ impl Contract for WinzaContract {
                 amount,
                 target_account,
                 prediction: Some(prediction),
+                memo,
             } => {
-                // Transfer with prediction - this is our main betting operation
-                
+                // A same-chain transfer to oneself moves no tokens, so it must not place a bet
+                if target_account.owner == owner && target_account.chain_id == self.runtime.chain_id() {
+                    panic!("Self-transfers cannot place bets");
+                }
+                if let Err(e) = validate_memo(memo.as_deref()) {
+                    panic!("Invalid transfer memo: {}", e);
+                }
 
-                
+                // Transfer with prediction - this is our main betting operation
                 let params = self.runtime.application_parameters();
                 let native_app_id = params.native_app_id.with_abi::<native::NativeAbi>();
-                let rounds_app_id = params.rounds_app_id.with_abi::<rounds::RoundsAbi>();
 
                 // Step 1: Call Native app to transfer tokens
                 let _native_response: native::NativeResponse = self.runtime.call_application(
@@ -85,40 +119,61 @@ impl Contract for WinzaContract {
                         owner,
                         amount,
                         target_account,
+                        memo: memo.clone(),
                     },
                 );
 
-                // Step 2: Place bet in Rounds app
-                if target_account.chain_id == self.runtime.chain_id() {
-                    // Same chain - no source_chain_id needed
-                    let _rounds_response: rounds::RoundsResponse = self.runtime.call_application(
-                        true,
-                        rounds_app_id,
-                        &rounds::RoundsOperation::PlaceBet {
-                            owner, // Sender makes the bet
-                            amount,
-                            prediction: to_rounds_prediction(prediction),
-                            source_chain_id: None,
-                        },
-                    );
-                } else {
-                    // Cross-chain - send message with SENDER'S chain_id
-                    let message = Message::TransferWithPrediction {
-                        owner: target_account.owner,
+                // Step 2: Place bet in Rounds app - the sender makes the bet
+                let sender_chain = self.runtime.chain_id();
+                self.route_bet(owner, sender_chain, target_account, amount, prediction, memo).await;
+
+                ExtendedResponse::Ok
+            }
+
+            ExtendedOperation::Claim {
+                source_account,
+                amount,
+                target_account,
+                prediction: Some(prediction),
+            } => {
+                // Claim with prediction - bet the claimed tokens on behalf of the receiver
+                let params = self.runtime.application_parameters();
+                let native_app_id = params.native_app_id.with_abi::<native::NativeAbi>();
+
+                // Step 1: Call Native app to claim tokens
+                let _native_response: native::NativeResponse = self.runtime.call_application(
+                    true,
+                    native_app_id,
+                    &native::NativeOperation::Claim {
+                        source_account,
                         amount,
-                        prediction,
-                        source_chain_id: self.runtime.chain_id().to_string(), // SENDER'S chain!
-                        source_owner: owner,
-                    };
-                    self.runtime
-                        .prepare_message(message)
-                        .with_authentication()
-                        .send_to(target_account.chain_id);
-                }
+                        target_account,
+                    },
+                );
+
+                // Step 2: Place bet in Rounds app - the claimed tokens belong to the target owner
+                self.route_bet(target_account.owner, target_account.chain_id, target_account, amount, prediction, None).await;
+
+                ExtendedResponse::Ok
+            }
 
+            ExtendedOperation::ResetMetrics => {
+                self.state.reset_metrics();
                 ExtendedResponse::Ok
             }
 
+            ExtendedOperation::GetConfig => ExtendedResponse::Config(self.state.get_config()),
+
+            ExtendedOperation::ImportConfig { config } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("ImportConfig can only be executed on the application creator chain");
+                }
+                match self.state.import_config(config) {
+                    Ok(()) => ExtendedResponse::Ok,
+                    Err(e) => panic!("Failed to import config: {}", e),
+                }
+            }
+
             ExtendedOperation::SendReward { recipient, amount, source_chain_id } => {
                 // Called by Rounds to distribute rewards
                 let params = self.runtime.application_parameters();
@@ -145,14 +200,69 @@ impl Contract for WinzaContract {
                         owner: resolver_owner,
                         amount,
                         target_account,
+                        memo: None,
                     },
                 );
 
                 ExtendedResponse::Ok
             }
 
+            ExtendedOperation::SendRewardVested { recipient, amount, duration_blocks, cliff_blocks, source_chain_id } => {
+                // Called by Rounds like SendReward, but the tokens stay with this app until claimed
+                let entry = VestingEntry {
+                    total: amount,
+                    released: Amount::ZERO,
+                    start_block: self.runtime.block_height().0,
+                    duration_blocks,
+                    cliff_blocks,
+                    source_chain_id,
+                };
+                let entry_id = match self.state.create_vesting_entry(recipient, entry) {
+                    Ok(entry_id) => entry_id,
+                    Err(e) => panic!("Failed to create vesting entry: {}", e),
+                };
+
+                let resolver_owner = self.runtime.authenticated_signer()
+                    .expect("Authentication required for reward distribution");
+                let native_app_id = self.runtime.application_parameters().native_app_id.with_abi::<native::NativeAbi>();
+                let vesting_account = linera_sdk::abis::fungible::Account {
+                    chain_id: self.runtime.chain_id(),
+                    owner: self.vesting_owner(),
+                };
+                let _native_response: native::NativeResponse = self.runtime.call_application(
+                    true,
+                    native_app_id,
+                    &native::NativeOperation::Transfer {
+                        owner: resolver_owner,
+                        amount,
+                        target_account: vesting_account,
+                        memo: None,
+                    },
+                );
+
+                ExtendedResponse::VestingEntryId(entry_id)
+            }
+
+            ExtendedOperation::ClaimVested { entry_id } => {
+                let recipient = self.runtime.authenticated_signer()
+                    .expect("ClaimVested requires an authenticated signer");
+                let height = self.runtime.block_height().0;
+                let (amount, source_chain_id) = match self.state.release_vested(recipient, entry_id, height).await {
+                    Ok(released) => released,
+                    Err(e) => panic!("Failed to claim vested reward: {}", e),
+                };
+
+                let target_chain = source_chain_id
+                    .and_then(|chain_id| chain_id.parse::<ChainId>().ok())
+                    .unwrap_or_else(|| self.runtime.chain_id());
+                let source = self.vesting_owner();
+                self.runtime.transfer(source, Account { chain_id: target_chain, owner: recipient }, amount);
+
+                ExtendedResponse::VestedClaimed(amount)
+            }
+
             // Pass-through operations to Native app
-            ExtendedOperation::Transfer { owner, amount, target_account, prediction: None } => {
+            ExtendedOperation::Transfer { owner, amount, target_account, prediction: None, memo } => {
                 // Regular transfer without prediction - pass to Native
                 let params = self.runtime.application_parameters();
                 let native_app_id = params.native_app_id.with_abi::<native::NativeAbi>();
@@ -160,7 +270,7 @@ impl Contract for WinzaContract {
                 let _response: native::NativeResponse = self.runtime.call_application(
                     true,
                     native_app_id,
-                    &native::NativeOperation::Transfer { owner, amount, target_account },
+                    &native::NativeOperation::Transfer { owner, amount, target_account, memo },
                 );
                 ExtendedResponse::Ok
             }
@@ -187,22 +297,29 @@ impl Contract for WinzaContract {
 
     async fn execute_message(&mut self, message: Self::Message) {
         match message {
-            Message::TransferWithPrediction { owner: _, amount, prediction, source_chain_id, source_owner } => {
-                // Handle cross-chain transfer with prediction
-                // Place bet for source owner with SENDER'S chain_id
-                let params = self.runtime.application_parameters();
-                let rounds_app_id = params.rounds_app_id.with_abi::<rounds::RoundsAbi>();
+            Message::TransferWithPrediction { owner, amount, prediction, source_chain_id, source_owner, memo } => {
+                self.state.increment_metric("message.TransferWithPrediction").await
+                    .expect("Failed to update metrics");
                 
-                let _response: rounds::RoundsResponse = self.runtime.call_application(
-                    true,
-                    rounds_app_id,
-                    &rounds::RoundsOperation::PlaceBet {
-                        owner: source_owner,
+                // The memo is only recorded for the recipient, it never affects the bet
+                if let Some(memo) = memo {
+                    self.state.record_transfer(TransferNotice {
+                        recipient: owner,
+                        source_chain_id: source_chain_id.clone(),
+                        source_owner,
                         amount,
-                        prediction: to_rounds_prediction(prediction),
-                        source_chain_id: Some(source_chain_id), // Use SENDER'S chain from message!
-                    },
-                );
+                        memo,
+                    }).expect("Failed to record transfer");
+                }
+                
+                // Handle cross-chain transfer with prediction
+                // Place bet for source owner with SENDER'S chain_id
+                let source_chain_id = if source_chain_id == self.runtime.chain_id().to_string() {
+                    None
+                } else {
+                    Some(source_chain_id) // Use SENDER'S chain from message!
+                };
+                self.place_bet(source_owner, amount, prediction, source_chain_id);
             }
         }
     }
@@ -210,4 +327,306 @@ impl Contract for WinzaContract {
     async fn store(mut self) {
         self.state.save().await.expect("Failed to save state");
     }
-}
\ No newline at end of file
+}
+
+impl WinzaContract {
+    /// This app's own account, which holds vested rewards until they are claimed
+    fn vesting_owner(&mut self) -> AccountOwner {
+        AccountOwner::from(self.runtime.application_id().forget_abi())
+    }
+
+    /// Route a bet to the Rounds app. Bets whose tokens land on this chain are placed
+    /// directly; otherwise a message carries the bet to the target chain together with
+    /// the bettor's chain so rewards are sent back there.
+    async fn route_bet(
+        &mut self,
+        bettor: AccountOwner,
+        bettor_chain: ChainId,
+        target_account: linera_sdk::abis::fungible::Account,
+        amount: Amount,
+        prediction: Prediction,
+        memo: Option<String>,
+    ) {
+        let current_chain = self.runtime.chain_id();
+        if target_account.chain_id == current_chain {
+            // Same chain - no source_chain_id needed
+            let source_chain_id = (bettor_chain != current_chain).then(|| bettor_chain.to_string());
+            self.place_bet(bettor, amount, prediction, source_chain_id);
+        } else {
+            // Cross-chain - send message with the bettor's chain_id
+            let message = Message::TransferWithPrediction {
+                owner: target_account.owner,
+                amount,
+                prediction,
+                source_chain_id: bettor_chain.to_string(),
+                source_owner: bettor,
+                memo,
+            };
+            self.runtime
+                .prepare_message(message)
+                .with_authentication()
+                .send_to(target_account.chain_id);
+            self.state.increment_metric("messages_sent").await
+                .expect("Failed to update metrics");
+        }
+    }
+
+    /// Forward a bet to the Rounds app, failing the whole transaction (including the
+    /// token transfer) if the Rounds app is not configured or does not accept the bet
+    fn place_bet(&mut self, owner: AccountOwner, amount: Amount, prediction: Prediction, source_chain_id: Option<String>) {
+        let rounds_app_id = self.state.rounds_app_id.get()
+            .expect("Rounds app ID not set - bets cannot be forwarded");
+
+        let response: rounds::RoundsResponse = self.runtime.call_application(
+            true,
+            rounds_app_id,
+            &rounds::RoundsOperation::PlaceBet {
+                owner,
+                amount,
+                prediction: to_rounds_prediction(prediction),
+                source_chain_id,
+            },
+        );
+
+        match response {
+            rounds::RoundsResponse::Ok => {}
+            other => panic!("Rounds app rejected bet: {:?}", other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use futures::FutureExt as _;
+    use linera_sdk::{
+        abis::fungible::Account,
+        linera_base_types::{AccountOwner, Amount, ApplicationId, BlockHeight, ChainId, CryptoHash},
+        util::BlockingWait,
+        views::View,
+        Contract, ContractRuntime,
+    };
+    use rounds::RoundsOperation;
+    use Winzareal::{ExtendedOperation, ExtendedResponse, Message, Prediction, WinzaConfig, WinzaParameters};
+
+    use super::{WinzaContract, WinzaState};
+
+    fn app_id(name: &str) -> ApplicationId {
+        ApplicationId::new(CryptoHash::test_hash(name))
+    }
+
+    fn chain_id(name: &str) -> ChainId {
+        ChainId(CryptoHash::test_hash(name))
+    }
+
+    /// Creates an instantiated contract on `chain` that records every operation sent to the Rounds app
+    fn create_contract(chain: ChainId) -> (WinzaContract, Arc<Mutex<Vec<RoundsOperation>>>) {
+        let rounds_calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = rounds_calls.clone();
+        let runtime = ContractRuntime::new()
+            .with_application_parameters(WinzaParameters {
+                native_app_id: app_id("native"),
+                rounds_app_id: app_id("rounds"),
+            })
+            .with_chain_id(chain)
+            .with_application_creator_chain_id(chain)
+            .with_call_application_handler(move |_authenticated, application_id, operation| {
+                if application_id == app_id("rounds") {
+                    recorder.lock().unwrap().push(bcs::from_bytes(&operation).unwrap());
+                    bcs::to_bytes(&rounds::RoundsResponse::Ok).unwrap()
+                } else {
+                    bcs::to_bytes(&native::NativeResponse::Ok).unwrap()
+                }
+            });
+        let state = WinzaState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to load state");
+        let mut contract = WinzaContract { state, runtime };
+        contract.instantiate(()).now_or_never().expect("Instantiation should not await anything");
+        (contract, rounds_calls)
+    }
+
+    /// A bet paid into the game pool account on `target_chain`
+    fn bet_transfer(owner: AccountOwner, target_chain: ChainId) -> ExtendedOperation {
+        ExtendedOperation::Transfer {
+            owner,
+            amount: Amount::from_tokens(5),
+            target_account: Account { chain_id: target_chain, owner: AccountOwner::from(CryptoHash::test_hash("pool")) },
+            prediction: Some(Prediction::Up),
+            memo: None,
+        }
+    }
+
+    #[test]
+    fn same_chain_bet_is_forwarded_to_rounds() {
+        let chain = chain_id("home");
+        let (mut contract, rounds_calls) = create_contract(chain);
+        let owner = AccountOwner::from(CryptoHash::test_hash("alice"));
+
+        contract.execute_operation(bet_transfer(owner, chain)).now_or_never().unwrap();
+
+        let calls = rounds_calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        assert!(matches!(
+            &calls[0],
+            RoundsOperation::PlaceBet { owner: bettor, source_chain_id: None, .. } if *bettor == owner
+        ));
+    }
+
+    #[test]
+    fn cross_chain_bet_is_forwarded_with_sender_chain() {
+        let sender_chain = chain_id("sender");
+        let owner = AccountOwner::from(CryptoHash::test_hash("alice"));
+
+        // Sender side: no local bet, a message is sent instead
+        let (mut sender, sender_calls) = create_contract(sender_chain);
+        sender.execute_operation(bet_transfer(owner, chain_id("game"))).now_or_never().unwrap();
+        assert!(sender_calls.lock().unwrap().is_empty());
+        assert_eq!(sender.runtime.created_send_message_requests().len(), 1);
+
+        // Receiving side: the message becomes a PlaceBet attributed to the sender's chain
+        let (mut receiver, receiver_calls) = create_contract(chain_id("game"));
+        receiver.execute_message(Message::TransferWithPrediction {
+            owner,
+            amount: Amount::from_tokens(5),
+            prediction: Prediction::Down,
+            source_chain_id: sender_chain.to_string(),
+            source_owner: owner,
+            memo: None,
+        }).now_or_never().unwrap();
+
+        let calls = receiver_calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        assert!(matches!(
+            &calls[0],
+            RoundsOperation::PlaceBet { source_chain_id: Some(source), .. } if *source == sender_chain.to_string()
+        ));
+    }
+
+    #[test]
+    fn received_bet_memo_is_recorded_without_changing_the_bet() {
+        let sender_chain = chain_id("sender");
+        let owner = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let pool = AccountOwner::from(CryptoHash::test_hash("pool"));
+
+        let (mut receiver, receiver_calls) = create_contract(chain_id("game"));
+        receiver.execute_message(Message::TransferWithPrediction {
+            owner: pool,
+            amount: Amount::from_tokens(5),
+            prediction: Prediction::Up,
+            source_chain_id: sender_chain.to_string(),
+            source_owner: owner,
+            memo: Some("ref-7".to_string()),
+        }).now_or_never().unwrap();
+
+        let calls = receiver_calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        assert!(matches!(
+            &calls[0],
+            RoundsOperation::PlaceBet { owner: bettor, amount, .. } if *bettor == owner && *amount == Amount::from_tokens(5)
+        ));
+        let inbox = receiver.state.incoming_transfers(pool, 10).now_or_never().unwrap().unwrap();
+        assert_eq!(inbox.len(), 1);
+        assert_eq!(inbox[0].memo, "ref-7");
+        assert_eq!(inbox[0].source_owner, owner);
+    }
+
+    #[test]
+    #[should_panic(expected = "Self-transfers cannot place bets")]
+    fn same_chain_self_transfer_places_no_bet() {
+        let chain = chain_id("home");
+        let (mut contract, _) = create_contract(chain);
+
+        let owner = AccountOwner::from(CryptoHash::test_hash("alice"));
+        contract.execute_operation(ExtendedOperation::Transfer {
+            owner,
+            amount: Amount::from_tokens(5),
+            target_account: Account { chain_id: chain, owner },
+            prediction: Some(Prediction::Up),
+            memo: None,
+        }).now_or_never().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Rounds app ID not set")]
+    fn bet_fails_when_rounds_app_id_unset() {
+        let chain = chain_id("home");
+        let (mut contract, _) = create_contract(chain);
+        contract.state.rounds_app_id.set(None);
+
+        let owner = AccountOwner::from(CryptoHash::test_hash("alice"));
+        contract.execute_operation(bet_transfer(owner, chain)).now_or_never().unwrap();
+    }
+
+    #[test]
+    fn exported_config_imports_onto_another_chain() {
+        let (mut source, _) = create_contract(chain_id("source"));
+        let ExtendedResponse::Config(config) = source.execute_operation(ExtendedOperation::GetConfig).now_or_never().unwrap() else {
+            panic!("GetConfig should return the config");
+        };
+        assert_eq!(config.rounds_app_id, Some(app_id("rounds").to_string()));
+
+        let (mut target, _) = create_contract(chain_id("target"));
+        target.state.rounds_app_id.set(None);
+        target.execute_operation(ExtendedOperation::ImportConfig { config: config.clone() }).now_or_never().unwrap();
+        assert_eq!(target.state.get_config(), config);
+    }
+
+    #[test]
+    fn invalid_config_is_not_applied() {
+        let (mut contract, _) = create_contract(chain_id("home"));
+        let before = contract.state.get_config();
+        let invalid = WinzaConfig {
+            native_app_id: Some(app_id("other-native").to_string()),
+            rounds_app_id: Some("not-an-app-id".to_string()),
+        };
+        assert!(contract.state.import_config(invalid).is_err());
+        assert_eq!(contract.state.get_config(), before);
+    }
+
+    #[test]
+    fn vested_reward_is_released_linearly_after_the_cliff() {
+        let chain = chain_id("home");
+        let (mut contract, _) = create_contract(chain);
+        let resolver = AccountOwner::from(CryptoHash::test_hash("resolver"));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        contract.runtime.set_application_id(app_id("winza").with_abi());
+        contract.runtime.set_owner_balance(AccountOwner::from(app_id("winza")), Amount::from_tokens(100));
+        contract.runtime.set_authenticated_signer(Some(resolver));
+        contract.runtime.set_block_height(BlockHeight(100));
+
+        let response = contract.execute_operation(ExtendedOperation::SendRewardVested {
+            recipient: alice,
+            amount: Amount::from_tokens(100),
+            duration_blocks: 100,
+            cliff_blocks: 20,
+            source_chain_id: None,
+        }).now_or_never().unwrap();
+        let ExtendedResponse::VestingEntryId(entry_id) = response else {
+            panic!("SendRewardVested should return the entry ID");
+        };
+
+        // Within the cliff nothing can be claimed
+        let err = contract.state.release_vested(alice, entry_id, 119).now_or_never().unwrap().unwrap_err();
+        assert!(err.starts_with("Nothing has vested yet"), "{}", err);
+
+        contract.runtime.set_authenticated_signer(Some(alice));
+        let claim_at = |contract: &mut WinzaContract, height| {
+            contract.runtime.set_block_height(BlockHeight(height));
+            match contract.execute_operation(ExtendedOperation::ClaimVested { entry_id }).now_or_never().unwrap() {
+                ExtendedResponse::VestedClaimed(amount) => amount,
+                _ => panic!("ClaimVested should return the amount released"),
+            }
+        };
+        // Past the cliff, vesting is linear from the start block
+        assert_eq!(claim_at(&mut contract, 150), Amount::from_tokens(50));
+        assert_eq!(claim_at(&mut contract, 175), Amount::from_tokens(25));
+        // After the duration the rest is released, and no more
+        assert_eq!(claim_at(&mut contract, 400), Amount::from_tokens(25));
+        let entries = contract.state.vesting_entries_of(alice).now_or_never().unwrap().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].1.released, Amount::from_tokens(100));
+        assert!(contract.state.release_vested(alice, entry_id, 500).now_or_never().unwrap().is_err());
+    }
+}
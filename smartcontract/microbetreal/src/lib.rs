@@ -3,12 +3,14 @@
 
 /*! Winzareal - Betting Wrapper Application */
 
-use async_graphql::{Request, Response};
+use async_graphql::{Json, Request, Response, SimpleObject, Value};
 use linera_sdk::linera_base_types::{AccountOwner, Amount, ContractAbi, ServiceAbi};
 use serde::{Deserialize, Serialize};
 
 // Re-export from native-fungible-abi
-pub use native_fungible_abi::{Prediction, ExtendedOperation, ExtendedResponse, ExtendedNativeFungibleTokenAbi};
+pub use native_fungible_abi::{Prediction, ExtendedOperation, ExtendedResponse, ExtendedNativeFungibleTokenAbi, WinzaConfig};
+pub use native_fungible_abi::balance_breakdown::{balance_breakdown, BalanceBreakdown};
+pub use native_fungible_abi::transfer_memo::{validate_memo, TransferNotice};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum Message {
@@ -19,9 +21,70 @@ pub enum Message {
         prediction: Prediction,
         source_chain_id: String, // Chain ID of the sender
         source_owner: AccountOwner,
+        memo: Option<String>, // Informational only, never affects the bet
     },
 }
 
+// A single metrics counter for GraphQL queries
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MetricEntry {
+    pub name: String,
+    pub count: u64,
+}
+
+/// Answer of the Rounds service to a query proxied by the Winzareal service
+#[derive(Debug, Clone, SimpleObject)]
+pub struct RoundsProxyResult {
+    /// Rounds application that answered
+    pub rounds_app_id: String,
+    /// `data` of the Rounds response, in the Rounds schema's field names
+    pub data: Json<Value>,
+    /// Error messages of the Rounds response
+    pub errors: Vec<String>,
+}
+
+/// A reward released gradually: nothing for `cliff_blocks` after `start_block`, then linearly
+/// until `duration_blocks` after it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct VestingEntry {
+    pub total: Amount,
+    pub released: Amount,
+    pub start_block: u64,
+    pub duration_blocks: u64,
+    pub cliff_blocks: u64,
+    /// Chain the released tokens are sent to (None = this chain)
+    pub source_chain_id: Option<String>,
+}
+
+impl VestingEntry {
+    /// Part of `total` vested at block `height`
+    pub fn vested_at(&self, height: u64) -> Amount {
+        let elapsed = height.saturating_sub(self.start_block);
+        if elapsed < self.cliff_blocks {
+            return Amount::ZERO;
+        }
+        if self.duration_blocks == 0 || elapsed >= self.duration_blocks {
+            return self.total;
+        }
+        // total * elapsed / duration, split so the multiplication cannot overflow
+        let total = u128::from(self.total);
+        let (elapsed, duration) = (u128::from(elapsed), u128::from(self.duration_blocks));
+        Amount::from_attos(total / duration * elapsed + total % duration * elapsed / duration)
+    }
+
+    /// Vested at block `height` but not yet released
+    pub fn claimable_at(&self, height: u64) -> Amount {
+        self.vested_at(height).saturating_sub(self.released)
+    }
+}
+
+/// A vesting entry with its ID, for GraphQL queries
+#[derive(Debug, Clone, SimpleObject)]
+pub struct VestingEntryInfo {
+    pub entry_id: u64,
+    pub entry: VestingEntry,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct WinzaParameters {
     pub native_app_id: ::linera_sdk::linera_base_types::ApplicationId,
@@ -30,7 +93,7 @@ pub struct WinzaParameters {
 
 // Winzareal implements the same ABI as NativeFungible (ExtendedNativeFungibleTokenAbi)
 // This allows Rounds to call operations on Winzareal using the shared ABI
-// Winzareal handles: TransferWithPrediction, SendReward, SetNativeAppId, SetRoundsAppId
+// Winzareal handles: TransferWithPrediction, SendReward, SendRewardVested, ClaimVested, SetNativeAppId, SetRoundsAppId
 // Other operations are passed through to Native app
 
 pub struct WinzaAbi;
@@ -43,4 +106,4 @@ impl ContractAbi for WinzaAbi {
 impl ServiceAbi for WinzaAbi {
     type Query = Request;
     type QueryResponse = Response;
-}
\ No newline at end of file
+}
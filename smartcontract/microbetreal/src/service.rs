@@ -5,12 +5,12 @@
 
 use std::sync::Arc;
 
-use async_graphql::{EmptySubscription, Object, Request, Response, Schema};
+use async_graphql::{EmptySubscription, Json, Object, Request, Response, Schema};
 use linera_sdk::{
     linera_base_types::{AccountOwner, Amount, WithServiceAbi},
     Service, ServiceRuntime,
 };
-use Winzareal::{WinzaAbi, ExtendedOperation, Prediction};
+use Winzareal::{balance_breakdown, BalanceBreakdown, WinzaAbi, ExtendedOperation, MetricEntry, Prediction, RoundsProxyResult, TransferNotice, VestingEntryInfo, WinzaConfig};
 use native::AccountInput;
 
 linera_sdk::service!(WinzaService);
@@ -63,6 +63,26 @@ struct QueryRoot {
     runtime: Arc<ServiceRuntime<WinzaService>>,
 }
 
+/// Round fields requested from the Rounds service by the proxy queries
+const PROXIED_ROUND_FIELDS: &str = "id createdAt closedAt resolvedAt status closingPrice resolutionPrice \
+    numOutcomes outcomeBets outcomePools prizePool result totalBurnedThisRound manualResolution";
+/// Winner fields requested from the Rounds service by the proxy queries
+const PROXIED_WINNER_FIELDS: &str = "owner betAmount winnings sourceChainId";
+
+impl QueryRoot {
+    /// Run `query` against the Rounds service on this chain, so frontends can read
+    /// rounds through the Winzareal endpoint
+    fn query_rounds(&self, query: String) -> async_graphql::Result<RoundsProxyResult> {
+        let rounds_app_id = (*self.state.rounds_app_id.get()).ok_or("Rounds app ID not set")?;
+        let response = self.runtime.query_application(rounds_app_id, &Request::new(query));
+        Ok(RoundsProxyResult {
+            rounds_app_id: rounds_app_id.forget_abi().to_string(),
+            data: Json(response.data),
+            errors: response.errors.into_iter().map(|error| error.message).collect(),
+        })
+    }
+}
+
 #[Object]
 impl QueryRoot {
     /// Get the configured Native app ID
@@ -77,11 +97,60 @@ impl QueryRoot {
         Some(format!("{}", params.rounds_app_id))
     }
     
+    /// Get the coordinated app IDs stored on this chain
+    async fn config(&self) -> WinzaConfig {
+        self.state.get_config()
+    }
+    
     /// Check if app IDs are configured (always true with parameters)
     async fn is_configured(&self) -> bool {
         true
     }
     
+    /// Get operation/message counters sorted by name
+    async fn metrics(&self) -> Vec<MetricEntry> {
+        self.state.get_metrics().await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, count)| MetricEntry { name, count })
+            .collect()
+    }
+    
+    /// Bets with a memo received by `owner`, newest first
+    async fn incoming_transfers(&self, owner: AccountOwner, limit: usize) -> Vec<TransferNotice> {
+        self.state.incoming_transfers(owner, limit).await
+            .unwrap_or_default()
+    }
+    
+    /// Vested rewards held for `owner`, oldest first
+    async fn vesting_entries(&self, owner: AccountOwner) -> Vec<VestingEntryInfo> {
+        self.state.vesting_entries_of(owner).await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(entry_id, entry)| VestingEntryInfo { entry_id, entry })
+            .collect()
+    }
+    
+    /// Chain account balance versus tokens held by owners on this chain
+    async fn balance_breakdown(&self) -> BalanceBreakdown {
+        balance_breakdown(self.runtime.chain_balance(), self.runtime.owner_balances())
+    }
+    
+    /// The active round, as answered by the Rounds app (`data.activeRound`)
+    async fn active_round(&self) -> async_graphql::Result<RoundsProxyResult> {
+        self.query_rounds(format!("query {{ activeRound {{ {} }} }}", PROXIED_ROUND_FIELDS))
+    }
+    
+    /// A round by ID, as answered by the Rounds app (`data.round`)
+    async fn round(&self, id: u64) -> async_graphql::Result<RoundsProxyResult> {
+        self.query_rounds(format!("query {{ round(id: {}) {{ {} }} }}", id, PROXIED_ROUND_FIELDS))
+    }
+    
+    /// Winners of a resolved round, as answered by the Rounds app (`data.roundWinners`)
+    async fn round_winners(&self, id: u64) -> async_graphql::Result<RoundsProxyResult> {
+        self.query_rounds(format!("query {{ roundWinners(roundId: {}) {{ {} }} }}", id, PROXIED_WINNER_FIELDS))
+    }
+    
     /// Get version
     async fn version(&self) -> String {
         "1.0.0".to_string()
@@ -106,6 +175,18 @@ impl MutationRoot {
         format!("SetRoundsAppId operation scheduled with ID: {}", rounds_app_id)
     }
 
+    /// Zero all operation/message counters
+    async fn reset_metrics(&self) -> String {
+        self.runtime.schedule_operation(&ExtendedOperation::ResetMetrics);
+        "ResetMetrics operation scheduled".to_string()
+    }
+
+    /// Release the vested part of one of the signer's vesting entries
+    async fn claim_vested(&self, entry_id: u64) -> String {
+        self.runtime.schedule_operation(&ExtendedOperation::ClaimVested { entry_id });
+        format!("ClaimVested operation scheduled for entry {}", entry_id)
+    }
+
     /// Transfer tokens with prediction (betting)
     /// Optionally set app IDs on-the-fly
     async fn transfer_with_prediction(
@@ -114,6 +195,7 @@ impl MutationRoot {
         amount: String,
         target_account: AccountInput,
         prediction: Prediction,
+        memo: Option<String>,
     ) -> String {
         let fungible_account = linera_sdk::abis::fungible::Account {
             chain_id: target_account.chain_id,
@@ -126,8 +208,9 @@ impl MutationRoot {
             amount: amount.parse::<Amount>().unwrap_or_default(),
             target_account: fungible_account,
             prediction: Some(prediction),
+            memo,
         });
         
         "TransferWithPrediction operation scheduled - bet will be placed".to_string()
     }
-}
\ No newline at end of file
+}
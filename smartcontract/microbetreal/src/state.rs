@@ -1,8 +1,9 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use linera_sdk::views::{linera_views, RegisterView, RootView, ViewStorageContext};
-use linera_sdk::linera_base_types::ApplicationId;
+use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, View, ViewError, ViewStorageContext};
+use linera_sdk::linera_base_types::{AccountOwner, Amount, ApplicationId};
+use Winzareal::{TransferNotice, VestingEntry, WinzaConfig};
 
 /// Minimal state for Winzareal - just stores app IDs for coordination
 #[derive(RootView)]
@@ -12,4 +13,153 @@ pub struct WinzaState {
     pub native_app_id: RegisterView<Option<ApplicationId<native::NativeAbi>>>,
     /// ApplicationId of the Rounds game app
     pub rounds_app_id: RegisterView<Option<ApplicationId<rounds::RoundsAbi>>>,
+    /// Operation/message counters keyed by metric name
+    pub metrics: MapView<String, u64>,
+    /// Received bets that carried a memo, keyed by (recipient, arrival sequence)
+    pub transfer_inbox: MapView<(AccountOwner, u64), TransferNotice>,
+    /// Sequence number of the next inbox entry
+    pub next_transfer_seq: RegisterView<u64>,
+    /// Vested rewards held by this app, keyed by (recipient, entry ID)
+    pub vesting_entries: MapView<(AccountOwner, u64), VestingEntry>,
+    /// ID of the next vesting entry
+    pub vesting_entry_counter: RegisterView<u64>,
+}
+
+#[allow(dead_code)]
+impl WinzaState {
+    /// Increment a metrics counter (single read + insert)
+    pub async fn increment_metric(&mut self, name: &str) -> Result<(), String> {
+        let count = self.metrics.get(name).await
+            .map_err(|e: ViewError| format!("Failed to get metric: {:?}", e))?
+            .unwrap_or(0);
+        self.metrics.insert(name, count + 1)
+            .map_err(|e: ViewError| format!("Failed to update metric: {:?}", e))
+    }
+    
+    /// Get all metrics counters sorted by name
+    pub async fn get_metrics(&self) -> Result<Vec<(String, u64)>, String> {
+        let names = self.metrics.indices().await
+            .map_err(|e: ViewError| format!("Failed to get metric indices: {:?}", e))?;
+        
+        let mut metrics = Vec::with_capacity(names.len());
+        for name in names {
+            if let Some(count) = self.metrics.get(&name).await
+                .map_err(|e: ViewError| format!("Failed to get metric: {:?}", e))? {
+                metrics.push((name, count));
+            }
+        }
+        metrics.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(metrics)
+    }
+    
+    /// Snapshot of the coordinated app IDs
+    pub fn get_config(&self) -> WinzaConfig {
+        WinzaConfig {
+            native_app_id: self.native_app_id.get().map(|app_id| app_id.forget_abi().to_string()),
+            rounds_app_id: self.rounds_app_id.get().map(|app_id| app_id.forget_abi().to_string()),
+        }
+    }
+    
+    /// Apply a full configuration, validating every app ID before changing anything
+    pub fn import_config(&mut self, config: WinzaConfig) -> Result<(), String> {
+        let native_app_id = parse_app_id(config.native_app_id.as_deref())?;
+        let rounds_app_id = parse_app_id(config.rounds_app_id.as_deref())?;
+        
+        self.native_app_id.set(native_app_id.map(|app_id| app_id.with_abi()));
+        self.rounds_app_id.set(rounds_app_id.map(|app_id| app_id.with_abi()));
+        Ok(())
+    }
+    
+    /// Store a new vesting entry for `recipient` and return its ID
+    pub fn create_vesting_entry(&mut self, recipient: AccountOwner, entry: VestingEntry) -> Result<u64, String> {
+        if entry.total.is_zero() {
+            return Err("A vested reward needs a positive amount".to_string());
+        }
+        if entry.cliff_blocks > entry.duration_blocks {
+            return Err(format!("Cliff of {} blocks is longer than the vesting duration of {} blocks", entry.cliff_blocks, entry.duration_blocks));
+        }
+        let entry_id = *self.vesting_entry_counter.get();
+        self.vesting_entries.insert(&(recipient, entry_id), entry)
+            .map_err(|e: ViewError| format!("Failed to create vesting entry: {:?}", e))?;
+        self.vesting_entry_counter.set(entry_id + 1);
+        Ok(entry_id)
+    }
+    
+    /// Mark what `recipient`'s entry `entry_id` has vested by block `height` as released,
+    /// returning that amount and the chain to send it to
+    pub async fn release_vested(&mut self, recipient: AccountOwner, entry_id: u64, height: u64) -> Result<(Amount, Option<String>), String> {
+        let mut entry = self.vesting_entries.get(&(recipient, entry_id)).await
+            .map_err(|e: ViewError| format!("Failed to get vesting entry: {:?}", e))?
+            .ok_or_else(|| format!("{} has no vesting entry {}", recipient, entry_id))?;
+        let claimable = entry.claimable_at(height);
+        if claimable.is_zero() {
+            return Err(format!("Nothing has vested yet in entry {}", entry_id));
+        }
+        entry.released = entry.released.saturating_add(claimable);
+        let source_chain_id = entry.source_chain_id.clone();
+        self.vesting_entries.insert(&(recipient, entry_id), entry)
+            .map_err(|e: ViewError| format!("Failed to update vesting entry: {:?}", e))?;
+        Ok((claimable, source_chain_id))
+    }
+    
+    /// An owner's vesting entries with their IDs, oldest first
+    pub async fn vesting_entries_of(&self, owner: AccountOwner) -> Result<Vec<(u64, VestingEntry)>, String> {
+        let mut entry_ids: Vec<u64> = self.vesting_entries.indices().await
+            .map_err(|e: ViewError| format!("Failed to get vesting entries: {:?}", e))?
+            .into_iter()
+            .filter(|(recipient, _)| *recipient == owner)
+            .map(|(_, entry_id)| entry_id)
+            .collect();
+        entry_ids.sort_unstable();
+        
+        let mut entries = Vec::with_capacity(entry_ids.len());
+        for entry_id in entry_ids {
+            if let Some(entry) = self.vesting_entries.get(&(owner, entry_id)).await
+                .map_err(|e: ViewError| format!("Failed to get vesting entry: {:?}", e))? {
+                entries.push((entry_id, entry));
+            }
+        }
+        Ok(entries)
+    }
+    
+    /// Zero all metrics counters
+    pub fn reset_metrics(&mut self) {
+        self.metrics.clear();
+    }
+    
+    /// Store a received transfer notice in the recipient's inbox
+    pub fn record_transfer(&mut self, notice: TransferNotice) -> Result<(), String> {
+        let seq = *self.next_transfer_seq.get();
+        self.transfer_inbox.insert(&(notice.recipient, seq), notice)
+            .map_err(|e: ViewError| format!("Failed to record transfer: {:?}", e))?;
+        self.next_transfer_seq.set(seq + 1);
+        Ok(())
+    }
+    
+    /// Up to `limit` of an owner's received transfers, newest first
+    pub async fn incoming_transfers(&self, owner: AccountOwner, limit: usize) -> Result<Vec<TransferNotice>, String> {
+        let keys = self.transfer_inbox.indices().await
+            .map_err(|e: ViewError| format!("Failed to get transfer inbox: {:?}", e))?;
+        
+        let mut seqs: Vec<u64> = keys.into_iter()
+            .filter(|(recipient, _)| *recipient == owner)
+            .map(|(_, seq)| seq)
+            .collect();
+        seqs.sort_unstable_by(|a, b| b.cmp(a));
+        
+        let mut notices = Vec::new();
+        for seq in seqs.into_iter().take(limit) {
+            if let Some(notice) = self.transfer_inbox.get(&(owner, seq)).await
+                .map_err(|e: ViewError| format!("Failed to get transfer: {:?}", e))? {
+                notices.push(notice);
+            }
+        }
+        Ok(notices)
+    }
+}
+
+fn parse_app_id(app_id: Option<&str>) -> Result<Option<ApplicationId>, String> {
+    app_id
+        .map(|app_id| app_id.parse::<ApplicationId>().map_err(|e| format!("Invalid application ID {}: {:?}", app_id, e)))
+        .transpose()
 }
@@ -0,0 +1,48 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Unambiguous string forms of token amounts for GraphQL clients */
+
+use linera_sdk::linera_base_types::Amount;
+
+/// Number of attos in one token
+const ATTOS_PER_TOKEN: u128 = 1_000_000_000_000_000_000;
+
+/// Exact amount in attos as a decimal integer, e.g. "1500000000000000000"
+pub fn amount_attos(amount: Amount) -> String {
+    u128::from(amount).to_string()
+}
+
+/// Amount in tokens without trailing zeros, e.g. "1.5"
+pub fn amount_display(amount: Amount) -> String {
+    let attos = u128::from(amount);
+    let tokens = attos / ATTOS_PER_TOKEN;
+    let fraction = attos % ATTOS_PER_TOKEN;
+    if fraction == 0 {
+        return tokens.to_string();
+    }
+    let fraction = format!("{:018}", fraction);
+    format!("{}.{}", tokens, fraction.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::Amount;
+
+    use super::{amount_attos, amount_display};
+
+    #[test]
+    fn test_one_and_a_half_tokens() {
+        let amount = Amount::from_millis(1_500);
+        assert_eq!(amount_attos(amount), "1500000000000000000");
+        assert_eq!(amount_display(amount), "1.5");
+    }
+
+    #[test]
+    fn test_whole_and_tiny_amounts() {
+        assert_eq!(amount_display(Amount::ZERO), "0");
+        assert_eq!(amount_display(Amount::from_tokens(42)), "42");
+        assert_eq!(amount_attos(Amount::from_attos(1)), "1");
+        assert_eq!(amount_display(Amount::from_attos(1)), "0.000000000000000001");
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Split of a chain's tokens between the chain account and the balances of its owners */
+
+use async_graphql::{ComplexObject, SimpleObject};
+use linera_sdk::linera_base_types::{AccountOwner, Amount};
+use serde::{Deserialize, Serialize};
+
+/// Most owner balances a breakdown sums. `owner_balances()` returns every owner of the
+/// chain in a single call, so its cost grows linearly with the number of owners; past
+/// this many owners only `owner_count` stays exact.
+pub const MAX_BREAKDOWN_OWNERS: usize = 1_000;
+
+/// Tokens held by the chain account versus user accounts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
+pub struct BalanceBreakdown {
+    /// Balance of AccountOwner::CHAIN
+    pub chain: Amount,
+    /// Sum of the first MAX_BREAKDOWN_OWNERS owner balances
+    pub owners_total: Amount,
+    /// Owners with a balance, including any beyond MAX_BREAKDOWN_OWNERS
+    pub owner_count: u64,
+    #[graphql(skip)]
+    pub largest_owner: Option<(AccountOwner, Amount)>,
+}
+
+#[ComplexObject]
+impl BalanceBreakdown {
+    /// Owner with the largest balance among those summed
+    async fn largest_owner_account(&self) -> Option<AccountOwner> {
+        self.largest_owner.map(|(owner, _)| owner)
+    }
+
+    async fn largest_owner_balance(&self) -> Option<Amount> {
+        self.largest_owner.map(|(_, balance)| balance)
+    }
+
+    /// Whether some owners were left out of `owners_total` and `largest_owner`
+    async fn truncated(&self) -> bool {
+        self.owner_count > MAX_BREAKDOWN_OWNERS as u64
+    }
+}
+
+/// Break down `chain_balance` and `owner_balances` (as returned by the runtime)
+pub fn balance_breakdown(chain_balance: Amount, owner_balances: Vec<(AccountOwner, Amount)>) -> BalanceBreakdown {
+    let owner_count = owner_balances.len() as u64;
+    let mut owners_total = Amount::ZERO;
+    let mut largest_owner: Option<(AccountOwner, Amount)> = None;
+    for (owner, balance) in owner_balances.into_iter().take(MAX_BREAKDOWN_OWNERS) {
+        owners_total = owners_total.saturating_add(balance);
+        if largest_owner.is_none_or(|(_, largest)| balance > largest) {
+            largest_owner = Some((owner, balance));
+        }
+    }
+    BalanceBreakdown { chain: chain_balance, owners_total, owner_count, largest_owner }
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::{AccountOwner, Amount};
+
+    use super::{balance_breakdown, MAX_BREAKDOWN_OWNERS};
+
+    fn owner(index: usize) -> AccountOwner {
+        let mut address = [0; 20];
+        address[..8].copy_from_slice(&(index as u64).to_le_bytes());
+        AccountOwner::Address20(address)
+    }
+
+    #[test]
+    fn test_breakdown_sums_owners_and_finds_the_largest() {
+        let breakdown = balance_breakdown(Amount::from_tokens(100), vec![
+            (owner(0), Amount::from_tokens(3)),
+            (owner(1), Amount::from_tokens(7)),
+            (owner(2), Amount::from_tokens(7)),
+        ]);
+        assert_eq!(breakdown.chain, Amount::from_tokens(100));
+        assert_eq!(breakdown.owners_total, Amount::from_tokens(17));
+        assert_eq!(breakdown.owner_count, 3);
+        // Ties keep the first owner seen
+        assert_eq!(breakdown.largest_owner, Some((owner(1), Amount::from_tokens(7))));
+    }
+
+    #[test]
+    fn test_breakdown_without_owners() {
+        let breakdown = balance_breakdown(Amount::ONE, Vec::new());
+        assert_eq!((breakdown.owners_total, breakdown.owner_count, breakdown.largest_owner), (Amount::ZERO, 0, None));
+    }
+
+    #[test]
+    fn test_breakdown_sums_at_most_the_cap() {
+        let owners = (0..MAX_BREAKDOWN_OWNERS + 5).map(|index| (owner(index), Amount::ONE)).collect();
+        let breakdown = balance_breakdown(Amount::ZERO, owners);
+        assert_eq!(breakdown.owner_count, MAX_BREAKDOWN_OWNERS as u64 + 5);
+        assert_eq!(breakdown.owners_total, Amount::from_tokens(MAX_BREAKDOWN_OWNERS as u128));
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Overflow-free percentage and ticket count arithmetic shared by the lottery apps */
+
+use linera_sdk::linera_base_types::Amount;
+
+/// Basis points in 100%
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+/// `bps` basis points of `total`, rounded down. Computed in u128 so it cannot overflow;
+/// results above `u64::MAX` (only possible with `bps` over 100%) saturate.
+pub fn checked_percentage(total: u64, bps: u32) -> u64 {
+    let share = u128::from(total) * u128::from(bps) / u128::from(BPS_DENOMINATOR);
+    u64::try_from(share).unwrap_or(u64::MAX)
+}
+
+/// Why a ticket count cannot be computed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicketCountError {
+    ZeroTicketPrice,
+    /// The amount buys more tickets than fit in a u64
+    TooManyTickets(u128),
+}
+
+impl std::fmt::Display for TicketCountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TicketCountError::ZeroTicketPrice => write!(f, "Invalid ticket price"),
+            TicketCountError::TooManyTickets(count) => {
+                write!(f, "Purchase of {} tickets exceeds the maximum of {}", count, u64::MAX)
+            }
+        }
+    }
+}
+
+/// Number of whole tickets `amount` buys at `price`
+pub fn tickets_for_amount(amount: Amount, price: Amount) -> Result<u64, TicketCountError> {
+    let price = u128::from(price);
+    if price == 0 {
+        return Err(TicketCountError::ZeroTicketPrice);
+    }
+    let count = u128::from(amount) / price;
+    u64::try_from(count).map_err(|_| TicketCountError::TooManyTickets(count))
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::Amount;
+
+    use super::{checked_percentage, tickets_for_amount, TicketCountError, BPS_DENOMINATOR};
+
+    /// Values spread over the whole u64 range, including both ends and the powers of two
+    fn u64_samples() -> Vec<u64> {
+        let mut samples: Vec<u64> = (0..64).flat_map(|shift| {
+            let power = 1u64 << shift;
+            [power - 1, power, power + 1]
+        }).collect();
+        samples.extend((0..1_000u64).map(|step| step.wrapping_mul(18_446_744_073_709_551)));
+        samples.extend([u64::MAX - 1, u64::MAX]);
+        samples
+    }
+
+    #[test]
+    fn test_percentage_matches_exact_arithmetic() {
+        for total in u64_samples() {
+            for bps in [0, 1, 300, 500, 700, 1_500, 9_999, BPS_DENOMINATOR] {
+                let expected = u128::from(total) * u128::from(bps) / 10_000;
+                let share = checked_percentage(total, bps);
+                assert_eq!(u128::from(share), expected, "{} bps of {}", bps, total);
+                assert!(share <= total);
+            }
+        }
+    }
+
+    #[test]
+    fn test_percentage_above_one_hundred_saturates() {
+        assert_eq!(checked_percentage(u64::MAX, 20_000), u64::MAX);
+        assert_eq!(checked_percentage(u64::MAX, u32::MAX), u64::MAX);
+        assert_eq!(checked_percentage(10, 20_000), 20);
+    }
+
+    #[test]
+    fn test_ticket_count_never_truncates() {
+        for count in u64_samples() {
+            for price in [1u128, 7, 1_000_000_000_000_000_000] {
+                let amount = Amount::from_attos(u128::from(count) * price);
+                assert_eq!(tickets_for_amount(amount, Amount::from_attos(price)), Ok(count));
+            }
+        }
+    }
+
+    #[test]
+    fn test_ticket_count_above_u64_is_an_error() {
+        let count = u128::from(u64::MAX) + 1;
+        assert_eq!(
+            tickets_for_amount(Amount::from_attos(count), Amount::from_attos(1)),
+            Err(TicketCountError::TooManyTickets(count))
+        );
+        assert_eq!(
+            tickets_for_amount(Amount::from_attos(u128::MAX), Amount::from_attos(1)),
+            Err(TicketCountError::TooManyTickets(u128::MAX))
+        );
+    }
+
+    #[test]
+    fn test_zero_price_is_an_error() {
+        assert_eq!(tickets_for_amount(Amount::ONE, Amount::ZERO), Err(TicketCountError::ZeroTicketPrice));
+    }
+}
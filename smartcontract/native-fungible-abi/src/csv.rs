@@ -0,0 +1,59 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Minimal RFC 4180 CSV writer used for round exports */
+
+/// Builds CSV text with CRLF record separators, quoting fields only when needed
+#[derive(Debug, Default)]
+pub struct CsvWriter {
+    buffer: String,
+}
+
+impl CsvWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append one record
+    pub fn write_record<I, S>(&mut self, fields: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for (index, field) in fields.into_iter().enumerate() {
+            if index > 0 {
+                self.buffer.push(',');
+            }
+            let field = field.as_ref();
+            if field.contains([',', '"', '\r', '\n']) {
+                // Quoted field with embedded quotes doubled
+                self.buffer.push('"');
+                self.buffer.push_str(&field.replace('"', "\"\""));
+                self.buffer.push('"');
+            } else {
+                self.buffer.push_str(field);
+            }
+        }
+        self.buffer.push_str("\r\n");
+    }
+
+    /// Finish writing and return the CSV as UTF-8 bytes
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buffer.into_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CsvWriter;
+
+    #[test]
+    fn test_quotes_only_when_needed() {
+        let mut writer = CsvWriter::new();
+        writer.write_record(["plain", "with,comma", "with \"quote\""]);
+        assert_eq!(
+            String::from_utf8(writer.into_bytes()).unwrap(),
+            "plain,\"with,comma\",\"with \"\"quote\"\"\"\r\n"
+        );
+    }
+}
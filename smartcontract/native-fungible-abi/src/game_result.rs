@@ -0,0 +1,80 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Settlement events shared by the prediction and lottery games, for cross-game indexing */
+
+use linera_sdk::linera_base_types::{Amount, StreamName};
+use serde::{Deserialize, Serialize};
+
+/// Name of the event stream both games publish their GameResult events on
+pub const GAME_RESULTS_STREAM: &str = "game_results";
+
+/// The event stream both games publish their GameResult events on
+pub fn game_results_stream() -> StreamName {
+    StreamName(GAME_RESULTS_STREAM.as_bytes().to_vec())
+}
+
+/// Game an event comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
+pub enum GameKind {
+    Prediction,
+    Lottery,
+}
+
+/// A game event, in the same shape whichever game emitted it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameResult {
+    /// Every prize of a round is known: a prediction round was resolved or the
+    /// last lottery winner was drawn
+    RoundSettled {
+        game_kind: GameKind,
+        round_id: u64,
+        winners: u64,
+        total_paid: Amount,
+    },
+}
+
+impl GameResult {
+    /// Settlement of `round_id` from the amount each player won; players who won
+    /// nothing are not counted as winners
+    pub fn round_settled(game_kind: GameKind, round_id: u64, payouts: impl IntoIterator<Item = Amount>) -> Self {
+        let (mut winners, mut total_paid) = (0, Amount::ZERO);
+        for payout in payouts.into_iter().filter(|payout| !payout.is_zero()) {
+            winners += 1;
+            total_paid = total_paid.saturating_add(payout);
+        }
+        GameResult::RoundSettled { game_kind, round_id, winners, total_paid }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::Amount;
+
+    use super::{GameKind, GameResult};
+
+    #[test]
+    fn test_losers_are_not_counted_as_winners() {
+        let result = GameResult::round_settled(
+            GameKind::Prediction,
+            7,
+            [Amount::from_tokens(20), Amount::ZERO, Amount::from_tokens(5)],
+        );
+        assert_eq!(result, GameResult::RoundSettled {
+            game_kind: GameKind::Prediction,
+            round_id: 7,
+            winners: 2,
+            total_paid: Amount::from_tokens(25),
+        });
+    }
+
+    #[test]
+    fn test_both_games_emit_the_same_shape() {
+        let payouts = [Amount::from_tokens(3), Amount::from_tokens(4)];
+        let prediction = GameResult::round_settled(GameKind::Prediction, 1, payouts);
+        let lottery = GameResult::round_settled(GameKind::Lottery, 1, payouts);
+        let GameResult::RoundSettled { winners, total_paid, .. } = lottery.clone();
+        assert_eq!(prediction, GameResult::RoundSettled { game_kind: GameKind::Prediction, round_id: 1, winners, total_paid });
+        assert_ne!(prediction, lottery);
+    }
+}
@@ -3,17 +3,34 @@
 
 /*! ABI definitions for Native Fungible Token Application */
 
-use async_graphql::{Request, Response, SimpleObject, InputObject};
+use async_graphql::{ComplexObject, Request, Response, SimpleObject, InputObject};
 use linera_sdk::linera_base_types::{AccountOwner, Amount, ContractAbi, ServiceAbi, ChainId};
 use serde::{Deserialize, Serialize};
 
+pub mod amount_format;
+pub mod balance_breakdown;
+pub mod checked_math;
+pub mod csv;
+pub mod game_result;
+pub mod list_query;
+pub mod payout_summary;
+pub mod stored_fields;
+pub mod ticket_quote;
+pub mod transfer_memo;
+
+use self::amount_format::{amount_attos, amount_display};
+
 pub const TICKER_SYMBOL: &str = "NAT";
 
-// Prediction direction for the Up/Down game
+/// Most recipients a single BulkMint may credit
+pub const MAX_BULK_MINT_RECIPIENTS: usize = 100;
+
+// Prediction direction for the Up/Down game (Flat only applies to three-outcome rounds)
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, async_graphql::Enum)]
 pub enum Prediction {
     Up,
     Down,
+    Flat,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -26,6 +43,7 @@ pub enum Message {
         prediction: Prediction,
         source_chain_id: ChainId,
         source_owner: AccountOwner,
+        memo: Option<String>,
     },
     // Send reward to winner (called by Rounds app)
     SendReward {
@@ -43,11 +61,23 @@ pub struct AccountInput {
 }
 
 #[derive(SimpleObject)]
+#[graphql(complex)]
 pub struct AccountEntry {
     pub key: AccountOwner,
     pub value: Amount,
 }
 
+#[ComplexObject]
+impl AccountEntry {
+    async fn value_attos(&self) -> String {
+        amount_attos(self.value)
+    }
+
+    async fn value_display(&self) -> String {
+        amount_display(self.value)
+    }
+}
+
 // Extended operations for NativeFungible with game integration
 #[derive(Debug, Deserialize, Serialize)]
 pub enum ExtendedOperation {
@@ -63,6 +93,8 @@ pub enum ExtendedOperation {
         amount: Amount,
         target_account: linera_sdk::abis::fungible::Account,
         prediction: Option<Prediction>,
+        /// Optional tag for the recipient (at most 64 bytes), ignored by the game
+        memo: Option<String>,
     },
     /// Claim tokens from another chain
     Claim {
@@ -78,6 +110,10 @@ pub enum ExtendedOperation {
         owner: AccountOwner,
         amount: Amount,
     },
+    /// Mint to up to `MAX_BULK_MINT_RECIPIENTS` accounts at once
+    BulkMint { recipients: Vec<(AccountOwner, Amount)> },
+    /// Get the total amount minted so far
+    GetTotalMinted,
     
     // Admin operations (for Winzareal)
     /// Set the Native token app ApplicationId (Winzareal only)
@@ -92,6 +128,26 @@ pub enum ExtendedOperation {
         amount: Amount,
         source_chain_id: Option<String>,
     },
+    /// Lock a reward in a vesting entry instead of paying it at once. Nothing vests for
+    /// `cliff_blocks`, then it vests linearly until `duration_blocks` after the current block.
+    SendRewardVested {
+        recipient: AccountOwner,
+        amount: Amount,
+        duration_blocks: u64,
+        cliff_blocks: u64,
+        source_chain_id: Option<String>,
+    },
+    /// Pay the signer the vested, not yet released part of their vesting entry `entry_id`
+    ClaimVested { entry_id: u64 },
+    
+    /// Zero all operation/message counters (Winzareal only)
+    ResetMetrics,
+    
+    /// Get the coordinated app IDs (Winzareal only)
+    GetConfig,
+    /// Replace the coordinated app IDs; nothing is applied unless every ID is valid
+    /// (Winzareal only, creator chain only)
+    ImportConfig { config: WinzaConfig },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -100,6 +156,21 @@ pub enum ExtendedResponse {
     Balance(Amount),
     ChainBalance(Amount),
     TickerSymbol(String),
+    Config(WinzaConfig),
+    TotalMinted(Amount),
+    /// Recipients credited and recipients skipped because of the maximum supply
+    BulkMintResult { minted_count: u64, skipped_count: u64 },
+    /// ID of the vesting entry created by SendRewardVested
+    VestingEntryId(u64),
+    /// Amount ClaimVested released
+    VestedClaimed(Amount),
+}
+
+/// The app IDs Winzareal coordinates, for export and import between chains
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct WinzaConfig {
+    pub native_app_id: Option<String>,
+    pub rounds_app_id: Option<String>,
 }
 
 pub struct ExtendedNativeFungibleTokenAbi;
@@ -0,0 +1,94 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Sort order and creation-time bounds shared by the list queries of every service */
+
+use std::cmp::Reverse;
+
+use serde::{Deserialize, Serialize};
+
+/// Direction of a list query
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, async_graphql::Enum)]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    /// Sort `items` ascending or descending
+    pub fn sort<T: Ord>(self, items: &mut [T]) {
+        match self {
+            SortOrder::Asc => items.sort_unstable(),
+            SortOrder::Desc => items.sort_unstable_by(|a, b| b.cmp(a)),
+        }
+    }
+
+    /// Sort `items` by `key`, ascending or descending; equal keys keep their order
+    pub fn sort_by_key<T, K: Ord>(self, items: &mut [T], mut key: impl FnMut(&T) -> K) {
+        match self {
+            SortOrder::Asc => items.sort_by_key(key),
+            SortOrder::Desc => items.sort_by_key(|item| Reverse(key(item))),
+        }
+    }
+}
+
+/// Creation-time bounds (micros) of a round filter, both exclusive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CreatedRange {
+    pub after: Option<u64>,
+    pub before: Option<u64>,
+}
+
+impl CreatedRange {
+    /// Whether a round created at `created_at` is within the bounds
+    pub fn contains(&self, created_at: u64) -> bool {
+        self.after.is_none_or(|after| created_at > after)
+            && self.before.is_none_or(|before| created_at < before)
+    }
+
+    /// Whether a walk over rounds in creation order, going in `order`, can stop at a round
+    /// created at `created_at` because every later round is out of bounds too
+    pub fn is_exhausted(&self, created_at: u64, order: SortOrder) -> bool {
+        match order {
+            SortOrder::Asc => self.before.is_some_and(|before| created_at >= before),
+            SortOrder::Desc => self.after.is_some_and(|after| created_at <= after),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CreatedRange, SortOrder};
+
+    #[test]
+    fn test_sort_in_both_directions() {
+        let mut ids = vec![2, 3, 1];
+        SortOrder::Desc.sort(&mut ids);
+        assert_eq!(ids, vec![3, 2, 1]);
+        SortOrder::Asc.sort(&mut ids);
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        let mut pairs = vec![(1, 'a'), (2, 'b'), (1, 'c')];
+        SortOrder::Desc.sort_by_key(&mut pairs, |(key, _)| *key);
+        assert_eq!(pairs, vec![(2, 'b'), (1, 'a'), (1, 'c')]);
+    }
+
+    #[test]
+    fn test_created_range_bounds_are_exclusive() {
+        let range = CreatedRange { after: Some(10), before: Some(20) };
+        assert!(!range.contains(10));
+        assert!(range.contains(15));
+        assert!(!range.contains(20));
+        assert!(CreatedRange::default().contains(0));
+    }
+
+    #[test]
+    fn test_walk_stops_past_the_far_bound() {
+        let range = CreatedRange { after: Some(10), before: Some(20) };
+        assert!(!range.is_exhausted(5, SortOrder::Asc));
+        assert!(range.is_exhausted(20, SortOrder::Asc));
+        assert!(!range.is_exhausted(25, SortOrder::Desc));
+        assert!(range.is_exhausted(10, SortOrder::Desc));
+    }
+}
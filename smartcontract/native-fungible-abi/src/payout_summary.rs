@@ -0,0 +1,78 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Paid versus unpaid prize totals of a lottery round, for reconciling prize pools */
+
+use async_graphql::SimpleObject;
+use linera_sdk::linera_base_types::Amount;
+use serde::{Deserialize, Serialize};
+
+/// Running prize totals of one round, updated as winners are drawn and their prizes delivered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoutTotals {
+    pub total_awarded: Amount,
+    pub total_paid: Amount,
+}
+
+impl PayoutTotals {
+    /// Count a prize awarded to a drawn ticket
+    pub fn award(&mut self, amount: Amount) {
+        self.total_awarded = self.total_awarded.saturating_add(amount);
+    }
+
+    /// Count an awarded prize as delivered
+    pub fn pay(&mut self, amount: Amount) {
+        self.total_paid = self.total_paid.saturating_add(amount);
+    }
+
+    /// The round's summary, given its pool, the part of it the winner pools hand out and
+    /// its winner counts
+    pub fn summary(self, prize_pool: Amount, distributable: Amount, winners_drawn: u64, winners_total: u64) -> RoundPayoutSummary {
+        RoundPayoutSummary {
+            prize_pool,
+            distributable,
+            total_awarded: self.total_awarded,
+            total_paid: self.total_paid,
+            total_unclaimed: self.total_awarded.saturating_sub(self.total_paid),
+            winners_drawn,
+            winners_remaining: winners_total.saturating_sub(winners_drawn),
+        }
+    }
+}
+
+/// How much of a round's prize pool has been awarded and paid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct RoundPayoutSummary {
+    pub prize_pool: Amount,
+    /// Sum of the four winner pool allocations (the prize pool minus rounding dust)
+    pub distributable: Amount,
+    /// Prizes of the tickets drawn so far
+    pub total_awarded: Amount,
+    /// Awarded prizes delivered to their winners
+    pub total_paid: Amount,
+    /// Awarded prizes not delivered yet (escrowed or awaiting delivery)
+    pub total_unclaimed: Amount,
+    pub winners_drawn: u64,
+    pub winners_remaining: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::Amount;
+
+    use super::PayoutTotals;
+
+    #[test]
+    fn test_unclaimed_is_awarded_minus_paid() {
+        let mut totals = PayoutTotals::default();
+        totals.award(Amount::from_tokens(30));
+        totals.award(Amount::from_tokens(20));
+        totals.pay(Amount::from_tokens(30));
+
+        let summary = totals.summary(Amount::from_tokens(100), Amount::from_tokens(100), 2, 5);
+        assert_eq!(summary.total_awarded, Amount::from_tokens(50));
+        assert_eq!(summary.total_paid, Amount::from_tokens(30));
+        assert_eq!(summary.total_unclaimed, Amount::from_tokens(20));
+        assert_eq!(summary.winners_remaining, 3);
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Serde support for fields appended to types that are already in storage */
+
+use serde::{Deserialize, Deserializer};
+
+/// How BCS reports running out of input
+const END_OF_INPUT: &str = "unexpected end of input";
+
+/// Read a field appended to a stored struct, or its default for values written before the
+/// field existed. Views store values with BCS, which has no field names, so an old value
+/// simply ends where the field would start and `#[serde(default)]` alone does not apply.
+/// Any other decoding error is returned, so corrupt data is not mistaken for an old value.
+/// Only use it on the trailing fields of a type stored as a top-level view value:
+///
+/// `#[serde(default, deserialize_with = "native_fungible_abi::stored_fields::default_if_missing")]`
+pub fn default_if_missing<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    match T::deserialize(deserializer) {
+        Err(error) if error.to_string() == END_OF_INPUT => Ok(T::default()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize)]
+    struct Before {
+        id: u64,
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct After {
+        id: u64,
+        name: String,
+        #[serde(default, deserialize_with = "super::default_if_missing")]
+        count: u64,
+    }
+
+    #[test]
+    fn test_old_values_get_the_default() {
+        let bytes = bcs::to_bytes(&Before { id: 7, name: "round".to_string() }).unwrap();
+        let after: After = bcs::from_bytes(&bytes).unwrap();
+        assert_eq!(after, After { id: 7, name: "round".to_string(), count: 0 });
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct WithFlag {
+        id: u64,
+        name: String,
+        #[serde(default, deserialize_with = "super::default_if_missing")]
+        flag: bool,
+    }
+
+    #[test]
+    fn test_corrupt_values_are_errors() {
+        let mut bytes = bcs::to_bytes(&Before { id: 7, name: "round".to_string() }).unwrap();
+        bytes.push(2);
+        let error = bcs::from_bytes::<WithFlag>(&bytes).unwrap_err();
+        assert_eq!(error, bcs::Error::ExpectedBoolean);
+    }
+
+    #[test]
+    fn test_new_values_keep_the_field() {
+        let value = After { id: 7, name: "round".to_string(), count: 3 };
+        let after: After = bcs::from_bytes(&bcs::to_bytes(&value).unwrap()).unwrap();
+        assert_eq!(after, value);
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Ticket purchase quotes shared by the lottery apps and their services */
+
+use async_graphql::SimpleObject;
+use linera_sdk::linera_base_types::Amount;
+use serde::{Deserialize, Serialize};
+
+/// Most entries a round's price schedule may have
+pub const MAX_PRICE_SCHEDULE_ENTRIES: usize = 5;
+
+/// Ticket price that applies from `effective_from` (microseconds) onwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct PriceStep {
+    pub effective_from: u64,
+    pub ticket_price: Amount,
+}
+
+/// What a purchase of a given amount buys in the active round
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct PurchaseQuote {
+    pub round_id: u64,
+    pub ticket_price: Amount,
+    pub ticket_count: u64,
+    /// Cost of the quoted tickets (`ticket_count * ticket_price`)
+    pub total_cost: Amount,
+    /// Part of the amount that does not buy a whole ticket
+    pub remainder: Amount,
+    /// Scheduled price changes of the round (empty when the price is fixed)
+    pub price_schedule: Vec<PriceStep>,
+}
+
+/// Why a purchase cannot be quoted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuoteError {
+    NoActiveRound,
+    ZeroTicketPrice,
+}
+
+impl std::fmt::Display for QuoteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuoteError::NoActiveRound => write!(f, "No active round accepting ticket purchases"),
+            QuoteError::ZeroTicketPrice => write!(f, "The active round has a zero ticket price"),
+        }
+    }
+}
+
+/// Quote a purchase of `amount` in round `round_id` at `ticket_price`
+pub fn quote_purchase(round_id: u64, ticket_price: Amount, amount: Amount) -> Result<PurchaseQuote, QuoteError> {
+    let price = u128::from(ticket_price);
+    if price == 0 {
+        return Err(QuoteError::ZeroTicketPrice);
+    }
+    let amount = u128::from(amount);
+    let ticket_count = amount / price;
+    Ok(PurchaseQuote {
+        round_id,
+        ticket_price,
+        ticket_count: u64::try_from(ticket_count).unwrap_or(u64::MAX),
+        total_cost: Amount::from_attos(ticket_count * price),
+        remainder: Amount::from_attos(amount % price),
+        price_schedule: Vec::new(),
+    })
+}
+
+/// Check a price schedule: at most MAX_PRICE_SCHEDULE_ENTRIES entries, strictly
+/// ascending timestamps and no zero price
+pub fn validate_price_schedule(schedule: &[(u64, Amount)]) -> Result<(), String> {
+    if schedule.len() > MAX_PRICE_SCHEDULE_ENTRIES {
+        return Err(format!("Price schedule has {} entries, at most {} are allowed", schedule.len(), MAX_PRICE_SCHEDULE_ENTRIES));
+    }
+    if let Some((effective_from, _)) = schedule.iter().find(|(_, price)| price.is_zero()) {
+        return Err(format!("Price schedule entry at {} has a zero ticket price", effective_from));
+    }
+    if let Some(pair) = schedule.windows(2).find(|pair| pair[1].0 <= pair[0].0) {
+        return Err(format!("Price schedule timestamps must be ascending ({} follows {})", pair[1].0, pair[0].0));
+    }
+    Ok(())
+}
+
+/// Ticket price at `timestamp`: the last schedule entry that has taken effect, or
+/// `base_price` before the first one
+pub fn effective_ticket_price(base_price: Amount, schedule: &[(u64, Amount)], timestamp: u64) -> Amount {
+    schedule.iter()
+        .take_while(|(effective_from, _)| *effective_from <= timestamp)
+        .last()
+        .map_or(base_price, |(_, price)| *price)
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::Amount;
+
+    use super::{effective_ticket_price, quote_purchase, validate_price_schedule, QuoteError, MAX_PRICE_SCHEDULE_ENTRIES};
+
+    #[test]
+    fn test_quote_with_remainder() {
+        let quote = quote_purchase(3, Amount::from_tokens(2), Amount::from_millis(7_500)).unwrap();
+        assert_eq!(quote.round_id, 3);
+        assert_eq!(quote.ticket_count, 3);
+        assert_eq!(quote.total_cost, Amount::from_tokens(6));
+        assert_eq!(quote.remainder, Amount::from_millis(1_500));
+    }
+
+    #[test]
+    fn test_amount_below_price_buys_nothing() {
+        let quote = quote_purchase(1, Amount::from_tokens(2), Amount::ONE).unwrap();
+        assert_eq!(quote.ticket_count, 0);
+        assert_eq!(quote.total_cost, Amount::ZERO);
+        assert_eq!(quote.remainder, Amount::ONE);
+    }
+
+    #[test]
+    fn test_zero_price_is_an_error() {
+        assert_eq!(quote_purchase(1, Amount::ZERO, Amount::ONE), Err(QuoteError::ZeroTicketPrice));
+    }
+
+    #[test]
+    fn test_effective_price_follows_the_schedule() {
+        let schedule = [(100, Amount::from_tokens(2)), (200, Amount::from_tokens(3))];
+        assert_eq!(effective_ticket_price(Amount::ONE, &schedule, 99), Amount::ONE);
+        assert_eq!(effective_ticket_price(Amount::ONE, &schedule, 100), Amount::from_tokens(2));
+        assert_eq!(effective_ticket_price(Amount::ONE, &schedule, 199), Amount::from_tokens(2));
+        assert_eq!(effective_ticket_price(Amount::ONE, &schedule, 500), Amount::from_tokens(3));
+        assert_eq!(effective_ticket_price(Amount::ONE, &[], 500), Amount::ONE);
+    }
+
+    #[test]
+    fn test_invalid_price_schedules_are_rejected() {
+        assert!(validate_price_schedule(&[]).is_ok());
+        assert!(validate_price_schedule(&[(100, Amount::ONE), (100, Amount::ONE)]).is_err());
+        assert!(validate_price_schedule(&[(200, Amount::ONE), (100, Amount::ONE)]).is_err());
+        assert!(validate_price_schedule(&[(100, Amount::ZERO)]).is_err());
+        let too_long: Vec<_> = (0..=MAX_PRICE_SCHEDULE_ENTRIES as u64).map(|index| (index, Amount::ONE)).collect();
+        assert!(validate_price_schedule(&too_long).is_err());
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Transfer memos (e.g. exchange deposit tags) carried alongside token transfers */
+
+use async_graphql::SimpleObject;
+use linera_sdk::linera_base_types::{AccountOwner, Amount};
+use serde::{Deserialize, Serialize};
+
+/// Longest memo accepted on a transfer, in bytes
+pub const MAX_MEMO_BYTES: usize = 64;
+
+/// A received transfer that carried a memo; the memo is informational only
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct TransferNotice {
+    pub recipient: AccountOwner,
+    pub source_chain_id: String,
+    pub source_owner: AccountOwner,
+    pub amount: Amount,
+    pub memo: String,
+}
+
+/// Reject memos longer than `MAX_MEMO_BYTES`
+pub fn validate_memo(memo: Option<&str>) -> Result<(), String> {
+    match memo {
+        Some(memo) if memo.len() > MAX_MEMO_BYTES => Err(format!(
+            "Memo is {} bytes, the maximum is {}",
+            memo.len(),
+            MAX_MEMO_BYTES
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{validate_memo, MAX_MEMO_BYTES};
+
+    #[test]
+    fn test_memo_up_to_the_limit_is_accepted() {
+        assert!(validate_memo(None).is_ok());
+        assert!(validate_memo(Some("")).is_ok());
+        assert!(validate_memo(Some(&"a".repeat(MAX_MEMO_BYTES))).is_ok());
+    }
+
+    #[test]
+    fn test_oversized_memo_is_rejected() {
+        let err = validate_memo(Some(&"a".repeat(MAX_MEMO_BYTES + 1))).unwrap_err();
+        assert_eq!(err, "Memo is 65 bytes, the maximum is 64");
+    }
+
+    #[test]
+    fn test_memo_length_counts_bytes_not_characters() {
+        // 22 three-byte characters: 22 chars but 66 bytes
+        assert!(validate_memo(Some(&"€".repeat(22))).is_err());
+        assert!(validate_memo(Some(&"€".repeat(21))).is_ok());
+    }
+}
@@ -9,11 +9,11 @@ use linera_sdk::{
     abis::fungible::{
         Account as FungibleAccount, InitialState, Parameters,
     },
-    linera_base_types::{Account, AccountOwner, ChainId, WithContractAbi},
+    linera_base_types::{Account, AccountOwner, Amount, ChainId, WithContractAbi},
     views::{RootView, View},
     Contract, ContractRuntime,
 };
-use native::{Message, TICKER_SYMBOL, NativeAbi, NativeOperation, NativeResponse};
+use native::{validate_memo, Message, TransferNotice, MAX_BULK_MINT_RECIPIENTS, TICKER_SYMBOL, NativeAbi, NativeOperation, NativeResponse};
 use self::state::NativeState;
 
 pub struct NativeContract {
@@ -54,10 +54,13 @@ impl Contract for NativeContract {
                 owner,
             };
             self.runtime.transfer(AccountOwner::CHAIN, account, amount);
+            self.record_holder(owner);
         }
     }
 
     async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response {
+        self.auto_snapshot().await;
+        
         match operation {
             NativeOperation::Balance { owner } => {
                 let balance = self.runtime.owner_balance(owner);
@@ -77,17 +80,34 @@ impl Contract for NativeContract {
                 owner,
                 amount,
                 target_account,
+                memo,
             } => {
                 self.runtime
                     .check_account_permission(owner)
                     .expect("Permission for Transfer operation");
+                if let Err(e) = validate_memo(memo.as_deref()) {
+                    panic!("Invalid transfer memo: {}", e);
+                }
 
                 let target_account = self.normalize_account(target_account);
                 self.runtime.transfer(owner, target_account, amount);
+                self.record_holder(owner);
+                self.record_local_holder(target_account);
+
+                // The memo goes to the recipient's inbox on the chain the tokens land on
+                let notice = memo.map(|memo| TransferNotice {
+                    recipient: target_account.owner,
+                    source_chain_id: self.runtime.chain_id().to_string(),
+                    source_owner: owner,
+                    amount,
+                    memo,
+                });
                 
                 // Send notify message for cross-chain transfers
                 if target_account.chain_id != self.runtime.chain_id() {
-                    self.transfer(target_account.chain_id);
+                    self.transfer(target_account.chain_id, notice);
+                } else if let Some(notice) = notice {
+                    self.state.record_transfer(notice).expect("Failed to record transfer");
                 }
 
                 NativeResponse::Ok
@@ -106,6 +126,7 @@ impl Contract for NativeContract {
                 let target_account = self.normalize_account(target_account);
 
                 self.runtime.claim(source_account, target_account, amount);
+                self.record_local_holder(target_account);
                 
                 self.claim(source_account.chain_id, target_account.chain_id);
                 NativeResponse::Ok
@@ -119,22 +140,192 @@ impl Contract for NativeContract {
                     owner: AccountOwner::CHAIN,
                 };
                 self.runtime.transfer(owner, target_account, balance);
+                self.record_holder(owner);
+                self.state.record_burn(balance);
+                NativeResponse::Ok
+            }
+
+            NativeOperation::WithdrawToChain { target_chain_id, amount } => {
+                let owner = self.runtime.authenticated_signer()
+                    .expect("Authentication required for WithdrawToChain");
+                if target_chain_id == self.runtime.chain_id() {
+                    panic!("WithdrawToChain requires another chain - use Withdraw for the local chain");
+                }
+                let target_account = Account {
+                    chain_id: target_chain_id,
+                    owner,
+                };
+                self.runtime.transfer(owner, target_account, amount);
+                self.record_holder(owner);
+                
+                // Notify the target chain so it picks up the incoming transfer
+                self.transfer(target_chain_id, None);
                 NativeResponse::Ok
             }
 
             NativeOperation::Mint { owner, amount } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("Mint can only be executed on the application creator chain");
+                }
+                if let (Some(remaining), Some(cap)) = (self.state.remaining_supply(), *self.state.max_supply.get()) {
+                    if amount > remaining {
+                        return NativeResponse::MintRejected { current: *self.state.total_minted.get(), cap };
+                    }
+                }
+                if let Err(e) = self.state.record_mint(amount) {
+                    panic!("Failed to mint: {}", e);
+                }
                 let target_account = Account {
                     chain_id: self.runtime.chain_id(),
                     owner,
                 };
                 self.runtime.transfer(AccountOwner::CHAIN, target_account, amount);
+                self.record_holder(owner);
                 NativeResponse::Ok
             }
+
+            NativeOperation::BulkMint { recipients } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("BulkMint can only be executed on the application creator chain");
+                }
+                if recipients.len() > MAX_BULK_MINT_RECIPIENTS {
+                    panic!("BulkMint accepts at most {} recipients, got {}", MAX_BULK_MINT_RECIPIENTS, recipients.len());
+                }
+                // Keep the longest prefix of the batch that fits in the remaining supply
+                let mut mintable = self.state.remaining_supply();
+                let fitting = recipients.iter()
+                    .take_while(|(_, amount)| match mintable.as_mut() {
+                        Some(remaining) => match remaining.try_sub(*amount) {
+                            Ok(rest) => {
+                                *remaining = rest;
+                                true
+                            }
+                            Err(_) => false,
+                        },
+                        None => true,
+                    })
+                    .count();
+                let skipped_count = (recipients.len() - fitting) as u64;
+                let mut minted_count = 0;
+                for (owner, amount) in recipients.into_iter().take(fitting) {
+                    if let Err(e) = self.state.record_mint(amount) {
+                        panic!("Failed to mint: {}", e);
+                    }
+                    let target_account = Account {
+                        chain_id: self.runtime.chain_id(),
+                        owner,
+                    };
+                    self.runtime.transfer(AccountOwner::CHAIN, target_account, amount);
+                    self.record_holder(owner);
+                    minted_count += 1;
+                }
+                NativeResponse::BulkMintResult { minted_count, skipped_count }
+            }
+
+            NativeOperation::GetTotalMinted => NativeResponse::TotalMinted(*self.state.total_minted.get()),
+
+            NativeOperation::GetCirculatingInfo => NativeResponse::CirculatingInfo(self.state.circulating_info()),
+
+            NativeOperation::SetMaxSupply { cap } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("SetMaxSupply can only be executed on the application creator chain");
+                }
+                self.state.max_supply.set(cap);
+                NativeResponse::Ok
+            }
+
+            NativeOperation::SnapshotBalance { owner } => {
+                let height = self.runtime.block_height().0;
+                let balance = self.runtime.owner_balance(owner);
+                if let Err(e) = self.state.snapshot_balance(owner, height, balance) {
+                    panic!("Failed to snapshot balance: {}", e);
+                }
+                self.record_holder(owner);
+                NativeResponse::Ok
+            }
+
+            NativeOperation::GetBalanceAtBlock { owner, block } => {
+                match self.state.balance_at(owner, block).await {
+                    Ok(balance) => NativeResponse::HistoricalBalance(balance),
+                    Err(e) => panic!("Failed to get historical balance: {}", e),
+                }
+            }
+
+            NativeOperation::BulkSnapshotAllBalances { limit } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("BulkSnapshotAllBalances can only be executed on the application creator chain");
+                }
+                let height = self.runtime.block_height().0;
+                let balances = self.holder_balances().await;
+                match self.state.bulk_snapshot(&balances, height, limit) {
+                    Ok((snapshotted_count, done)) => NativeResponse::BulkSnapshotResult { snapshotted_count, done },
+                    Err(e) => panic!("Failed to snapshot balances: {}", e),
+                }
+            }
+
+            NativeOperation::SetSnapshotInterval { interval } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("SetSnapshotInterval can only be executed on the application creator chain");
+                }
+                self.state.snapshot_interval.set(interval);
+                NativeResponse::Ok
+            }
+
+            NativeOperation::Approve { spender, amount } => {
+                let owner = self.runtime.authenticated_signer()
+                    .expect("Authentication required for Approve");
+                let previous = match self.state.allowance(owner, spender).await {
+                    Ok(previous) => previous,
+                    Err(e) => panic!("Failed to approve: {}", e),
+                };
+                // The escrow account holds exactly the outstanding allowances
+                let escrow = self.escrow_account();
+                if amount > previous {
+                    self.runtime.transfer(owner, escrow, amount.saturating_sub(previous));
+                    self.record_holder(owner);
+                } else if amount < previous {
+                    let refund = Account { chain_id: self.runtime.chain_id(), owner };
+                    self.runtime.transfer(escrow.owner, refund, previous.saturating_sub(amount));
+                }
+                if let Err(e) = self.state.set_allowance(owner, spender, amount) {
+                    panic!("Failed to approve: {}", e);
+                }
+                NativeResponse::Ok
+            }
+
+            NativeOperation::TransferFrom { from, to, amount } => {
+                let spender = self.runtime.authenticated_caller_id()
+                    .map(AccountOwner::from)
+                    .or(self.runtime.authenticated_signer())
+                    .expect("Authentication required for TransferFrom");
+                if let Err(e) = self.state.spend_allowance(from, spender, amount).await {
+                    panic!("Failed to transfer from {}: {}", from, e);
+                }
+                let target_account = self.normalize_account(to);
+                let escrow = self.escrow_account();
+                self.runtime.transfer(escrow.owner, target_account, amount);
+                self.record_local_holder(target_account);
+                self.transfer(target_account.chain_id, None);
+                NativeResponse::Ok
+            }
+
+            NativeOperation::Allowance { owner, spender } => {
+                match self.state.allowance(owner, spender).await {
+                    Ok(amount) => NativeResponse::Allowance(amount),
+                    Err(e) => panic!("Failed to get allowance: {}", e),
+                }
+            }
         }
     }
 
-    async fn execute_message(&mut self, _message: Self::Message) {
+    async fn execute_message(&mut self, message: Self::Message) {
         // Auto-deploy on notify
+        match message {
+            Message::Notify { notice: Some(notice) } => {
+                self.state.record_transfer(notice).expect("Failed to record transfer");
+            }
+            Message::Notify { notice: None } => {}
+        }
     }
 
     async fn store(mut self) {
@@ -143,9 +334,51 @@ impl Contract for NativeContract {
 }
 
 impl NativeContract {
-    fn transfer(&mut self, chain_id: ChainId) {
+    /// Every recorded balance holder with their current balance
+    async fn holder_balances(&mut self) -> Vec<(AccountOwner, Amount)> {
+        let holders = self.state.holders().await.expect("Failed to get balance holders");
+        holders.into_iter()
+            .map(|owner| (owner, self.runtime.owner_balance(owner)))
+            .collect()
+    }
+
+    fn record_holder(&mut self, owner: AccountOwner) {
+        self.state.record_holder(owner).expect("Failed to record balance holder");
+    }
+
+    /// Record the owner of `account` if the tokens land on this chain
+    fn record_local_holder(&mut self, account: Account) {
+        if account.chain_id == self.runtime.chain_id() {
+            self.record_holder(account.owner);
+        }
+    }
+
+    /// Snapshot every balance once per block whose height is a multiple of the snapshot interval
+    async fn auto_snapshot(&mut self) {
+        if *self.state.snapshot_interval.get() == 0 {
+            return;
+        }
+        let height = self.runtime.block_height().0;
+        if !self.state.auto_snapshot_due(height) {
+            return;
+        }
+        for (owner, balance) in self.holder_balances().await {
+            self.state.snapshot_balance(owner, height, balance).expect("Failed to snapshot balance");
+        }
+        self.state.last_auto_snapshot.set(Some(height));
+    }
+
+    /// This application's own account on this chain, where approved tokens wait for TransferFrom
+    fn escrow_account(&mut self) -> Account {
+        Account {
+            chain_id: self.runtime.chain_id(),
+            owner: AccountOwner::from(self.runtime.application_id().forget_abi()),
+        }
+    }
+
+    fn transfer(&mut self, chain_id: ChainId, notice: Option<TransferNotice>) {
         if chain_id != self.runtime.chain_id() {
-            let message = Message::Notify;
+            let message = Message::Notify { notice };
             self.runtime
                 .prepare_message(message)
                 .with_authentication()
@@ -155,9 +388,9 @@ impl NativeContract {
 
     fn claim(&mut self, source_chain_id: ChainId, target_chain_id: ChainId) {
         if source_chain_id == self.runtime.chain_id() {
-            self.transfer(target_chain_id);
+            self.transfer(target_chain_id, None);
         } else {
-            let message = Message::Notify;
+            let message = Message::Notify { notice: None };
             self.runtime
                 .prepare_message(message)
                 .with_authentication()
@@ -171,4 +404,364 @@ impl NativeContract {
             owner: account.owner,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt as _;
+    use linera_sdk::{
+        abis::fungible::Parameters,
+        linera_base_types::{AccountOwner, Amount, ApplicationId, BlockHeight, ChainId, CryptoHash},
+        util::BlockingWait,
+        views::View,
+        Contract, ContractRuntime,
+    };
+    use linera_sdk::abis::fungible::Account;
+    use native::{CirculatingInfo, Message, NativeAbi, NativeOperation, NativeResponse, TransferNotice};
+
+    use super::{NativeContract, NativeState};
+
+    fn create_contract(owner: AccountOwner, balance: Amount) -> NativeContract {
+        let runtime = ContractRuntime::new()
+            .with_application_parameters(Parameters::new("NAT"))
+            .with_chain_id(ChainId(CryptoHash::test_hash("game")))
+            .with_authenticated_signer(owner)
+            .with_owner_balances([(owner, balance)]);
+        let state = NativeState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to load state");
+        NativeContract { state, runtime }
+    }
+
+    #[test]
+    fn withdraw_to_chain_sends_tokens_to_the_target_chain() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let home = ChainId(CryptoHash::test_hash("home"));
+        let mut contract = create_contract(alice, Amount::from_tokens(10));
+
+        contract.execute_operation(NativeOperation::WithdrawToChain {
+            target_chain_id: home,
+            amount: Amount::from_tokens(4),
+        }).now_or_never().unwrap();
+
+        assert_eq!(contract.runtime.owner_balance(alice), Amount::from_tokens(6));
+        let messages = contract.runtime.created_send_message_requests();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].destination, home);
+        assert!(messages[0].authenticated);
+        assert!(matches!(messages[0].message, Message::Notify { notice: None }));
+    }
+
+    #[test]
+    #[should_panic(expected = "WithdrawToChain requires another chain")]
+    fn withdraw_to_chain_rejects_the_local_chain() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let mut contract = create_contract(alice, Amount::from_tokens(10));
+
+        contract.execute_operation(NativeOperation::WithdrawToChain {
+            target_chain_id: ChainId(CryptoHash::test_hash("game")),
+            amount: Amount::from_tokens(4),
+        }).now_or_never().unwrap();
+    }
+
+    fn memo_transfer(owner: AccountOwner, target_chain_id: ChainId, memo: String) -> NativeOperation {
+        NativeOperation::Transfer {
+            owner,
+            amount: Amount::from_tokens(3),
+            target_account: Account {
+                chain_id: target_chain_id,
+                owner: AccountOwner::from(CryptoHash::test_hash("exchange")),
+            },
+            memo: Some(memo),
+        }
+    }
+
+    #[test]
+    fn transfer_memo_reaches_the_recipient_inbox() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let exchange = AccountOwner::from(CryptoHash::test_hash("exchange"));
+        let mut sender = create_contract(alice, Amount::from_tokens(10));
+
+        sender.execute_operation(memo_transfer(alice, ChainId(CryptoHash::test_hash("exchange")), "deposit-42".to_string()))
+            .now_or_never()
+            .unwrap();
+
+        let messages = sender.runtime.created_send_message_requests();
+        assert_eq!(messages.len(), 1);
+        let notice = match &messages[0].message {
+            Message::Notify { notice: Some(notice) } => notice.clone(),
+            other => panic!("Expected a notice, got {:?}", other),
+        };
+        assert_eq!(notice, TransferNotice {
+            recipient: exchange,
+            source_chain_id: ChainId(CryptoHash::test_hash("game")).to_string(),
+            source_owner: alice,
+            amount: Amount::from_tokens(3),
+            memo: "deposit-42".to_string(),
+        });
+
+        let mut receiver = create_contract(exchange, Amount::ZERO);
+        receiver.execute_message(Message::Notify { notice: Some(notice.clone()) }).now_or_never().unwrap();
+        receiver.execute_message(Message::Notify { notice: None }).now_or_never().unwrap();
+        assert_eq!(receiver.state.incoming_transfers(exchange, 10).now_or_never().unwrap().unwrap(), vec![notice]);
+        assert!(receiver.state.incoming_transfers(alice, 10).now_or_never().unwrap().unwrap().is_empty());
+    }
+
+    fn create_minter(cap: Option<Amount>) -> NativeContract {
+        let chain = ChainId(CryptoHash::test_hash("game"));
+        let runtime = ContractRuntime::new()
+            .with_application_parameters(Parameters::new("NAT"))
+            .with_chain_id(chain)
+            .with_application_creator_chain_id(chain)
+            .with_chain_balance(Amount::from_tokens(1_000))
+            .with_owner_balances([]);
+        let mut contract = NativeContract {
+            state: NativeState::load(runtime.root_view_storage_context())
+                .blocking_wait()
+                .expect("Failed to load state"),
+            runtime,
+        };
+        contract.execute_operation(NativeOperation::SetMaxSupply { cap }).now_or_never().unwrap();
+        contract
+    }
+
+    #[test]
+    fn bulk_mint_credits_every_recipient() {
+        let mut contract = create_minter(None);
+        let recipients: Vec<(AccountOwner, Amount)> = (1..=5u128)
+            .map(|i| (AccountOwner::from(CryptoHash::test_hash(format!("recipient-{}", i))), Amount::from_tokens(i)))
+            .collect();
+
+        let response = contract.execute_operation(NativeOperation::BulkMint { recipients: recipients.clone() })
+            .now_or_never()
+            .unwrap();
+
+        assert!(matches!(response, NativeResponse::BulkMintResult { minted_count: 5, skipped_count: 0 }));
+        for (owner, amount) in recipients {
+            assert_eq!(contract.runtime.owner_balance(owner), amount);
+        }
+        let total = contract.execute_operation(NativeOperation::GetTotalMinted).now_or_never().unwrap();
+        assert!(matches!(total, NativeResponse::TotalMinted(amount) if amount == Amount::from_tokens(15)));
+    }
+
+    #[test]
+    fn bulk_mint_is_cut_at_the_max_supply() {
+        let mut contract = create_minter(Some(Amount::from_tokens(10)));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let carol = AccountOwner::from(CryptoHash::test_hash("carol"));
+
+        let response = contract.execute_operation(NativeOperation::BulkMint {
+            recipients: vec![(alice, Amount::from_tokens(6)), (bob, Amount::from_tokens(5)), (carol, Amount::from_tokens(4))],
+        }).now_or_never().unwrap();
+
+        // Carol would fit, but the batch stops at Bob
+        assert!(matches!(response, NativeResponse::BulkMintResult { minted_count: 1, skipped_count: 2 }));
+        assert_eq!(contract.runtime.owner_balance(alice), Amount::from_tokens(6));
+        assert_eq!(contract.runtime.owner_balance(carol), Amount::ZERO);
+        assert_eq!(*contract.state.total_minted.get(), Amount::from_tokens(6));
+    }
+
+    #[test]
+    fn mint_is_rejected_past_the_max_supply() {
+        let mut contract = create_minter(Some(Amount::from_tokens(10)));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let mint = |contract: &mut NativeContract, owner, tokens| {
+            contract.execute_operation(NativeOperation::Mint { owner, amount: Amount::from_tokens(tokens) }).now_or_never().unwrap()
+        };
+
+        assert!(matches!(mint(&mut contract, alice, 6), NativeResponse::Ok));
+        let rejected = mint(&mut contract, bob, 5);
+        assert!(matches!(rejected, NativeResponse::MintRejected { current, cap }
+            if current == Amount::from_tokens(6) && cap == Amount::from_tokens(10)));
+        assert_eq!(contract.runtime.owner_balance(bob), Amount::ZERO);
+        assert!(matches!(mint(&mut contract, bob, 4), NativeResponse::Ok));
+
+        contract.runtime.set_authenticated_signer(Some(alice));
+        contract.execute_operation(NativeOperation::Withdraw).now_or_never().unwrap();
+        let info = contract.execute_operation(NativeOperation::GetCirculatingInfo).now_or_never().unwrap();
+        assert!(matches!(info, NativeResponse::CirculatingInfo(info) if info == CirculatingInfo {
+            total_minted: Amount::from_tokens(10),
+            total_burned: Amount::from_tokens(6),
+            circulating: Amount::from_tokens(4),
+            max_supply: Some(Amount::from_tokens(10)),
+        }));
+    }
+
+    #[test]
+    #[should_panic(expected = "Mint can only be executed on the application creator chain")]
+    fn mint_is_creator_chain_only() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let mut contract = create_contract(alice, Amount::ZERO);
+        contract.runtime.set_application_creator_chain_id(ChainId(CryptoHash::test_hash("creator")));
+
+        contract.execute_operation(NativeOperation::Mint { owner: alice, amount: Amount::ONE }).now_or_never().unwrap();
+    }
+
+    fn balance_at(contract: &mut NativeContract, owner: AccountOwner, block: u64) -> Option<Amount> {
+        match contract.execute_operation(NativeOperation::GetBalanceAtBlock { owner, block }).now_or_never().unwrap() {
+            NativeResponse::HistoricalBalance(balance) => balance,
+            other => panic!("Expected a historical balance, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn balance_snapshots_survive_later_balance_changes() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let mut contract = create_contract(alice, Amount::from_tokens(10));
+        contract.runtime.set_block_height(BlockHeight(5));
+        contract.execute_operation(NativeOperation::SnapshotBalance { owner: alice }).now_or_never().unwrap();
+
+        contract.runtime.set_block_height(BlockHeight(8));
+        contract.execute_operation(NativeOperation::WithdrawToChain {
+            target_chain_id: ChainId(CryptoHash::test_hash("home")),
+            amount: Amount::from_tokens(4),
+        }).now_or_never().unwrap();
+        contract.execute_operation(NativeOperation::SnapshotBalance { owner: alice }).now_or_never().unwrap();
+
+        assert_eq!(balance_at(&mut contract, alice, 4), None);
+        assert_eq!(balance_at(&mut contract, alice, 5), Some(Amount::from_tokens(10)));
+        assert_eq!(balance_at(&mut contract, alice, 7), Some(Amount::from_tokens(10)));
+        assert_eq!(balance_at(&mut contract, alice, 8), Some(Amount::from_tokens(6)));
+        assert_eq!(balance_at(&mut contract, alice, 100), Some(Amount::from_tokens(6)));
+    }
+
+    #[test]
+    fn bulk_and_interval_snapshots_cover_every_owner() {
+        let mut contract = create_minter(None);
+        let owners: Vec<AccountOwner> = (1..=3u128)
+            .map(|i| AccountOwner::from(CryptoHash::test_hash(format!("holder-{}", i))))
+            .collect();
+        contract.execute_operation(NativeOperation::BulkMint {
+            recipients: owners.iter().map(|owner| (*owner, Amount::from_tokens(2))).collect(),
+        }).now_or_never().unwrap();
+
+        contract.runtime.set_block_height(BlockHeight(3));
+        let first = contract.execute_operation(NativeOperation::BulkSnapshotAllBalances { limit: 2 }).now_or_never().unwrap();
+        assert!(matches!(first, NativeResponse::BulkSnapshotResult { snapshotted_count: 2, done: false }));
+        let second = contract.execute_operation(NativeOperation::BulkSnapshotAllBalances { limit: 2 }).now_or_never().unwrap();
+        assert!(matches!(second, NativeResponse::BulkSnapshotResult { snapshotted_count: 1, done: true }));
+        for owner in &owners {
+            assert_eq!(balance_at(&mut contract, *owner, 3), Some(Amount::from_tokens(2)));
+        }
+
+        contract.execute_operation(NativeOperation::SetSnapshotInterval { interval: 10 }).now_or_never().unwrap();
+        contract.runtime.set_block_height(BlockHeight(10));
+        contract.execute_operation(NativeOperation::Mint { owner: owners[0], amount: Amount::ONE }).now_or_never().unwrap();
+        // The automatic snapshot is taken before the operation runs
+        assert_eq!(contract.state.balance_snapshots.get(&(owners[0], 10)).now_or_never().unwrap().unwrap(), Some(Amount::from_tokens(2)));
+        assert_eq!(balance_at(&mut contract, owners[2], 10), Some(Amount::from_tokens(2)));
+    }
+
+    #[test]
+    fn transfer_recipients_are_snapshotted_as_holders() {
+        let mut contract = create_minter(None);
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        contract.execute_operation(NativeOperation::Mint { owner: alice, amount: Amount::from_tokens(5) }).now_or_never().unwrap();
+        contract.runtime.set_authenticated_signer(Some(alice));
+        contract.execute_operation(NativeOperation::Transfer {
+            owner: alice,
+            amount: Amount::from_tokens(2),
+            target_account: Account { chain_id: ChainId(CryptoHash::test_hash("game")), owner: bob },
+            memo: None,
+        }).now_or_never().unwrap();
+
+        contract.runtime.set_block_height(BlockHeight(4));
+        let response = contract.execute_operation(NativeOperation::BulkSnapshotAllBalances { limit: 10 }).now_or_never().unwrap();
+        assert!(matches!(response, NativeResponse::BulkSnapshotResult { snapshotted_count: 2, done: true }));
+        assert_eq!(balance_at(&mut contract, alice, 4), Some(Amount::from_tokens(3)));
+        assert_eq!(balance_at(&mut contract, bob, 4), Some(Amount::from_tokens(2)));
+    }
+
+    /// A contract where `owner` signs and the `spender` application is the caller
+    fn create_allowance_contract(owner: AccountOwner, spender: ApplicationId) -> NativeContract {
+        let runtime = ContractRuntime::new()
+            .with_application_parameters(Parameters::new("NAT"))
+            .with_application_id(ApplicationId::new(CryptoHash::test_hash("native")).with_abi::<NativeAbi>())
+            .with_chain_id(ChainId(CryptoHash::test_hash("game")))
+            .with_authenticated_signer(owner)
+            .with_authenticated_caller_id(spender)
+            .with_owner_balances([(owner, Amount::from_tokens(10))]);
+        let state = NativeState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to load state");
+        NativeContract { state, runtime }
+    }
+
+    fn allowance(contract: &mut NativeContract, owner: AccountOwner, spender: AccountOwner) -> Amount {
+        match contract.execute_operation(NativeOperation::Allowance { owner, spender }).now_or_never().unwrap() {
+            NativeResponse::Allowance(amount) => amount,
+            other => panic!("Expected an allowance, got {:?}", other),
+        }
+    }
+
+    fn transfer_from(from: AccountOwner, to: AccountOwner, amount: Amount) -> NativeOperation {
+        NativeOperation::TransferFrom {
+            from,
+            to: Account { chain_id: ChainId(CryptoHash::test_hash("game")), owner: to },
+            amount,
+        }
+    }
+
+    #[test]
+    fn approve_holds_the_allowance_until_it_is_lowered() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let rounds = ApplicationId::new(CryptoHash::test_hash("rounds"));
+        let mut contract = create_allowance_contract(alice, rounds);
+
+        contract.execute_operation(NativeOperation::Approve { spender: AccountOwner::from(rounds), amount: Amount::from_tokens(5) })
+            .now_or_never()
+            .unwrap();
+        assert_eq!(allowance(&mut contract, alice, AccountOwner::from(rounds)), Amount::from_tokens(5));
+        assert_eq!(contract.runtime.owner_balance(alice), Amount::from_tokens(5));
+
+        contract.execute_operation(NativeOperation::Approve { spender: AccountOwner::from(rounds), amount: Amount::from_tokens(3) })
+            .now_or_never()
+            .unwrap();
+        assert_eq!(allowance(&mut contract, alice, AccountOwner::from(rounds)), Amount::from_tokens(3));
+        assert_eq!(contract.runtime.owner_balance(alice), Amount::from_tokens(7));
+    }
+
+    #[test]
+    fn transfer_from_spends_part_of_the_allowance() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let rounds = ApplicationId::new(CryptoHash::test_hash("rounds"));
+        let mut contract = create_allowance_contract(alice, rounds);
+        contract.execute_operation(NativeOperation::Approve { spender: AccountOwner::from(rounds), amount: Amount::from_tokens(5) })
+            .now_or_never()
+            .unwrap();
+
+        contract.execute_operation(transfer_from(alice, bob, Amount::from_tokens(2))).now_or_never().unwrap();
+        assert_eq!(contract.runtime.owner_balance(bob), Amount::from_tokens(2));
+        assert_eq!(allowance(&mut contract, alice, AccountOwner::from(rounds)), Amount::from_tokens(3));
+        // Other spenders were never approved
+        assert_eq!(allowance(&mut contract, alice, bob), Amount::ZERO);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the allowance")]
+    fn transfer_from_rejects_more_than_the_allowance() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let rounds = ApplicationId::new(CryptoHash::test_hash("rounds"));
+        let mut contract = create_allowance_contract(alice, rounds);
+        contract.execute_operation(NativeOperation::Approve { spender: AccountOwner::from(rounds), amount: Amount::from_tokens(5) })
+            .now_or_never()
+            .unwrap();
+
+        contract.execute_operation(transfer_from(alice, alice, Amount::from_tokens(6))).now_or_never().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Memo is 65 bytes, the maximum is 64")]
+    fn oversized_transfer_memo_is_rejected() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let mut contract = create_contract(alice, Amount::from_tokens(10));
+
+        contract.execute_operation(memo_transfer(alice, ChainId(CryptoHash::test_hash("exchange")), "x".repeat(65)))
+            .now_or_never()
+            .unwrap();
+    }
+}
@@ -3,15 +3,21 @@
 
 /*! Pure Native Fungible Token Application - No Game Logic */
 
-use async_graphql::{Request, Response, SimpleObject, InputObject};
+use async_graphql::{ComplexObject, Request, Response, SimpleObject, InputObject};
 use linera_sdk::linera_base_types::{AccountOwner, Amount, ContractAbi, ServiceAbi, ChainId};
+use native_fungible_abi::amount_format::{amount_attos, amount_display};
 use serde::{Deserialize, Serialize};
 
+pub use native_fungible_abi::transfer_memo::{validate_memo, TransferNotice, MAX_MEMO_BYTES};
+pub use native_fungible_abi::MAX_BULK_MINT_RECIPIENTS;
+pub use native_fungible_abi::balance_breakdown::{balance_breakdown, BalanceBreakdown};
+
 pub const TICKER_SYMBOL: &str = "NAT";
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum Message {
-    Notify,
+    /// Wakes the receiving chain; a transfer with a memo carries it for the recipient's inbox
+    Notify { notice: Option<TransferNotice> },
 }
 
 // GraphQL Input type для Account
@@ -22,11 +28,40 @@ pub struct AccountInput {
 }
 
 #[derive(SimpleObject)]
+#[graphql(complex)]
 pub struct AccountEntry {
     pub key: AccountOwner,
     pub value: Amount,
 }
 
+#[ComplexObject]
+impl AccountEntry {
+    async fn value_attos(&self) -> String {
+        amount_attos(self.value)
+    }
+
+    async fn value_display(&self) -> String {
+        amount_display(self.value)
+    }
+}
+
+// GraphQL Input type for one BulkMint recipient
+#[derive(InputObject, Debug, Clone)]
+pub struct MintRecipientInput {
+    pub owner: AccountOwner,
+    pub amount: String,
+}
+
+/// Supply totals of the token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, SimpleObject)]
+pub struct CirculatingInfo {
+    pub total_minted: Amount,
+    /// Tokens returned to the chain account by Withdraw
+    pub total_burned: Amount,
+    pub circulating: Amount,
+    pub max_supply: Option<Amount>,
+}
+
 // Pure token operations - NO prediction/betting logic
 #[derive(Debug, Deserialize, Serialize)]
 pub enum NativeOperation {
@@ -41,6 +76,8 @@ pub enum NativeOperation {
         owner: AccountOwner,
         amount: Amount,
         target_account: linera_sdk::abis::fungible::Account,
+        /// Optional tag for the recipient (at most 64 bytes)
+        memo: Option<String>,
     },
     /// Claim tokens from another chain
     Claim {
@@ -50,11 +87,48 @@ pub enum NativeOperation {
     },
     /// Withdraw all tokens to chain account
     Withdraw,
-    /// Mint new tokens to an account
+    /// Move the signer's tokens to their own account on another chain
+    WithdrawToChain {
+        target_chain_id: ChainId,
+        amount: Amount,
+    },
+    /// Mint new tokens to an account (creator chain only)
     Mint {
         owner: AccountOwner,
         amount: Amount,
     },
+    /// Mint to up to `MAX_BULK_MINT_RECIPIENTS` accounts at once. The batch is minted in
+    /// order and cut at the first recipient that would exceed the maximum supply (creator chain only).
+    BulkMint { recipients: Vec<(AccountOwner, Amount)> },
+    /// Get the total amount minted so far
+    GetTotalMinted,
+    /// Get the minted, burned and circulating totals and the maximum supply
+    GetCirculatingInfo,
+    /// Cap the total amount that can ever be minted (None removes the cap, creator chain only)
+    SetMaxSupply { cap: Option<Amount> },
+    /// Record an owner's current balance at the current block height
+    SnapshotBalance { owner: AccountOwner },
+    /// Get an owner's balance from the latest snapshot taken at or before `block`
+    GetBalanceAtBlock { owner: AccountOwner, block: u64 },
+    /// Snapshot up to `limit` balance holders (owners this application has credited or
+    /// debited on this chain), continuing where the previous call stopped (creator chain only)
+    BulkSnapshotAllBalances { limit: u64 },
+    /// Snapshot every balance automatically on blocks whose height is a multiple
+    /// of `interval` (0 disables, creator chain only)
+    SetSnapshotInterval { interval: u64 },
+    /// Let `spender` move up to `amount` of the signer's tokens with TransferFrom,
+    /// replacing any previous allowance. The allowance is held by this application
+    /// until spent or lowered, since only an account's owner can move its tokens.
+    Approve { spender: AccountOwner, amount: Amount },
+    /// Move `amount` approved by `from` to `to`. The spender is the calling
+    /// application, else the signer.
+    TransferFrom {
+        from: AccountOwner,
+        to: linera_sdk::abis::fungible::Account,
+        amount: Amount,
+    },
+    /// Get what `spender` may still move for `owner`
+    Allowance { owner: AccountOwner, spender: AccountOwner },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -63,6 +137,16 @@ pub enum NativeResponse {
     Balance(Amount),
     ChainBalance(Amount),
     TickerSymbol(String),
+    TotalMinted(Amount),
+    /// Recipients credited and recipients skipped because of the maximum supply
+    BulkMintResult { minted_count: u64, skipped_count: u64 },
+    /// Nothing was minted: the amount would take the total minted past the maximum supply
+    MintRejected { current: Amount, cap: Amount },
+    CirculatingInfo(CirculatingInfo),
+    HistoricalBalance(Option<Amount>),
+    /// Owners snapshotted by this call and whether every owner has now been covered
+    BulkSnapshotResult { snapshotted_count: u64, done: bool },
+    Allowance(Amount),
 }
 
 pub struct NativeAbi;
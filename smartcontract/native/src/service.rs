@@ -3,14 +3,18 @@
 
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
+mod state;
+
 use std::sync::Arc;
 
 use async_graphql::{EmptySubscription, Object, Request, Response, Schema};
 use linera_sdk::{
-    linera_base_types::{AccountOwner, WithServiceAbi},
+    linera_base_types::{AccountOwner, Amount, ChainId, WithServiceAbi},
+    views::View,
     Service, ServiceRuntime,
 };
-use native::{AccountEntry, TICKER_SYMBOL, NativeAbi, NativeOperation, AccountInput};
+use native::{balance_breakdown, AccountEntry, BalanceBreakdown, CirculatingInfo, TICKER_SYMBOL, NativeAbi, NativeOperation, AccountInput, MintRecipientInput, TransferNotice};
+use self::state::NativeState;
 
 linera_sdk::service!(NativeService);
 
@@ -98,6 +102,53 @@ impl QueryRoot {
         let balance = self.runtime.chain_balance();
         Ok(balance.to_string())
     }
+
+    /// Chain account balance versus tokens held by owners
+    async fn balance_breakdown(&self) -> Result<BalanceBreakdown, async_graphql::Error> {
+        Ok(balance_breakdown(self.runtime.chain_balance(), self.runtime.owner_balances()))
+    }
+
+    /// Transfers with a memo received by `owner`, newest first
+    async fn incoming_transfers(&self, owner: AccountOwner, limit: usize) -> Result<Vec<TransferNotice>, async_graphql::Error> {
+        let state = NativeState::load(self.runtime.root_view_storage_context()).await?;
+        Ok(state.incoming_transfers(owner, limit).await?)
+    }
+
+    /// Sum of every Mint and BulkMint
+    async fn total_minted(&self) -> Result<Amount, async_graphql::Error> {
+        let state = NativeState::load(self.runtime.root_view_storage_context()).await?;
+        Ok(*state.total_minted.get())
+    }
+
+    /// Cap on the total minted amount (null = unlimited)
+    async fn max_supply(&self) -> Result<Option<Amount>, async_graphql::Error> {
+        let state = NativeState::load(self.runtime.root_view_storage_context()).await?;
+        Ok(*state.max_supply.get())
+    }
+
+    /// Minted, burned and circulating totals with the maximum supply
+    async fn circulating_info(&self) -> Result<CirculatingInfo, async_graphql::Error> {
+        let state = NativeState::load(self.runtime.root_view_storage_context()).await?;
+        Ok(state.circulating_info())
+    }
+
+    /// `owner`'s balance from the latest snapshot taken at or before `block`
+    async fn balance_at_block(&self, owner: AccountOwner, block: u64) -> Result<Option<Amount>, async_graphql::Error> {
+        let state = NativeState::load(self.runtime.root_view_storage_context()).await?;
+        Ok(state.balance_at(owner, block).await?)
+    }
+
+    /// What `spender` may still move for `owner` with TransferFrom
+    async fn allowance(&self, owner: AccountOwner, spender: AccountOwner) -> Result<Amount, async_graphql::Error> {
+        let state = NativeState::load(self.runtime.root_view_storage_context()).await?;
+        Ok(state.allowance(owner, spender).await?)
+    }
+
+    /// Blocks between automatic balance snapshots (0 = disabled)
+    async fn snapshot_interval(&self) -> Result<u64, async_graphql::Error> {
+        let state = NativeState::load(self.runtime.root_view_storage_context()).await?;
+        Ok(*state.snapshot_interval.get())
+    }
 }
 
 struct MutationRoot {
@@ -126,6 +177,7 @@ impl MutationRoot {
         owner: AccountOwner,
         amount: String,
         target_account: AccountInput,
+        memo: Option<String>,
     ) -> String {
         use linera_sdk::linera_base_types::Amount;
         let fungible_account = linera_sdk::abis::fungible::Account {
@@ -137,6 +189,7 @@ impl MutationRoot {
             owner,
             amount: amount.parse::<Amount>().unwrap_or_default(),
             target_account: fungible_account,
+            memo,
         });
         "Transfer operation scheduled".to_string()
     }
@@ -170,6 +223,15 @@ impl MutationRoot {
         "Withdraw operation scheduled successfully".to_string()
     }
 
+    async fn withdraw_to_chain(&self, target_chain_id: ChainId, amount: String) -> String {
+        use linera_sdk::linera_base_types::Amount;
+        self.runtime.schedule_operation(&NativeOperation::WithdrawToChain {
+            target_chain_id,
+            amount: amount.parse::<Amount>().unwrap_or_default(),
+        });
+        format!("WithdrawToChain operation scheduled for chain {}", target_chain_id)
+    }
+
     async fn mint(&self, owner: AccountOwner, amount: String) -> String {
         use linera_sdk::linera_base_types::Amount;
         self.runtime.schedule_operation(&NativeOperation::Mint {
@@ -178,4 +240,54 @@ impl MutationRoot {
         });
         "Mint operation scheduled successfully".to_string()
     }
+
+    async fn bulk_mint(&self, recipients: Vec<MintRecipientInput>) -> String {
+        let recipients = recipients
+            .into_iter()
+            .map(|recipient| (recipient.owner, recipient.amount.parse::<Amount>().unwrap_or_default()))
+            .collect();
+        self.runtime.schedule_operation(&NativeOperation::BulkMint { recipients });
+        "BulkMint operation scheduled successfully".to_string()
+    }
+
+    async fn set_max_supply(&self, cap: Option<String>) -> String {
+        let cap = cap.map(|amount| amount.parse::<Amount>().unwrap_or_default());
+        self.runtime.schedule_operation(&NativeOperation::SetMaxSupply { cap });
+        "SetMaxSupply operation scheduled successfully".to_string()
+    }
+
+    async fn snapshot_balance(&self, owner: AccountOwner) -> String {
+        self.runtime.schedule_operation(&NativeOperation::SnapshotBalance { owner });
+        "SnapshotBalance operation scheduled successfully".to_string()
+    }
+
+    async fn bulk_snapshot_all_balances(&self, limit: u64) -> String {
+        self.runtime.schedule_operation(&NativeOperation::BulkSnapshotAllBalances { limit });
+        "BulkSnapshotAllBalances operation scheduled successfully".to_string()
+    }
+
+    async fn set_snapshot_interval(&self, interval: u64) -> String {
+        self.runtime.schedule_operation(&NativeOperation::SetSnapshotInterval { interval });
+        "SetSnapshotInterval operation scheduled successfully".to_string()
+    }
+
+    async fn approve(&self, spender: AccountOwner, amount: String) -> String {
+        self.runtime.schedule_operation(&NativeOperation::Approve {
+            spender,
+            amount: amount.parse::<Amount>().unwrap_or_default(),
+        });
+        "Approve operation scheduled successfully".to_string()
+    }
+
+    async fn transfer_from(&self, from: AccountOwner, amount: String, to: AccountInput) -> String {
+        self.runtime.schedule_operation(&NativeOperation::TransferFrom {
+            from,
+            to: linera_sdk::abis::fungible::Account {
+                chain_id: to.chain_id,
+                owner: to.owner,
+            },
+            amount: amount.parse::<Amount>().unwrap_or_default(),
+        });
+        "TransferFrom operation scheduled successfully".to_string()
+    }
 }
\ No newline at end of file
@@ -1,7 +1,9 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use linera_sdk::views::{linera_views, RegisterView, RootView, ViewStorageContext};
+use linera_sdk::linera_base_types::{AccountOwner, Amount};
+use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, SetView, ViewError, ViewStorageContext};
+use native::{CirculatingInfo, TransferNotice};
 
 /// Pure token state - no game logic
 /// Note: We need at least one field for RootView, so we use a dummy field
@@ -10,4 +12,177 @@ use linera_sdk::views::{linera_views, RegisterView, RootView, ViewStorageContext
 pub struct NativeState {
     /// Dummy field (RootView requires at least one field)
     pub _dummy: RegisterView<()>,
+    /// Received transfers that carried a memo, keyed by (recipient, arrival sequence)
+    pub transfer_inbox: MapView<(AccountOwner, u64), TransferNotice>,
+    /// Sequence number of the next inbox entry
+    pub next_transfer_seq: RegisterView<u64>,
+    /// Sum of every Mint and BulkMint
+    pub total_minted: RegisterView<Amount>,
+    /// Cap on `total_minted` (None = unlimited)
+    pub max_supply: RegisterView<Option<Amount>>,
+    /// Sum of every Withdraw, which returns tokens to the chain account Mint draws from
+    pub total_burned: RegisterView<Amount>,
+    /// Recorded balances keyed by (owner, block height)
+    pub balance_snapshots: MapView<(AccountOwner, u64), Amount>,
+    /// Blocks between automatic snapshots of every balance (0 = disabled)
+    pub snapshot_interval: RegisterView<u64>,
+    /// Block height of the last automatic snapshot
+    pub last_auto_snapshot: RegisterView<Option<u64>>,
+    /// Position in the holder list where the next BulkSnapshotAllBalances resumes
+    pub bulk_snapshot_cursor: RegisterView<u64>,
+    /// Tokens each (owner, spender) pair may still move with TransferFrom
+    pub allowances: MapView<(AccountOwner, AccountOwner), Amount>,
+    /// Every owner this application has credited or debited on this chain, which are
+    /// the balances the bulk and automatic snapshots cover
+    pub holders: SetView<AccountOwner>,
+}
+
+#[allow(dead_code)]
+impl NativeState {
+    /// Count `amount` as minted, unless that would exceed the maximum supply
+    pub fn record_mint(&mut self, amount: Amount) -> Result<(), String> {
+        let total = self.total_minted.get().try_add(amount)
+            .map_err(|_| "Total minted overflows".to_string())?;
+        if let Some(max_supply) = *self.max_supply.get() {
+            if total > max_supply {
+                return Err(format!("Minting {} would exceed the maximum supply of {}", amount, max_supply));
+            }
+        }
+        self.total_minted.set(total);
+        Ok(())
+    }
+
+    /// How much can still be minted before reaching the maximum supply (None = unlimited)
+    pub fn remaining_supply(&self) -> Option<Amount> {
+        self.max_supply.get().map(|max_supply| max_supply.saturating_sub(*self.total_minted.get()))
+    }
+
+    /// Count `amount` as burned
+    pub fn record_burn(&mut self, amount: Amount) {
+        let total = self.total_burned.get().saturating_add(amount);
+        self.total_burned.set(total);
+    }
+
+    /// Minted, burned and circulating totals
+    pub fn circulating_info(&self) -> CirculatingInfo {
+        let total_minted = *self.total_minted.get();
+        let total_burned = *self.total_burned.get();
+        CirculatingInfo {
+            total_minted,
+            total_burned,
+            circulating: total_minted.saturating_sub(total_burned),
+            max_supply: *self.max_supply.get(),
+        }
+    }
+
+    /// What `spender` may still move for `owner`
+    pub async fn allowance(&self, owner: AccountOwner, spender: AccountOwner) -> Result<Amount, String> {
+        Ok(self.allowances.get(&(owner, spender)).await
+            .map_err(|e: ViewError| format!("Failed to get allowance: {:?}", e))?
+            .unwrap_or(Amount::ZERO))
+    }
+
+    /// Replace `spender`'s allowance for `owner` (zero removes it)
+    pub fn set_allowance(&mut self, owner: AccountOwner, spender: AccountOwner, amount: Amount) -> Result<(), String> {
+        if amount.is_zero() {
+            self.allowances.remove(&(owner, spender))
+        } else {
+            self.allowances.insert(&(owner, spender), amount)
+        }
+        .map_err(|e: ViewError| format!("Failed to set allowance: {:?}", e))
+    }
+
+    /// Take `amount` out of `spender`'s allowance for `owner`
+    pub async fn spend_allowance(&mut self, owner: AccountOwner, spender: AccountOwner, amount: Amount) -> Result<(), String> {
+        let allowance = self.allowance(owner, spender).await?;
+        let remaining = allowance.try_sub(amount)
+            .map_err(|_| format!("TransferFrom of {} exceeds the allowance of {}", amount, allowance))?;
+        self.set_allowance(owner, spender, remaining)
+    }
+
+    /// Record `balance` as `owner`'s balance at block `height`
+    pub fn snapshot_balance(&mut self, owner: AccountOwner, height: u64, balance: Amount) -> Result<(), String> {
+        self.balance_snapshots.insert(&(owner, height), balance)
+            .map_err(|e: ViewError| format!("Failed to record balance snapshot: {:?}", e))
+    }
+
+    /// An owner's balance from the latest snapshot taken at or before block `height`
+    pub async fn balance_at(&self, owner: AccountOwner, height: u64) -> Result<Option<Amount>, String> {
+        let latest = self.balance_snapshots.indices().await
+            .map_err(|e: ViewError| format!("Failed to get balance snapshots: {:?}", e))?
+            .into_iter()
+            .filter(|(snapshot_owner, snapshot_height)| *snapshot_owner == owner && *snapshot_height <= height)
+            .map(|(_, snapshot_height)| snapshot_height)
+            .max();
+        match latest {
+            Some(snapshot_height) => self.balance_snapshots.get(&(owner, snapshot_height)).await
+                .map_err(|e: ViewError| format!("Failed to get balance snapshot: {:?}", e)),
+            None => Ok(None),
+        }
+    }
+
+    /// Remember `owner` as a balance holder (the chain account is not one)
+    pub fn record_holder(&mut self, owner: AccountOwner) -> Result<(), String> {
+        if owner == AccountOwner::CHAIN {
+            return Ok(());
+        }
+        self.holders.insert(&owner)
+            .map_err(|e: ViewError| format!("Failed to record balance holder: {:?}", e))
+    }
+
+    /// Every recorded balance holder, in key order
+    pub async fn holders(&self) -> Result<Vec<AccountOwner>, String> {
+        self.holders.indices().await
+            .map_err(|e: ViewError| format!("Failed to get balance holders: {:?}", e))
+    }
+
+    /// Snapshot up to `limit` of `balances` (in holder order) from the stored cursor at
+    /// block `height`. Returns the number snapshotted and whether the list was finished;
+    /// the cursor restarts from the first owner once it is.
+    pub fn bulk_snapshot(&mut self, balances: &[(AccountOwner, Amount)], height: u64, limit: u64) -> Result<(u64, bool), String> {
+        let start = (*self.bulk_snapshot_cursor.get() as usize).min(balances.len());
+        let end = start.saturating_add(limit as usize).min(balances.len());
+        for (owner, balance) in &balances[start..end] {
+            self.snapshot_balance(*owner, height, *balance)?;
+        }
+        let done = end == balances.len();
+        self.bulk_snapshot_cursor.set(if done { 0 } else { end as u64 });
+        Ok(((end - start) as u64, done))
+    }
+
+    /// Whether every balance should be snapshotted automatically at block `height`
+    pub fn auto_snapshot_due(&self, height: u64) -> bool {
+        let interval = *self.snapshot_interval.get();
+        interval != 0 && height % interval == 0 && *self.last_auto_snapshot.get() != Some(height)
+    }
+
+    /// Store a received transfer notice in the recipient's inbox
+    pub fn record_transfer(&mut self, notice: TransferNotice) -> Result<(), String> {
+        let seq = *self.next_transfer_seq.get();
+        self.transfer_inbox.insert(&(notice.recipient, seq), notice)
+            .map_err(|e: ViewError| format!("Failed to record transfer: {:?}", e))?;
+        self.next_transfer_seq.set(seq + 1);
+        Ok(())
+    }
+
+    /// Up to `limit` of an owner's received transfers, newest first
+    pub async fn incoming_transfers(&self, owner: AccountOwner, limit: usize) -> Result<Vec<TransferNotice>, String> {
+        let keys = self.transfer_inbox.indices().await
+            .map_err(|e: ViewError| format!("Failed to get transfer inbox: {:?}", e))?;
+
+        let mut seqs: Vec<u64> = keys.into_iter()
+            .filter(|(recipient, _)| *recipient == owner)
+            .map(|(_, seq)| seq)
+            .collect();
+        seqs.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut notices = Vec::new();
+        for seq in seqs.into_iter().take(limit) {
+            if let Some(notice) = self.transfer_inbox.get(&(owner, seq)).await
+                .map_err(|e: ViewError| format!("Failed to get transfer: {:?}", e))? {
+                notices.push(notice);
+            }
+        }
+        Ok(notices)
+    }
 }
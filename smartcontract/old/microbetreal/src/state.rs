@@ -112,6 +112,14 @@ pub enum Prediction {
 impl NativeFungibleTokenState {
     /// Creates a new prediction round
     pub async fn create_round(&mut self, timestamp: u64) -> Result<u64, String> {
+        // Bets already in active_bets were paid for; a new round must not drop them
+        let stranded = self.active_bets.indices().await
+            .map_err(|e: ViewError| format!("Failed to get active bet indices: {:?}", e))?
+            .len();
+        if stranded > 0 {
+            return Err(format!("{} active bets would be stranded; close the active round first", stranded));
+        }
+        
         let round_id = *self.round_counter.get() + 1;
         self.round_counter.set(round_id);
         
@@ -135,17 +143,6 @@ impl NativeFungibleTokenState {
             .map_err(|e: ViewError| format!("Failed to insert round: {:?}", e))?;
         self.active_round.set(Some(round_id));
         
-        // Clear active bets for the new round by removing all entries
-        let keys: Vec<AccountOwner> = self.active_bets.indices().await
-            .map_err(|e: ViewError| format!("Failed to get active bet indices: {:?}", e))?
-            .into_iter()
-            .collect();
-        
-        for key in keys {
-            self.active_bets.remove(&key)
-                .map_err(|e: ViewError| format!("Failed to remove active bet: {:?}", e))?;
-        }
-        
         Ok(round_id)
     }
     
@@ -518,6 +515,9 @@ impl NativeFungibleTokenState {
                 }
             }
         }
+        // Stable order for clients that diff repeated queries; the stable sort keeps
+        // each owner's bets in the order they were placed
+        bets.sort_by_key(|(owner, _)| *owner);
         Ok(bets)
     }
     
@@ -602,6 +602,7 @@ impl NativeFungibleTokenState {
                 }
             }
         }
+        winners.sort_by_key(|(owner, ..)| *owner);
         
         Ok(winners)
     }
@@ -6,62 +6,107 @@
 mod state;
 
 use linera_sdk::{
-    linera_base_types::{Amount, ApplicationId, WithContractAbi},
+    linera_base_types::{Account, AccountOwner, Amount, ApplicationId, WithContractAbi},
     views::{RootView, View},
     Contract, ContractRuntime,
 };
-use rounds::{RoundsAbi, RoundsOperation, RoundsResponse, Prediction, Message};
-use self::state::{RoundsState, PredictionRound, RoundStatus, Prediction as StatePrediction};
+use rounds::{game_results_stream, GameKind, GameResult, RoundsAbi, RoundsOperation, RoundsResponse, Message};
+use self::state::{RoundsState, PredictionRound, RoundStatus};
 
-// Conversion functions between lib types and state types
-fn prediction_from_lib(lib_prediction: Prediction) -> StatePrediction {
-    match lib_prediction {
-        Prediction::Up => StatePrediction::Up,
-        Prediction::Down => StatePrediction::Down,
+/// Metrics counter name for an operation
+fn operation_metric(operation: &RoundsOperation) -> &'static str {
+    match operation {
+        RoundsOperation::CreateRound => "operation.CreateRound",
+        RoundsOperation::CloseRound { .. } => "operation.CloseRound",
+        RoundsOperation::ResolveRound { .. } => "operation.ResolveRound",
+        RoundsOperation::CloseAndResolve { .. } => "operation.CloseAndResolve",
+        RoundsOperation::ResolveWithCustomWinner { .. } => "operation.ResolveWithCustomWinner",
+        RoundsOperation::ResumeResolution { .. } => "operation.ResumeResolution",
+        RoundsOperation::ReResolve { .. } => "operation.ReResolve",
+        RoundsOperation::PlaceBet { .. } => "operation.PlaceBet",
+        RoundsOperation::PlaceBetWithSlippage { .. } => "operation.PlaceBetWithSlippage",
+        RoundsOperation::PlaceBetDirect { .. } => "operation.PlaceBetDirect",
+        RoundsOperation::PlaceSplitBet { .. } => "operation.PlaceSplitBet",
+        RoundsOperation::ClaimWinnings { .. } => "operation.ClaimWinnings",
+        RoundsOperation::PayQueuedClaims { .. } => "operation.PayQueuedClaims",
+        RoundsOperation::SetRecurringBet { .. } => "operation.SetRecurringBet",
+        RoundsOperation::CancelRecurringBet => "operation.CancelRecurringBet",
+        RoundsOperation::SetMySpendLimit { .. } => "operation.SetMySpendLimit",
+        RoundsOperation::SubscribeResults { .. } => "operation.SubscribeResults",
+        RoundsOperation::UnsubscribeResults { .. } => "operation.UnsubscribeResults",
+        RoundsOperation::SetWinzaAppId { .. } => "operation.SetWinzaAppId",
+        RoundsOperation::SetLeaderboardChainId { .. } => "operation.SetLeaderboardChainId",
+        RoundsOperation::SetLeaderboardMinAmount { .. } => "operation.SetLeaderboardMinAmount",
+        RoundsOperation::SetOutcomeConfig { .. } => "operation.SetOutcomeConfig",
+        RoundsOperation::SetOracle { .. } => "operation.SetOracle",
+        RoundsOperation::SetTreasury { .. } => "operation.SetTreasury",
+        RoundsOperation::SetBurnFeeBps { .. } => "operation.SetBurnFeeBps",
+        RoundsOperation::SetMaxHistoryRounds { .. } => "operation.SetMaxHistoryRounds",
+        RoundsOperation::SetDisputeWindow { .. } => "operation.SetDisputeWindow",
+        RoundsOperation::SetMaxBetsPerUser { .. } => "operation.SetMaxBetsPerUser",
+        RoundsOperation::ImportConfig { .. } => "operation.ImportConfig",
+        RoundsOperation::GetConfig => "operation.GetConfig",
+        RoundsOperation::GetActiveRound => "operation.GetActiveRound",
+        RoundsOperation::GetActiveRoundId => "operation.GetActiveRoundId",
+        RoundsOperation::GetUniqueBettors => "operation.GetUniqueBettors",
+        RoundsOperation::GetRound { .. } => "operation.GetRound",
+        RoundsOperation::GetAllRounds => "operation.GetAllRounds",
+        RoundsOperation::GetRoundsByStatus { .. } => "operation.GetRoundsByStatus",
+        RoundsOperation::GetRoundsAwaitingResolution { .. } => "operation.GetRoundsAwaitingResolution",
+        RoundsOperation::GetActiveBets => "operation.GetActiveBets",
+        RoundsOperation::GetRoundWinners { .. } => "operation.GetRoundWinners",
+        RoundsOperation::GetRoundNetFlow { .. } => "operation.GetRoundNetFlow",
+        RoundsOperation::GetActiveRoundForPrice => "operation.GetActiveRoundForPrice",
+        RoundsOperation::GetActiveRoundOdds => "operation.GetActiveRoundOdds",
+        RoundsOperation::GetActiveRoundPools => "operation.GetActiveRoundPools",
+        RoundsOperation::GetResolutionNote { .. } => "operation.GetResolutionNote",
+        RoundsOperation::GetResolutionHistory { .. } => "operation.GetResolutionHistory",
+        RoundsOperation::ExportRoundBetCSV { .. } => "operation.ExportRoundBetCSV",
+        RoundsOperation::ExportRound { .. } => "operation.ExportRound",
+        RoundsOperation::GetBetSizeHistogram { .. } => "operation.GetBetSizeHistogram",
+        RoundsOperation::GetNetworkParticipation => "operation.GetNetworkParticipation",
+        RoundsOperation::ResetNetworkStats => "operation.ResetNetworkStats",
+        RoundsOperation::ForceClearActiveBets => "operation.ForceClearActiveBets",
+        RoundsOperation::ResetMetrics => "operation.ResetMetrics",
+        RoundsOperation::TransferAdminRole { .. } => "operation.TransferAdminRole",
+        RoundsOperation::GetAdminLog { .. } => "operation.GetAdminLog",
     }
 }
 
-fn prediction_to_lib(prediction: StatePrediction) -> Prediction {
-    match prediction {
-        StatePrediction::Up => Prediction::Up,
-        StatePrediction::Down => Prediction::Down,
-    }
-}
-
-fn round_status_to_lib(status: RoundStatus) -> rounds::RoundStatus {
-    match status {
-        RoundStatus::Active => rounds::RoundStatus::Active,
-        RoundStatus::Closed => rounds::RoundStatus::Closed,
-        RoundStatus::Resolved => rounds::RoundStatus::Resolved,
-    }
+/// Whether an operation changes app configuration and needs the admin as signer
+fn requires_admin(operation: &RoundsOperation) -> bool {
+    matches!(
+        operation,
+        RoundsOperation::SetWinzaAppId { .. }
+            | RoundsOperation::SetLeaderboardChainId { .. }
+            | RoundsOperation::SetLeaderboardMinAmount { .. }
+            | RoundsOperation::SetOutcomeConfig { .. }
+            | RoundsOperation::SetOracle { .. }
+            | RoundsOperation::SetTreasury { .. }
+            | RoundsOperation::SetBurnFeeBps { .. }
+            | RoundsOperation::SetMaxHistoryRounds { .. }
+            | RoundsOperation::SetDisputeWindow { .. }
+            | RoundsOperation::SetMaxBetsPerUser { .. }
+            | RoundsOperation::ImportConfig { .. }
+            | RoundsOperation::ResetNetworkStats
+            | RoundsOperation::ForceClearActiveBets
+            | RoundsOperation::ResetMetrics
+            | RoundsOperation::TransferAdminRole { .. }
+            | RoundsOperation::ResolveWithCustomWinner { .. }
+            | RoundsOperation::ResumeResolution { .. }
+            | RoundsOperation::CreateRound
+    )
 }
 
-fn prediction_round_to_lib(round: PredictionRound) -> rounds::PredictionRound {
-    rounds::PredictionRound {
-        id: round.id,
-        created_at: round.created_at,
-        closed_at: round.closed_at,
-        resolved_at: round.resolved_at,
-        status: round_status_to_lib(round.status),
-        closing_price: round.closing_price,
-        resolution_price: round.resolution_price,
-        up_bets: round.up_bets,
-        down_bets: round.down_bets,
-        up_bets_pool: round.up_bets_pool,
-        down_bets_pool: round.down_bets_pool,
-        prize_pool: round.prize_pool,
-        result: round.result.map(prediction_to_lib),
+/// Metrics counter name for a received message
+fn message_metric(message: &Message) -> &'static str {
+    match message {
+        Message::Notify => "message.Notify",
+        Message::LeaderboardUpdate { .. } => "message.LeaderboardUpdate",
+        Message::RoundResolvedNotice(_) => "message.RoundResolvedNotice",
     }
 }
 
-fn prediction_round_option_to_lib(round: Option<PredictionRound>) -> Option<rounds::PredictionRound> {
-    round.map(prediction_round_to_lib)
-}
-
-fn prediction_rounds_to_lib(rounds_vec: Vec<PredictionRound>) -> Vec<rounds::PredictionRound> {
-    rounds_vec.into_iter().map(prediction_round_to_lib).collect()
-}
-
 pub struct RoundsContract {
     state: RoundsState,
     runtime: ContractRuntime<Self>,
@@ -77,7 +122,7 @@ impl Contract for RoundsContract {
     type Message = Message;
     type Parameters = rounds::RoundsParameters; // No parameters needed
     type InstantiationArgument = (); // Native App ID
-    type EventValue = ();
+    type EventValue = GameResult;
 
     async fn load(runtime: ContractRuntime<Self>) -> Self {
         let state = RoundsState::load(runtime.root_view_storage_context())
@@ -87,13 +132,26 @@ impl Contract for RoundsContract {
     }
 
     async fn instantiate(&mut self, _arg: Self::InstantiationArgument) {
-        // Validate params access
-        let _ = self.runtime.application_parameters();
+        let params = self.runtime.application_parameters();
+        self.state.admin.set(Some(params.admin));
         // Initialize Winzareal ID as None (will be set via operation)
         self.state.Winza_app_id.set(None);
     }
 
     async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response {
+        self.state.increment_metric(operation_metric(&operation)).await
+            .expect("Failed to update metrics");
+        
+        if requires_admin(&operation) {
+            if let Err(e) = self.state.check_admin(self.runtime.authenticated_signer()) {
+                panic!("Failed to authorize {}: {}", operation_metric(&operation), e);
+            }
+            // A failing operation reverts the whole block, so only executed ones stay logged
+            let action = operation_metric(&operation).trim_start_matches("operation.");
+            let actor = self.runtime.authenticated_signer().unwrap_or(AccountOwner::CHAIN);
+            self.state.record_admin_action(self.runtime.system_time().micros(), action, actor);
+        }
+        
         match operation {
             RoundsOperation::SetWinzaAppId { Winza_app_id } => {
                 match Winza_app_id.parse::<ApplicationId>() {
@@ -111,144 +169,347 @@ impl Contract for RoundsContract {
                 self.state.leaderboard_chain_id.set(chain_id);
                 RoundsResponse::Ok
             }
+            
+            RoundsOperation::SetLeaderboardMinAmount { amount } => {
+                self.state.leaderboard_min_amount.set(amount);
+                RoundsResponse::Ok
+            }
+            
+            RoundsOperation::SetOutcomeConfig { num_outcomes, flat_band } => {
+                match self.state.set_outcome_config(num_outcomes, flat_band) {
+                    Ok(()) => RoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set outcome config: {}", e),
+                }
+            }
+
+
+            RoundsOperation::SetBurnFeeBps { bps } => {
+                match self.state.set_burn_fee_bps(bps) {
+                    Ok(()) => RoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set burn fee: {}", e),
+                }
+            }
+
+            RoundsOperation::SetMaxHistoryRounds { max_rounds } => {
+                if let Err(e) = self.state.set_max_history_rounds(max_rounds) {
+                    panic!("Failed to set max history rounds: {}", e);
+                }
+                let now = self.runtime.system_time().micros();
+                match self.state.prune_resolved_rounds(now).await {
+                    Ok(_) => RoundsResponse::Ok,
+                    Err(e) => panic!("Failed to prune resolved rounds: {}", e),
+                }
+            }
+
+            RoundsOperation::SetMaxBetsPerUser { max_bets } => {
+                match self.state.set_max_bets_per_user(max_bets) {
+                    Ok(()) => RoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set max bets per user: {}", e),
+                }
+            }
+
+            RoundsOperation::SetDisputeWindow { window_micros } => {
+                match self.state.set_dispute_window(window_micros) {
+                    Ok(()) => RoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set dispute window: {}", e),
+                }
+            }
+
+            RoundsOperation::ImportConfig { config } => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("ImportConfig can only be executed on the application creator chain");
+                }
+                match self.state.import_config(config) {
+                    Ok(()) => RoundsResponse::Ok,
+                    Err(e) => panic!("Failed to import config: {}", e),
+                }
+            }
+            
+            RoundsOperation::GetConfig => RoundsResponse::Config(self.state.get_config()),
+
+            RoundsOperation::SetOracle { oracle } => {
+                self.state.oracle.set(oracle);
+                RoundsResponse::Ok
+            }
 
+            RoundsOperation::SetTreasury { treasury } => {
+                self.state.treasury.set(Some(treasury));
+                RoundsResponse::Ok
+            }
 
             RoundsOperation::CreateRound => {
                 let timestamp = self.runtime.system_time().micros();
-                match self.state.create_round(timestamp).await {
-                    Ok(round_id) => RoundsResponse::RoundId(round_id),
+                let round_id = match self.state.create_round(timestamp).await {
+                    Ok(round_id) => round_id,
                     Err(e) => panic!("Failed to create round: {}", e),
-                }
+                };
+                self.place_recurring_bets(timestamp).await;
+                RoundsResponse::RoundId(round_id)
             }
             
             RoundsOperation::CloseRound { closing_price } => {
+                if let Err(e) = self.state.check_oracle(self.runtime.authenticated_signer()) {
+                    panic!("Failed to close round: {}", e);
+                }
                 let timestamp = self.runtime.system_time().micros();
-                match self.state.close_round(closing_price, timestamp).await {
-                    Ok(new_round_id) => RoundsResponse::RoundId(new_round_id),
+                let new_round_id = match self.state.close_round(closing_price, timestamp).await {
+                    Ok(new_round_id) => new_round_id,
                     Err(e) => panic!("Failed to close round: {}", e),
-                }
+                };
+                self.place_recurring_bets(timestamp).await;
+                RoundsResponse::RoundId(new_round_id)
             }
             
-            RoundsOperation::ResolveRound { resolution_price } => {
+            RoundsOperation::ResolveRound { round_id, resolution_price } => {
+                if let Err(e) = self.state.check_oracle(self.runtime.authenticated_signer()) {
+                    panic!("Failed to resolve round: {}", e);
+                }
                 let timestamp = self.runtime.system_time().micros();
                 
-                // Get all rounds and find the last closed one
-                match self.state.get_all_rounds().await {
-                    Ok(rounds) => {
-                        // Find the last closed round that is not yet resolved
-                        let closed_round = rounds.into_iter().filter(|round| {
+                let round_id = match round_id {
+                    Some(round_id) => round_id,
+                    // Without an ID, fall back to the last closed round that is not yet resolved
+                    None => match self.state.get_all_rounds().await {
+                        Ok(rounds) => match rounds.into_iter().filter(|round| {
                             round.status == RoundStatus::Closed && round.resolved_at.is_none()
-                        }).max_by_key(|round| round.id);
-                        
-                        match closed_round {
-                            Some(round) => {
-                                // Resolve the round and get winners
-                                match self.state.resolve_round_and_distribute_rewards(round.id, resolution_price, timestamp).await {
-                                    Ok(results) => {
-                                        // Get app IDs
-                                        let params = self.runtime.application_parameters();
-                                        let leaderboard_app_id = params.leaderboard_app_id.with_abi::<leaderboard::LeaderboardAbi>();
-                                        
-                                        let Winzareal_app_id = self.state.Winza_app_id.get()
-                                            .expect("Winzareal app ID not set");
-                                        
-                                        for (owner, bet_amount, winnings, is_win, source_chain_id) in results {
-                                            // Distribute rewards if any
-                                            if winnings > Amount::ZERO {
-                                                let _response: native_fungible_abi::ExtendedResponse = self.runtime.call_application(
-                                                    true, // authenticated
-                                                    Winzareal_app_id,
-                                                    &native_fungible_abi::ExtendedOperation::SendReward {
-                                                        recipient: owner,
-                                                        amount: winnings,
-                                                        source_chain_id: source_chain_id.clone(),
-                                                    },
-                                                );
-                                            }
-
-                                            // Update leaderboard stats (for everyone)
-                                            let player_chain_id_str = source_chain_id.clone().unwrap_or_else(|| self.runtime.chain_id().to_string());
-                                            
-                                            // Check if leaderboard is on a different chain
-                                            let leaderboard_target_chain = self.state.leaderboard_chain_id.get().clone();
-                                            
-                                            // Calculate clean amount (Net Profit or Net Loss)
-                                            let clean_amount = if is_win {
-                                                winnings.saturating_sub(bet_amount)
-                                            } else {
-                                                bet_amount.saturating_sub(winnings)
-                                            };
-
-                                            if let Some(target_chain_str) = leaderboard_target_chain {
-                                                // Cross-chain: send message to target chain
-                                                let target_chain_id = target_chain_str.parse::<linera_sdk::linera_base_types::ChainId>()
-                                                    .expect("Invalid leaderboard_chain_id format");
-                                                
-                                                if target_chain_id != self.runtime.chain_id() {
-                                                    // Send cross-chain message
-                                                    self.runtime
-                                                        .prepare_message(Message::LeaderboardUpdate {
-                                                            owner,
-                                                            chain_id: player_chain_id_str.clone(),
-                                                            is_win,
-                                                            amount: clean_amount,
-                                                        })
-                                                        .with_authentication()
-                                                        .send_to(target_chain_id);
-                                                    eprintln!("Sent LeaderboardUpdate cross-chain to {:?}", target_chain_id);
-                                                } else {
-                                                    // Same chain, call directly  
-                                                    let _response: () = self.runtime.call_application(
-                                                        true,
-                                                        leaderboard_app_id,
-                                                        &leaderboard::Operation::UpdateScore {
-                                                            owner,
-                                                            chain_id: player_chain_id_str,
-                                                            is_win,
-                                                            amount: clean_amount,
-                                                        }
-                                                    );
-                                                }
-                                            } else {
-                                                // No target chain set, call leaderboard on same chain
-                                                let _response: () = self.runtime.call_application(
-                                                    true,
-                                                    leaderboard_app_id,
-                                                    &leaderboard::Operation::UpdateScore {
-                                                        owner,
-                                                        chain_id: player_chain_id_str,
-                                                        is_win,
-                                                        amount: clean_amount,
-                                                    }
-                                                );
-                                            }
-                                        }
-                                        
-                                        RoundsResponse::Ok
-                                    },
-                                    Err(e) => panic!("Failed to resolve round: {}", e),
-                                }
-                            },
+                        }).max_by_key(|round| round.id) {
+                            Some(round) => round.id,
                             None => panic!("No closed round to resolve"),
-                        }
+                        },
+                        Err(e) => panic!("Failed to get all rounds: {}", e),
                     },
-                    Err(e) => panic!("Failed to get all rounds: {}", e),
+                };
+                match self.settle_round(round_id, resolution_price, timestamp).await {
+                    Ok(leaderboard_updates) => self.round_resolved(round_id, leaderboard_updates).await,
+                    Err(pending) => pending,
+                }
+            }
+
+            RoundsOperation::CloseAndResolve { closing_price, resolution_price } => {
+                if let Err(e) = self.state.check_oracle(self.runtime.authenticated_signer()) {
+                    panic!("Failed to close and resolve round: {}", e);
+                }
+                let timestamp = self.runtime.system_time().micros();
+
+                // Resolve by ID so the successor round opened by the close is left untouched
+                let round_id = match self.state.get_active_round().await {
+                    Ok(Some(round_id)) => round_id,
+                    Ok(None) => panic!("No active round to close"),
+                    Err(e) => panic!("Failed to get active round: {}", e),
+                };
+                let new_round_id = match self.state.close_round(closing_price, timestamp).await {
+                    Ok(new_round_id) => new_round_id,
+                    Err(e) => panic!("Failed to close round: {}", e),
+                };
+                self.place_recurring_bets(timestamp).await;
+                match self.settle_round(round_id, resolution_price, timestamp).await {
+                    Ok(_) => RoundsResponse::RoundId(new_round_id),
+                    Err(pending) => pending,
+                }
+            }
+
+            RoundsOperation::ResolveWithCustomWinner { round_id, result, justification } => {
+                let timestamp = self.runtime.system_time().micros();
+                let results = match self.state.resolve_with_custom_winner(round_id, result, justification, timestamp).await {
+                    Ok(results) => results,
+                    Err(e) => panic!("Failed to resolve round {} manually: {}", round_id, e),
+                };
+                let leaderboard_updates = self.pay_out(round_id, results).await;
+                self.round_resolved(round_id, leaderboard_updates).await
+            }
+
+            RoundsOperation::ResumeResolution { round_id } => {
+                let timestamp = self.runtime.system_time().micros();
+                let payout = match self.state.pending_payout(round_id).await {
+                    Ok(payout) => payout,
+                    Err(e) => panic!("Failed to resume resolution of round {}: {}", round_id, e),
+                };
+                let burned = self.round_burn(round_id).await;
+                let shortfall = match self.payout_shortfall(payout, burned) {
+                    Ok(shortfall) => shortfall,
+                    Err(e) => panic!("Failed to resume resolution of round {}: {}", round_id, e),
+                };
+                if let Some(shortfall) = shortfall {
+                    panic!("Treasury is still {} short of round {}'s payout of {}", shortfall, round_id, payout);
+                }
+                let results = match self.state.resume_resolution(round_id, timestamp).await {
+                    Ok(results) => results,
+                    Err(e) => panic!("Failed to resume resolution of round {}: {}", round_id, e),
+                };
+                let leaderboard_updates = self.pay_out(round_id, results).await;
+                self.round_resolved(round_id, leaderboard_updates).await
+            }
+
+            RoundsOperation::ReResolve { round_id, corrected_price } => {
+                let signer = self.runtime.authenticated_signer();
+                if let (Err(e), Err(_)) = (self.state.check_oracle(signer), self.state.check_admin(signer)) {
+                    panic!("Failed to re-resolve round {}: {}", round_id, e);
+                }
+                let timestamp = self.runtime.system_time().micros();
+                if let Err(e) = self.state.re_resolve(round_id, corrected_price, timestamp).await {
+                    panic!("Failed to re-resolve round {}: {}", round_id, e);
+                }
+                // Rewards are still unclaimed and the leaderboard keeps the original report
+                self.notify_result_subscribers(round_id).await;
+                self.round_resolved(round_id, (0, 0)).await
+            }
+
+            RoundsOperation::GetResolutionHistory { round_id } => {
+                match self.state.get_resolution_history(round_id).await {
+                    Ok(history) => RoundsResponse::ResolutionHistory(history),
+                    Err(e) => panic!("Failed to get resolution history: {}", e),
+                }
+            }
+
+            RoundsOperation::GetResolutionNote { round_id } => {
+                match self.state.get_resolution_note(round_id).await {
+                    Ok(note) => RoundsResponse::ResolutionNote(note),
+                    Err(e) => panic!("Failed to get resolution note: {}", e),
                 }
             }
 
             RoundsOperation::PlaceBet { owner, amount, prediction, source_chain_id } => {
-                let state_prediction = prediction_from_lib(prediction);
-                match self.state.place_bet(owner, amount, state_prediction, source_chain_id).await {
-                    Ok(()) => RoundsResponse::Ok,
+                let timestamp = self.runtime.system_time().micros();
+                // The stake already reached the treasury; its burn fee leaves it on settlement
+                match self.state.place_bet(owner, amount, prediction, source_chain_id, timestamp).await {
+                    Ok(_) => RoundsResponse::Ok,
                     Err(e) => panic!("Failed to place bet: {}", e),
                 }
             }
             
+            RoundsOperation::PlaceBetWithSlippage { amount, prediction, max_slippage_bps } => {
+                let owner = self.runtime.authenticated_signer()
+                    .expect("PlaceBetWithSlippage requires an authenticated signer");
+                // A rejected bet is turned away before anything is taken from the signer
+                match self.state.check_slippage(amount, prediction, max_slippage_bps).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(rejection)) => return RoundsResponse::BetRejected(rejection),
+                    Err(e) => panic!("Failed to place bet: {}", e),
+                }
+                self.pull_stake_to_treasury(owner, amount);
+                let timestamp = self.runtime.system_time().micros();
+                match self.state.place_bet(owner, amount, prediction, None, timestamp).await {
+                    Ok(_) => RoundsResponse::Ok,
+                    Err(e) => panic!("Failed to place bet: {}", e),
+                }
+            }
+            
+            RoundsOperation::PlaceBetDirect { amount, prediction } => {
+                let owner = self.runtime.authenticated_signer()
+                    .expect("PlaceBetDirect requires an authenticated signer");
+                // The stake must be in hand before the bet is recorded
+                self.pull_stake_to_treasury(owner, amount);
+                let timestamp = self.runtime.system_time().micros();
+                match self.state.place_bet(owner, amount, prediction, None, timestamp).await {
+                    Ok(_) => RoundsResponse::Ok,
+                    Err(e) => panic!("Failed to place direct bet: {}", e),
+                }
+            }
+            
+            RoundsOperation::PlaceSplitBet { amount_up, amount_down } => {
+                let owner = self.runtime.authenticated_signer()
+                    .expect("PlaceSplitBet requires an authenticated signer");
+                // The stake must be in hand before the bet is recorded
+                self.pull_stake_to_treasury(owner, amount_up.saturating_add(amount_down));
+                let timestamp = self.runtime.system_time().micros();
+                match self.state.place_split_bet(owner, amount_up, amount_down, None, timestamp).await {
+                    Ok((_, bet_id)) => RoundsResponse::PlaceSplitBetResponse { bet_id },
+                    Err(e) => panic!("Failed to place split bet: {}", e),
+                }
+            }
+            
+            RoundsOperation::SetRecurringBet { amount, prediction, max_rounds } => {
+                let owner = self.runtime.authenticated_signer()
+                    .expect("SetRecurringBet requires an authenticated signer");
+                match self.state.set_recurring_bet(owner, amount, prediction, max_rounds) {
+                    Ok(()) => RoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set recurring bet: {}", e),
+                }
+            }
+            
+            RoundsOperation::CancelRecurringBet => {
+                let owner = self.runtime.authenticated_signer()
+                    .expect("CancelRecurringBet requires an authenticated signer");
+                match self.state.cancel_recurring_bet(owner) {
+                    Ok(()) => RoundsResponse::Ok,
+                    Err(e) => panic!("Failed to cancel recurring bet: {}", e),
+                }
+            }
+            
+            RoundsOperation::SetMySpendLimit { per_round, cooldown_micros } => {
+                // Only the owner can set their own limit
+                let owner = self.runtime.authenticated_signer()
+                    .expect("SetMySpendLimit requires an authenticated signer");
+                let now = self.runtime.system_time().micros();
+                match self.state.set_spend_limit(owner, per_round, cooldown_micros, now).await {
+                    Ok(()) => RoundsResponse::Ok,
+                    Err(e) => panic!("Failed to set spend limit: {}", e),
+                }
+            }
+            
+            RoundsOperation::SubscribeResults { chain_id } => {
+                let owner = self.runtime.authenticated_signer()
+                    .expect("SubscribeResults requires an authenticated signer");
+                match self.state.subscribe_results(chain_id, owner).await {
+                    Ok(()) => RoundsResponse::Ok,
+                    Err(e) => panic!("Failed to subscribe to results: {}", e),
+                }
+            }
+            
+            RoundsOperation::UnsubscribeResults { chain_id } => {
+                match self.state.unsubscribe_results(chain_id, self.runtime.authenticated_signer()).await {
+                    Ok(()) => RoundsResponse::Ok,
+                    Err(e) => panic!("Failed to unsubscribe from results: {}", e),
+                }
+            }
+            
             RoundsOperation::ClaimWinnings { round_id } => {
-                // This operation is not used in the current design since rewards are auto-distributed
-                // But we keep it for potential future use
-                let _ = round_id;
-                panic!("ClaimWinnings is deprecated - rewards are automatically distributed on resolve");
+                let owner = self.runtime.authenticated_signer()
+                    .expect("ClaimWinnings requires an authenticated signer");
+                let now = self.runtime.system_time().micros();
+                match self.state.claim_winnings(round_id, owner, now).await {
+                    Ok(Some(_)) => RoundsResponse::Ok,
+                    Ok(None) => RoundsResponse::NothingToClaim,
+                    Err(e) => panic!("Failed to claim winnings: {}", e),
+                }
+            }
+            
+            RoundsOperation::PayQueuedClaims { limit } => {
+                // SendReward pays from the signer, which must be the treasury holding the stakes
+                let treasury = match self.treasury_signer() {
+                    Ok(treasury) => treasury,
+                    Err(e) => panic!("Failed to pay queued claims: {}", e),
+                };
+                let claims = match self.state.queued_claims(limit as usize).await {
+                    Ok(claims) => claims,
+                    Err(e) => panic!("Failed to pay queued claims: {}", e),
+                };
+                let Winzareal_app_id = self.state.Winza_app_id.get()
+                    .expect("Winzareal app ID not set");
+                let mut available = self.runtime.owner_balance(treasury);
+                let mut paid = 0;
+                for ((round_id, owner), (amount, source_chain_id)) in claims {
+                    if amount > available {
+                        break;
+                    }
+                    available = available.saturating_sub(amount);
+                    let _response: native_fungible_abi::ExtendedResponse = self.runtime.call_application(
+                        true,
+                        Winzareal_app_id,
+                        &native_fungible_abi::ExtendedOperation::SendReward {
+                            recipient: owner,
+                            amount,
+                            source_chain_id,
+                        },
+                    );
+                    if let Err(e) = self.state.remove_queued_claim((round_id, owner)) {
+                        panic!("Failed to pay queued claims: {}", e);
+                    }
+                    paid += 1;
+                }
+                let remaining = self.state.queued_claims.count().await
+                    .expect("Failed to count queued claims") as u64;
+                RoundsResponse::ClaimsPaid { paid, remaining }
             }
 
             // Query operations
@@ -256,7 +517,7 @@ impl Contract for RoundsContract {
                 match self.state.get_active_round().await {
                     Ok(Some(round_id)) => {
                         match self.state.get_round(round_id).await {
-                            Ok(Some(round)) => RoundsResponse::PredictionRound(prediction_round_option_to_lib(Some(round))),
+                            Ok(Some(round)) => RoundsResponse::PredictionRound(Some(round)),
                             Ok(None) => RoundsResponse::PredictionRound(None),
                             Err(e) => panic!("Failed to get round: {}", e),
                         }
@@ -266,9 +527,42 @@ impl Contract for RoundsContract {
                 }
             }
             
+            RoundsOperation::GetActiveRoundId => RoundsResponse::ActiveRoundId(*self.state.active_round.get()),
+            
+            RoundsOperation::GetUniqueBettors => match self.state.active_unique_bettors().await {
+                Ok(count) => RoundsResponse::UniqueBettors(count),
+                Err(e) => panic!("Failed to get unique bettors: {}", e),
+            },
+            
+            RoundsOperation::GetActiveRoundOdds => {
+                match self.state.get_active_round().await {
+                    Ok(Some(round_id)) => {
+                        match self.state.get_round(round_id).await {
+                            Ok(round) => RoundsResponse::Odds(round.map(|round| round.odds())),
+                            Err(e) => panic!("Failed to get round: {}", e),
+                        }
+                    },
+                    Ok(None) => RoundsResponse::Odds(None),
+                    Err(e) => panic!("Failed to get active round: {}", e),
+                }
+            }
+            
+            RoundsOperation::GetActiveRoundPools => {
+                match self.state.get_active_round().await {
+                    Ok(Some(round_id)) => {
+                        match self.state.get_round(round_id).await {
+                            Ok(round) => RoundsResponse::Pools(round.map(|round| round.pools())),
+                            Err(e) => panic!("Failed to get round: {}", e),
+                        }
+                    },
+                    Ok(None) => RoundsResponse::Pools(None),
+                    Err(e) => panic!("Failed to get active round: {}", e),
+                }
+            }
+            
             RoundsOperation::GetRound { id } => {
                 match self.state.get_round(id).await {
-                    Ok(Some(round)) => RoundsResponse::PredictionRound(prediction_round_option_to_lib(Some(round))),
+                    Ok(Some(round)) => RoundsResponse::PredictionRound(Some(round)),
                     Ok(None) => RoundsResponse::PredictionRound(None),
                     Err(e) => panic!("Failed to get round: {}", e),
                 }
@@ -276,31 +570,33 @@ impl Contract for RoundsContract {
             
             RoundsOperation::GetAllRounds => {
                 match self.state.get_all_rounds().await {
-                    Ok(rounds) => RoundsResponse::PredictionRounds(prediction_rounds_to_lib(rounds)),
+                    Ok(rounds) => RoundsResponse::PredictionRounds(rounds),
                     Err(e) => panic!("Failed to get all rounds: {}", e),
                 }
             }
             
+            RoundsOperation::GetRoundsByStatus { status, limit } => {
+                match self.state.get_rounds_by_status(status, limit).await {
+                    Ok(rounds) => RoundsResponse::PredictionRounds(rounds),
+                    Err(e) => panic!("Failed to get rounds by status: {}", e),
+                }
+            }
+            
+            RoundsOperation::GetRoundsAwaitingResolution { limit } => {
+                match self.state.get_rounds_awaiting_resolution(limit).await {
+                    Ok(rounds) => RoundsResponse::PredictionRounds(rounds),
+                    Err(e) => panic!("Failed to get rounds awaiting resolution: {}", e),
+                }
+            }
+            
             RoundsOperation::GetActiveBets => {
                 match self.state.get_active_bets().await {
                     Ok(bets) => {
                         let active_bets: Vec<_> = bets.into_iter().flat_map(|(owner, bet)| {
-                            let mut list = Vec::new();
-                            if !bet.amount_up.is_zero() {
-                                list.push(rounds::ActiveBetInfo {
-                                    owner,
-                                    amount: bet.amount_up,
-                                    prediction: Prediction::Up,
-                                });
-                            }
-                            if !bet.amount_down.is_zero() {
-                                list.push(rounds::ActiveBetInfo {
-                                    owner,
-                                    amount: bet.amount_down,
-                                    prediction: Prediction::Down,
-                                });
-                            }
-                            list
+                            // One entry per outcome the user has staked on
+                            bet.stakes()
+                                .map(|(prediction, amount)| rounds::ActiveBetInfo { owner, amount, prediction })
+                                .collect::<Vec<_>>()
                         }).collect();
                         RoundsResponse::ActiveBets(active_bets)
                     },
@@ -324,10 +620,117 @@ source_chain_id,
                     Err(e) => panic!("Failed to get round winners: {}", e),
                 }
             }
+            
+            RoundsOperation::GetRoundNetFlow { round_id } => {
+                match self.state.round_net_flow(round_id).await {
+                    Ok(net_flow) => RoundsResponse::RoundNetFlow(net_flow),
+                    Err(e) => panic!("Failed to get round net flow: {}", e),
+                }
+            }
+            
+            RoundsOperation::ResetMetrics => {
+                self.state.reset_metrics();
+                RoundsResponse::Ok
+            }
+            
+            RoundsOperation::GetAdminLog { limit } => {
+                match self.state.get_admin_log(limit).await {
+                    Ok(entries) => RoundsResponse::AdminLog(entries),
+                    Err(e) => panic!("Failed to get admin log: {}", e),
+                }
+            }
+            
+            RoundsOperation::TransferAdminRole { new_admin } => {
+                self.state.admin.set(Some(new_admin));
+                RoundsResponse::Ok
+            }
+            
+            RoundsOperation::GetActiveRoundForPrice => {
+                let now = self.runtime.system_time().micros();
+                match self.state.get_round_price_inputs(now).await {
+                    Ok(inputs) => RoundsResponse::RoundPriceInputs(inputs),
+                    Err(e) => panic!("Failed to get round price inputs: {}", e),
+                }
+            }
+            
+            RoundsOperation::ExportRoundBetCSV { round_id } => {
+                match self.state.export_round_bets_csv(round_id).await {
+                    Ok(csv_bytes) => RoundsResponse::RoundExport(rounds::RoundExport { round_id, csv_bytes }),
+                    Err(e) => panic!("Failed to export round: {}", e),
+                }
+            }
+            
+            RoundsOperation::ExportRound { round_id } => {
+                match self.state.round_archive(round_id).await {
+                    Ok(archive) => RoundsResponse::Archive(
+                        linera_sdk::bcs::to_bytes(&archive).expect("Round archive is serializable"),
+                    ),
+                    Err(e) => panic!("Failed to archive round: {}", e),
+                }
+            }
+            
+            RoundsOperation::GetBetSizeHistogram { round_id, buckets } => {
+                match self.state.get_bet_size_histogram(round_id, &buckets).await {
+                    Ok(histogram) => RoundsResponse::BetSizeHistogram(histogram),
+                    Err(e) => panic!("Failed to get bet size histogram: {}", e),
+                }
+            }
+            
+            RoundsOperation::GetNetworkParticipation => {
+                match self.state.get_network_participation().await {
+                    Ok(participation) => RoundsResponse::NetworkParticipation(participation),
+                    Err(e) => panic!("Failed to get network participation: {}", e),
+                }
+            }
+            
+            RoundsOperation::ResetNetworkStats => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("ResetNetworkStats can only be executed on the application creator chain");
+                }
+                self.state.reset_network_stats();
+                RoundsResponse::Ok
+            }
+            
+            RoundsOperation::ForceClearActiveBets => {
+                if self.runtime.chain_id() != self.runtime.application_creator_chain_id() {
+                    panic!("ForceClearActiveBets can only be executed on the application creator chain");
+                }
+                let bets = match self.state.clear_active_bets().await {
+                    Ok(bets) => bets,
+                    Err(e) => panic!("Failed to clear active bets: {}", e),
+                };
+                // SendReward pays from the signer, which must be the treasury holding the stakes
+                if bets.iter().any(|bet| !bet.total_amount().is_zero()) {
+                    if let Err(e) = self.treasury_signer() {
+                        panic!("Failed to refund active bets: {}", e);
+                    }
+                }
+                for bet in bets {
+                    let refund = bet.total_amount();
+                    if refund.is_zero() {
+                        continue;
+                    }
+                    let Winzareal_app_id = self.state.Winza_app_id.get()
+                        .expect("Winzareal app ID not set");
+                    let _response: native_fungible_abi::ExtendedResponse = self.runtime.call_application(
+                        true,
+                        Winzareal_app_id,
+                        &native_fungible_abi::ExtendedOperation::SendReward {
+                            recipient: bet.owner,
+                            amount: refund,
+                            source_chain_id: bet.source_chain_id,
+                        },
+                    );
+                }
+                RoundsResponse::Ok
+            }
         }
     }
 
     async fn execute_message(&mut self, message: Self::Message) {
+        self.state.increment_metric(message_metric(&message)).await
+            .expect("Failed to update metrics");
+        
         match message {
             Message::Notify => {
                 // Auto-deploy notification
@@ -342,7 +745,7 @@ source_chain_id,
                 let params = self.runtime.application_parameters();
                 let leaderboard_app_id = params.leaderboard_app_id.with_abi::<leaderboard::LeaderboardAbi>();
                 
-                let _response: () = self.runtime.call_application(
+                let _response: leaderboard::LeaderboardResponse = self.runtime.call_application(
                     true,
                     leaderboard_app_id,
                     &leaderboard::Operation::UpdateScore {
@@ -355,6 +758,11 @@ source_chain_id,
                 
                 eprintln!("Rounds::execute_message - LeaderboardUpdate completed");
             }
+            Message::RoundResolvedNotice(notice) => {
+                if let Err(e) = self.state.record_result_notice(notice) {
+                    panic!("Failed to record round result: {}", e);
+                }
+            }
         }
     }
 
@@ -362,3 +770,2413 @@ source_chain_id,
         self.state.save().await.expect("Failed to save state");
     }
 }
+
+impl RoundsContract {
+    /// Resolve a closed round, send rewards through Winzareal and report bets to the leaderboard.
+    /// Returns the number of leaderboard updates sent and skipped (below leaderboard_min_amount),
+    /// or the ResolutionPending response when the treasury cannot cover the payout; the
+    /// round is then parked without paying anyone.
+    async fn settle_round(&mut self, round_id: u64, resolution_price: Amount, timestamp: u64) -> Result<(u64, u64), RoundsResponse> {
+        let payout = match self.state.resolution_payout(round_id, resolution_price).await {
+            Ok(payout) => payout,
+            Err(e) => panic!("Failed to resolve round: {}", e),
+        };
+        let burned = self.round_burn(round_id).await;
+        let shortfall = match self.payout_shortfall(payout, burned) {
+            Ok(shortfall) => shortfall,
+            Err(e) => panic!("Failed to resolve round {}: {}", round_id, e),
+        };
+        if let Some(shortfall) = shortfall {
+            if let Err(e) = self.state.mark_resolution_pending(round_id, resolution_price).await {
+                panic!("Failed to mark round {} pending: {}", round_id, e);
+            }
+            self.state.increment_metric("resolution.pending").await
+                .expect("Failed to update metrics");
+            eprintln!("Round {} pending resolution: payout {} is {} more than the treasury holds", round_id, payout, shortfall);
+            return Err(RoundsResponse::ResolutionPending { round_id, payout, shortfall });
+        }
+        let results = match self.state.resolve_round_and_distribute_rewards(round_id, resolution_price, timestamp).await {
+            Ok(results) => results,
+            Err(e) => panic!("Failed to resolve round: {}", e),
+        };
+        Ok(self.pay_out(round_id, results).await)
+    }
+
+    /// Pull a stake of `amount` from `owner` in full to the treasury that pays the rewards,
+    /// which burns the fee on settlement
+    fn pull_stake_to_treasury(&mut self, owner: AccountOwner, amount: Amount) {
+        let treasury = match self.state.treasury() {
+            Ok(treasury) => treasury,
+            Err(e) => panic!("Failed to pull stake: {}", e),
+        };
+        let treasury_account = linera_sdk::abis::fungible::Account { chain_id: self.runtime.chain_id(), owner: treasury };
+        self.pull_stake(owner, treasury_account, amount);
+    }
+
+    /// Place every active recurring bet in the just created active round. Each stake is pulled
+    /// from its owner's allowance like a direct bet; a subscription whose allowance does not
+    /// cover the stake, or whose bet the round rejects, is deactivated instead of failing the round.
+    async fn place_recurring_bets(&mut self, timestamp: u64) {
+        let recurring_bets = match self.state.active_recurring_bets().await {
+            Ok(recurring_bets) => recurring_bets,
+            Err(e) => panic!("Failed to place recurring bets: {}", e),
+        };
+        for (owner, recurring_bet) in recurring_bets {
+            // Checked first so a rejected bet or a short allowance moves no tokens
+            let placed = self.allowance(owner) >= recurring_bet.amount
+                && self.state.place_bet(owner, recurring_bet.amount, recurring_bet.prediction, None, timestamp).await.is_ok();
+            if placed {
+                self.pull_stake_to_treasury(owner, recurring_bet.amount);
+            }
+            if let Err(e) = self.state.record_recurring_bet(owner, recurring_bet, placed) {
+                panic!("Failed to place recurring bets: {}", e);
+            }
+        }
+    }
+
+    /// What `owner` currently allows this application to spend in the native app
+    fn allowance(&mut self, owner: AccountOwner) -> Amount {
+        let native_app_id = self.runtime.application_parameters().native_app_id.with_abi::<native::NativeAbi>();
+        let spender = AccountOwner::from(self.runtime.application_id().forget_abi());
+        match self.runtime.call_application(
+            true,
+            native_app_id,
+            &native::NativeOperation::Allowance { owner, spender },
+        ) {
+            native::NativeResponse::Allowance(allowance) => allowance,
+            other => panic!("Unexpected allowance response: {:?}", other),
+        }
+    }
+
+    /// Move `amount` of `owner`'s tokens to `to` through the allowance `owner` granted this
+    /// application in the native app, failing unless the allowance covers it
+    fn pull_stake(&mut self, owner: AccountOwner, to: linera_sdk::abis::fungible::Account, amount: Amount) {
+        if amount.is_zero() {
+            return;
+        }
+        let allowance = self.allowance(owner);
+        if allowance < amount {
+            panic!("Failed to pull stake: allowance of {} does not cover {}", allowance, amount);
+        }
+        let native_app_id = self.runtime.application_parameters().native_app_id.with_abi::<native::NativeAbi>();
+        let response: native::NativeResponse = self.runtime.call_application(
+            true,
+            native_app_id,
+            &native::NativeOperation::TransferFrom { from: owner, to, amount },
+        );
+        if !matches!(response, native::NativeResponse::Ok) {
+            panic!("Failed to pull stake: unexpected TransferFrom response {:?}", response);
+        }
+    }
+
+    /// How much `payout` plus the `burned` fees exceed the treasury's balance, if at all.
+    /// Winzareal's SendReward pays every reward from the signer, so a payout must be signed by
+    /// the treasury, which the round's stakes were sent to; any other signer (or none) is refused.
+    fn payout_shortfall(&mut self, payout: Amount, burned: Amount) -> Result<Option<Amount>, String> {
+        let due = payout.saturating_add(burned);
+        if due.is_zero() {
+            return Ok(None);
+        }
+        let treasury = self.treasury_signer()?;
+        let available = self.runtime.owner_balance(treasury);
+        Ok((due > available).then(|| due.saturating_sub(available)))
+    }
+
+    /// Burn fees kept out of round `round_id`'s prize pool
+    async fn round_burn(&self, round_id: u64) -> Amount {
+        match self.state.get_round(round_id).await {
+            Ok(Some(round)) => round.total_burned_this_round,
+            Ok(None) => panic!("Round {} not found", round_id),
+            Err(e) => panic!("Failed to get round {}: {}", round_id, e),
+        }
+    }
+
+    /// Move round `round_id`'s burn fees out of the treasury, which received the full stakes,
+    /// into this application's own account. Nothing ever spends from that account, so unlike
+    /// the chain balance (which Mint draws from) the tokens leave circulation for good.
+    async fn burn_round_fees(&mut self, round_id: u64) {
+        let burned = self.round_burn(round_id).await;
+        if burned.is_zero() {
+            return;
+        }
+        let treasury = match self.treasury_signer() {
+            Ok(treasury) => treasury,
+            Err(e) => panic!("Failed to burn round {}'s fees: {}", round_id, e),
+        };
+        let sink = Account {
+            chain_id: self.runtime.chain_id(),
+            owner: AccountOwner::from(self.runtime.application_id().forget_abi()),
+        };
+        self.runtime.transfer(treasury, sink, burned);
+    }
+
+    /// The treasury, provided it signed the current operation
+    fn treasury_signer(&mut self) -> Result<AccountOwner, String> {
+        let treasury = self.state.treasury()?;
+        match self.runtime.authenticated_signer() {
+            Some(signer) if signer == treasury => Ok(treasury),
+            signer => Err(format!("Payouts must be signed by the treasury {} (signer: {:?})", treasury, signer)),
+        }
+    }
+
+    /// The RoundResolved response for a just resolved round
+    async fn round_resolved(&self, round_id: u64, (leaderboard_updates_sent, leaderboard_updates_skipped): (u64, u64)) -> RoundsResponse {
+        let winners = self.state.get_round_winners(round_id).await
+            .expect("Failed to get round winners")
+            .into_iter()
+            .map(|(owner, bet_amount, winnings, source_chain_id)| rounds::RoundWinnerInfo {
+                owner,
+                bet_amount,
+                winnings,
+                source_chain_id,
+            })
+            .collect();
+        RoundsResponse::RoundResolved { winners, leaderboard_updates_sent, leaderboard_updates_skipped }
+    }
+
+    /// Send the rewards of a resolution's (owner, wagered, winnings, is_win, source chain)
+    /// results through Winzareal, report them to the leaderboard and emit the round's GameResult
+    async fn pay_out(&mut self, round_id: u64, results: Vec<(AccountOwner, Amount, Amount, bool, Option<String>)>) -> (u64, u64) {
+        let result = GameResult::round_settled(GameKind::Prediction, round_id, results.iter().map(|(_, _, winnings, ..)| *winnings));
+        self.runtime.emit(game_results_stream(), &result);
+        self.burn_round_fees(round_id).await;
+
+        // Get app IDs
+        let params = self.runtime.application_parameters();
+        let leaderboard_app_id = params.leaderboard_app_id.with_abi::<leaderboard::LeaderboardAbi>();
+
+        let Winzareal_app_id = self.state.Winza_app_id.get()
+            .expect("Winzareal app ID not set");
+        let leaderboard_min_amount = *self.state.leaderboard_min_amount.get();
+        // Rounds under a dispute window are claimed once it ends and paid by PayQueuedClaims
+        let claim_later = self.state.dispute_deadline(round_id).await
+            .expect("Failed to get dispute deadline")
+            .is_some();
+        let (mut updates_sent, mut updates_skipped) = (0, 0);
+
+        for (owner, bet_amount, winnings, is_win, source_chain_id) in results {
+            // Distribute rewards if any
+            if winnings > Amount::ZERO && !claim_later {
+                let _response: native_fungible_abi::ExtendedResponse = self.runtime.call_application(
+                    true, // authenticated
+                    Winzareal_app_id,
+                    &native_fungible_abi::ExtendedOperation::SendReward {
+                        recipient: owner,
+                        amount: winnings,
+                        source_chain_id: source_chain_id.clone(),
+                    },
+                );
+            }
+
+            // Update leaderboard stats (for everyone)
+            let player_chain_id_str = source_chain_id.clone().unwrap_or_else(|| self.runtime.chain_id().to_string());
+
+            // Check if leaderboard is on a different chain
+            let leaderboard_target_chain = self.state.leaderboard_chain_id.get().clone();
+
+            // Calculate clean amount (Net Profit or Net Loss)
+            let clean_amount = if is_win {
+                winnings.saturating_sub(bet_amount)
+            } else {
+                bet_amount.saturating_sub(winnings)
+            };
+            if clean_amount < leaderboard_min_amount {
+                updates_skipped += 1;
+                continue;
+            }
+            updates_sent += 1;
+
+            if let Some(target_chain_str) = leaderboard_target_chain {
+                // Cross-chain: send message to target chain
+                let target_chain_id = target_chain_str.parse::<linera_sdk::linera_base_types::ChainId>()
+                    .expect("Invalid leaderboard_chain_id format");
+
+                if target_chain_id != self.runtime.chain_id() {
+                    // Send cross-chain message
+                    self.runtime
+                        .prepare_message(Message::LeaderboardUpdate {
+                            owner,
+                            chain_id: player_chain_id_str.clone(),
+                            is_win,
+                            amount: clean_amount,
+                        })
+                        .with_authentication()
+                        .send_to(target_chain_id);
+                    self.state.increment_metric("messages_sent").await
+                        .expect("Failed to update metrics");
+                    eprintln!("Sent LeaderboardUpdate cross-chain to {:?}", target_chain_id);
+                } else {
+                    // Same chain, call directly  
+                    let _response: leaderboard::LeaderboardResponse = self.runtime.call_application(
+                        true,
+                        leaderboard_app_id,
+                        &leaderboard::Operation::UpdateScore {
+                            owner,
+                            chain_id: player_chain_id_str,
+                            is_win,
+                            amount: clean_amount,
+                        }
+                    );
+                }
+            } else {
+                // No target chain set, call leaderboard on same chain
+                let _response: leaderboard::LeaderboardResponse = self.runtime.call_application(
+                    true,
+                    leaderboard_app_id,
+                    &leaderboard::Operation::UpdateScore {
+                        owner,
+                        chain_id: player_chain_id_str,
+                        is_win,
+                        amount: clean_amount,
+                    }
+                );
+            }
+        }
+        self.notify_result_subscribers(round_id).await;
+        (updates_sent, updates_skipped)
+    }
+
+    /// Send the resolved round's RoundResolvedNotice to every subscribed chain
+    async fn notify_result_subscribers(&mut self, round_id: u64) {
+        let subscribers = self.state.result_subscribers.indices().await
+            .expect("Failed to get result subscribers");
+        if subscribers.is_empty() {
+            return;
+        }
+        let notice = self.state.round_resolved_notice(round_id).await
+            .expect("Failed to build round result notice");
+        for chain_id in subscribers {
+            self.runtime
+                .prepare_message(Message::RoundResolvedNotice(notice.clone()))
+                .with_authentication()
+                .send_to(chain_id);
+            self.state.increment_metric("messages_sent").await
+                .expect("Failed to update metrics");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use futures::FutureExt as _;
+    use linera_sdk::{
+        linera_base_types::{AccountOwner, Amount, ApplicationId, ChainId, CryptoHash, Timestamp},
+        util::BlockingWait,
+        views::View,
+        Contract, ContractRuntime,
+    };
+    use rounds::{
+        AppConfig, BetSizeBucket, ChainParticipation, Message, Prediction, RoundArchive, RoundFilter, RoundOdds, RoundPools, RoundResolvedNotice,
+        RoundStatus, RoundsOperation, RoundsParameters, RoundsResponse, SortOrder, StateError, RECENT_RESULTS_CAPACITY,
+    };
+    use winner_feed::{FeedEntry, Game, WINNER_FEED_CAPACITY};
+
+    use super::{RoundsContract, RoundsState};
+
+    fn create_runtime() -> ContractRuntime<RoundsContract> {
+        let parameters = RoundsParameters {
+            native_app_id: ApplicationId::new(CryptoHash::test_hash("native")),
+            leaderboard_app_id: ApplicationId::new(CryptoHash::test_hash("leaderboard")),
+            admin: AccountOwner::from(CryptoHash::test_hash("admin")),
+        };
+        ContractRuntime::new()
+            .with_application_parameters(parameters)
+            .with_system_time(Timestamp::from(1_000))
+    }
+
+    fn create_contract() -> RoundsContract {
+        load_contract(create_runtime())
+    }
+
+    fn load_contract(runtime: ContractRuntime<RoundsContract>) -> RoundsContract {
+        let state = RoundsState::load(runtime.root_view_storage_context())
+            .blocking_wait()
+            .expect("Failed to load state");
+        RoundsContract { state, runtime }
+    }
+
+    fn execute(contract: &mut RoundsContract, operation: RoundsOperation) -> RoundsResponse {
+        contract
+            .execute_operation(operation)
+            .now_or_never()
+            .expect("Execution should not await anything")
+    }
+
+    /// Make a funded treasury the signer, so resolutions can pay out
+    fn sign_as_treasury(contract: &mut RoundsContract, balance: Amount) -> AccountOwner {
+        let treasury = AccountOwner::from(CryptoHash::test_hash("treasury"));
+        contract.state.treasury.set(Some(treasury));
+        contract.runtime.set_authenticated_signer(Some(treasury));
+        contract.runtime.set_owner_balance(treasury, balance);
+        treasury
+    }
+
+    #[test]
+    fn three_outcome_round_pays_flat_bettors() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let carol = AccountOwner::from(CryptoHash::test_hash("carol"));
+
+        execute(&mut contract, RoundsOperation::SetOutcomeConfig {
+            num_outcomes: 3,
+            flat_band: Amount::from_tokens(1),
+        });
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+
+        for (owner, prediction) in [(alice, Prediction::Up), (bob, Prediction::Down), (carol, Prediction::Flat)] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner,
+                amount: Amount::from_tokens(10),
+                prediction,
+                source_chain_id: None,
+            });
+        }
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.num_outcomes, 3);
+        assert_eq!(round.outcome_bets, vec![1, 1, 1]);
+        assert_eq!(round.prize_pool, Amount::from_tokens(30));
+
+        // A 0.5 token move is inside the flat band
+        let results = contract.state
+            .resolve_round_and_distribute_rewards(round_id, Amount::from_millis(100_500), 2_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.result, Some(Prediction::Flat));
+        for (owner, wagered, winnings, is_win, _) in results {
+            assert_eq!(wagered, Amount::from_tokens(10));
+            if owner == carol {
+                assert!(is_win);
+                assert!(winnings > wagered);
+            } else {
+                assert!(!is_win);
+                assert_eq!(winnings, Amount::ZERO);
+            }
+        }
+    }
+
+    #[test]
+    fn split_bet_stakes_both_directions_in_one_bet() {
+        let (mut contract, pulled) = create_allowance_contract(Amount::from_tokens(100));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        contract.state.admin.set(Some(admin));
+        contract.runtime.set_authenticated_signer(Some(admin));
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+
+        contract.runtime.set_authenticated_signer(Some(alice));
+        let RoundsResponse::PlaceSplitBetResponse { bet_id } = execute(&mut contract, RoundsOperation::PlaceSplitBet {
+            amount_up: Amount::from_tokens(70),
+            amount_down: Amount::from_tokens(30),
+        }) else {
+            panic!("PlaceSplitBet should return the bet ID");
+        };
+        assert_eq!(bet_id, 1);
+        // Both stakes are pulled from the signer to the treasury, which defaults to the admin
+        assert_eq!(*pulled.lock().unwrap(), vec![(alice, admin, Amount::from_tokens(100))]);
+
+        let bet = contract.state.active_bets.get(&alice).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(bet.amounts, vec![Amount::from_tokens(70), Amount::from_tokens(30)]);
+        assert_eq!(contract.state.active_unique_bettors().now_or_never().unwrap().unwrap(), 1);
+
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.outcome_pools, vec![Amount::from_tokens(70), Amount::from_tokens(30)]);
+        assert_eq!(round.outcome_bets, vec![1, 1]);
+        assert_eq!(round.prize_pool, Amount::from_tokens(100));
+        // The split is one bet in the network participation stats
+        let participation = contract.state.get_network_participation().now_or_never().unwrap().unwrap();
+        assert_eq!(
+            participation.iter().map(|stats| (stats.bet_count, stats.total_volume)).collect::<Vec<_>>(),
+            vec![(1, Amount::from_tokens(100))],
+        );
+    }
+
+    #[test]
+    fn custom_winner_resolution_is_flagged_and_justified() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        for (owner, prediction) in [(alice, Prediction::Up), (bob, Prediction::Down)] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner,
+                amount: Amount::from_tokens(10),
+                prediction,
+                source_chain_id: None,
+            });
+        }
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+
+        let justification = "Oracle feed stalled; settled on the exchange close".to_string();
+        let unknown_outcome = contract.state
+            .resolve_with_custom_winner(round_id, Prediction::Flat, justification.clone(), 2_000)
+            .now_or_never()
+            .unwrap();
+        assert!(unknown_outcome.is_err());
+
+        let results = contract.state
+            .resolve_with_custom_winner(round_id, Prediction::Down, justification.clone(), 2_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        for (owner, _, winnings, is_win, _) in results {
+            assert_eq!(is_win, owner == bob);
+            assert_eq!(winnings, if owner == bob { Amount::from_tokens(20) } else { Amount::ZERO });
+        }
+
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert!(round.manual_resolution);
+        assert_eq!((round.result, round.resolution_price), (Some(Prediction::Down), None));
+        assert!(matches!(
+            execute(&mut contract, RoundsOperation::GetResolutionNote { round_id }),
+            RoundsResponse::ResolutionNote(Some(note)) if note == justification
+        ));
+    }
+
+    #[test]
+    fn binary_round_rejects_flat_bets() {
+        let mut contract = create_contract();
+        contract.state.create_round(0).now_or_never().unwrap().unwrap();
+
+        let result = contract.state
+            .place_bet(AccountOwner::from(CryptoHash::test_hash("alice")), Amount::ONE, Prediction::Flat, None, 0)
+            .now_or_never()
+            .unwrap();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn price_inputs_follow_round_lifecycle() {
+        let mut contract = create_contract();
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+
+        let RoundsResponse::RoundPriceInputs(Some(inputs)) = execute(&mut contract, RoundsOperation::GetActiveRoundForPrice) else {
+            panic!("Active round should have price inputs");
+        };
+        assert_eq!(inputs.round_id, round_id);
+        assert_eq!(inputs.status, RoundStatus::Active);
+        assert_eq!(inputs.created_at, 1_000);
+        assert_eq!(inputs.closing_price, None);
+
+        // Once closed, the round awaiting resolution takes precedence over the new active round
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+        let inputs = contract.state.get_round_price_inputs(5_000).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(inputs.round_id, round_id);
+        assert_eq!(inputs.status, RoundStatus::Closed);
+        assert_eq!(inputs.elapsed_micros, 4_000);
+        assert_eq!(inputs.closing_price, Some(Amount::from_tokens(100)));
+    }
+
+    #[test]
+    fn operations_are_counted_and_reset() {
+        let mut contract = create_contract();
+        execute(&mut contract, RoundsOperation::CreateRound);
+        execute(&mut contract, RoundsOperation::GetActiveRound);
+        execute(&mut contract, RoundsOperation::GetActiveRound);
+
+        let metrics = contract.state.get_metrics().now_or_never().unwrap().unwrap();
+        assert_eq!(metrics, vec![
+            ("operation.CreateRound".to_string(), 1),
+            ("operation.GetActiveRound".to_string(), 2),
+        ]);
+
+        execute(&mut contract, RoundsOperation::ResetMetrics);
+        assert!(contract.state.get_metrics().now_or_never().unwrap().unwrap().is_empty());
+    }
+
+    #[test]
+    fn csv_export_has_one_row_per_staked_outcome() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+
+        for (owner, prediction) in [(alice, Prediction::Up), (alice, Prediction::Down), (bob, Prediction::Down)] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner,
+                amount: Amount::from_tokens(10),
+                prediction,
+                source_chain_id: None,
+            });
+        }
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+        contract.state
+            .resolve_round_and_distribute_rewards(round_id, Amount::from_tokens(90), 2_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let RoundsResponse::RoundExport(export) = execute(&mut contract, RoundsOperation::ExportRoundBetCSV { round_id }) else {
+            panic!("ExportRoundBetCSV should return a round export");
+        };
+        let csv = String::from_utf8(export.csv_bytes).unwrap();
+        let rows: Vec<&str> = csv.split_terminator("\r\n").collect();
+
+        assert_eq!(rows[0], "owner,outcome,amount,is_winner,winnings,claimed,source_chain_id");
+        assert_eq!(rows.len() - 1, 3);
+        let column = |row: &str, index: usize| row.split(',').nth(index).unwrap().to_string();
+        assert_eq!(rows[1..].iter().filter(|row| column(row, 3) == "true").count(), 2);
+        // Alice and Bob both won something, so both bets were paid on resolution
+        assert!(rows[1..].iter().all(|row| column(row, 5) == "true"));
+    }
+
+    /// Resolves a round with the given bets (placed in the given order) and exports its archive
+    fn archived_round_bytes(bets: &[(&str, Prediction, u128)]) -> Vec<u8> {
+        let mut contract = create_contract();
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        for (name, prediction, tokens) in bets {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner: AccountOwner::from(CryptoHash::test_hash(*name)),
+                amount: Amount::from_tokens(*tokens),
+                prediction: *prediction,
+                source_chain_id: None,
+            });
+        }
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+        contract.state
+            .resolve_round_and_distribute_rewards(round_id, Amount::from_tokens(110), 2_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let RoundsResponse::Archive(bytes) = execute(&mut contract, RoundsOperation::ExportRound { round_id }) else {
+            panic!("ExportRound should return an archive");
+        };
+        bytes
+    }
+
+    #[test]
+    fn round_archive_bytes_are_stable() {
+        let bets = [
+            ("alice", Prediction::Up, 10),
+            ("bob", Prediction::Down, 5),
+            ("carol", Prediction::Up, 10),
+            ("alice", Prediction::Down, 5),
+        ];
+        let mut reordered = bets;
+        reordered.reverse();
+
+        let bytes = archived_round_bytes(&bets);
+        assert_eq!(bytes, archived_round_bytes(&bets));
+        assert_eq!(bytes, archived_round_bytes(&reordered));
+
+        let archive: RoundArchive = bcs::from_bytes(&bytes).unwrap();
+        assert_eq!(archive.round.status, RoundStatus::Resolved);
+        assert_eq!(archive.participants.len(), 3);
+        assert!(archive.participants.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(archive.bets.len(), 4);
+        assert_eq!(archive.winners.len(), 2);
+        let payouts = archive.winners.iter().fold(Amount::ZERO, |total, winner| total.saturating_add(winner.winnings));
+        assert_eq!(payouts, Amount::from_tokens(30));
+    }
+
+    #[test]
+    fn network_participation_is_tracked_per_source_chain() {
+        let chain = ChainId(CryptoHash::test_hash("rounds"));
+        let mut contract = load_contract(create_runtime().with_chain_id(chain).with_application_creator_chain_id(chain));
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        for (name, prediction, tokens, source_chain_id) in [
+            ("alice", Prediction::Up, 10, Some("chain-a")),
+            ("bob", Prediction::Down, 5, Some("chain-b")),
+            ("carol", Prediction::Up, 10, None),
+            ("dave", Prediction::Down, 5, Some("chain-a")),
+        ] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner: AccountOwner::from(CryptoHash::test_hash(name)),
+                amount: Amount::from_tokens(tokens),
+                prediction,
+                source_chain_id: source_chain_id.map(str::to_string),
+            });
+        }
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+        contract.state
+            .resolve_round_and_distribute_rewards(round_id, Amount::from_tokens(110), 2_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let stats = |chain_id: &str, bet_count, tokens, win_count| ChainParticipation {
+            chain_id: chain_id.to_string(),
+            bet_count,
+            total_volume: Amount::from_tokens(tokens),
+            win_count,
+        };
+        let RoundsResponse::NetworkParticipation(participation) = execute(&mut contract, RoundsOperation::GetNetworkParticipation) else {
+            panic!("GetNetworkParticipation should return participation");
+        };
+        assert_eq!(participation, vec![stats("chain-a", 2, 15, 1), stats("chain-b", 1, 5, 0), stats("local", 1, 10, 1)]);
+
+        execute(&mut contract, RoundsOperation::ResetNetworkStats);
+        assert!(contract.state.get_network_participation().now_or_never().unwrap().unwrap().is_empty());
+    }
+
+    /// A contract whose native app grants `allowance` and records each TransferFrom as (from, to, amount)
+    fn create_allowance_contract(allowance: Amount) -> (RoundsContract, Arc<Mutex<Vec<(AccountOwner, AccountOwner, Amount)>>>) {
+        let pulled = Arc::new(Mutex::new(Vec::new()));
+        let native_pulled = pulled.clone();
+        let runtime = create_runtime()
+            .with_application_id(ApplicationId::new(CryptoHash::test_hash("rounds")).with_abi())
+            .with_call_application_handler(move |_authenticated, _application_id, operation| {
+                let response = match bcs::from_bytes(&operation).unwrap() {
+                    native::NativeOperation::Allowance { .. } => native::NativeResponse::Allowance(allowance),
+                    native::NativeOperation::TransferFrom { from, to, amount } => {
+                        native_pulled.lock().unwrap().push((from, to.owner, amount));
+                        native::NativeResponse::Ok
+                    }
+                    other => panic!("Unexpected native operation {:?}", other),
+                };
+                bcs::to_bytes(&response).unwrap()
+            });
+        (load_contract(runtime), pulled)
+    }
+
+    #[test]
+    fn recurring_bet_is_placed_for_max_rounds() {
+        let (mut contract, pulled) = create_allowance_contract(Amount::from_tokens(100));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let treasury = AccountOwner::from(CryptoHash::test_hash("treasury"));
+        contract.state.treasury.set(Some(treasury));
+        contract.runtime.set_authenticated_signer(Some(alice));
+
+        execute(&mut contract, RoundsOperation::SetRecurringBet {
+            amount: Amount::from_tokens(5),
+            prediction: Prediction::Up,
+            max_rounds: Some(3),
+        });
+
+        let mut next_round = RoundsOperation::CreateRound;
+        for expected_pool in [5, 5, 5, 0] {
+            let RoundsResponse::RoundId(round_id) = execute(&mut contract, next_round) else {
+                panic!("CreateRound and CloseRound should return a round ID");
+            };
+            next_round = RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) };
+            let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+            assert_eq!(round.prize_pool, Amount::from_tokens(expected_pool), "round {}", round_id);
+        }
+
+        let recurring_bet = contract.state.get_recurring_bet(alice).now_or_never().unwrap().unwrap().unwrap();
+        assert!(!recurring_bet.active);
+        assert_eq!(recurring_bet.rounds_remaining, 0);
+        // Every placed bet was paid for from Alice's wallet
+        assert_eq!(*pulled.lock().unwrap(), vec![(alice, treasury, Amount::from_tokens(5)); 3]);
+
+        execute(&mut contract, RoundsOperation::CancelRecurringBet);
+        assert!(contract.state.get_recurring_bet(alice).now_or_never().unwrap().unwrap().is_none());
+    }
+
+    #[test]
+    fn recurring_bet_without_allowance_is_deactivated() {
+        let (mut contract, pulled) = create_allowance_contract(Amount::from_tokens(4));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        contract.state.treasury.set(Some(AccountOwner::from(CryptoHash::test_hash("treasury"))));
+        contract.runtime.set_authenticated_signer(Some(alice));
+        execute(&mut contract, RoundsOperation::SetRecurringBet {
+            amount: Amount::from_tokens(5),
+            prediction: Prediction::Up,
+            max_rounds: None,
+        });
+
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.prize_pool, Amount::ZERO);
+        assert!(pulled.lock().unwrap().is_empty());
+        let recurring_bet = contract.state.get_recurring_bet(alice).now_or_never().unwrap().unwrap().unwrap();
+        assert!(!recurring_bet.active);
+    }
+
+    #[test]
+    fn oracle_can_close_round() {
+        let mut contract = create_contract();
+        let oracle = AccountOwner::from(CryptoHash::test_hash("oracle"));
+        contract.runtime.set_authenticated_signer(Some(oracle));
+
+        execute(&mut contract, RoundsOperation::SetOracle { oracle: Some(oracle) });
+        execute(&mut contract, RoundsOperation::CreateRound);
+        let RoundsResponse::RoundId(new_round_id) =
+            execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) })
+        else {
+            panic!("CloseRound should return the new round ID");
+        };
+        assert_eq!(new_round_id, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the oracle")]
+    fn non_oracle_resolution_is_rejected() {
+        let mut contract = create_contract();
+        let oracle = AccountOwner::from(CryptoHash::test_hash("oracle"));
+        let mallory = AccountOwner::from(CryptoHash::test_hash("mallory"));
+        contract.runtime.set_authenticated_signer(Some(oracle));
+
+        execute(&mut contract, RoundsOperation::SetOracle { oracle: Some(oracle) });
+        execute(&mut contract, RoundsOperation::CreateRound);
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+
+        contract.runtime.set_authenticated_signer(Some(mallory));
+        execute(&mut contract, RoundsOperation::ResolveRound { round_id: None, resolution_price: Amount::from_tokens(1) });
+    }
+
+    /// Creates a contract whose admin was set from the application parameters
+    fn create_instantiated_contract() -> RoundsContract {
+        let mut contract = create_contract();
+        contract.instantiate(()).now_or_never().expect("Instantiation should not await anything");
+        contract
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the admin")]
+    fn non_admin_cannot_install_an_oracle() {
+        // Even before any oracle is set, which would let anyone supply prices
+        let mut contract = create_instantiated_contract();
+        let mallory = AccountOwner::from(CryptoHash::test_hash("mallory"));
+        contract.runtime.set_authenticated_signer(Some(mallory));
+        execute(&mut contract, RoundsOperation::SetOracle { oracle: Some(mallory) });
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the admin")]
+    fn non_admin_cannot_create_rounds() {
+        let mut contract = create_instantiated_contract();
+        contract.runtime.set_authenticated_signer(Some(AccountOwner::from(CryptoHash::test_hash("mallory"))));
+        execute(&mut contract, RoundsOperation::CreateRound);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the admin")]
+    fn non_admin_cannot_change_config() {
+        let mut contract = create_instantiated_contract();
+        contract.runtime.set_authenticated_signer(Some(AccountOwner::from(CryptoHash::test_hash("mallory"))));
+        execute(&mut contract, RoundsOperation::SetBurnFeeBps { bps: 100 });
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the admin")]
+    fn old_admin_is_rejected_after_transfer() {
+        let mut contract = create_instantiated_contract();
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        let new_admin = AccountOwner::from(CryptoHash::test_hash("new-admin"));
+        contract.runtime.set_authenticated_signer(Some(admin));
+        execute(&mut contract, RoundsOperation::TransferAdminRole { new_admin });
+
+        contract.runtime.set_authenticated_signer(Some(new_admin));
+        execute(&mut contract, RoundsOperation::SetBurnFeeBps { bps: 100 });
+        assert_eq!(*contract.state.burn_fee_bps.get(), 100);
+
+        contract.runtime.set_authenticated_signer(Some(admin));
+        execute(&mut contract, RoundsOperation::SetBurnFeeBps { bps: 0 });
+    }
+
+    #[test]
+    fn admin_operations_are_logged_in_order() {
+        let mut contract = create_instantiated_contract();
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        contract.runtime.set_authenticated_signer(Some(admin));
+        execute(&mut contract, RoundsOperation::SetWinzaAppId {
+            Winza_app_id: ApplicationId::new(CryptoHash::test_hash("winza")).to_string(),
+        });
+        execute(&mut contract, RoundsOperation::CreateRound);
+        contract.runtime.set_system_time(Timestamp::from(2_000));
+        execute(&mut contract, RoundsOperation::SetLeaderboardChainId { chain_id: Some("leaderboard-chain".to_string()) });
+
+        let RoundsResponse::AdminLog(log) = execute(&mut contract, RoundsOperation::GetAdminLog { limit: 10 }) else {
+            panic!("GetAdminLog should return the admin log");
+        };
+        let entries: Vec<_> = log.iter().map(|entry| (entry.timestamp, entry.action.as_str(), entry.actor)).collect();
+        assert_eq!(entries, vec![(1_000, "SetWinzaAppId", admin), (1_000, "CreateRound", admin), (2_000, "SetLeaderboardChainId", admin)]);
+
+        let RoundsResponse::AdminLog(latest) = execute(&mut contract, RoundsOperation::GetAdminLog { limit: 1 }) else {
+            panic!("GetAdminLog should return the admin log");
+        };
+        assert_eq!(latest, log[1..].to_vec());
+    }
+
+    #[test]
+    fn active_round_id_tracks_the_active_round() {
+        let mut contract = create_contract();
+        assert!(matches!(execute(&mut contract, RoundsOperation::GetActiveRoundId), RoundsResponse::ActiveRoundId(None)));
+
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        assert!(matches!(
+            execute(&mut contract, RoundsOperation::GetActiveRoundId),
+            RoundsResponse::ActiveRoundId(Some(id)) if id == round_id
+        ));
+    }
+
+    #[test]
+    fn active_bets_are_ordered_by_owner() {
+        let mut contract = create_contract();
+        execute(&mut contract, RoundsOperation::CreateRound);
+        for name in ["erin", "alice", "dave", "bob", "carol"] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner: AccountOwner::from(CryptoHash::test_hash(name)),
+                amount: Amount::ONE,
+                prediction: Prediction::Up,
+                source_chain_id: None,
+            });
+        }
+
+        let owners = |contract: &mut RoundsContract| {
+            let RoundsResponse::ActiveBets(bets) = execute(contract, RoundsOperation::GetActiveBets) else {
+                panic!("GetActiveBets should return the active bets");
+            };
+            bets.into_iter().map(|bet| bet.owner).collect::<Vec<_>>()
+        };
+        let first = owners(&mut contract);
+        assert_eq!(first.len(), 5);
+        assert!(first.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(owners(&mut contract), first);
+    }
+
+    fn bet(contract: &mut RoundsContract, owner: AccountOwner, tokens: u128, prediction: Prediction, now: u64) -> Result<(), String> {
+        contract.state
+            .place_bet(owner, Amount::from_tokens(tokens), prediction, None, now)
+            .now_or_never()
+            .unwrap()
+            .map(|_burned| ())
+    }
+
+    #[test]
+    fn spend_limit_caps_both_sides_and_enforces_cooldown() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        contract.runtime.set_authenticated_signer(Some(alice));
+
+        execute(&mut contract, RoundsOperation::CreateRound);
+        execute(&mut contract, RoundsOperation::SetMySpendLimit {
+            per_round: Some(Amount::from_tokens(10)),
+            cooldown_micros: Some(100),
+        });
+
+        bet(&mut contract, alice, 6, Prediction::Up, 1_000).unwrap();
+        // Within the cooldown
+        assert!(bet(&mut contract, alice, 1, Prediction::Down, 1_050).is_err());
+        // Cap is cumulative across outcomes: 6 + 5 > 10
+        assert!(bet(&mut contract, alice, 5, Prediction::Down, 1_100).is_err());
+        bet(&mut contract, alice, 4, Prediction::Down, 1_100).unwrap();
+    }
+
+    #[test]
+    fn spend_limit_removal_is_delayed() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        contract.runtime.set_authenticated_signer(Some(alice));
+
+        execute(&mut contract, RoundsOperation::CreateRound);
+        execute(&mut contract, RoundsOperation::SetMySpendLimit {
+            per_round: Some(Amount::from_tokens(1)),
+            cooldown_micros: None,
+        });
+        execute(&mut contract, RoundsOperation::SetMySpendLimit { per_round: None, cooldown_micros: None });
+
+        // Still enforced until the delay has passed
+        assert!(bet(&mut contract, alice, 2, Prediction::Up, 2_000).is_err());
+        let removal_at = 1_000 + rounds::SPEND_LIMIT_REMOVAL_DELAY_MICROS;
+        bet(&mut contract, alice, 2, Prediction::Up, removal_at).unwrap();
+        assert!(contract.state.get_spend_limit(alice, removal_at).now_or_never().unwrap().unwrap().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "only be tightened")]
+    fn spend_limit_cannot_be_loosened_directly() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        contract.runtime.set_authenticated_signer(Some(alice));
+
+        execute(&mut contract, RoundsOperation::SetMySpendLimit {
+            per_round: Some(Amount::from_tokens(1)),
+            cooldown_micros: None,
+        });
+        execute(&mut contract, RoundsOperation::SetMySpendLimit {
+            per_round: Some(Amount::from_tokens(100)),
+            cooldown_micros: None,
+        });
+    }
+
+    #[test]
+    fn resolved_winners_are_added_to_feed() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        for (owner, prediction) in [(alice, Prediction::Up), (bob, Prediction::Down)] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner,
+                amount: Amount::from_tokens(10),
+                prediction,
+                source_chain_id: None,
+            });
+        }
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+        contract.state
+            .resolve_round_and_distribute_rewards(round_id, Amount::from_tokens(110), 2_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let feed = contract.state.winner_feed.recent(10).now_or_never().unwrap().unwrap();
+        assert_eq!(feed, vec![FeedEntry {
+            game: Game::Prediction,
+            owner: alice,
+            amount: Amount::from_tokens(20),
+            round_id,
+            timestamp: 2_000,
+            chain_id: None,
+        }]);
+    }
+
+    #[test]
+    fn winners_split_the_losing_side_in_proportion_to_their_stakes() {
+        let mut contract = create_contract();
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        let up_bettors: Vec<_> = (0..10)
+            .map(|index| AccountOwner::from(CryptoHash::test_hash(format!("up-{}", index))))
+            .collect();
+        for owner in &up_bettors {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner: *owner,
+                amount: Amount::ONE,
+                prediction: Prediction::Up,
+                source_chain_id: None,
+            });
+        }
+        for name in ["down-0", "down-1"] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner: AccountOwner::from(CryptoHash::test_hash(name)),
+                amount: Amount::from_tokens(5),
+                prediction: Prediction::Down,
+                source_chain_id: None,
+            });
+        }
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+        contract.state
+            .resolve_round_and_distribute_rewards(round_id, Amount::from_tokens(110), 2_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        // Each up-bettor gets their stake back plus a tenth of the 10 tokens lost on Down
+        let winners = contract.state.get_round_winners(round_id).now_or_never().unwrap().unwrap();
+        assert_eq!(winners.len(), 10);
+        assert!(winners.iter().all(|(owner, stake, winnings, _)| {
+            up_bettors.contains(owner) && *stake == Amount::ONE && *winnings == Amount::from_tokens(2)
+        }));
+    }
+
+    #[test]
+    fn history_limit_prunes_oldest_resolved_rounds_and_their_bets() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bet_and_close = |contract: &mut RoundsContract| {
+            execute(contract, RoundsOperation::PlaceBet {
+                owner: alice,
+                amount: Amount::ONE,
+                prediction: Prediction::Up,
+                source_chain_id: None,
+            });
+            execute(contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+        };
+
+        execute(&mut contract, RoundsOperation::SetMaxHistoryRounds { max_rounds: Some(2) });
+        execute(&mut contract, RoundsOperation::CreateRound);
+        for round_id in 1..=4 {
+            bet_and_close(&mut contract);
+            contract.state
+                .resolve_round_and_distribute_rewards(round_id, Amount::from_tokens(110), 2_000)
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+        }
+        let round_ids = |contract: &RoundsContract| {
+            let mut ids = contract.state.rounds.indices().now_or_never().unwrap().unwrap();
+            ids.sort_unstable();
+            ids
+        };
+        let resolved_bet_rounds = |contract: &RoundsContract| {
+            let mut ids: Vec<u64> = contract.state.resolved_bets.indices().now_or_never().unwrap().unwrap()
+                .into_iter()
+                .map(|(round_id, _)| round_id)
+                .collect();
+            ids.sort_unstable();
+            ids
+        };
+        assert_eq!(round_ids(&contract), vec![3, 4, 5]);
+        assert_eq!(resolved_bet_rounds(&contract), vec![3, 4]);
+
+        // Round 5 is closed but unresolved, so a tighter limit leaves it alone
+        bet_and_close(&mut contract);
+        execute(&mut contract, RoundsOperation::SetMaxHistoryRounds { max_rounds: Some(1) });
+        assert_eq!(round_ids(&contract), vec![4, 5, 6]);
+        assert_eq!(resolved_bet_rounds(&contract), vec![4]);
+        assert!(contract.state.closed_bets.contains_key(&(5, alice)).now_or_never().unwrap().unwrap());
+        let resolved = execute(&mut contract, RoundsOperation::GetRoundsByStatus { status: RoundStatus::Resolved, limit: 10 });
+        assert!(matches!(resolved, RoundsResponse::PredictionRounds(rounds) if rounds.len() == 1 && rounds[0].id == 4));
+    }
+
+    #[test]
+    fn history_limit_keeps_rounds_that_still_owe_bettors() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let play_round = |contract: &mut RoundsContract, round_id: u64, resolution_price: u128, resolved_at: u64| {
+            bet(contract, alice, 1, Prediction::Up, resolved_at).unwrap();
+            bet(contract, bob, 1, Prediction::Down, resolved_at).unwrap();
+            contract.state.close_round(Amount::from_tokens(100), resolved_at).now_or_never().unwrap().unwrap();
+            contract.state
+                .resolve_round_and_distribute_rewards(round_id, Amount::from_tokens(resolution_price), resolved_at)
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+        };
+        let resolved_rounds = |contract: &RoundsContract| {
+            let mut ids = contract.state.round_ids_with_status(RoundStatus::Resolved).now_or_never().unwrap().unwrap();
+            ids.sort_unstable();
+            ids
+        };
+
+        contract.state.create_round(1_000).now_or_never().unwrap().unwrap();
+        // Round 1 ties, so both stakes wait to be claimed
+        play_round(&mut contract, 1, 100, 1_000);
+        // Round 2 resolves under a dispute window that is still open when round 3 resolves
+        contract.state.set_dispute_window(Some(10_000)).unwrap();
+        play_round(&mut contract, 2, 110, 2_000);
+        contract.state.set_dispute_window(None).unwrap();
+        play_round(&mut contract, 3, 110, 3_000);
+
+        contract.runtime.set_system_time(Timestamp::from(3_000));
+        execute(&mut contract, RoundsOperation::SetMaxHistoryRounds { max_rounds: Some(1) });
+        assert_eq!(resolved_rounds(&contract), vec![1, 2, 3]);
+        assert!(contract.state.resolved_bets.contains_key(&(1, alice)).now_or_never().unwrap().unwrap());
+        assert!(contract.state.dispute_deadline(2).now_or_never().unwrap().unwrap().is_some());
+
+        // Once the refunds are claimed and the window has closed, both rounds are pruned
+        for owner in [alice, bob] {
+            contract.state.claim_winnings(1, owner, 3_000).now_or_never().unwrap().unwrap();
+        }
+        contract.state.claim_winnings(2, alice, 12_000).now_or_never().unwrap().unwrap();
+        contract.runtime.set_system_time(Timestamp::from(12_000));
+        execute(&mut contract, RoundsOperation::SetMaxHistoryRounds { max_rounds: Some(1) });
+        assert_eq!(resolved_rounds(&contract), vec![3]);
+        assert!(contract.state.dispute_deadline(2).now_or_never().unwrap().unwrap().is_none());
+    }
+
+    #[test]
+    fn dust_bets_are_not_reported_to_the_leaderboard() {
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let recorder = updates.clone();
+        let winza_app_id = ApplicationId::new(CryptoHash::test_hash("winza"));
+        let runtime = create_runtime().with_call_application_handler(move |_authenticated, application_id, operation| {
+            if application_id == winza_app_id {
+                bcs::to_bytes(&native_fungible_abi::ExtendedResponse::Ok).unwrap()
+            } else {
+                recorder.lock().unwrap().push(bcs::from_bytes::<leaderboard::Operation>(&operation).unwrap());
+                bcs::to_bytes(&leaderboard::LeaderboardResponse::Ok).unwrap()
+            }
+        });
+        let mut contract = load_contract(runtime);
+        contract.state.Winza_app_id.set(Some(winza_app_id.with_abi()));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let carol = AccountOwner::from(CryptoHash::test_hash("carol"));
+        execute(&mut contract, RoundsOperation::SetLeaderboardMinAmount { amount: Amount::ONE });
+        sign_as_treasury(&mut contract, Amount::from_tokens(100));
+
+        execute(&mut contract, RoundsOperation::CreateRound);
+        for (owner, amount, prediction) in [
+            (alice, Amount::from_tokens(10), Prediction::Up),
+            (bob, Amount::from_tokens(10), Prediction::Down),
+            (carol, Amount::from_attos(5), Prediction::Down),
+        ] {
+            execute(&mut contract, RoundsOperation::PlaceBet { owner, amount, prediction, source_chain_id: None });
+        }
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+
+        let RoundsResponse::RoundResolved { winners, leaderboard_updates_sent, leaderboard_updates_skipped } =
+            execute(&mut contract, RoundsOperation::ResolveRound { round_id: None, resolution_price: Amount::from_tokens(110) })
+        else {
+            panic!("ResolveRound should report the resolution");
+        };
+        assert_eq!(winners.iter().map(|winner| winner.owner).collect::<Vec<_>>(), vec![alice]);
+        // Carol's 5 atto loss is below the 1 token threshold
+        assert_eq!((leaderboard_updates_sent, leaderboard_updates_skipped), (2, 1));
+        let mut reported: Vec<AccountOwner> = updates.lock().unwrap().iter()
+            .map(|operation| match operation {
+                leaderboard::Operation::UpdateScore { owner, .. } => *owner,
+                other => panic!("Unexpected leaderboard operation {:?}", other),
+            })
+            .collect();
+        reported.sort();
+        let mut expected = vec![alice, bob];
+        expected.sort();
+        assert_eq!(reported, expected);
+    }
+
+    #[test]
+    fn subscribed_chains_receive_round_results() {
+        let winza_app_id = ApplicationId::new(CryptoHash::test_hash("winza"));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let runtime = create_runtime()
+            .with_authenticated_signer(alice)
+            .with_owner_balances([(alice, Amount::from_tokens(1_000))])
+            .with_call_application_handler(move |_authenticated, application_id, _operation| {
+                if application_id == winza_app_id {
+                    bcs::to_bytes(&native_fungible_abi::ExtendedResponse::Ok).unwrap()
+                } else {
+                    bcs::to_bytes(&leaderboard::LeaderboardResponse::Ok).unwrap()
+                }
+            });
+        let mut contract = load_contract(runtime);
+        contract.state.Winza_app_id.set(Some(winza_app_id.with_abi()));
+        let home = ChainId(CryptoHash::test_hash("home"));
+        execute(&mut contract, RoundsOperation::SubscribeResults { chain_id: home });
+
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        for (owner, prediction) in [(alice, Prediction::Up), (bob, Prediction::Down)] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner,
+                amount: Amount::from_tokens(10),
+                prediction,
+                source_chain_id: None,
+            });
+        }
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+        sign_as_treasury(&mut contract, Amount::from_tokens(100));
+        execute(&mut contract, RoundsOperation::ResolveRound { round_id: None, resolution_price: Amount::from_tokens(110) });
+
+        let expected = RoundResolvedNotice {
+            round_id,
+            result: Some(Prediction::Up),
+            closing_price: Some(Amount::from_tokens(100)),
+            resolution_price: Some(Amount::from_tokens(110)),
+            prize_pool: Amount::from_tokens(20),
+        };
+        {
+            let messages = contract.runtime.created_send_message_requests();
+            let notices: Vec<_> = messages.iter()
+                .filter_map(|request| match &request.message {
+                    Message::RoundResolvedNotice(notice) => Some((request.destination, notice.clone())),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(notices, vec![(home, expected.clone())]);
+        }
+
+        // On the subscribed chain only the latest RECENT_RESULTS_CAPACITY notices are kept
+        for id in 0..RECENT_RESULTS_CAPACITY + 2 {
+            let notice = RoundResolvedNotice { round_id: id, ..expected.clone() };
+            contract.execute_message(Message::RoundResolvedNotice(notice)).now_or_never().unwrap();
+        }
+        let recent = contract.state.recent_results(u64::MAX).now_or_never().unwrap().unwrap();
+        assert_eq!(recent.len() as u64, RECENT_RESULTS_CAPACITY);
+        assert_eq!(recent[0].round_id, RECENT_RESULTS_CAPACITY + 1);
+        assert_eq!(recent.last().unwrap().round_id, 2);
+    }
+
+    #[test]
+    fn close_and_resolve_settles_the_round_in_one_call() {
+        let rewards = Arc::new(Mutex::new(Vec::new()));
+        let recorder = rewards.clone();
+        let winza_app_id = ApplicationId::new(CryptoHash::test_hash("winza"));
+        let runtime = create_runtime().with_call_application_handler(move |_authenticated, application_id, operation| {
+            if application_id == winza_app_id {
+                recorder.lock().unwrap().push(bcs::from_bytes(&operation).unwrap());
+                bcs::to_bytes(&native_fungible_abi::ExtendedResponse::Ok).unwrap()
+            } else {
+                bcs::to_bytes(&leaderboard::LeaderboardResponse::Ok).unwrap()
+            }
+        });
+        let mut contract = load_contract(runtime);
+        contract.state.Winza_app_id.set(Some(winza_app_id.with_abi()));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        for (owner, prediction) in [(alice, Prediction::Up), (bob, Prediction::Down)] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner,
+                amount: Amount::from_tokens(10),
+                prediction,
+                source_chain_id: None,
+            });
+        }
+
+        sign_as_treasury(&mut contract, Amount::from_tokens(100));
+        let RoundsResponse::RoundId(new_round_id) = execute(&mut contract, RoundsOperation::CloseAndResolve {
+            closing_price: Amount::from_tokens(100),
+            resolution_price: Amount::from_tokens(110),
+        }) else {
+            panic!("CloseAndResolve should return the new round ID");
+        };
+
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.status, RoundStatus::Resolved);
+        assert_eq!(round.result, Some(Prediction::Up));
+        let successor = contract.state.get_round(new_round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(successor.status, RoundStatus::Active);
+        assert_eq!(contract.state.get_active_round().now_or_never().unwrap().unwrap(), Some(new_round_id));
+
+        let rewards = rewards.lock().unwrap();
+        assert_eq!(rewards.len(), 1);
+        assert!(matches!(
+            &rewards[0],
+            native_fungible_abi::ExtendedOperation::SendReward { recipient, amount, .. }
+                if *recipient == alice && *amount == Amount::from_tokens(20)
+        ));
+    }
+
+    #[test]
+    fn direct_bet_pulls_the_stake_through_the_allowance() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        let rounds_app_id = ApplicationId::new(CryptoHash::test_hash("rounds"));
+        let spender = AccountOwner::from(rounds_app_id);
+        // A native app holding Alice's allowance for the Rounds app
+        let allowance = Arc::new(Mutex::new(Amount::from_tokens(10)));
+        let pulled = Arc::new(Mutex::new(Vec::new()));
+        let (native_allowance, native_pulled) = (allowance.clone(), pulled.clone());
+        let runtime = create_runtime()
+            .with_application_id(rounds_app_id.with_abi())
+            .with_authenticated_signer(alice)
+            .with_call_application_handler(move |authenticated, _application_id, operation| {
+                assert!(authenticated, "TransferFrom only trusts authenticated callers");
+                let response = match bcs::from_bytes(&operation).unwrap() {
+                    native::NativeOperation::Allowance { owner, spender: caller } => {
+                        assert_eq!((owner, caller), (alice, spender));
+                        native::NativeResponse::Allowance(*native_allowance.lock().unwrap())
+                    }
+                    native::NativeOperation::TransferFrom { from, to, amount } => {
+                        let mut allowance = native_allowance.lock().unwrap();
+                        *allowance = allowance.try_sub(amount).expect("TransferFrom exceeds the allowance");
+                        native_pulled.lock().unwrap().push((from, to.owner, amount));
+                        native::NativeResponse::Ok
+                    }
+                    other => panic!("Unexpected native operation {:?}", other),
+                };
+                bcs::to_bytes(&response).unwrap()
+            });
+        let mut contract = load_contract(runtime);
+        contract.state.admin.set(Some(admin));
+        contract.state.set_burn_fee_bps(100).unwrap();
+        contract.runtime.set_authenticated_signer(Some(admin));
+        execute(&mut contract, RoundsOperation::CreateRound);
+
+        contract.runtime.set_authenticated_signer(Some(alice));
+        execute(&mut contract, RoundsOperation::PlaceBetDirect { amount: Amount::from_tokens(10), prediction: Prediction::Up });
+
+        // The whole stake goes to the treasury, which defaults to the admin, before the bet counts
+        let burned = Amount::from_tokens(10).saturating_sub(Amount::from_millis(9_900));
+        assert_eq!(*pulled.lock().unwrap(), vec![(alice, admin, Amount::from_tokens(10))]);
+        assert_eq!(*allowance.lock().unwrap(), Amount::ZERO);
+        let bet = contract.state.active_bets.get(&alice).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(bet.amount_on(Prediction::Up), Amount::from_millis(9_900));
+        let round = contract.state.get_round(1).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!((round.prize_pool, round.total_burned_this_round), (Amount::from_millis(9_900), burned));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not cover")]
+    fn direct_bet_without_allowance_is_rejected() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let runtime = create_runtime()
+            .with_application_id(ApplicationId::new(CryptoHash::test_hash("rounds")).with_abi())
+            .with_authenticated_signer(alice)
+            .with_call_application_handler(|_authenticated, _application_id, _operation| {
+                bcs::to_bytes(&native::NativeResponse::Allowance(Amount::ONE)).unwrap()
+            });
+        let mut contract = load_contract(runtime);
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        contract.state.admin.set(Some(admin));
+        contract.runtime.set_authenticated_signer(Some(admin));
+        execute(&mut contract, RoundsOperation::CreateRound);
+        contract.runtime.set_authenticated_signer(Some(alice));
+        execute(&mut contract, RoundsOperation::PlaceBetDirect { amount: Amount::from_tokens(10), prediction: Prediction::Up });
+    }
+
+    #[test]
+    fn slippage_protection_turns_away_bets_that_move_the_odds_too_far() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let carol = AccountOwner::from(CryptoHash::test_hash("carol"));
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        let (mut contract, pulled) = create_allowance_contract(Amount::from_tokens(100));
+        contract.state.admin.set(Some(admin));
+        contract.runtime.set_authenticated_signer(Some(admin));
+        execute(&mut contract, RoundsOperation::CreateRound);
+        for (owner, prediction) in [(alice, Prediction::Up), (bob, Prediction::Down)] {
+            execute(&mut contract, RoundsOperation::PlaceBet { owner, amount: Amount::from_tokens(10), prediction, source_chain_id: None });
+        }
+        contract.runtime.set_authenticated_signer(Some(carol));
+
+        // 30 more on Up turns the 50/50 split into 80/20: a 3000 bps move
+        let bet = |max_slippage_bps| RoundsOperation::PlaceBetWithSlippage {
+            amount: Amount::from_tokens(30),
+            prediction: Prediction::Up,
+            max_slippage_bps,
+        };
+        let response = execute(&mut contract, bet(2_999));
+        assert!(matches!(
+            response,
+            RoundsResponse::BetRejected(StateError::SlippageExceeded { expected_bps: 5_000, actual_bps: 8_000 })
+        ));
+        // Nothing was taken, so there is nothing to refund
+        assert!(pulled.lock().unwrap().is_empty());
+        let round = contract.state.get_round(1).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.prize_pool, Amount::from_tokens(20));
+        assert!(contract.state.active_bets.get(&carol).now_or_never().unwrap().unwrap().is_none());
+
+        assert!(matches!(execute(&mut contract, bet(3_000)), RoundsResponse::Ok));
+        assert_eq!(*pulled.lock().unwrap(), vec![(carol, admin, Amount::from_tokens(30))]);
+        let round = contract.state.get_round(1).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.prize_pool, Amount::from_tokens(50));
+        assert_eq!(round.outcome_pools, vec![Amount::from_tokens(40), Amount::from_tokens(10)]);
+    }
+
+    #[test]
+    fn claim_winnings_refunds_ties_and_never_pays_twice() {
+        let rewards = Arc::new(Mutex::new(Vec::new()));
+        let recorder = rewards.clone();
+        let winza_app_id = ApplicationId::new(CryptoHash::test_hash("winza"));
+        let runtime = create_runtime().with_call_application_handler(move |_authenticated, application_id, operation| {
+            if application_id == winza_app_id {
+                recorder.lock().unwrap().push(bcs::from_bytes(&operation).unwrap());
+                bcs::to_bytes(&native_fungible_abi::ExtendedResponse::Ok).unwrap()
+            } else {
+                bcs::to_bytes(&leaderboard::LeaderboardResponse::Ok).unwrap()
+            }
+        });
+        let mut contract = load_contract(runtime);
+        contract.state.Winza_app_id.set(Some(winza_app_id.with_abi()));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let treasury = sign_as_treasury(&mut contract, Amount::from_tokens(100));
+        let play_round = |contract: &mut RoundsContract, resolution_price: u128| {
+            contract.runtime.set_authenticated_signer(Some(treasury));
+            let round_id = contract.state.get_active_round().now_or_never().unwrap().unwrap()
+                .expect("A round should be active");
+            for (owner, prediction) in [(alice, Prediction::Up), (bob, Prediction::Down)] {
+                execute(contract, RoundsOperation::PlaceBet {
+                    owner,
+                    amount: Amount::from_tokens(10),
+                    prediction,
+                    source_chain_id: None,
+                });
+            }
+            execute(contract, RoundsOperation::CloseAndResolve {
+                closing_price: Amount::from_tokens(100),
+                resolution_price: Amount::from_tokens(resolution_price),
+            });
+            round_id
+        };
+        let claim = |contract: &mut RoundsContract, owner: AccountOwner, round_id: u64| {
+            contract.runtime.set_authenticated_signer(Some(owner));
+            execute(contract, RoundsOperation::ClaimWinnings { round_id })
+        };
+
+        execute(&mut contract, RoundsOperation::CreateRound);
+        let won = play_round(&mut contract, 110);
+        assert_eq!(rewards.lock().unwrap().len(), 1, "Alice's winnings are paid on resolution");
+        assert!(matches!(claim(&mut contract, alice, won), RoundsResponse::NothingToClaim));
+        assert!(matches!(claim(&mut contract, bob, won), RoundsResponse::NothingToClaim));
+
+        let tied = play_round(&mut contract, 100);
+        assert_eq!(rewards.lock().unwrap().len(), 1, "A tie pays nothing on resolution");
+        assert!(matches!(claim(&mut contract, alice, tied), RoundsResponse::Ok));
+        assert!(matches!(claim(&mut contract, alice, tied), RoundsResponse::NothingToClaim));
+        assert_eq!(rewards.lock().unwrap().len(), 1, "A claim only queues the refund");
+
+        // The refund is paid from the treasury, which signs the payout
+        contract.runtime.set_authenticated_signer(Some(treasury));
+        assert!(matches!(
+            execute(&mut contract, RoundsOperation::PayQueuedClaims { limit: 10 }),
+            RoundsResponse::ClaimsPaid { paid: 1, remaining: 0 }
+        ));
+        let rewards = rewards.lock().unwrap();
+        assert_eq!(rewards.len(), 2);
+        assert!(matches!(
+            &rewards[1],
+            native_fungible_abi::ExtendedOperation::SendReward { recipient, amount, .. }
+                if *recipient == alice && *amount == Amount::from_tokens(10)
+        ));
+    }
+
+    /// A contract whose resolved round `round_id` tied, with alice's 10 token refund claimed
+    fn contract_with_a_queued_tie_refund() -> (RoundsContract, Arc<Mutex<Vec<native_fungible_abi::ExtendedOperation>>>, AccountOwner) {
+        let rewards = Arc::new(Mutex::new(Vec::new()));
+        let recorder = rewards.clone();
+        let winza_app_id = ApplicationId::new(CryptoHash::test_hash("winza"));
+        let runtime = create_runtime().with_call_application_handler(move |_authenticated, application_id, operation| {
+            assert_eq!(application_id, winza_app_id);
+            recorder.lock().unwrap().push(bcs::from_bytes(&operation).unwrap());
+            bcs::to_bytes(&native_fungible_abi::ExtendedResponse::Ok).unwrap()
+        });
+        let mut contract = load_contract(runtime);
+        contract.state.Winza_app_id.set(Some(winza_app_id.with_abi()));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let round_id = contract.state.create_round(1_000).now_or_never().unwrap().unwrap();
+        bet(&mut contract, alice, 10, Prediction::Up, 1_000).unwrap();
+        bet(&mut contract, bob, 10, Prediction::Down, 1_000).unwrap();
+        contract.state.close_round(Amount::from_tokens(100), 2_000).now_or_never().unwrap().unwrap();
+        contract.state
+            .resolve_round_and_distribute_rewards(round_id, Amount::from_tokens(100), 3_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        contract.runtime.set_authenticated_signer(Some(alice));
+        assert!(matches!(execute(&mut contract, RoundsOperation::ClaimWinnings { round_id }), RoundsResponse::Ok));
+        (contract, rewards, alice)
+    }
+
+    #[test]
+    #[should_panic(expected = "Payouts must be signed by the treasury")]
+    fn claimants_cannot_pay_their_own_claims() {
+        let (mut contract, _rewards, _alice) = contract_with_a_queued_tie_refund();
+        sign_as_treasury(&mut contract, Amount::from_tokens(100));
+
+        contract.runtime.set_authenticated_signer(Some(AccountOwner::from(CryptoHash::test_hash("alice"))));
+        execute(&mut contract, RoundsOperation::PayQueuedClaims { limit: 10 });
+    }
+
+    #[test]
+    fn queued_claims_wait_until_the_treasury_covers_them() {
+        let (mut contract, rewards, alice) = contract_with_a_queued_tie_refund();
+        assert!(rewards.lock().unwrap().is_empty());
+
+        sign_as_treasury(&mut contract, Amount::from_tokens(5));
+        assert!(matches!(
+            execute(&mut contract, RoundsOperation::PayQueuedClaims { limit: 10 }),
+            RoundsResponse::ClaimsPaid { paid: 0, remaining: 1 }
+        ));
+        assert!(rewards.lock().unwrap().is_empty());
+
+        sign_as_treasury(&mut contract, Amount::from_tokens(10));
+        assert!(matches!(
+            execute(&mut contract, RoundsOperation::PayQueuedClaims { limit: 10 }),
+            RoundsResponse::ClaimsPaid { paid: 1, remaining: 0 }
+        ));
+        assert!(matches!(
+            rewards.lock().unwrap().as_slice(),
+            [native_fungible_abi::ExtendedOperation::SendReward { recipient, amount, .. }]
+                if *recipient == alice && *amount == Amount::from_tokens(10)
+        ));
+    }
+
+    #[test]
+    fn tie_refunds_count_toward_the_resolution_payout() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        sign_as_treasury(&mut contract, Amount::from_tokens(5));
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        bet(&mut contract, alice, 10, Prediction::Up, 1_000).unwrap();
+        bet(&mut contract, bob, 10, Prediction::Down, 1_000).unwrap();
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+
+        // Both stakes go back on a tie, which the treasury's 5 tokens cannot cover
+        let pending = execute(&mut contract, RoundsOperation::ResolveRound { round_id: None, resolution_price: Amount::from_tokens(100) });
+        assert!(matches!(
+            pending,
+            RoundsResponse::ResolutionPending { round_id: id, payout, shortfall }
+                if id == round_id && payout == Amount::from_tokens(20) && shortfall == Amount::from_tokens(15)
+        ));
+    }
+
+    #[test]
+    fn wrong_resolution_is_corrected_within_the_dispute_window() {
+        let rewards = Arc::new(Mutex::new(Vec::new()));
+        let recorder = rewards.clone();
+        let winza_app_id = ApplicationId::new(CryptoHash::test_hash("winza"));
+        let runtime = create_runtime().with_call_application_handler(move |_authenticated, application_id, operation| {
+            if application_id == winza_app_id {
+                recorder.lock().unwrap().push(bcs::from_bytes(&operation).unwrap());
+                bcs::to_bytes(&native_fungible_abi::ExtendedResponse::Ok).unwrap()
+            } else {
+                bcs::to_bytes(&leaderboard::LeaderboardResponse::Ok).unwrap()
+            }
+        });
+        let mut contract = load_contract(runtime);
+        contract.state.Winza_app_id.set(Some(winza_app_id.with_abi()));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+
+        execute(&mut contract, RoundsOperation::SetDisputeWindow { window_micros: Some(10_000) });
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        for (owner, prediction) in [(alice, Prediction::Up), (bob, Prediction::Down)] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner,
+                amount: Amount::from_tokens(10),
+                prediction,
+                source_chain_id: None,
+            });
+        }
+        // The oracle reports a rise that did not happen
+        let treasury = sign_as_treasury(&mut contract, Amount::from_tokens(100));
+        execute(&mut contract, RoundsOperation::CloseAndResolve {
+            closing_price: Amount::from_tokens(100),
+            resolution_price: Amount::from_tokens(110),
+        });
+        assert!(rewards.lock().unwrap().is_empty(), "Rewards wait for the dispute window");
+        assert!(contract.state.claim_winnings(round_id, alice, 5_000).now_or_never().unwrap().is_err());
+
+        contract.runtime.set_system_time(Timestamp::from(5_000));
+        let RoundsResponse::RoundResolved { winners, .. } = execute(&mut contract, RoundsOperation::ReResolve {
+            round_id,
+            corrected_price: Amount::from_tokens(90),
+        }) else {
+            panic!("ReResolve should report the corrected resolution");
+        };
+        assert_eq!(winners.iter().map(|winner| winner.owner).collect::<Vec<_>>(), vec![bob]);
+        let RoundsResponse::ResolutionHistory(history) = execute(&mut contract, RoundsOperation::GetResolutionHistory { round_id }) else {
+            panic!("GetResolutionHistory should return the history");
+        };
+        assert_eq!(
+            history.iter().map(|record| (record.resolved_at, record.resolution_price, record.result)).collect::<Vec<_>>(),
+            vec![
+                (1_000, Some(Amount::from_tokens(110)), Some(Prediction::Up)),
+                (5_000, Some(Amount::from_tokens(90)), Some(Prediction::Down)),
+            ],
+        );
+
+        // Once the window has passed, only the corrected winner is paid
+        contract.runtime.set_system_time(Timestamp::from(11_000));
+        for (owner, expected_claim) in [(alice, false), (bob, true)] {
+            contract.runtime.set_authenticated_signer(Some(owner));
+            let response = execute(&mut contract, RoundsOperation::ClaimWinnings { round_id });
+            assert_eq!(matches!(response, RoundsResponse::Ok), expected_claim);
+        }
+        assert!(rewards.lock().unwrap().is_empty(), "Bob's claim is not paid from his own account");
+
+        // Only the treasury, which received the stakes, pays the claim out
+        contract.runtime.set_authenticated_signer(Some(treasury));
+        assert!(matches!(
+            execute(&mut contract, RoundsOperation::PayQueuedClaims { limit: 10 }),
+            RoundsResponse::ClaimsPaid { paid: 1, remaining: 0 }
+        ));
+        let paid = rewards.lock().unwrap();
+        assert_eq!(paid.len(), 1);
+        assert!(matches!(
+            &paid[0],
+            native_fungible_abi::ExtendedOperation::SendReward { recipient, amount, .. }
+                if *recipient == bob && *amount == Amount::from_tokens(20)
+        ));
+        assert!(contract.state.re_resolve(round_id, Amount::from_tokens(110), 11_000).now_or_never().unwrap().is_err());
+    }
+
+    #[test]
+    fn round_net_flow_accounts_for_every_atto_staked() {
+        let mut contract = create_contract();
+        contract.state.set_burn_fee_bps(300).unwrap();
+        let [alice, bob, carol] = ["alice", "bob", "carol"].map(|name| AccountOwner::from(CryptoHash::test_hash(name)));
+        let round_id = contract.state.create_round(1_000).now_or_never().unwrap().unwrap();
+        for (owner, tokens, prediction) in [(alice, 1, Prediction::Up), (bob, 2, Prediction::Up), (carol, 10, Prediction::Down)] {
+            contract.state
+                .place_bet(owner, Amount::from_tokens(tokens), prediction, None, 1_000)
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+        }
+        contract.state.close_round(Amount::from_tokens(100), 2_000).now_or_never().unwrap().unwrap();
+        contract.state
+            .resolve_round_and_distribute_rewards(round_id, Amount::from_tokens(110), 3_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let RoundsResponse::RoundNetFlow(flow) = execute(&mut contract, RoundsOperation::GetRoundNetFlow { round_id }) else {
+            panic!("GetRoundNetFlow should return the net flow");
+        };
+        // 3% of 13 tokens is burned; splitting the rest a third and two thirds leaves one atto
+        assert_eq!(flow.fees_taken, Amount::from_millis(390));
+        assert_eq!(flow.prize_pool, Amount::from_millis(12_610));
+        assert_eq!(flow.dust_retained, Amount::from_attos(1));
+        assert_eq!(flow.total_paid.saturating_add(flow.dust_retained), flow.prize_pool);
+        assert_eq!(flow.total_paid.saturating_add(flow.fees_taken).saturating_add(flow.dust_retained), flow.total_staked);
+        assert_eq!(flow.total_staked, Amount::from_tokens(13));
+    }
+
+    #[test]
+    fn underfunded_treasury_leaves_the_round_pending() {
+        let rewards = Arc::new(Mutex::new(Vec::new()));
+        let recorder = rewards.clone();
+        let winza_app_id = ApplicationId::new(CryptoHash::test_hash("winza"));
+        let runtime = create_runtime().with_call_application_handler(move |_authenticated, application_id, operation| {
+            if application_id == winza_app_id {
+                recorder.lock().unwrap().push(bcs::from_bytes::<native_fungible_abi::ExtendedOperation>(&operation).unwrap());
+                bcs::to_bytes(&native_fungible_abi::ExtendedResponse::Ok).unwrap()
+            } else {
+                bcs::to_bytes(&leaderboard::LeaderboardResponse::Ok).unwrap()
+            }
+        });
+        let mut contract = load_contract(runtime);
+        contract.state.Winza_app_id.set(Some(winza_app_id.with_abi()));
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        contract.state.admin.set(Some(admin));
+        contract.runtime.set_authenticated_signer(Some(admin));
+        contract.runtime.set_owner_balance(admin, Amount::from_tokens(5));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        for (owner, prediction) in [(alice, Prediction::Up), (bob, Prediction::Down)] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner,
+                amount: Amount::from_tokens(10),
+                prediction,
+                source_chain_id: None,
+            });
+        }
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+
+        // Alice is owed 20 tokens but the treasury only holds 5: nobody is paid
+        let pending = execute(&mut contract, RoundsOperation::ResolveRound { round_id: None, resolution_price: Amount::from_tokens(110) });
+        assert!(matches!(
+            pending,
+            RoundsResponse::ResolutionPending { round_id: id, payout, shortfall }
+                if id == round_id && payout == Amount::from_tokens(20) && shortfall == Amount::from_tokens(15)
+        ));
+        assert!(rewards.lock().unwrap().is_empty());
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.status, RoundStatus::ResolutionPending);
+        assert_eq!((round.result, round.resolution_price), (None, Some(Amount::from_tokens(110))));
+        assert!(contract.state.closed_bets.contains_key(&(round_id, alice)).now_or_never().unwrap().unwrap());
+        let pending_count = contract.state.metrics.get(&"resolution.pending".to_string()).now_or_never().unwrap().unwrap();
+        assert_eq!(pending_count, Some(1));
+        // A pending round is not picked up again by ResolveRound
+        let pending_rounds = execute(&mut contract, RoundsOperation::GetRoundsByStatus { status: RoundStatus::ResolutionPending, limit: 10 });
+        assert!(matches!(pending_rounds, RoundsResponse::PredictionRounds(rounds) if rounds.len() == 1));
+
+        contract.runtime.set_owner_balance(admin, Amount::from_tokens(20));
+        let RoundsResponse::RoundResolved { winners, .. } = execute(&mut contract, RoundsOperation::ResumeResolution { round_id }) else {
+            panic!("ResumeResolution should report the resolution");
+        };
+        assert_eq!(winners.iter().map(|winner| winner.owner).collect::<Vec<_>>(), vec![alice]);
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.status, RoundStatus::Resolved);
+        assert_eq!(round.result, Some(Prediction::Up));
+        let rewards = rewards.lock().unwrap();
+        assert!(matches!(
+            rewards.as_slice(),
+            [native_fungible_abi::ExtendedOperation::SendReward { recipient, amount, .. }]
+                if *recipient == alice && *amount == Amount::from_tokens(20)
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "Payouts must be signed by the treasury")]
+    fn payout_without_the_treasury_signature_is_refused() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        sign_as_treasury(&mut contract, Amount::from_tokens(100));
+        execute(&mut contract, RoundsOperation::CreateRound);
+        bet(&mut contract, alice, 10, Prediction::Up, 1_000).unwrap();
+        bet(&mut contract, bob, 10, Prediction::Down, 1_000).unwrap();
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+
+        // The treasury's balance says nothing about what another signer can pay
+        contract.runtime.set_authenticated_signer(None);
+        execute(&mut contract, RoundsOperation::ResolveRound { round_id: None, resolution_price: Amount::from_tokens(110) });
+    }
+
+    #[test]
+    fn pending_resolution_locks_out_other_rounds_until_it_finishes() {
+        let rewards = Arc::new(Mutex::new(Vec::new()));
+        let recorder = rewards.clone();
+        let winza_app_id = ApplicationId::new(CryptoHash::test_hash("winza"));
+        let runtime = create_runtime().with_call_application_handler(move |_authenticated, application_id, operation| {
+            if application_id == winza_app_id {
+                recorder.lock().unwrap().push(bcs::from_bytes::<native_fungible_abi::ExtendedOperation>(&operation).unwrap());
+                bcs::to_bytes(&native_fungible_abi::ExtendedResponse::Ok).unwrap()
+            } else {
+                bcs::to_bytes(&leaderboard::LeaderboardResponse::Ok).unwrap()
+            }
+        });
+        let mut contract = load_contract(runtime);
+        contract.state.Winza_app_id.set(Some(winza_app_id.with_abi()));
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        contract.state.admin.set(Some(admin));
+        contract.runtime.set_authenticated_signer(Some(admin));
+        contract.runtime.set_owner_balance(admin, Amount::from_tokens(5));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+
+        let RoundsResponse::RoundId(first) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        let mut closed = vec![first];
+        for _ in 0..2 {
+            for (owner, prediction) in [(alice, Prediction::Up), (bob, Prediction::Down)] {
+                execute(&mut contract, RoundsOperation::PlaceBet {
+                    owner,
+                    amount: Amount::from_tokens(10),
+                    prediction,
+                    source_chain_id: None,
+                });
+            }
+            let RoundsResponse::RoundId(next) = execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) }) else {
+                panic!("CloseRound should return the next round ID");
+            };
+            closed.push(next);
+        }
+        let (first, second) = (closed[0], closed[1]);
+
+        // The first operator's resolution stalls on the treasury and keeps the lock
+        let pending = execute(&mut contract, RoundsOperation::ResolveRound { round_id: Some(first), resolution_price: Amount::from_tokens(110) });
+        assert!(matches!(pending, RoundsResponse::ResolutionPending { round_id, .. } if round_id == first));
+        assert_eq!(*contract.state.resolution_in_progress.get(), Some(first));
+
+        // A second operator cannot resolve the other closed round in between
+        let err = contract.state
+            .resolve_round_and_distribute_rewards(second, Amount::from_tokens(110), 2_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap_err();
+        assert!(err.starts_with(&format!("Round {} is still being resolved", first)), "{}", err);
+        assert!(rewards.lock().unwrap().is_empty());
+
+        contract.runtime.set_owner_balance(admin, Amount::from_tokens(40));
+        execute(&mut contract, RoundsOperation::ResumeResolution { round_id: first });
+        assert_eq!(*contract.state.resolution_in_progress.get(), None);
+        let resolved = execute(&mut contract, RoundsOperation::ResolveRound { round_id: Some(second), resolution_price: Amount::from_tokens(110) });
+        assert!(matches!(resolved, RoundsResponse::RoundResolved { .. }));
+
+        // Re-targeting a resolved round pays nobody twice
+        let err = contract.state
+            .resolve_round_and_distribute_rewards(first, Amount::from_tokens(110), 3_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err, "Round is not closed");
+        assert_eq!(rewards.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "is still being resolved")]
+    fn resolve_round_fails_while_another_round_is_pending() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        execute(&mut contract, RoundsOperation::CreateRound);
+        for _ in 0..2 {
+            bet(&mut contract, alice, 10, Prediction::Up, 1_000).unwrap();
+            execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+        }
+        let rounds = contract.state.get_rounds_by_status(super::RoundStatus::Closed, 10).now_or_never().unwrap().unwrap();
+        contract.state.mark_resolution_pending(rounds[1].id, Amount::from_tokens(110)).now_or_never().unwrap().unwrap();
+        sign_as_treasury(&mut contract, Amount::from_tokens(100));
+
+        execute(&mut contract, RoundsOperation::ResolveRound { round_id: Some(rounds[0].id), resolution_price: Amount::from_tokens(110) });
+    }
+
+    #[test]
+    fn duplicate_create_round_keeps_active_bets() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        bet(&mut contract, alice, 10, Prediction::Up, 1_000).unwrap();
+
+        let err = contract.state.create_round(2_000).now_or_never().unwrap().unwrap_err();
+        assert_eq!(err, "1 active bets would be stranded; close the active round or use ForceClearActiveBets");
+        assert_eq!(contract.state.get_active_round().now_or_never().unwrap().unwrap(), Some(round_id));
+        let bet = contract.state.active_bets.get(&alice).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(bet.total_amount(), Amount::from_tokens(10));
+    }
+
+    #[test]
+    fn force_clear_refunds_stranded_bets() {
+        let refunds = Arc::new(Mutex::new(Vec::new()));
+        let recorder = refunds.clone();
+        let chain = ChainId(CryptoHash::test_hash("rounds"));
+        let winza_app_id = ApplicationId::new(CryptoHash::test_hash("winza"));
+        let runtime = create_runtime()
+            .with_chain_id(chain)
+            .with_application_creator_chain_id(chain)
+            .with_call_application_handler(move |_authenticated, _application_id, operation| {
+                recorder.lock().unwrap().push(bcs::from_bytes(&operation).unwrap());
+                bcs::to_bytes(&native_fungible_abi::ExtendedResponse::Ok).unwrap()
+            });
+        let mut contract = load_contract(runtime);
+        contract.state.Winza_app_id.set(Some(winza_app_id.with_abi()));
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        bet(&mut contract, alice, 10, Prediction::Up, 1_000).unwrap();
+        sign_as_treasury(&mut contract, Amount::from_tokens(10));
+        execute(&mut contract, RoundsOperation::ForceClearActiveBets);
+
+        let refunds = refunds.lock().unwrap();
+        assert_eq!(refunds.len(), 1);
+        assert!(matches!(
+            &refunds[0],
+            native_fungible_abi::ExtendedOperation::SendReward { recipient, amount, source_chain_id: None }
+                if *recipient == alice && *amount == Amount::from_tokens(10)
+        ));
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.prize_pool, Amount::ZERO);
+        assert!(matches!(execute(&mut contract, RoundsOperation::CreateRound), RoundsResponse::RoundId(id) if id == round_id + 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Payouts must be signed by the treasury")]
+    fn force_clear_refunds_must_be_signed_by_the_treasury() {
+        let mut contract = creator_chain_contract();
+        let admin = AccountOwner::from(CryptoHash::test_hash("admin"));
+        execute(&mut contract, RoundsOperation::CreateRound);
+        bet(&mut contract, AccountOwner::from(CryptoHash::test_hash("alice")), 10, Prediction::Up, 1_000).unwrap();
+        sign_as_treasury(&mut contract, Amount::from_tokens(10));
+
+        // The admin may clear the bets, but the stakes are not in its account
+        contract.state.admin.set(Some(admin));
+        contract.runtime.set_authenticated_signer(Some(admin));
+        execute(&mut contract, RoundsOperation::ForceClearActiveBets);
+    }
+
+    #[test]
+    fn winner_feed_is_capped() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+
+        for round_id in 0..WINNER_FEED_CAPACITY + 5 {
+            contract.state.winner_feed.append(FeedEntry {
+                game: Game::Lottery,
+                owner: alice,
+                amount: Amount::ONE,
+                round_id,
+                timestamp: round_id,
+                chain_id: None,
+            }).unwrap();
+        }
+
+        let feed = contract.state.winner_feed.recent(u64::MAX).now_or_never().unwrap().unwrap();
+        assert_eq!(feed.len() as u64, WINNER_FEED_CAPACITY);
+        assert_eq!(feed.first().unwrap().round_id, WINNER_FEED_CAPACITY + 4);
+        assert_eq!(feed.last().unwrap().round_id, 5);
+        assert_eq!(contract.state.winner_feed.entries.get(&4).now_or_never().unwrap().unwrap(), None);
+    }
+
+    #[test]
+    fn bet_size_histogram_spans_three_buckets() {
+        let mut contract = create_contract();
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        for (name, tokens) in [("alice", 1), ("bob", 5), ("carol", 5), ("dave", 50)] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner: AccountOwner::from(CryptoHash::test_hash(name)),
+                amount: Amount::from_tokens(tokens),
+                prediction: Prediction::Up,
+                source_chain_id: None,
+            });
+        }
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+
+        let RoundsResponse::BetSizeHistogram(histogram) = execute(&mut contract, RoundsOperation::GetBetSizeHistogram {
+            round_id,
+            buckets: vec![Amount::from_tokens(2), Amount::from_tokens(10)],
+        }) else {
+            panic!("GetBetSizeHistogram should return a histogram");
+        };
+        assert_eq!(histogram, vec![
+            BetSizeBucket { min: Amount::ZERO, max: Some(Amount::from_tokens(2)), count: 1 },
+            BetSizeBucket { min: Amount::from_tokens(2), max: Some(Amount::from_tokens(10)), count: 2 },
+            BetSizeBucket { min: Amount::from_tokens(10), max: None, count: 1 },
+        ]);
+    }
+
+    #[test]
+    fn active_round_pools_snapshot_mixed_bets() {
+        let mut contract = create_contract();
+        assert!(matches!(execute(&mut contract, RoundsOperation::GetActiveRoundPools), RoundsResponse::Pools(None)));
+
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        for (name, tokens, prediction) in [("alice", 5, Prediction::Up), ("bob", 7, Prediction::Down), ("carol", 3, Prediction::Up)] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner: AccountOwner::from(CryptoHash::test_hash(name)),
+                amount: Amount::from_tokens(tokens),
+                prediction,
+                source_chain_id: None,
+            });
+        }
+        // Alice hedges: she now has a stake on both sides
+        contract.state
+            .place_split_bet(alice, Amount::from_tokens(1), Amount::from_tokens(2), None, 1_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let RoundsResponse::Pools(Some(pools)) = execute(&mut contract, RoundsOperation::GetActiveRoundPools) else {
+            panic!("GetActiveRoundPools should return the active round's pools");
+        };
+        assert_eq!(pools, RoundPools {
+            round_id,
+            up_bets_pool: Amount::from_tokens(9),
+            down_bets_pool: Amount::from_tokens(9),
+            up_bets: 2,
+            down_bets: 2,
+            prize_pool: Amount::from_tokens(18),
+        });
+    }
+
+    #[test]
+    fn active_round_odds_follow_the_pools() {
+        let mut contract = create_contract();
+        assert!(matches!(execute(&mut contract, RoundsOperation::GetActiveRoundOdds), RoundsResponse::Odds(None)));
+
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+        for (name, tokens, prediction) in [("alice", 50, Prediction::Up), ("bob", 40, Prediction::Down)] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner: AccountOwner::from(CryptoHash::test_hash(name)),
+                amount: Amount::from_tokens(tokens),
+                prediction,
+                source_chain_id: None,
+            });
+        }
+
+        let RoundsResponse::Odds(Some(odds)) = execute(&mut contract, RoundsOperation::GetActiveRoundOdds) else {
+            panic!("GetActiveRoundOdds should return the active round's odds");
+        };
+        assert_eq!(odds, RoundOdds {
+            round_id,
+            up_multiplier: Some("1.8".to_string()),
+            down_multiplier: Some("2.25".to_string()),
+            implied_probability_up: Some("55.555555555555555555".to_string()),
+        });
+    }
+
+    #[test]
+    fn rounds_by_status_follow_transitions() {
+        let mut contract = create_contract();
+        execute(&mut contract, RoundsOperation::CreateRound);
+        // Each close opens the next round: rounds 1-3 end up closed and round 4 active
+        for _ in 0..3 {
+            execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+        }
+        contract.state
+            .resolve_round_and_distribute_rewards(1, Amount::from_tokens(100), 2_000)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+
+        let ids = |response: RoundsResponse| match response {
+            RoundsResponse::PredictionRounds(rounds) => rounds.into_iter().map(|round| round.id).collect::<Vec<_>>(),
+            other => panic!("Unexpected response {:?}", other),
+        };
+        let closed = execute(&mut contract, RoundsOperation::GetRoundsByStatus { status: RoundStatus::Closed, limit: 10 });
+        assert_eq!(ids(closed), vec![3, 2]);
+        let closed = execute(&mut contract, RoundsOperation::GetRoundsByStatus { status: RoundStatus::Closed, limit: 1 });
+        assert_eq!(ids(closed), vec![3]);
+        let resolved = execute(&mut contract, RoundsOperation::GetRoundsByStatus { status: RoundStatus::Resolved, limit: 10 });
+        assert_eq!(ids(resolved), vec![1]);
+        let active = execute(&mut contract, RoundsOperation::GetRoundsByStatus { status: RoundStatus::Active, limit: 10 });
+        assert_eq!(ids(active), vec![4]);
+
+        // Keeper bots resolve the oldest close first
+        let awaiting = execute(&mut contract, RoundsOperation::GetRoundsAwaitingResolution { limit: 10 });
+        assert_eq!(ids(awaiting), vec![2, 3]);
+        let awaiting = execute(&mut contract, RoundsOperation::GetRoundsAwaitingResolution { limit: 1 });
+        assert_eq!(ids(awaiting), vec![2]);
+
+        // The index keeps the keys it had as a MapView<(u8, u64), ()>
+        let mut keys = contract.state.rounds_by_status.keys().now_or_never().unwrap().unwrap();
+        keys.sort();
+        let mut expected: Vec<Vec<u8>> = [(0u8, 4u64), (1, 2), (1, 3), (2, 1)].iter()
+            .map(|key| bcs::to_bytes(key).unwrap())
+            .collect();
+        expected.sort();
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn round_filter_combines_status_and_creation_bounds() {
+        let mut contract = create_contract();
+        execute(&mut contract, RoundsOperation::CreateRound);
+        // Round N is created at N thousand micros; rounds 1-3 end up closed and round 4 active
+        for created_at in [2_000, 3_000, 4_000] {
+            contract.runtime.set_system_time(Timestamp::from(created_at));
+            execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+        }
+
+        let ids = |filter: RoundFilter, order: SortOrder, limit: u64| {
+            contract.state.filter_rounds(&filter, order, limit).now_or_never().unwrap().unwrap()
+                .into_iter()
+                .map(|round| round.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(RoundFilter::default(), SortOrder::Asc, 10), vec![1, 2, 3, 4]);
+        let closed = RoundFilter { status: Some(RoundStatus::Closed), ..RoundFilter::default() };
+        assert_eq!(ids(closed, SortOrder::Desc, 10), vec![3, 2, 1]);
+        let closed_after_first = RoundFilter { created_after: Some(1_000), ..closed };
+        assert_eq!(ids(closed_after_first, SortOrder::Asc, 10), vec![2, 3]);
+        let before_fourth = RoundFilter { created_before: Some(4_000), ..RoundFilter::default() };
+        assert_eq!(ids(before_fourth, SortOrder::Desc, 2), vec![3, 2]);
+    }
+
+    #[test]
+    fn repeat_bets_count_one_unique_bettor() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let unique_bettors = |contract: &mut RoundsContract| match execute(contract, RoundsOperation::GetUniqueBettors) {
+            RoundsResponse::UniqueBettors(count) => count,
+            other => panic!("Unexpected response {:?}", other),
+        };
+
+        execute(&mut contract, RoundsOperation::CreateRound);
+        for prediction in [Prediction::Up, Prediction::Up, Prediction::Down] {
+            execute(&mut contract, RoundsOperation::PlaceBet {
+                owner: alice,
+                amount: Amount::from_tokens(5),
+                prediction,
+                source_chain_id: None,
+            });
+        }
+        assert_eq!(unique_bettors(&mut contract), 1);
+
+        execute(&mut contract, RoundsOperation::PlaceBet {
+            owner: bob,
+            amount: Amount::from_tokens(5),
+            prediction: Prediction::Down,
+            source_chain_id: None,
+        });
+        assert_eq!(unique_bettors(&mut contract), 2);
+
+        execute(&mut contract, RoundsOperation::CloseRound { closing_price: Amount::from_tokens(100) });
+        assert_eq!(unique_bettors(&mut contract), 0);
+        // The closed round keeps its own count, with the four bets made by two people
+        let RoundsResponse::PredictionRound(Some(round)) = execute(&mut contract, RoundsOperation::GetRound { id: 1 }) else {
+            panic!("GetRound should return the round");
+        };
+        assert_eq!((round.outcome_bets.iter().sum::<u64>(), round.unique_bettors), (3, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "has already placed the maximum of 2 bets in this round")]
+    fn bets_beyond_the_per_user_maximum_are_rejected() {
+        let mut contract = create_contract();
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let bob = AccountOwner::from(CryptoHash::test_hash("bob"));
+        let bet = |owner, prediction| RoundsOperation::PlaceBet {
+            owner,
+            amount: Amount::from_tokens(5),
+            prediction,
+            source_chain_id: None,
+        };
+        execute(&mut contract, RoundsOperation::SetMaxBetsPerUser { max_bets: Some(2) });
+        execute(&mut contract, RoundsOperation::CreateRound);
+
+        // Up and down merge into one bet, but each PlaceBet counts
+        execute(&mut contract, bet(alice, Prediction::Up));
+        execute(&mut contract, bet(alice, Prediction::Down));
+        execute(&mut contract, bet(bob, Prediction::Up));
+        assert_eq!(contract.state.bet_actions.get(&(1, alice)).now_or_never().unwrap().unwrap(), Some(2));
+
+        execute(&mut contract, bet(alice, Prediction::Up));
+    }
+
+    #[test]
+    fn burn_fee_is_kept_out_of_prize_pool() {
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let rounds_app_id = ApplicationId::new(CryptoHash::test_hash("rounds"));
+        let winza_app_id = ApplicationId::new(CryptoHash::test_hash("winza"));
+        let runtime = create_runtime()
+            .with_chain_id(ChainId(CryptoHash::test_hash("rounds")))
+            .with_application_id(rounds_app_id.with_abi())
+            .with_chain_balance(Amount::ZERO)
+            .with_owner_balances([(alice, Amount::from_tokens(1))])
+            .with_call_application_handler(move |_authenticated, application_id, _operation| {
+                if application_id == winza_app_id {
+                    bcs::to_bytes(&native_fungible_abi::ExtendedResponse::Ok).unwrap()
+                } else {
+                    bcs::to_bytes(&leaderboard::LeaderboardResponse::Ok).unwrap()
+                }
+            });
+        let mut contract = load_contract(runtime);
+        contract.state.Winza_app_id.set(Some(winza_app_id.with_abi()));
+        execute(&mut contract, RoundsOperation::SetBurnFeeBps { bps: 250 });
+        let RoundsResponse::RoundId(round_id) = execute(&mut contract, RoundsOperation::CreateRound) else {
+            panic!("CreateRound should return a round ID");
+        };
+
+        execute(&mut contract, RoundsOperation::PlaceBet {
+            owner: alice,
+            amount: Amount::from_tokens(10),
+            prediction: Prediction::Up,
+            source_chain_id: None,
+        });
+
+        let burned = Amount::from_millis(250);
+        let round = contract.state.get_round(round_id).now_or_never().unwrap().unwrap().unwrap();
+        assert_eq!(round.prize_pool, Amount::from_tokens(10).saturating_sub(burned));
+        assert_eq!(round.outcome_pools[Prediction::Up.index()], round.prize_pool);
+        assert_eq!(round.total_burned_this_round, burned);
+        assert_eq!(*contract.state.total_burned_all_time.get(), burned);
+        // The bettor paid once, with the stake that reached the treasury
+        assert_eq!(contract.runtime.owner_balance(alice), Amount::from_tokens(1));
+
+        // Settlement moves the burn out of the treasury, and not to the chain balance Mint draws from
+        let treasury = sign_as_treasury(&mut contract, Amount::from_tokens(10));
+        execute(&mut contract, RoundsOperation::CloseAndResolve {
+            closing_price: Amount::from_tokens(100),
+            resolution_price: Amount::from_tokens(110),
+        });
+        let sink = AccountOwner::from(rounds_app_id);
+        assert_eq!(contract.runtime.owner_balance(sink), burned);
+        assert_eq!(contract.runtime.owner_balance(treasury), Amount::from_tokens(10).saturating_sub(burned));
+        assert_eq!(contract.runtime.chain_balance(), Amount::ZERO);
+    }
+
+    #[test]
+    #[should_panic(expected = "Burn fee must be at most 500 bps")]
+    fn burn_fee_is_capped() {
+        let mut contract = create_contract();
+        execute(&mut contract, RoundsOperation::SetBurnFeeBps { bps: 501 });
+    }
+
+    fn creator_chain_contract() -> RoundsContract {
+        let chain = ChainId(CryptoHash::test_hash("rounds"));
+        load_contract(create_runtime().with_chain_id(chain).with_application_creator_chain_id(chain))
+    }
+
+    #[test]
+    fn exported_config_imports_onto_another_chain() {
+        let mut staging = create_contract();
+        let oracle = AccountOwner::from(CryptoHash::test_hash("oracle"));
+        execute(&mut staging, RoundsOperation::SetWinzaAppId {
+            Winza_app_id: ApplicationId::new(CryptoHash::test_hash("winza")).to_string(),
+        });
+        execute(&mut staging, RoundsOperation::SetOutcomeConfig { num_outcomes: 3, flat_band: Amount::ONE });
+        execute(&mut staging, RoundsOperation::SetBurnFeeBps { bps: 100 });
+        execute(&mut staging, RoundsOperation::SetOracle { oracle: Some(oracle) });
+        let RoundsResponse::Config(config) = execute(&mut staging, RoundsOperation::GetConfig) else {
+            panic!("GetConfig should return the config");
+        };
+
+        let mut production = creator_chain_contract();
+        execute(&mut production, RoundsOperation::ImportConfig { config: config.clone() });
+        assert!(matches!(execute(&mut production, RoundsOperation::GetConfig), RoundsResponse::Config(imported) if imported == config));
+    }
+
+    #[test]
+    fn every_config_register_round_trips() {
+        let mut contract = creator_chain_contract();
+        let config = AppConfig {
+            winza_app_id: Some(ApplicationId::new(CryptoHash::test_hash("winza")).to_string()),
+            native_app_id: Some(ApplicationId::new(CryptoHash::test_hash("native")).to_string()),
+            leaderboard_chain_id: Some(ChainId(CryptoHash::test_hash("leaderboard")).to_string()),
+            num_outcomes: 3,
+            flat_band: Amount::ONE,
+            oracle: Some(AccountOwner::from(CryptoHash::test_hash("oracle"))),
+            burn_fee_bps: 100,
+            leaderboard_min_amount: Amount::from_tokens(5),
+            admin: Some(AccountOwner::from(CryptoHash::test_hash("admin"))),
+            treasury: Some(AccountOwner::from(CryptoHash::test_hash("treasury"))),
+            max_history_rounds: Some(50),
+            max_bets_per_user: Some(3),
+            dispute_window_micros: Some(60_000_000),
+        };
+
+        execute(&mut contract, RoundsOperation::ImportConfig { config: config.clone() });
+        assert_eq!(contract.state.get_config(), config);
+    }
+
+    #[test]
+    fn invalid_config_is_not_applied() {
+        let mut contract = creator_chain_contract();
+        let before = contract.state.get_config();
+        let config = AppConfig {
+            winza_app_id: Some(ApplicationId::new(CryptoHash::test_hash("winza")).to_string()),
+            native_app_id: Some("not-an-app-id".to_string()),
+            burn_fee_bps: 100,
+            ..before.clone()
+        };
+
+        assert!(contract.state.import_config(config).is_err());
+        assert_eq!(contract.state.get_config(), before);
+    }
+
+    #[test]
+    #[should_panic(expected = "ImportConfig can only be executed on the application creator chain")]
+    fn import_config_is_creator_chain_only() {
+        let mut contract = load_contract(
+            create_runtime()
+                .with_chain_id(ChainId(CryptoHash::test_hash("other")))
+                .with_application_creator_chain_id(ChainId(CryptoHash::test_hash("rounds"))),
+        );
+        let config = contract.state.get_config();
+        execute(&mut contract, RoundsOperation::ImportConfig { config });
+    }
+
+    #[test]
+    fn rounds_stored_in_the_former_state_layout_still_load() {
+        // Copies of the round types the state module used to define
+        #[derive(serde::Serialize)]
+        enum OldPrediction { Up, Down }
+        #[derive(serde::Serialize)]
+        #[allow(dead_code)]
+        enum OldRoundStatus { Active, Closed, Resolved }
+        #[derive(serde::Serialize)]
+        struct OldPredictionRound {
+            id: u64,
+            created_at: u64,
+            closed_at: Option<u64>,
+            resolved_at: Option<u64>,
+            status: OldRoundStatus,
+            closing_price: Option<Amount>,
+            resolution_price: Option<Amount>,
+            up_bets: u64,
+            down_bets: u64,
+            up_bets_pool: Amount,
+            down_bets_pool: Amount,
+            prize_pool: Amount,
+            result: Option<OldPrediction>,
+        }
+
+        let old = OldPredictionRound {
+            id: 4,
+            created_at: 100,
+            closed_at: Some(200),
+            resolved_at: Some(300),
+            status: OldRoundStatus::Resolved,
+            closing_price: Some(Amount::from_tokens(10)),
+            resolution_price: Some(Amount::from_tokens(12)),
+            up_bets: 3,
+            down_bets: 1,
+            up_bets_pool: Amount::from_tokens(6),
+            down_bets_pool: Amount::from_tokens(2),
+            prize_pool: Amount::from_tokens(8),
+            result: Some(OldPrediction::Down),
+        };
+        let bytes = bcs::to_bytes(&old).unwrap();
+        let round: super::state::PredictionRound = bcs::from_bytes(&bytes).unwrap();
+
+        assert_eq!((round.id, round.created_at, round.closed_at, round.resolved_at), (4, 100, Some(200), Some(300)));
+        assert_eq!(round.status, RoundStatus::Resolved);
+        assert_eq!(round.closing_price, Some(Amount::from_tokens(10)));
+        assert_eq!(round.resolution_price, Some(Amount::from_tokens(12)));
+        assert_eq!(round.num_outcomes, 2);
+        assert_eq!(round.outcome_bets, vec![3, 1]);
+        assert_eq!(round.pool_for(Prediction::Up), Amount::from_tokens(6));
+        assert_eq!(round.pool_for(Prediction::Down), Amount::from_tokens(2));
+        assert_eq!(round.prize_pool, Amount::from_tokens(8));
+        assert_eq!(round.result, Some(Prediction::Down));
+        assert_eq!(round.total_burned_this_round, Amount::ZERO);
+        assert!(!round.manual_resolution);
+        assert_eq!(round.unique_bettors, 0);
+
+        // Rewriting a two-outcome round keeps the original bytes as its prefix
+        let rewritten = bcs::to_bytes(&round).unwrap();
+        assert_eq!(&rewritten[..bytes.len()], &bytes[..]);
+    }
+
+    #[test]
+    fn bets_stored_in_the_former_layout_still_load() {
+        #[derive(serde::Serialize)]
+        struct OldPredictionBet {
+            owner: AccountOwner,
+            amount_up: Amount,
+            amount_down: Amount,
+            claimed: bool,
+            source_chain_id: Option<String>,
+        }
+
+        let alice = AccountOwner::from(CryptoHash::test_hash("alice"));
+        let old = OldPredictionBet {
+            owner: alice,
+            amount_up: Amount::from_tokens(3),
+            amount_down: Amount::from_tokens(1),
+            claimed: true,
+            source_chain_id: Some("remote".to_string()),
+        };
+        let bet: super::state::PredictionBet = bcs::from_bytes(&bcs::to_bytes(&old).unwrap()).unwrap();
+
+        assert_eq!(bet.owner, alice);
+        assert_eq!(bet.amount_on(Prediction::Up), Amount::from_tokens(3));
+        assert_eq!(bet.amount_on(Prediction::Down), Amount::from_tokens(1));
+        assert_eq!(bet.amount_on(Prediction::Flat), Amount::ZERO);
+        assert!(bet.claimed);
+        assert_eq!(bet.source_chain_id.as_deref(), Some("remote"));
+    }
+
+    #[test]
+    fn three_outcome_rounds_and_bets_round_trip() {
+        let round: super::state::PredictionRound = bcs::from_bytes(&bcs::to_bytes(&super::state::PredictionRound {
+            id: 1,
+            created_at: 0,
+            closed_at: None,
+            resolved_at: None,
+            status: RoundStatus::Active,
+            closing_price: None,
+            resolution_price: None,
+            num_outcomes: 3,
+            outcome_bets: vec![1, 2, 3],
+            outcome_pools: vec![Amount::from_tokens(1), Amount::from_tokens(2), Amount::from_tokens(3)],
+            prize_pool: Amount::from_tokens(6),
+            result: Some(Prediction::Flat),
+            total_burned_this_round: Amount::ZERO,
+            manual_resolution: false,
+            unique_bettors: 3,
+        }).unwrap()).unwrap();
+        assert_eq!(round.num_outcomes, 3);
+        assert_eq!(round.outcome_bets, vec![1, 2, 3]);
+        assert_eq!(round.pool_for(Prediction::Flat), Amount::from_tokens(3));
+        assert_eq!(round.result, Some(Prediction::Flat));
+        assert_eq!(round.unique_bettors, 3);
+
+        let bet = super::state::PredictionBet {
+            owner: AccountOwner::from(CryptoHash::test_hash("alice")),
+            amounts: vec![Amount::ZERO, Amount::from_tokens(1), Amount::from_tokens(2)],
+            claimed: false,
+            source_chain_id: None,
+        };
+        let decoded: super::state::PredictionBet = bcs::from_bytes(&bcs::to_bytes(&bet).unwrap()).unwrap();
+        assert_eq!(decoded.amounts, bet.amounts);
+    }
+}
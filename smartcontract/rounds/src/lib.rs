@@ -3,27 +3,103 @@
 
 /*! ABI of the Rounds Application for Prediction Game */
 
-use async_graphql::{Request, Response, SimpleObject};
-use linera_sdk::linera_base_types::{AccountOwner, Amount, ContractAbi, ServiceAbi};
+use async_graphql::{ComplexObject, InputObject, Request, Response, SimpleObject};
+use linera_sdk::linera_base_types::{AccountOwner, Amount, ChainId, ContractAbi, ServiceAbi};
+use native_fungible_abi::amount_format::{amount_attos, amount_display};
+use native_fungible_abi::list_query::CreatedRange;
 use serde::{Deserialize, Serialize};
 
-// Prediction direction for the Up/Down game
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
+pub mod odds;
+
+pub use native_fungible_abi::csv;
+pub use native_fungible_abi::game_result::{game_results_stream, GameKind, GameResult, GAME_RESULTS_STREAM};
+pub use native_fungible_abi::list_query::SortOrder;
+
+// Outcome a bet is placed on. Binary markets use Up/Down,
+// three-outcome markets additionally use Flat. Flat comes last so
+// Up/Down values stored before it existed keep their encoding.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, async_graphql::Enum)]
 pub enum Prediction {
     Up,
     Down,
+    Flat,
+}
+
+impl Prediction {
+    /// Every outcome, in index order
+    pub const ALL: [Prediction; 3] = [Prediction::Up, Prediction::Down, Prediction::Flat];
+
+    /// Position of this outcome in per-outcome vectors
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The outcome at `index` in per-outcome vectors
+    pub fn from_index(index: usize) -> Option<Prediction> {
+        Self::ALL.get(index).copied()
+    }
 }
 
+/// Number of outcomes used when none has been configured (Up/Down)
+pub const DEFAULT_NUM_OUTCOMES: u32 = 2;
+/// Largest supported number of outcomes (Up/Down/Flat)
+pub const MAX_NUM_OUTCOMES: u32 = 3;
+
+/// Largest configurable burn fee in basis points (5%)
+pub const MAX_BURN_FEE_BPS: u16 = 500;
+
+/// Delay before a requested spend limit removal takes effect (24 hours)
+pub const SPEND_LIMIT_REMOVAL_DELAY_MICROS: u64 = 24 * 60 * 60 * 1_000_000;
+
+/// Network participation key for bets placed without a source chain (on the rounds chain itself)
+pub const LOCAL_CHAIN_KEY: &str = "local";
+
+/// Largest number of chains that can subscribe to round results
+pub const MAX_RESULT_SUBSCRIBERS: u64 = 50;
+
+/// Number of received round results kept for recentResults; older ones are pruned
+pub const RECENT_RESULTS_CAPACITY: u64 = 20;
+
 // Status of a prediction round
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
 pub enum RoundStatus {
     Active,    // Round is accepting bets
     Closed,    // Round is closed, awaiting resolution
     Resolved,  // Round has been resolved with a result
+    ResolutionPending, // Result known, but the treasury cannot cover the payout yet
 }
 
-// A prediction round for the Up/Down game
+impl RoundStatus {
+    /// Key used for this status in the `rounds_by_status` index
+    pub fn index_key(self) -> u8 {
+        match self {
+            RoundStatus::Active => 0,
+            RoundStatus::Closed => 1,
+            RoundStatus::Resolved => 2,
+            RoundStatus::ResolutionPending => 3,
+        }
+    }
+}
+
+/// Filter of a round list query; the creation bounds are exclusive timestamps in micros
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, InputObject)]
+pub struct RoundFilter {
+    pub status: Option<RoundStatus>,
+    pub created_after: Option<u64>,
+    pub created_before: Option<u64>,
+}
+
+impl RoundFilter {
+    pub fn created_range(&self) -> CreatedRange {
+        CreatedRange { after: self.created_after, before: self.created_before }
+    }
+}
+
+// A prediction round for the Up/Down game. Stored in the Up/Down layout
+// rounds had before outcome counts were configurable, see StoredPredictionRound.
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
+#[serde(from = "StoredPredictionRound", into = "StoredPredictionRound")]
 pub struct PredictionRound {
     pub id: u64,
     pub created_at: u64,
@@ -32,16 +108,260 @@ pub struct PredictionRound {
     pub status: RoundStatus,
     pub closing_price: Option<Amount>,    // Price at which round was closed (fractional)
     pub resolution_price: Option<Amount>, // Price used to resolve the round (fractional)
-    pub up_bets: u64,                     // Number of up bets
-    pub down_bets: u64,                   // Number of down bets
-    pub up_bets_pool: Amount,             // Total amount of up bets
-    pub down_bets_pool: Amount,           // Total amount of down bets
+    pub num_outcomes: u32,                // Number of outcomes bettors can choose from
+    pub outcome_bets: Vec<u64>,           // Number of bets per outcome index
+    pub outcome_pools: Vec<Amount>,       // Total amount bet per outcome index
     pub prize_pool: Amount,               // Total amount of tokens bet in this round
-    pub result: Option<Prediction>,       // Result of the round (Up, Down, or None if not resolved)
+    pub result: Option<Prediction>,       // Winning outcome (None if not resolved or tied)
+    pub total_burned_this_round: Amount,  // Burn fees taken from this round's bets
+    pub manual_resolution: bool,          // Result set by the admin instead of the prices
+    // Owners who bet in this round, however many bets each made (0 for rounds stored before it was tracked)
+    pub unique_bettors: u64,
+}
+
+// Serialized form of PredictionRound: the original Up/Down fields in their
+// original order, then every later field. Values written before a trailing
+// field existed read it as its default.
+#[derive(Serialize, Deserialize)]
+struct StoredPredictionRound {
+    id: u64,
+    created_at: u64,
+    closed_at: Option<u64>,
+    resolved_at: Option<u64>,
+    status: RoundStatus,
+    closing_price: Option<Amount>,
+    resolution_price: Option<Amount>,
+    up_bets: u64,
+    down_bets: u64,
+    up_bets_pool: Amount,
+    down_bets_pool: Amount,
+    prize_pool: Amount,
+    result: Option<Prediction>,
+    // 0 for rounds stored before outcome counts were configurable
+    #[serde(default, deserialize_with = "native_fungible_abi::stored_fields::default_if_missing")]
+    num_outcomes: u32,
+    #[serde(default, deserialize_with = "native_fungible_abi::stored_fields::default_if_missing")]
+    flat_bets: u64,
+    #[serde(default, deserialize_with = "native_fungible_abi::stored_fields::default_if_missing")]
+    flat_bets_pool: Amount,
+    #[serde(default, deserialize_with = "native_fungible_abi::stored_fields::default_if_missing")]
+    total_burned_this_round: Amount,
+    #[serde(default, deserialize_with = "native_fungible_abi::stored_fields::default_if_missing")]
+    manual_resolution: bool,
+    #[serde(default, deserialize_with = "native_fungible_abi::stored_fields::default_if_missing")]
+    unique_bettors: u64,
+}
+
+impl From<StoredPredictionRound> for PredictionRound {
+    fn from(stored: StoredPredictionRound) -> Self {
+        let num_outcomes = match stored.num_outcomes {
+            0 => DEFAULT_NUM_OUTCOMES,
+            n => n,
+        };
+        let mut outcome_bets = vec![stored.up_bets, stored.down_bets, stored.flat_bets];
+        let mut outcome_pools = vec![stored.up_bets_pool, stored.down_bets_pool, stored.flat_bets_pool];
+        outcome_bets.truncate(num_outcomes as usize);
+        outcome_pools.truncate(num_outcomes as usize);
+        PredictionRound {
+            id: stored.id,
+            created_at: stored.created_at,
+            closed_at: stored.closed_at,
+            resolved_at: stored.resolved_at,
+            status: stored.status,
+            closing_price: stored.closing_price,
+            resolution_price: stored.resolution_price,
+            num_outcomes,
+            outcome_bets,
+            outcome_pools,
+            prize_pool: stored.prize_pool,
+            result: stored.result,
+            total_burned_this_round: stored.total_burned_this_round,
+            manual_resolution: stored.manual_resolution,
+            unique_bettors: stored.unique_bettors,
+        }
+    }
+}
+
+impl From<PredictionRound> for StoredPredictionRound {
+    fn from(round: PredictionRound) -> Self {
+        let bets = |outcome: Prediction| round.outcome_bets.get(outcome.index()).copied().unwrap_or(0);
+        StoredPredictionRound {
+            id: round.id,
+            created_at: round.created_at,
+            closed_at: round.closed_at,
+            resolved_at: round.resolved_at,
+            status: round.status,
+            closing_price: round.closing_price,
+            resolution_price: round.resolution_price,
+            up_bets: bets(Prediction::Up),
+            down_bets: bets(Prediction::Down),
+            up_bets_pool: round.pool_for(Prediction::Up),
+            down_bets_pool: round.pool_for(Prediction::Down),
+            prize_pool: round.prize_pool,
+            result: round.result,
+            num_outcomes: round.num_outcomes,
+            flat_bets: bets(Prediction::Flat),
+            flat_bets_pool: round.pool_for(Prediction::Flat),
+            total_burned_this_round: round.total_burned_this_round,
+            manual_resolution: round.manual_resolution,
+            unique_bettors: round.unique_bettors,
+        }
+    }
+}
+
+#[ComplexObject]
+impl PredictionRound {
+    async fn closing_price_attos(&self) -> Option<String> {
+        self.closing_price.map(amount_attos)
+    }
+
+    async fn closing_price_display(&self) -> Option<String> {
+        self.closing_price.map(amount_display)
+    }
+
+    async fn resolution_price_attos(&self) -> Option<String> {
+        self.resolution_price.map(amount_attos)
+    }
+
+    async fn resolution_price_display(&self) -> Option<String> {
+        self.resolution_price.map(amount_display)
+    }
+
+    async fn outcome_pools_attos(&self) -> Vec<String> {
+        self.outcome_pools.iter().copied().map(amount_attos).collect()
+    }
+
+    async fn outcome_pools_display(&self) -> Vec<String> {
+        self.outcome_pools.iter().copied().map(amount_display).collect()
+    }
+
+    async fn prize_pool_attos(&self) -> String {
+        amount_attos(self.prize_pool)
+    }
+
+    async fn prize_pool_display(&self) -> String {
+        amount_display(self.prize_pool)
+    }
+
+    async fn total_burned_this_round_attos(&self) -> String {
+        amount_attos(self.total_burned_this_round)
+    }
+
+    async fn total_burned_this_round_display(&self) -> String {
+        amount_display(self.total_burned_this_round)
+    }
+
+    /// Number of bets on Up (outcomeBets[0], kept for Up/Down clients)
+    async fn up_bets(&self) -> u64 {
+        self.pools().up_bets
+    }
+
+    /// Number of bets on Down (outcomeBets[1], kept for Up/Down clients)
+    async fn down_bets(&self) -> u64 {
+        self.pools().down_bets
+    }
+
+    /// Total amount bet on Up (outcomePools[0], kept for Up/Down clients)
+    async fn up_bets_pool(&self) -> Amount {
+        self.pools().up_bets_pool
+    }
+
+    /// Total amount bet on Down (outcomePools[1], kept for Up/Down clients)
+    async fn down_bets_pool(&self) -> Amount {
+        self.pools().down_bets_pool
+    }
+
+    /// Current payout per token bet on Up (None while nothing is bet on Up)
+    async fn up_multiplier(&self) -> Option<String> {
+        self.odds().up_multiplier
+    }
+
+    /// Current payout per token bet on Down (None while nothing is bet on Down)
+    async fn down_multiplier(&self) -> Option<String> {
+        self.odds().down_multiplier
+    }
+
+    /// Share of the prize pool bet on Up, in percent
+    async fn implied_probability_up(&self) -> Option<String> {
+        self.odds().implied_probability_up
+    }
+}
+
+impl PredictionRound {
+    /// Creates an empty active round with `num_outcomes` outcome slots
+    pub fn new(id: u64, created_at: u64, num_outcomes: u32) -> Self {
+        PredictionRound {
+            id,
+            created_at,
+            closed_at: None,
+            resolved_at: None,
+            status: RoundStatus::Active,
+            closing_price: None,
+            resolution_price: None,
+            num_outcomes,
+            outcome_bets: vec![0; num_outcomes as usize],
+            outcome_pools: vec![Amount::ZERO; num_outcomes as usize],
+            prize_pool: Amount::default(),
+            result: None,
+            total_burned_this_round: Amount::ZERO,
+            manual_resolution: false,
+            unique_bettors: 0,
+        }
+    }
+
+    /// Total amount bet on a given outcome (zero for unknown outcomes)
+    pub fn pool_for(&self, outcome: Prediction) -> Amount {
+        self.outcome_pools.get(outcome.index()).copied().unwrap_or(Amount::ZERO)
+    }
+
+    /// Up/Down pool sizes and bet counts
+    pub fn pools(&self) -> RoundPools {
+        RoundPools {
+            round_id: self.id,
+            up_bets_pool: self.pool_for(Prediction::Up),
+            down_bets_pool: self.pool_for(Prediction::Down),
+            up_bets: self.outcome_bets.get(Prediction::Up.index()).copied().unwrap_or(0),
+            down_bets: self.outcome_bets.get(Prediction::Down.index()).copied().unwrap_or(0),
+            prize_pool: self.prize_pool,
+        }
+    }
+
+    /// Payout multipliers implied by the current pools
+    pub fn odds(&self) -> RoundOdds {
+        let up_pool = odds::side_pool(&self.outcome_pools, Prediction::Up);
+        let down_pool = odds::side_pool(&self.outcome_pools, Prediction::Down);
+        RoundOdds {
+            round_id: self.id,
+            up_multiplier: odds::payout_multiplier(self.prize_pool, up_pool),
+            down_multiplier: odds::payout_multiplier(self.prize_pool, down_pool),
+            implied_probability_up: odds::implied_probability(self.prize_pool, up_pool),
+        }
+    }
+}
+
+// Up/Down pool sizes of a round, for UIs polling the live split
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct RoundPools {
+    pub round_id: u64,
+    pub up_bets_pool: Amount,
+    pub down_bets_pool: Amount,
+    pub up_bets: u64,   // Bettors with a stake on Up
+    pub down_bets: u64, // Bettors with a stake on Down
+    pub prize_pool: Amount,
+}
+
+// Live odds of a round as decimal strings (None when the relevant pool is empty)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct RoundOdds {
+    pub round_id: u64,
+    pub up_multiplier: Option<String>,
+    pub down_multiplier: Option<String>,
+    pub implied_probability_up: Option<String>,
 }
 
 // A user's bet in a prediction round
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
 pub struct PredictionBet {
     pub owner: AccountOwner,
     pub amount: Amount,
@@ -49,16 +369,40 @@ pub struct PredictionBet {
     pub claimed: bool, // Whether the reward has been claimed
 }
 
+#[ComplexObject]
+impl PredictionBet {
+    async fn amount_attos(&self) -> String {
+        amount_attos(self.amount)
+    }
+
+    async fn amount_display(&self) -> String {
+        amount_display(self.amount)
+    }
+}
+
 // Active bet information for GraphQL queries
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
 pub struct ActiveBetInfo {
     pub owner: AccountOwner,
     pub amount: Amount,
     pub prediction: Prediction,
 }
 
+#[ComplexObject]
+impl ActiveBetInfo {
+    async fn amount_attos(&self) -> String {
+        amount_attos(self.amount)
+    }
+
+    async fn amount_display(&self) -> String {
+        amount_display(self.amount)
+    }
+}
+
 // Winner information for a resolved round
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
 pub struct RoundWinnerInfo {
     pub owner: AccountOwner,
     pub bet_amount: Amount,
@@ -66,6 +410,183 @@ pub struct RoundWinnerInfo {
     pub source_chain_id: Option<String>, // Add source chain ID for cross-chain winners
 }
 
+#[ComplexObject]
+impl RoundWinnerInfo {
+    async fn bet_amount_attos(&self) -> String {
+        amount_attos(self.bet_amount)
+    }
+
+    async fn bet_amount_display(&self) -> String {
+        amount_display(self.bet_amount)
+    }
+
+    async fn winnings_attos(&self) -> String {
+        amount_attos(self.winnings)
+    }
+
+    async fn winnings_display(&self) -> String {
+        amount_display(self.winnings)
+    }
+}
+
+// A standing order to bet the same amount on the same outcome every new round
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RecurringBet {
+    pub amount: Amount,
+    pub prediction: Prediction,
+    pub max_rounds: Option<u64>,  // None = until cancelled
+    pub rounds_remaining: u64,    // Only meaningful when max_rounds is set
+    pub active: bool,
+}
+
+// A bettor's self-imposed limits (responsible gaming)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, SimpleObject)]
+pub struct SpendLimit {
+    pub per_round: Option<Amount>,       // Cap on the total staked in one round (all outcomes)
+    pub cooldown_micros: Option<u64>,    // Minimum time between two bets
+    pub last_bet_at: Option<u64>,
+    pub removal_at: Option<u64>,         // Pending removal takes effect at this time
+}
+
+// Price inputs for the round an operator has to supply a price for next
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RoundPriceInputs {
+    pub round_id: u64,
+    pub status: RoundStatus,
+    pub created_at: u64,
+    pub elapsed_micros: u64,              // Time since the round was created
+    pub closing_price: Option<Amount>,    // Set once the round is closed and awaiting resolution
+}
+
+// CSV export of a round's bets (UTF-8, RFC 4180)
+// Columns: owner,outcome,amount,is_winner,winnings,claimed,source_chain_id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoundExport {
+    pub round_id: u64,
+    pub csv_bytes: Vec<u8>,
+}
+
+// One stake in an archived round; an owner who bet on two outcomes has two entries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct ArchivedBet {
+    pub owner: AccountOwner,
+    pub outcome: Prediction,
+    pub amount: Amount,
+    pub claimed: bool,
+    pub source_chain_id: Option<String>,
+}
+
+// Canonical record of a resolved round for compliance exports. Participants and winners are
+// sorted by owner and bets by (owner, outcome), so identical state always yields identical bytes.
+// Each winner's payout is its `winnings`.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct RoundArchive {
+    pub round: PredictionRound,
+    pub participants: Vec<AccountOwner>,
+    pub bets: Vec<ArchivedBet>,
+    pub winners: Vec<RoundWinnerInfo>,
+}
+
+// Number of bets whose size falls in [min, max); max is None for the open-ended top bucket
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct BetSizeBucket {
+    pub min: Amount,
+    pub max: Option<Amount>,
+    pub count: u64,
+}
+
+// Money in and out of a resolved round. Burn fees are taken before a bet reaches the pools, so
+// prize_pool = total_paid + dust_retained and total_staked = prize_pool + fees_taken.
+// total_paid counts winnings and tie refunds whether delivered or still claimable; dust_retained
+// is what floor division (or a winning outcome nobody bet on) leaves in the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct RoundNetFlow {
+    pub round_id: u64,
+    pub total_staked: Amount,
+    pub fees_taken: Amount,
+    pub prize_pool: Amount,
+    pub total_paid: Amount,
+    pub dust_retained: Amount,
+}
+
+// Outcome of a resolved round, sent to every chain subscribed to round results
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct RoundResolvedNotice {
+    pub round_id: u64,
+    pub result: Option<Prediction>,       // None if the round tied
+    pub closing_price: Option<Amount>,
+    pub resolution_price: Option<Amount>,
+    pub prize_pool: Amount,
+}
+
+// One resolution of a round; a correction within the dispute window adds another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct ResolutionRecord {
+    pub resolved_at: u64,
+    pub resolution_price: Option<Amount>, // None for a manual resolution
+    pub result: Option<Prediction>,       // None if the round tied
+}
+
+/// Why a bet was turned away (nothing is taken from the bettor rather than the transaction failing)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StateError {
+    /// The bet would move its side's share of the prize pool from `expected_bps` to
+    /// `actual_bps`, further than the bettor's `max_slippage_bps`
+    SlippageExceeded { expected_bps: u16, actual_bps: u16 },
+}
+
+impl std::fmt::Display for StateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateError::SlippageExceeded { expected_bps, actual_bps } => {
+                write!(f, "Bet would move its side's pool share from {} to {} bps", expected_bps, actual_bps)
+            }
+        }
+    }
+}
+
+// Every configuration register of the Rounds app, for export and import between chains
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct AppConfig {
+    pub winza_app_id: Option<String>,
+    pub native_app_id: Option<String>,
+    pub leaderboard_chain_id: Option<String>,
+    pub num_outcomes: u32,                // 0 means DEFAULT_NUM_OUTCOMES
+    pub flat_band: Amount,
+    pub oracle: Option<AccountOwner>,
+    pub burn_fee_bps: u16,
+    pub leaderboard_min_amount: Amount,
+    pub admin: Option<AccountOwner>,
+    pub treasury: Option<AccountOwner>,   // None falls back to the admin
+    pub max_history_rounds: Option<u64>,  // None means unlimited
+    pub max_bets_per_user: Option<u32>,   // None means unlimited
+    pub dispute_window_micros: Option<u64>,
+}
+
+// Betting activity from one source chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct ChainParticipation {
+    pub chain_id: String,       // LOCAL_CHAIN_KEY for bets without a source chain
+    pub bet_count: u64,
+    pub total_volume: Amount,   // Staked into the pools, after the burn fee
+    pub win_count: u64,         // Bets that received winnings
+}
+
+// An admin operation that was executed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, SimpleObject)]
+pub struct AdminLogEntry {
+    pub timestamp: u64,
+    pub action: String,        // Operation name, e.g. "SetWinzaAppId"
+    pub actor: AccountOwner,   // AccountOwner::CHAIN when the operation was unsigned
+}
+
+// A single metrics counter for GraphQL queries
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct MetricEntry {
+    pub name: String,
+    pub count: u64,
+}
+
 // Rounds Application ABI
 pub struct RoundsAbi;
 
@@ -83,17 +604,32 @@ impl ServiceAbi for RoundsAbi {
 pub struct RoundsParameters {
     pub native_app_id: ::linera_sdk::linera_base_types::ApplicationId,
     pub leaderboard_app_id: ::linera_sdk::linera_base_types::ApplicationId,
+    /// Account allowed to run admin operations (rotate with TransferAdminRole)
+    pub admin: AccountOwner,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum RoundsOperation {
     // Round management operations
-    /// Create a new prediction round
+    /// Create a new prediction round (admin only)
     CreateRound,
     /// Close the active round with a closing price
     CloseRound { closing_price: Amount },
-    /// Resolve a closed round with a resolution price and distribute rewards
-    ResolveRound { resolution_price: Amount },
+    /// Resolve closed round `round_id` (None = the latest closed round) with a resolution price
+    /// and distribute rewards. Fails while another round's resolution is unfinished.
+    ResolveRound { round_id: Option<u64>, resolution_price: Amount },
+    /// Close the active round and resolve it in the same transaction
+    CloseAndResolve { closing_price: Amount, resolution_price: Amount },
+    /// Resolve a closed round with `result` instead of comparing prices, for when the
+    /// oracle is unavailable or its prices are manipulated (admin only). The
+    /// justification is kept for GetResolutionNote.
+    ResolveWithCustomWinner { round_id: u64, result: Prediction, justification: String },
+    /// Pay out a round left in ResolutionPending once the treasury has been topped up (admin only)
+    ResumeResolution { round_id: u64 },
+    /// Correct the resolution price of resolved round `round_id` before its dispute window
+    /// ends (oracle or admin). Only rounds resolved while a dispute window was set qualify:
+    /// their rewards wait to be claimed, so nothing paid has to be taken back.
+    ReResolve { round_id: u64, corrected_price: Amount },
     
     // Betting operations (called by NativeFungible app)
     /// Place a bet in the active round
@@ -103,8 +639,37 @@ pub enum RoundsOperation {
         prediction: Prediction,
         source_chain_id: Option<String>, // For cross-chain attribution
     },
-    /// Claim winnings from a resolved round (called by user directly)
+    /// Like PlaceBetDirect, unless the bet would move its side's share of the prize pool
+    /// by more than `max_slippage_bps`; a rejected bet takes nothing from the signer
+    PlaceBetWithSlippage { amount: Amount, prediction: Prediction, max_slippage_bps: u16 },
+    /// Bet the signer's tokens in the active round without a transfer wrapper. The stake is
+    /// pulled with the native app's TransferFrom, so the signer must first Approve this
+    /// application as spender for at least `amount`.
+    PlaceBetDirect { amount: Amount, prediction: Prediction },
+    /// Bet the signer's tokens on both Up and Down in the active round as a single bet action.
+    /// Like PlaceBetDirect, the whole stake is pulled with TransferFrom, so the signer must
+    /// first Approve this application for at least `amount_up + amount_down`.
+    PlaceSplitBet { amount_up: Amount, amount_down: Amount },
+    /// Claim what the signer is still owed from a resolved round: their stake back if the
+    /// round tied. Winnings are paid on resolution, so winning bets have nothing left to claim,
+    /// unless the round was resolved with a dispute window; those are claimed here once it ends.
+    /// The claim is queued for the treasury, which holds the stakes, to pay with PayQueuedClaims.
     ClaimWinnings { round_id: u64 },
+    /// Pay up to `limit` queued claims, oldest round first, stopping at the first one the
+    /// treasury's balance cannot cover (treasury only: rewards are paid from the signer)
+    PayQueuedClaims { limit: u64 },
+    /// Place a bet automatically in every new round (authenticated, signer is the bettor)
+    SetRecurringBet { amount: Amount, prediction: Prediction, max_rounds: Option<u64> },
+    /// Cancel the signer's recurring bet
+    CancelRecurringBet,
+    /// Set the signer's own spend limit. Limits can be tightened immediately;
+    /// passing None for both removes the limit after SPEND_LIMIT_REMOVAL_DELAY_MICROS.
+    SetMySpendLimit { per_round: Option<Amount>, cooldown_micros: Option<u64> },
+    /// Send a RoundResolvedNotice to `chain_id` after every resolution (authenticated, at most
+    /// MAX_RESULT_SUBSCRIBERS chains)
+    SubscribeResults { chain_id: ChainId },
+    /// Stop sending results to `chain_id` (its subscriber or the admin only)
+    UnsubscribeResults { chain_id: ChainId },
     
     // Configuration operations
     /// Set the Winzareal app ID (called after deployment)
@@ -112,18 +677,91 @@ pub enum RoundsOperation {
     /// Set the chain ID where Leaderboard app is deployed (for cross-chain updates)
     /// If None, leaderboard is on the same chain as rounds
     SetLeaderboardChainId { chain_id: Option<String> },
+    /// Only report bets whose net win or loss is at least `amount` to the leaderboard
+    /// (zero, the default, reports every bet)
+    SetLeaderboardMinAmount { amount: Amount },
+    /// Set the number of outcomes for new rounds (2 = Up/Down, 3 = Up/Down/Flat)
+    /// and the price band around the closing price that resolves to Flat
+    SetOutcomeConfig { num_outcomes: u32, flat_band: Amount },
+    /// Set the only account allowed to close and resolve rounds (None = anyone; admin only)
+    SetOracle { oracle: Option<AccountOwner> },
+    /// Set the account bets are sent to and rewards are paid from. Resolutions that pay
+    /// anything must be signed by it, since Winzareal pays rewards from the signer.
+    SetTreasury { treasury: AccountOwner },
+    /// Set the share of every bet that is burned, in basis points (at most MAX_BURN_FEE_BPS)
+    SetBurnFeeBps { bps: u16 },
+    /// Keep at most `max_rounds` resolved rounds, pruning the oldest ones and their
+    /// bets (None = unlimited, the default)
+    SetMaxHistoryRounds { max_rounds: Option<u64> },
+    /// Allow each owner at most `max_bets` PlaceBet operations per round (None = unlimited,
+    /// the default)
+    SetMaxBetsPerUser { max_bets: Option<u32> },
+    /// Keep rounds resolved from now on open to ReResolve for `window_micros`, with their
+    /// rewards claimed once it ends and paid from the treasury by PayQueuedClaims instead of
+    /// paid on resolution (None = no window, the default)
+    SetDisputeWindow { window_micros: Option<u64> },
+    /// Replace the whole configuration (admin only, on the creator chain). Nothing is applied unless
+    /// every app id, chain id and limit in `config` is valid.
+    ImportConfig { config: AppConfig },
 
     // Query operations for prediction game state
     /// Get the active round
     GetActiveRound,
+    /// Get only the ID of the active round (None when no round is active)
+    GetActiveRoundId,
+    /// Get how many distinct owners have bet in the active round
+    GetUniqueBettors,
     /// Get a specific round by ID
     GetRound { id: u64 },
     /// Get all rounds
     GetAllRounds,
+    /// Get up to `limit` rounds in a status, newest first
+    GetRoundsByStatus { status: RoundStatus, limit: u64 },
+    /// Get up to `limit` closed rounds that still need a resolution price, oldest close first
+    GetRoundsAwaitingResolution { limit: u64 },
+    /// Get every configuration register
+    GetConfig,
     /// Get all active bets
     GetActiveBets,
     /// Get winners for a resolved round
     GetRoundWinners { round_id: u64 },
+    /// Get what a resolved round took in, paid out, burned and kept
+    GetRoundNetFlow { round_id: u64 },
+    /// Get the price inputs for the round awaiting a price (closed round first, else active round)
+    GetActiveRoundForPrice,
+    /// Get the payout multipliers of the active round
+    GetActiveRoundOdds,
+    /// Get just the Up/Down pools of the active round, a light alternative to GetActiveRound
+    GetActiveRoundPools,
+    /// Get the admin's justification for a manually resolved round
+    GetResolutionNote { round_id: u64 },
+    /// Get every resolution of a round, oldest first
+    GetResolutionHistory { round_id: u64 },
+    /// Export a round's bets as CSV (one row per owner and outcome staked on)
+    ExportRoundBetCSV { round_id: u64 },
+    /// Export a resolved round as a BCS-serialized `RoundArchive`
+    ExportRound { round_id: u64 },
+    /// Count a round's bets (one per owner and outcome staked on) by size.
+    /// `buckets` are ascending boundaries: [0, b0), [b0, b1), ..., [bN, ∞)
+    GetBetSizeHistogram { round_id: u64, buckets: Vec<Amount> },
+    /// Get betting activity per source chain
+    GetNetworkParticipation,
+    /// Zero the betting activity of every chain (admin only, on the creator chain)
+    ResetNetworkStats,
+    /// Refund every active bet through Winzareal and remove it, emptying the active
+    /// round's pools (admin only, on the creator chain). The refunds are paid from the
+    /// treasury, so it must sign too. Needed before CreateRound while bets are active.
+    ForceClearActiveBets,
+    
+    // Metrics
+    /// Zero all operation/message counters
+    ResetMetrics,
+    
+    // Administration
+    /// Hand the admin role to another account (admin only)
+    TransferAdminRole { new_admin: AccountOwner },
+    /// Get the most recent `limit` admin operations, oldest first
+    GetAdminLog { limit: u64 },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -137,6 +775,38 @@ pub enum RoundsResponse {
     RoundWinners(Vec<RoundWinnerInfo>),
     // Add Winners response for ResolveRound to return winners list
     Winners(Vec<RoundWinnerInfo>),
+    RoundPriceInputs(Option<RoundPriceInputs>),
+    RoundExport(RoundExport),
+    BetSizeHistogram(Vec<BetSizeBucket>),
+    Config(AppConfig),
+    Odds(Option<RoundOdds>),
+    Pools(Option<RoundPools>),
+    Archive(Vec<u8>),
+    NetworkParticipation(Vec<ChainParticipation>),
+    ActiveRoundId(Option<u64>),
+    UniqueBettors(u64),
+    RoundNetFlow(RoundNetFlow),
+    /// A resolved round's winners and how many leaderboard updates were sent or
+    /// skipped for falling below the reporting threshold
+    RoundResolved {
+        winners: Vec<RoundWinnerInfo>,
+        leaderboard_updates_sent: u64,
+        leaderboard_updates_skipped: u64,
+    },
+    AdminLog(Vec<AdminLogEntry>),
+    ResolutionNote(Option<String>),
+    ResolutionHistory(Vec<ResolutionRecord>),
+    /// The round's payout exceeds the funds available to the resolver, so it was left
+    /// in ResolutionPending without paying anyone
+    ResolutionPending { round_id: u64, payout: Amount, shortfall: Amount },
+    /// The signer has no unclaimed winnings or refund in the round
+    NothingToClaim,
+    /// Queued claims PayQueuedClaims paid and those still waiting
+    ClaimsPaid { paid: u64, remaining: u64 },
+    /// Sequence number of the bet action PlaceSplitBet recorded
+    PlaceSplitBetResponse { bet_id: u64 },
+    /// The bet was not placed; it was refused before any stake was taken from the bettor
+    BetRejected(StateError),
 }
 
 // Message for cross-application communication
@@ -151,4 +821,6 @@ pub enum Message {
         is_win: bool,
         amount: Amount,
     },
+    /// Result of a round resolved on the game chain, for subscribed chains
+    RoundResolvedNotice(RoundResolvedNotice),
 }
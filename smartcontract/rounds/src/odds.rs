@@ -0,0 +1,92 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Live payout multipliers and implied probabilities for a round */
+
+use linera_sdk::linera_base_types::Amount;
+use num_bigint::BigUint;
+use num_traits::cast::ToPrimitive;
+
+use crate::Prediction;
+
+/// Number of decimals kept in a ratio (the precision of `Amount`)
+const RATIO_DECIMALS: usize = 18;
+
+/// `numerator * factor / denominator` as a decimal string without trailing zeros,
+/// None when the denominator is zero
+fn ratio(numerator: Amount, factor: u32, denominator: Amount) -> Option<String> {
+    if denominator.is_zero() {
+        return None;
+    }
+    let scale = BigUint::from(10u32).pow(RATIO_DECIMALS as u32);
+    let scaled = BigUint::from(u128::from(numerator)) * factor * &scale / u128::from(denominator);
+    let whole = &scaled / &scale;
+    let fraction = &scaled % &scale;
+    if fraction == BigUint::from(0u32) {
+        return Some(whole.to_string());
+    }
+    let fraction = format!("{:0>width$}", fraction.to_string(), width = RATIO_DECIMALS);
+    Some(format!("{}.{}", whole, fraction.trim_end_matches('0')))
+}
+
+/// What one token on a side pays if that side wins: `prize_pool / side_pool`
+pub fn payout_multiplier(prize_pool: Amount, side_pool: Amount) -> Option<String> {
+    ratio(prize_pool, 1, side_pool)
+}
+
+/// Share of the prize pool staked on a side, in percent
+pub fn implied_probability(prize_pool: Amount, side_pool: Amount) -> Option<String> {
+    ratio(side_pool, 100, prize_pool)
+}
+
+/// Share of the prize pool staked on a side, in basis points (zero for an empty pool)
+pub fn share_bps(prize_pool: Amount, side_pool: Amount) -> u16 {
+    if prize_pool.is_zero() {
+        return 0;
+    }
+    let share = BigUint::from(u128::from(side_pool)) * 10_000u32 / u128::from(prize_pool);
+    share.to_u16().unwrap_or(10_000).min(10_000)
+}
+
+/// Amount staked on `prediction`, zero when the round has no pool for it
+pub fn side_pool(outcome_pools: &[Amount], prediction: Prediction) -> Amount {
+    outcome_pools.get(prediction.index()).copied().unwrap_or(Amount::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use linera_sdk::linera_base_types::Amount;
+
+    use super::{implied_probability, payout_multiplier, share_bps, side_pool};
+    use crate::Prediction;
+
+    #[test]
+    fn test_balanced_and_uneven_sides() {
+        let prize_pool = Amount::from_tokens(100);
+        assert_eq!(payout_multiplier(prize_pool, Amount::from_tokens(50)).as_deref(), Some("2"));
+        assert_eq!(payout_multiplier(prize_pool, Amount::from_tokens(40)).as_deref(), Some("2.5"));
+        assert_eq!(payout_multiplier(Amount::from_tokens(90), Amount::from_tokens(50)).as_deref(), Some("1.8"));
+        assert_eq!(payout_multiplier(Amount::from_tokens(2), Amount::from_tokens(3)).as_deref(), Some("0.666666666666666666"));
+        assert_eq!(implied_probability(prize_pool, Amount::from_tokens(40)).as_deref(), Some("40"));
+        assert_eq!(share_bps(prize_pool, Amount::from_tokens(40)), 4_000);
+        assert_eq!(share_bps(Amount::from_tokens(3), Amount::ONE), 3_333);
+    }
+
+    #[test]
+    fn test_empty_sides() {
+        assert_eq!(payout_multiplier(Amount::from_tokens(10), Amount::ZERO), None);
+        assert_eq!(implied_probability(Amount::ZERO, Amount::ZERO), None);
+        assert_eq!(share_bps(Amount::ZERO, Amount::ZERO), 0);
+        assert_eq!(side_pool(&[Amount::ONE], Prediction::Down), Amount::ZERO);
+    }
+
+    #[test]
+    fn test_extreme_imbalance() {
+        let prize_pool = Amount::from_tokens(1_000_000);
+        let dust = Amount::from_attos(1);
+        assert_eq!(payout_multiplier(prize_pool, dust).as_deref(), Some("1000000000000000000000000"));
+        assert_eq!(implied_probability(prize_pool, dust).as_deref(), Some("0"));
+        assert_eq!(payout_multiplier(prize_pool, prize_pool).as_deref(), Some("1"));
+        assert_eq!(implied_probability(prize_pool, prize_pool).as_deref(), Some("100"));
+    }
+}
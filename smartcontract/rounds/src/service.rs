@@ -7,18 +7,23 @@ mod state;
 
 use std::sync::Arc;
 
+use base64::Engine as _;
+
 use async_graphql::{EmptySubscription, Object, Request, Response, Schema};
 use linera_sdk::{
-    linera_base_types::{AccountOwner, Amount, WithServiceAbi},
+    linera_base_types::{AccountOwner, Amount, ChainId, WithServiceAbi},
     views::View,
     Service, ServiceRuntime,
 };
 use rounds::{
     RoundsAbi, RoundsOperation, Prediction, 
-    PredictionRound as LibPredictionRound, RoundStatus as LibRoundStatus, 
-    ActiveBetInfo as LibActiveBetInfo, RoundWinnerInfo as LibRoundWinnerInfo
+    PredictionRound, RoundStatus,
+    ActiveBetInfo as LibActiveBetInfo, RoundWinnerInfo as LibRoundWinnerInfo,
+    RoundPriceInputs as LibRoundPriceInputs, MetricEntry, AdminLogEntry, RecurringBet, SpendLimit, BetSizeBucket, AppConfig, RoundArchive, RoundNetFlow, ChainParticipation,
+    ResolutionRecord, RoundFilter, RoundResolvedNotice, SortOrder,
 };
-use self::state::{RoundsState, PredictionRound};
+use winner_feed::FeedEntry;
+use self::state::RoundsState;
 
 linera_sdk::service!(RoundsService);
 
@@ -85,41 +90,29 @@ struct QueryRoot {
 
 #[Object]
 impl QueryRoot {
+    /// Get the ID of the active round
+    async fn active_round_id(&self) -> Option<u64> {
+        let state = RoundsState::load(self.storage_context.clone()).await.ok()?;
+        *state.active_round.get()
+    }
+    
+    /// Get how many distinct owners have bet in the active round
+    async fn unique_bettors(&self) -> u64 {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.active_unique_bettors().await.unwrap_or(0),
+            Err(_) => 0,
+        }
+    }
+    
     /// Get the active round
-    async fn active_round(&self) -> Option<LibPredictionRound> {
+    async fn active_round(&self) -> Option<PredictionRound> {
         // Load a fresh state to query the active round
         match RoundsState::load(self.storage_context.clone()).await {
             Ok(state) => {
                 match state.get_active_round().await {
                     Ok(Some(round_id)) => {
                         match state.get_round(round_id).await {
-                            Ok(Some(round)) => {
-                                // Convert RoundStatus from state to lib
-                                let status = match round.status {
-                                    self::state::RoundStatus::Active => LibRoundStatus::Active,
-                                    self::state::RoundStatus::Closed => LibRoundStatus::Closed,
-                                    self::state::RoundStatus::Resolved => LibRoundStatus::Resolved,
-                                };
-                                
-                                Some(LibPredictionRound {
-                                    id: round.id,
-                                    created_at: round.created_at,
-                                    closed_at: round.closed_at,
-                                    resolved_at: round.resolved_at,
-                                    status,
-                                    closing_price: round.closing_price,
-                                    resolution_price: round.resolution_price,
-                                    up_bets: round.up_bets,
-                                    down_bets: round.down_bets,
-                                    up_bets_pool: round.up_bets_pool,
-                                    down_bets_pool: round.down_bets_pool,
-                                    prize_pool: round.prize_pool,
-                                    result: round.result.map(|p| match p {
-                                        self::state::Prediction::Up => Prediction::Up,
-                                        self::state::Prediction::Down => Prediction::Down,
-                                    }),
-                                })
-                            },
+                            Ok(Some(round)) => Some(round),
                             Ok(None) => None,
                             Err(_) => None,
                         }
@@ -133,69 +126,42 @@ impl QueryRoot {
     }
     
     /// Get a specific round by ID
-    async fn round(&self, id: u64) -> Option<LibPredictionRound> {
+    async fn round(&self, id: u64) -> Option<PredictionRound> {
         // Find the round with the given ID
-        if let Some(round) = self.all_rounds.iter().find(|round| round.id == id) {
-            // Convert RoundStatus from state to lib
-            let status = match round.status {
-                self::state::RoundStatus::Active => LibRoundStatus::Active,
-                self::state::RoundStatus::Closed => LibRoundStatus::Closed,
-                self::state::RoundStatus::Resolved => LibRoundStatus::Resolved,
-            };
-            
-            Some(LibPredictionRound {
-                id: round.id,
-                created_at: round.created_at,
-                closed_at: round.closed_at,
-                resolved_at: round.resolved_at,
-                status,
-                closing_price: round.closing_price,
-                resolution_price: round.resolution_price,
-                up_bets: round.up_bets,
-                down_bets: round.down_bets,
-                up_bets_pool: round.up_bets_pool,
-                down_bets_pool: round.down_bets_pool,
-                prize_pool: round.prize_pool,
-                result: round.result.map(|p| match p {
-                    self::state::Prediction::Up => Prediction::Up,
-                    self::state::Prediction::Down => Prediction::Down,
-                }),
-            })
-        } else {
-            None
-        }
+        self.all_rounds.iter().find(|round| round.id == id).cloned()
     }
     
     /// Get all rounds
-    async fn all_rounds(&self) -> Vec<LibPredictionRound> {
-        // Convert our internal PredictionRound to the library version
-        self.all_rounds.iter().map(|round| {
-            // Convert RoundStatus from state to lib
-            let status = match round.status {
-                self::state::RoundStatus::Active => LibRoundStatus::Active,
-                self::state::RoundStatus::Closed => LibRoundStatus::Closed,
-                self::state::RoundStatus::Resolved => LibRoundStatus::Resolved,
-            };
-            
-            LibPredictionRound {
-                id: round.id,
-                created_at: round.created_at,
-                closed_at: round.closed_at,
-                resolved_at: round.resolved_at,
-                status,
-                closing_price: round.closing_price,
-                resolution_price: round.resolution_price,
-                up_bets: round.up_bets,
-                down_bets: round.down_bets,
-                up_bets_pool: round.up_bets_pool,
-                down_bets_pool: round.down_bets_pool,
-                prize_pool: round.prize_pool,
-                result: round.result.map(|p| match p {
-                    self::state::Prediction::Up => Prediction::Up,
-                    self::state::Prediction::Down => Prediction::Down,
-                }),
-            }
-        }).collect()
+    async fn all_rounds(&self) -> Vec<PredictionRound> {
+        self.all_rounds.clone()
+    }
+    
+    /// Get up to `limit` rounds in a status, newest first
+    async fn rounds_by_status(&self, status: RoundStatus, limit: u64) -> Vec<PredictionRound> {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.get_rounds_by_status(status, limit).await
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+    
+    /// Get up to `limit` closed rounds still awaiting resolution, oldest close first,
+    /// with the closing time and price needed to pick the resolution candle
+    async fn rounds_awaiting_resolution(&self, limit: u64) -> Vec<PredictionRound> {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.get_rounds_awaiting_resolution(limit).await
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+    
+    /// Up to `limit` rounds matching `filter` (all rounds when omitted), by ID in `order` (ascending by default)
+    async fn rounds(&self, filter: Option<RoundFilter>, order: Option<SortOrder>, limit: u64) -> Vec<PredictionRound> {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.filter_rounds(&filter.unwrap_or_default(), order.unwrap_or_default(), limit).await
+                .unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
     }
     
     /// Get all active bets
@@ -207,22 +173,10 @@ impl QueryRoot {
                     Ok(bets) => {
 
                         bets.into_iter().flat_map(|(owner, bet)| {
-                            let mut list = Vec::new();
-                            if !bet.amount_up.is_zero() {
-                                list.push(LibActiveBetInfo {
-                                    owner,
-                                    amount: bet.amount_up,
-                                    prediction: Prediction::Up,
-                                });
-                            }
-                            if !bet.amount_down.is_zero() {
-                                list.push(LibActiveBetInfo {
-                                    owner,
-                                    amount: bet.amount_down,
-                                    prediction: Prediction::Down,
-                                });
-                            }
-                            list
+                            // One entry per outcome the user has staked on
+                            bet.stakes()
+                                .map(|(prediction, amount)| LibActiveBetInfo { owner, amount, prediction })
+                                .collect::<Vec<_>>()
                         }).collect()
                     },
                     Err(_) => Vec::new(),
@@ -232,21 +186,214 @@ impl QueryRoot {
         }
     }
     
-    /// Get winners for a resolved round
-    async fn round_winners(&self, round_id: u64) -> Vec<LibRoundWinnerInfo> {
+    /// Get the price inputs for the round awaiting a price (closed round first, else active round)
+    async fn active_round_for_price(&self) -> Option<LibRoundPriceInputs> {
+        let now = self.runtime.system_time().micros();
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.get_round_price_inputs(now).await.ok().flatten(),
+            Err(_) => None,
+        }
+    }
+    
+    /// Export a round's bets as base64-encoded CSV
+    async fn round_export_csv(&self, round_id: u64) -> Option<String> {
+        let state = RoundsState::load(self.storage_context.clone()).await.ok()?;
+        let csv_bytes = state.export_round_bets_csv(round_id).await.ok()?;
+        Some(base64::engine::general_purpose::STANDARD.encode(csv_bytes))
+    }
+    
+    /// Canonical archive of a resolved round (the same data ExportRound serializes)
+    async fn round_archive(&self, round_id: u64) -> Option<RoundArchive> {
+        let state = RoundsState::load(self.storage_context.clone()).await.ok()?;
+        state.round_archive(round_id).await.ok()
+    }
+    
+    /// What a resolved round took in, paid out, burned and kept
+    async fn round_net_flow(&self, round_id: u64) -> Option<RoundNetFlow> {
+        let state = RoundsState::load(self.storage_context.clone()).await.ok()?;
+        state.round_net_flow(round_id).await.ok()
+    }
+    
+    /// Betting activity per source chain, ordered by chain ID
+    async fn network_participation(&self) -> Vec<ChainParticipation> {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.get_network_participation().await.unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+    
+    /// The `n` chains with the largest betting volume
+    async fn top_chains_by_volume(&self, n: usize) -> Vec<ChainParticipation> {
+        let mut participation = match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.get_network_participation().await.unwrap_or_default(),
+            Err(_) => Vec::new(),
+        };
+        participation.sort_by(|a, b| b.total_volume.cmp(&a.total_volume));
+        participation.truncate(n);
+        participation
+    }
+    
+    /// Get every configuration register
+    async fn config(&self) -> Option<AppConfig> {
+        let state = RoundsState::load(self.storage_context.clone()).await.ok()?;
+        Some(state.get_config())
+    }
+    
+    /// Get the account authorized to close and resolve rounds
+    async fn oracle(&self) -> Option<AccountOwner> {
+        let state = RoundsState::load(self.storage_context.clone()).await.ok()?;
+        *state.oracle.get()
+    }
+    
+    /// Get the account allowed to run admin operations
+    async fn admin(&self) -> Option<AccountOwner> {
+        let state = RoundsState::load(self.storage_context.clone()).await.ok()?;
+        *state.admin.get()
+    }
+    
+    /// Share of every bet that is burned, in basis points
+    async fn burn_fee_bps(&self) -> u16 {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => *state.burn_fee_bps.get(),
+            Err(_) => 0,
+        }
+    }
+    
+    /// Smallest net win or loss reported to the leaderboard
+    async fn leaderboard_min_amount(&self) -> String {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.leaderboard_min_amount.get().to_string(),
+            Err(_) => Amount::ZERO.to_string(),
+        }
+    }
+    
+    /// Resolved rounds kept in history (null = unlimited)
+    async fn max_history_rounds(&self) -> Option<u64> {
+        let state = RoundsState::load(self.storage_context.clone()).await.ok()?;
+        *state.max_history_rounds.get()
+    }
+    
+    /// Most bets one owner may place per round (null = unlimited)
+    async fn max_bets_per_user(&self) -> Option<u32> {
+        let state = RoundsState::load(self.storage_context.clone()).await.ok()?;
+        *state.max_bets_per_user.get()
+    }
+    
+    /// Total burned across all rounds
+    async fn total_burned_all_time(&self) -> String {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.total_burned_all_time.get().to_string(),
+            Err(_) => Amount::ZERO.to_string(),
+        }
+    }
+    
+    /// Get an owner's current spend limit (including any pending removal time)
+    async fn spend_limit(&self, owner: AccountOwner) -> Option<SpendLimit> {
+        let now = self.runtime.system_time().micros();
+        let state = RoundsState::load(self.storage_context.clone()).await.ok()?;
+        state.get_spend_limit(owner, now).await.ok().flatten()
+    }
+    
+    /// Get an owner's recurring bet
+    async fn recurring_bet(&self, owner: AccountOwner) -> Option<RecurringBet> {
+        let state = RoundsState::load(self.storage_context.clone()).await.ok()?;
+        state.get_recurring_bet(owner).await.ok().flatten()
+    }
+    
+    /// Get the most recent prediction winners, newest first
+    async fn recent_winners(&self, limit: u64) -> Vec<FeedEntry> {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.winner_feed.recent(limit).await.unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+    
+    /// Round results received from the game chain, newest first (on subscribed chains)
+    async fn recent_results(&self, limit: u64) -> Vec<RoundResolvedNotice> {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.recent_results(limit).await.unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+    
+    /// Chains sent round results after every resolution
+    async fn result_subscribers(&self) -> Vec<ChainId> {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.result_subscribers.indices().await.unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+    
+    /// Count a round's bets by size; `buckets` are ascending boundaries, the top bucket is open-ended
+    async fn bet_size_histogram(&self, round_id: u64, buckets: Vec<String>) -> Vec<BetSizeBucket> {
+        let boundaries: Vec<Amount> = buckets.iter()
+            .map(|bucket| bucket.parse::<Amount>().unwrap_or_default())
+            .collect();
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.get_bet_size_histogram(round_id, &boundaries).await.unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+    
+    /// Get the most recent `limit` admin operations, oldest first
+    async fn admin_log(&self, limit: u64) -> Vec<AdminLogEntry> {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.get_admin_log(limit).await.unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+    
+    /// Get the admin's justification for a manually resolved round
+    async fn resolution_note(&self, round_id: u64) -> Option<String> {
+        let state = RoundsState::load(self.storage_context.clone()).await.ok()?;
+        state.get_resolution_note(round_id).await.ok().flatten()
+    }
+    
+    /// Every resolution of a round, oldest first; more than one means it was re-resolved
+    async fn resolution_history(&self, round_id: u64) -> Vec<ResolutionRecord> {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.get_resolution_history(round_id).await.unwrap_or_default(),
+            Err(_) => Vec::new(),
+        }
+    }
+    
+    /// When a round's dispute window ends (null if its rewards were paid on resolution)
+    async fn dispute_deadline(&self, round_id: u64) -> Option<u64> {
+        let state = RoundsState::load(self.storage_context.clone()).await.ok()?;
+        state.dispute_deadline(round_id).await.ok().flatten()
+    }
+    
+    /// Get operation/message counters sorted by name
+    async fn metrics(&self) -> Vec<MetricEntry> {
+        match RoundsState::load(self.storage_context.clone()).await {
+            Ok(state) => state.get_metrics().await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, count)| MetricEntry { name, count })
+                .collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+    
+    /// Get winners for a resolved round, sorted by winnings when `order` is given
+    async fn round_winners(&self, round_id: u64, order: Option<SortOrder>) -> Vec<LibRoundWinnerInfo> {
         // Load a fresh state to query round winners
         match RoundsState::load(self.storage_context.clone()).await {
             Ok(state) => {
                 match state.get_round_winners(round_id).await {
                     Ok(winners) => {
-                        winners.into_iter().map(|(owner, bet_amount, winnings, source_chain_id)| {
+                        let mut winners: Vec<LibRoundWinnerInfo> = winners.into_iter().map(|(owner, bet_amount, winnings, source_chain_id)| {
                             LibRoundWinnerInfo {
                                 owner,
                                 bet_amount,
                                 winnings,
                                 source_chain_id,
                             }
-                        }).collect()
+                        }).collect();
+                        if let Some(order) = order {
+                            order.sort_by_key(&mut winners, |winner| winner.winnings);
+                        }
+                        winners
                     },
                     Err(_) => Vec::new(),
                 }
@@ -278,6 +425,35 @@ impl MutationRoot {
         }
     }
 
+    /// Only report bets whose net win or loss is at least `amount` to the leaderboard
+    async fn set_leaderboard_min_amount(&self, amount: String) -> String {
+        let amount = amount.parse::<Amount>().unwrap_or_default();
+        self.runtime.schedule_operation(&RoundsOperation::SetLeaderboardMinAmount { amount });
+        format!("SetLeaderboardMinAmount operation scheduled: {}", amount)
+    }
+
+    /// Set the number of outcomes for new rounds (2 = Up/Down, 3 = Up/Down/Flat)
+    /// and the price band around the closing price that resolves to Flat
+    async fn set_outcome_config(&self, num_outcomes: u32, flat_band: String) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::SetOutcomeConfig {
+            num_outcomes,
+            flat_band: flat_band.parse::<Amount>().unwrap_or_default(),
+        });
+        format!("SetOutcomeConfig operation scheduled: {} outcomes", num_outcomes)
+    }
+
+    /// Zero all operation/message counters
+    async fn reset_metrics(&self) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::ResetMetrics);
+        "ResetMetrics operation scheduled".to_string()
+    }
+
+    /// Hand the admin role to another account (admin only)
+    async fn transfer_admin_role(&self, new_admin: AccountOwner) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::TransferAdminRole { new_admin });
+        format!("TransferAdminRole operation scheduled: {}", new_admin)
+    }
+
     /// Create a new prediction round
     async fn create_round(&self) -> String {
         self.runtime.schedule_operation(&RoundsOperation::CreateRound);
@@ -291,12 +467,49 @@ impl MutationRoot {
         "CloseRound operation scheduled".to_string()
     }
 
-    /// Resolve a round and distribute rewards (calls NativeFungible to send rewards)
-    async fn resolve_round(&self, resolution_price: String) -> String {
+    /// Resolve a round and distribute rewards (calls NativeFungible to send rewards).
+    /// Without `round_id` the latest closed round is resolved.
+    async fn resolve_round(&self, resolution_price: String, round_id: Option<u64>) -> String {
         let amount = resolution_price.parse::<Amount>().unwrap_or_default();
-        self.runtime.schedule_operation(&RoundsOperation::ResolveRound { resolution_price: amount });
+        self.runtime.schedule_operation(&RoundsOperation::ResolveRound { round_id, resolution_price: amount });
         "ResolveRound operation scheduled - will call NativeFungible for reward distribution".to_string()
     }
+
+    /// Close the active round and resolve it at once (for oracles with an instantaneous price)
+    async fn close_and_resolve(&self, closing_price: String, resolution_price: String) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::CloseAndResolve {
+            closing_price: closing_price.parse::<Amount>().unwrap_or_default(),
+            resolution_price: resolution_price.parse::<Amount>().unwrap_or_default(),
+        });
+        "CloseAndResolve operation scheduled".to_string()
+    }
+
+    /// Resolve a closed round with an admin-chosen outcome when the oracle cannot be trusted (admin only)
+    async fn resolve_with_custom_winner(&self, round_id: u64, result: Prediction, justification: String) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::ResolveWithCustomWinner { round_id, result, justification });
+        format!("ResolveWithCustomWinner operation scheduled for round {}", round_id)
+    }
+
+    /// Pay out a round pending resolution after the treasury has been topped up (admin only)
+    async fn resume_resolution(&self, round_id: u64) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::ResumeResolution { round_id });
+        format!("ResumeResolution operation scheduled for round {}", round_id)
+    }
+    
+    /// Pay up to `limit` queued claims from the treasury (treasury only)
+    async fn pay_queued_claims(&self, limit: u64) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::PayQueuedClaims { limit });
+        "PayQueuedClaims operation scheduled".to_string()
+    }
+    
+    /// Correct a resolved round's price while its dispute window is open (oracle or admin)
+    async fn re_resolve(&self, round_id: u64, corrected_price: String) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::ReResolve {
+            round_id,
+            corrected_price: corrected_price.parse::<Amount>().unwrap_or_default(),
+        });
+        format!("ReResolve operation scheduled for round {}", round_id)
+    }
     
     /// Place a bet in the active round (typically called via cross-app call from NativeFungible)
     async fn place_bet(&self, owner: AccountOwner, amount: String, prediction: Prediction, source_chain_id: Option<String>) -> String {
@@ -308,4 +521,107 @@ impl MutationRoot {
         });
         "PlaceBet operation scheduled".to_string()
     }
+    
+    /// Bet your own tokens unless the bet would move its side's pool share by more than
+    /// `max_slippage_bps`; approve this application as spender in the native app first
+    async fn place_bet_with_slippage(&self, amount: String, prediction: Prediction, max_slippage_bps: u16) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::PlaceBetWithSlippage {
+            amount: amount.parse::<Amount>().unwrap_or_default(),
+            prediction,
+            max_slippage_bps,
+        });
+        "PlaceBetWithSlippage operation scheduled".to_string()
+    }
+    
+    /// Bet your own tokens; approve this application as spender in the native app first
+    async fn place_bet_direct(&self, amount: String, prediction: Prediction) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::PlaceBetDirect {
+            amount: amount.parse::<Amount>().unwrap_or_default(),
+            prediction,
+        });
+        "PlaceBetDirect operation scheduled".to_string()
+    }
+    
+    /// Bet your own tokens on both Up and Down in the active round with one operation;
+    /// approve this application as spender in the native app first
+    async fn place_split_bet(&self, amount_up: String, amount_down: String) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::PlaceSplitBet {
+            amount_up: amount_up.parse::<Amount>().unwrap_or_default(),
+            amount_down: amount_down.parse::<Amount>().unwrap_or_default(),
+        });
+        "PlaceSplitBet operation scheduled".to_string()
+    }
+    
+    /// Place a bet automatically in each new round (max_rounds omitted = until cancelled)
+    async fn set_recurring_bet(&self, amount: String, prediction: Prediction, max_rounds: Option<u64>) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::SetRecurringBet {
+            amount: amount.parse::<Amount>().unwrap_or_default(),
+            prediction,
+            max_rounds,
+        });
+        "SetRecurringBet operation scheduled".to_string()
+    }
+    
+    /// Set the only account allowed to close and resolve rounds (omit to allow anyone; admin only)
+    async fn set_oracle(&self, oracle: Option<AccountOwner>) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::SetOracle { oracle });
+        format!("SetOracle operation scheduled: {:?}", oracle)
+    }
+    
+    /// Set the account bets are sent to and rewards are paid from (admin only)
+    async fn set_treasury(&self, treasury: AccountOwner) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::SetTreasury { treasury });
+        format!("SetTreasury operation scheduled: {}", treasury)
+    }
+    
+    /// Set the share of every bet that is burned, in basis points (max 500)
+    async fn set_burn_fee_bps(&self, bps: u16) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::SetBurnFeeBps { bps });
+        format!("SetBurnFeeBps operation scheduled: {} bps", bps)
+    }
+    
+    /// Keep at most `max_rounds` resolved rounds (omit for unlimited)
+    async fn set_max_history_rounds(&self, max_rounds: Option<u64>) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::SetMaxHistoryRounds { max_rounds });
+        format!("SetMaxHistoryRounds operation scheduled: {:?}", max_rounds)
+    }
+    
+    /// Hold rewards of newly resolved rounds for a dispute window (omit to pay on resolution)
+    async fn set_dispute_window(&self, window_micros: Option<u64>) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::SetDisputeWindow { window_micros });
+        format!("SetDisputeWindow operation scheduled: {:?}", window_micros)
+    }
+    
+    /// Allow each owner at most `max_bets` bets per round (omit for unlimited)
+    async fn set_max_bets_per_user(&self, max_bets: Option<u32>) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::SetMaxBetsPerUser { max_bets });
+        format!("SetMaxBetsPerUser operation scheduled: {:?}", max_bets)
+    }
+    
+    /// Set your own spend limit; omit both arguments to request removal (after 24 hours)
+    async fn set_my_spend_limit(&self, per_round: Option<String>, cooldown_micros: Option<u64>) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::SetMySpendLimit {
+            per_round: per_round.map(|limit| limit.parse::<Amount>().unwrap_or_default()),
+            cooldown_micros,
+        });
+        "SetMySpendLimit operation scheduled".to_string()
+    }
+    
+    /// Send round results to `chain_id` after every resolution
+    async fn subscribe_results(&self, chain_id: ChainId) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::SubscribeResults { chain_id });
+        format!("SubscribeResults operation scheduled for chain {}", chain_id)
+    }
+    
+    /// Stop sending round results to `chain_id`
+    async fn unsubscribe_results(&self, chain_id: ChainId) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::UnsubscribeResults { chain_id });
+        format!("UnsubscribeResults operation scheduled for chain {}", chain_id)
+    }
+    
+    /// Cancel your recurring bet
+    async fn cancel_recurring_bet(&self) -> String {
+        self.runtime.schedule_operation(&RoundsOperation::CancelRecurringBet);
+        "CancelRecurringBet operation scheduled".to_string()
+    }
 }
@@ -1,12 +1,21 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext, ViewError};
-use linera_sdk::linera_base_types::{AccountOwner, Amount, ApplicationId};
+use linera_sdk::views::{linera_views, ByteMapView, LogView, MapView, RegisterView, RootView, View, ViewStorageContext, ViewError};
+use linera_sdk::linera_base_types::{AccountOwner, Amount, ApplicationId, ChainId};
 use serde::{Deserialize, Serialize};
 use async_graphql::SimpleObject;
 use num_bigint::BigUint;
 use num_traits::cast::ToPrimitive;
+use view_batch::BatchLoader;
+use winner_feed::{FeedEntry, Game, WinnerFeed};
+use rounds::{
+    csv::CsvWriter, odds, AdminLogEntry, AppConfig, ArchivedBet, BetSizeBucket, ChainParticipation, Prediction, RecurringBet, RoundArchive, RoundFilter, RoundNetFlow, RoundPriceInputs, ResolutionRecord, RoundResolvedNotice, RoundWinnerInfo, SortOrder, SpendLimit, StateError,
+    DEFAULT_NUM_OUTCOMES, LOCAL_CHAIN_KEY, MAX_BURN_FEE_BPS, MAX_NUM_OUTCOMES, MAX_RESULT_SUBSCRIBERS, RECENT_RESULTS_CAPACITY,
+    SPEND_LIMIT_REMOVAL_DELAY_MICROS,
+};
+// Rounds are stored in their ABI layout; serde output matches the former state-local copies
+pub use rounds::{PredictionRound, RoundStatus};
 
 /// Calculate winnings proportionally based on bet amount
 /// Returns bet_amount + (bet_amount / winner_pool) * total_prize_pool
@@ -38,6 +47,78 @@ fn calculate_winnings_proportional(bet_amount: Amount, winner_pool: Amount, tota
     Amount::from_attos(winnings_u128)
 }
 
+/// Winnings of `bet` in `round` when `result` wins; only the stake on the winning outcome earns
+fn bet_winnings(bet: &PredictionBet, round: &PredictionRound, result: Option<Prediction>) -> Amount {
+    let Some(outcome) = result else {
+        return Amount::ZERO;
+    };
+    let winner_pool = round.pool_for(outcome);
+    let winning_stake = bet.amount_on(outcome);
+    if winner_pool.is_zero() || winning_stake.is_zero() {
+        return Amount::ZERO;
+    }
+    calculate_winnings_proportional(winning_stake, winner_pool, round.prize_pool)
+}
+
+/// What `bet` is owed from `round` resolved with `result`: its winnings, or its whole stake back on a tie
+fn bet_owed(bet: &PredictionBet, round: &PredictionRound, result: Option<Prediction>) -> Amount {
+    match result {
+        Some(_) => bet_winnings(bet, round, result),
+        None => bet.total_amount(),
+    }
+}
+
+/// Share of `amount` burned at a fee of `bps` basis points, rounded down
+pub fn burn_amount(amount: Amount, bps: u16) -> Amount {
+    let burned = BigUint::from(u128::from(amount)) * BigUint::from(bps) / BigUint::from(10_000u32);
+    Amount::from_attos(burned.to_u128().unwrap_or(u128::MAX))
+}
+
+/// Map closing/resolution prices to the winning outcome index
+/// Binary markets: Up if the price rose, Down if it fell, None (tie) if unchanged.
+/// Three-outcome markets: Flat if the price moved by at most `flat_band`, otherwise Up/Down.
+pub fn resolve_outcome(closing_price: Amount, resolution_price: Amount, num_outcomes: u32, flat_band: Amount) -> Option<Prediction> {
+    let movement = if resolution_price > closing_price {
+        resolution_price.saturating_sub(closing_price)
+    } else {
+        closing_price.saturating_sub(resolution_price)
+    };
+    
+    if num_outcomes >= 3 && movement <= flat_band {
+        return Some(Prediction::Flat);
+    }
+    
+    if resolution_price > closing_price {
+        Some(Prediction::Up)
+    } else if resolution_price < closing_price {
+        Some(Prediction::Down)
+    } else {
+        // If prices are equal, no one wins
+        None
+    }
+}
+
+/// Count `amounts` into the buckets delimited by the ascending `boundaries`:
+/// [0, b0), [b0, b1), ..., [bN, ∞)
+pub fn bet_size_histogram(amounts: impl IntoIterator<Item = Amount>, boundaries: &[Amount]) -> Result<Vec<BetSizeBucket>, String> {
+    if boundaries.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err("Bucket boundaries must be strictly ascending".to_string());
+    }
+    
+    let mut buckets: Vec<BetSizeBucket> = std::iter::once(Amount::ZERO)
+        .chain(boundaries.iter().copied())
+        .zip(boundaries.iter().copied().map(Some).chain(std::iter::once(None)))
+        .map(|(min, max)| BetSizeBucket { min, max, count: 0 })
+        .collect();
+    
+    for amount in amounts {
+        // Index of the first boundary above the amount is the bucket it falls into
+        let index = boundaries.partition_point(|boundary| *boundary <= amount);
+        buckets[index].count += 1;
+    }
+    Ok(buckets)
+}
+
 /// The application state for the Rounds application
 #[derive(RootView)]
 #[view(context = ViewStorageContext)]
@@ -52,6 +133,34 @@ pub struct RoundsState {
     /// Chain ID where Leaderboard app is deployed (for cross-chain updates)
     /// If None, leaderboard is on the same chain
     pub leaderboard_chain_id: RegisterView<Option<String>>,
+    /// Bets with a smaller net win or loss are not reported to the leaderboard
+    pub leaderboard_min_amount: RegisterView<Amount>,
+    
+    /// Number of outcomes for newly created rounds (0 means DEFAULT_NUM_OUTCOMES)
+    pub num_outcomes: RegisterView<u32>,
+    /// Maximum price movement that still resolves to Flat in three-outcome rounds
+    pub flat_band: RegisterView<Amount>,
+    
+    /// Account authorized to supply closing and resolution prices (None = anyone)
+    pub oracle: RegisterView<Option<AccountOwner>>,
+    /// Account allowed to run admin operations (None = anyone, for apps created before admins existed)
+    pub admin: RegisterView<Option<AccountOwner>>,
+    
+    /// Share of every bet that is burned, in basis points
+    pub burn_fee_bps: RegisterView<u16>,
+    /// Total burned across all rounds
+    pub total_burned_all_time: RegisterView<Amount>,
+    
+    /// Resolved rounds kept in history (None = unlimited)
+    pub max_history_rounds: RegisterView<Option<u64>>,
+    /// Most PlaceBet operations one owner may make per round (None = unlimited)
+    pub max_bets_per_user: RegisterView<Option<u32>>,
+    /// PlaceBet operations made by each owner in each round
+    pub bet_actions: MapView<(u64, AccountOwner), u32>,
+    /// How long newly resolved rounds stay open to ReResolve (None = paid on resolution)
+    pub dispute_window_micros: RegisterView<Option<u64>>,
+    /// End of the dispute window of every round resolved while one was set; their rewards are claimed
+    pub dispute_deadlines: MapView<u64, u64>,
     
     /// Counter for generating unique round IDs
     pub round_counter: RegisterView<u64>,
@@ -61,94 +170,493 @@ pub struct RoundsState {
     pub active_round: RegisterView<Option<u64>>,
     /// Bets placed in the active round
     pub active_bets: MapView<AccountOwner, PredictionBet>,
+    /// Sequence number of the most recent bet action, across all rounds
+    pub bet_sequence: RegisterView<u64>,
+    /// Round whose resolution has started and not finished; set while it waits in ResolutionPending
+    pub resolution_in_progress: RegisterView<Option<u64>>,
     /// Bets placed in closed rounds (awaiting resolution)
     pub closed_bets: MapView<(u64, AccountOwner), PredictionBet>,
     /// Bets placed in resolved rounds (awaiting claim)
     pub resolved_bets: MapView<(u64, AccountOwner), PredictionBet>,
-}
-
-/// A prediction round for the Up/Down game
-#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
-pub struct PredictionRound {
-    pub id: u64,
-    pub created_at: u64,
-    pub closed_at: Option<u64>,
-    pub resolved_at: Option<u64>,
-    pub status: RoundStatus,
-    pub closing_price: Option<Amount>,
-    pub resolution_price: Option<Amount>,
-    pub up_bets: u64,                  // Number of up bets
-    pub down_bets: u64,                // Number of down bets
-    pub up_bets_pool: Amount,          // Total amount of up bets
-    pub down_bets_pool: Amount,        // Total amount of down bets
-    pub prize_pool: Amount,            // Total amount of tokens bet in this round
-    pub result: Option<Prediction>,    // Result of the round (Up, Down, or None if not resolved)
-}
-
-/// Status of a prediction round
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
-pub enum RoundStatus {
-    Active,
-    Closed,
-    Resolved,
+    /// Operation/message counters keyed by metric name
+    pub metrics: MapView<String, u64>,
+    /// (timestamp, action, actor) of every admin operation, in execution order
+    pub admin_log: LogView<(u64, String, AccountOwner)>,
+    /// Admin justification of every manually resolved round
+    pub resolution_notes: MapView<u64, String>,
+    /// Every resolution of each round, oldest first
+    pub resolution_history: MapView<u64, Vec<ResolutionRecord>>,
+    /// Recurring bets placed automatically whenever a new round starts
+    pub recurring_bets: MapView<AccountOwner, RecurringBet>,
+    /// Self-imposed spend limits per bettor
+    pub spend_limits: MapView<AccountOwner, SpendLimit>,
+    /// Recent winners shown on the homepage ticker
+    pub winner_feed: WinnerFeed<ViewStorageContext>,
+    /// Secondary index of rounds by status: BCS of (status key, round_id), the keys a
+    /// MapView<(u8, u64), ()> stores, kept as bytes so one status is read by prefix
+    pub rounds_by_status: ByteMapView<()>,
+    /// Betting activity per source chain (LOCAL_CHAIN_KEY for local bets)
+    pub chain_participation_stats: MapView<String, ChainParticipation>,
+    /// Chains sent a RoundResolvedNotice after every resolution, with the owner who subscribed them
+    pub result_subscribers: MapView<ChainId, AccountOwner>,
+    /// Results received from the game chain (on subscribed chains), keyed by arrival sequence
+    pub recent_results: MapView<u64, RoundResolvedNotice>,
+    /// Sequence number the next received result will be stored under
+    pub next_result_sequence: RegisterView<u64>,
+    /// Account the stakes are sent to and rewards are paid from (None = the admin)
+    pub treasury: RegisterView<Option<AccountOwner>>,
+    /// Claimed (amount, source chain) waiting for the treasury to pay them, keyed by (round_id, owner)
+    pub queued_claims: MapView<(u64, AccountOwner), (Amount, Option<String>)>,
 }
 
 /// A user's bet in a prediction round
 #[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+#[serde(from = "StoredPredictionBet", into = "StoredPredictionBet")]
 pub struct PredictionBet {
     pub owner: AccountOwner,
-    pub amount_up: Amount,
-    pub amount_down: Amount,
+    pub amounts: Vec<Amount>,  // Amount staked per outcome index
     pub claimed: bool,
     pub source_chain_id: Option<String>, // Add source chain ID for cross-chain bets
 }
 
-/// Prediction direction for the Up/Down game
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
-pub enum Prediction {
-    Up,
-    Down,
+/// Serialized form of PredictionBet: the original Up/Down stakes, then the Flat
+/// stake, which bets stored before three-outcome rounds read as zero
+#[derive(Serialize, Deserialize)]
+struct StoredPredictionBet {
+    owner: AccountOwner,
+    amount_up: Amount,
+    amount_down: Amount,
+    claimed: bool,
+    source_chain_id: Option<String>,
+    #[serde(default, deserialize_with = "native_fungible_abi::stored_fields::default_if_missing")]
+    amount_flat: Amount,
+}
+
+impl From<StoredPredictionBet> for PredictionBet {
+    fn from(stored: StoredPredictionBet) -> Self {
+        let mut amounts = vec![stored.amount_up, stored.amount_down];
+        if !stored.amount_flat.is_zero() {
+            amounts.push(stored.amount_flat);
+        }
+        PredictionBet {
+            owner: stored.owner,
+            amounts,
+            claimed: stored.claimed,
+            source_chain_id: stored.source_chain_id,
+        }
+    }
+}
+
+impl From<PredictionBet> for StoredPredictionBet {
+    fn from(bet: PredictionBet) -> Self {
+        let amount_up = bet.amount_on(Prediction::Up);
+        let amount_down = bet.amount_on(Prediction::Down);
+        let amount_flat = bet.amount_on(Prediction::Flat);
+        StoredPredictionBet {
+            owner: bet.owner,
+            amount_up,
+            amount_down,
+            claimed: bet.claimed,
+            source_chain_id: bet.source_chain_id,
+            amount_flat,
+        }
+    }
+}
+
+impl PredictionBet {
+    /// Amount staked on a given outcome (zero for unknown outcomes)
+    pub fn amount_on(&self, outcome: Prediction) -> Amount {
+        self.amounts.get(outcome.index()).copied().unwrap_or(Amount::ZERO)
+    }
+    
+    /// Total amount staked across all outcomes
+    pub fn total_amount(&self) -> Amount {
+        self.amounts.iter().fold(Amount::ZERO, |total, amount| total.saturating_add(*amount))
+    }
+    
+    /// Every outcome with a non-zero stake, in outcome order
+    pub fn stakes(&self) -> impl Iterator<Item = (Prediction, Amount)> + '_ {
+        Prediction::ALL.into_iter()
+            .zip(self.amounts.iter().copied())
+            .filter(|(_, amount)| !amount.is_zero())
+    }
 }
 
 #[allow(dead_code)]
 impl RoundsState {
+    /// Increment a metrics counter (single read + insert)
+    pub async fn increment_metric(&mut self, name: &str) -> Result<(), String> {
+        let count = self.metrics.get(name).await
+            .map_err(|e: ViewError| format!("Failed to get metric: {:?}", e))?
+            .unwrap_or(0);
+        self.metrics.insert(name, count + 1)
+            .map_err(|e: ViewError| format!("Failed to update metric: {:?}", e))
+    }
+    
+    pub fn record_admin_action(&mut self, timestamp: u64, action: &str, actor: AccountOwner) {
+        self.admin_log.push((timestamp, action.to_string(), actor));
+    }
+    
+    /// Get the most recent `limit` admin log entries, oldest first
+    pub async fn get_admin_log(&self, limit: u64) -> Result<Vec<AdminLogEntry>, String> {
+        let count = self.admin_log.count();
+        let start = count.saturating_sub(usize::try_from(limit).unwrap_or(usize::MAX));
+        let entries = self.admin_log.read(start..count).await
+            .map_err(|e: ViewError| format!("Failed to read admin log: {:?}", e))?;
+        Ok(entries.into_iter()
+            .map(|(timestamp, action, actor)| AdminLogEntry { timestamp, action, actor })
+            .collect())
+    }
+    
+    /// Subscribe `chain_id` to round results on behalf of `owner`
+    pub async fn subscribe_results(&mut self, chain_id: ChainId, owner: AccountOwner) -> Result<(), String> {
+        let subscribed = self.result_subscribers.contains_key(&chain_id).await
+            .map_err(|e: ViewError| format!("Failed to check result subscribers: {:?}", e))?;
+        let subscribers = self.result_subscribers.count().await
+            .map_err(|e: ViewError| format!("Failed to count result subscribers: {:?}", e))? as u64;
+        if !subscribed && subscribers >= MAX_RESULT_SUBSCRIBERS {
+            return Err(format!("Result subscriptions are limited to {} chains", MAX_RESULT_SUBSCRIBERS));
+        }
+        self.result_subscribers.insert(&chain_id, owner)
+            .map_err(|e: ViewError| format!("Failed to subscribe to results: {:?}", e))
+    }
+    
+    /// Unsubscribe `chain_id`; only the owner who subscribed it or the admin may do so
+    pub async fn unsubscribe_results(&mut self, chain_id: ChainId, signer: Option<AccountOwner>) -> Result<(), String> {
+        let subscriber = self.result_subscribers.get(&chain_id).await
+            .map_err(|e: ViewError| format!("Failed to get result subscriber: {:?}", e))?
+            .ok_or_else(|| format!("Chain {} is not subscribed to results", chain_id))?;
+        if signer != Some(subscriber) {
+            self.check_admin(signer)?;
+        }
+        self.result_subscribers.remove(&chain_id)
+            .map_err(|e: ViewError| format!("Failed to unsubscribe from results: {:?}", e))
+    }
+    
+    /// The result notice of a resolved round
+    pub async fn round_resolved_notice(&self, round_id: u64) -> Result<RoundResolvedNotice, String> {
+        let round = self.get_round(round_id).await?.ok_or("Round not found")?;
+        Ok(RoundResolvedNotice {
+            round_id,
+            result: round.result,
+            closing_price: round.closing_price,
+            resolution_price: round.resolution_price,
+            prize_pool: round.prize_pool,
+        })
+    }
+    
+    /// Store a received result, pruning the oldest once RECENT_RESULTS_CAPACITY are kept
+    pub fn record_result_notice(&mut self, notice: RoundResolvedNotice) -> Result<(), String> {
+        let sequence = *self.next_result_sequence.get();
+        self.recent_results.insert(&sequence, notice)
+            .map_err(|e: ViewError| format!("Failed to record round result: {:?}", e))?;
+        self.next_result_sequence.set(sequence + 1);
+        
+        if sequence >= RECENT_RESULTS_CAPACITY {
+            self.recent_results.remove(&(sequence - RECENT_RESULTS_CAPACITY))
+                .map_err(|e: ViewError| format!("Failed to prune round results: {:?}", e))?;
+        }
+        Ok(())
+    }
+    
+    /// Up to `limit` received results, newest first
+    pub async fn recent_results(&self, limit: u64) -> Result<Vec<RoundResolvedNotice>, String> {
+        let next_sequence = *self.next_result_sequence.get();
+        let start = next_sequence.saturating_sub(limit.min(RECENT_RESULTS_CAPACITY));
+        
+        let mut notices = Vec::new();
+        for sequence in (start..next_sequence).rev() {
+            if let Some(notice) = self.recent_results.get(&sequence).await
+                .map_err(|e: ViewError| format!("Failed to get round result: {:?}", e))? {
+                notices.push(notice);
+            }
+        }
+        Ok(notices)
+    }
+    
+    /// Get all metrics counters sorted by name
+    pub async fn get_metrics(&self) -> Result<Vec<(String, u64)>, String> {
+        let names = self.metrics.indices().await
+            .map_err(|e: ViewError| format!("Failed to get metric indices: {:?}", e))?;
+        
+        let mut metrics = Vec::with_capacity(names.len());
+        for name in names {
+            if let Some(count) = self.metrics.get(&name).await
+                .map_err(|e: ViewError| format!("Failed to get metric: {:?}", e))? {
+                metrics.push((name, count));
+            }
+        }
+        metrics.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(metrics)
+    }
+    
+    /// Zero all metrics counters
+    pub fn reset_metrics(&mut self) {
+        self.metrics.clear();
+    }
+    
+    /// Zero the betting activity of every chain
+    pub fn reset_network_stats(&mut self) {
+        self.chain_participation_stats.clear();
+    }
+    
+    /// Number of outcomes to use for the next round
+    pub fn configured_num_outcomes(&self) -> u32 {
+        match *self.num_outcomes.get() {
+            0 => DEFAULT_NUM_OUTCOMES,
+            n => n,
+        }
+    }
+    
+    /// Snapshot of every configuration register
+    pub fn get_config(&self) -> AppConfig {
+        AppConfig {
+            winza_app_id: self.Winza_app_id.get().map(|app_id| app_id.forget_abi().to_string()),
+            native_app_id: self.native_app_id.get().map(|app_id| app_id.to_string()),
+            leaderboard_chain_id: self.leaderboard_chain_id.get().clone(),
+            num_outcomes: *self.num_outcomes.get(),
+            flat_band: *self.flat_band.get(),
+            oracle: *self.oracle.get(),
+            burn_fee_bps: *self.burn_fee_bps.get(),
+            leaderboard_min_amount: *self.leaderboard_min_amount.get(),
+            admin: *self.admin.get(),
+            treasury: *self.treasury.get(),
+            max_history_rounds: *self.max_history_rounds.get(),
+            max_bets_per_user: *self.max_bets_per_user.get(),
+            dispute_window_micros: *self.dispute_window_micros.get(),
+        }
+    }
+    
+    /// Apply a full configuration, validating all of it before changing anything
+    pub fn import_config(&mut self, config: AppConfig) -> Result<(), String> {
+        let parse_app_id = |app_id: &Option<String>| -> Result<Option<ApplicationId>, String> {
+            app_id.as_deref()
+                .map(|app_id| app_id.parse::<ApplicationId>().map_err(|e| format!("Invalid application ID {}: {:?}", app_id, e)))
+                .transpose()
+        };
+        let winza_app_id = parse_app_id(&config.winza_app_id)?;
+        let native_app_id = parse_app_id(&config.native_app_id)?;
+        if let Some(chain_id) = &config.leaderboard_chain_id {
+            chain_id.parse::<ChainId>().map_err(|e| format!("Invalid leaderboard chain ID {}: {:?}", chain_id, e))?;
+        }
+        if config.num_outcomes != 0 && !(DEFAULT_NUM_OUTCOMES..=MAX_NUM_OUTCOMES).contains(&config.num_outcomes) {
+            return Err(format!("num_outcomes must be 0 or between {} and {}", DEFAULT_NUM_OUTCOMES, MAX_NUM_OUTCOMES));
+        }
+        if config.burn_fee_bps > MAX_BURN_FEE_BPS {
+            return Err(format!("Burn fee must be at most {} bps", MAX_BURN_FEE_BPS));
+        }
+        if config.max_history_rounds == Some(0) {
+            return Err("At least one resolved round must be kept".to_string());
+        }
+        if config.max_bets_per_user == Some(0) {
+            return Err("Owners must be allowed at least one bet per round".to_string());
+        }
+        if config.dispute_window_micros == Some(0) {
+            return Err("A dispute window must be longer than zero; pass None to disable it".to_string());
+        }
+        
+        self.Winza_app_id.set(winza_app_id.map(|app_id| app_id.with_abi()));
+        self.native_app_id.set(native_app_id);
+        self.leaderboard_chain_id.set(config.leaderboard_chain_id);
+        self.num_outcomes.set(config.num_outcomes);
+        self.flat_band.set(config.flat_band);
+        self.oracle.set(config.oracle);
+        self.burn_fee_bps.set(config.burn_fee_bps);
+        self.leaderboard_min_amount.set(config.leaderboard_min_amount);
+        self.admin.set(config.admin);
+        self.treasury.set(config.treasury);
+        self.max_history_rounds.set(config.max_history_rounds);
+        self.max_bets_per_user.set(config.max_bets_per_user);
+        self.dispute_window_micros.set(config.dispute_window_micros);
+        Ok(())
+    }
+    
+    /// Configure the outcome count for new rounds and the Flat price band
+    pub fn set_outcome_config(&mut self, num_outcomes: u32, flat_band: Amount) -> Result<(), String> {
+        if num_outcomes < DEFAULT_NUM_OUTCOMES || num_outcomes > MAX_NUM_OUTCOMES {
+            return Err(format!("num_outcomes must be between {} and {}", DEFAULT_NUM_OUTCOMES, MAX_NUM_OUTCOMES));
+        }
+        self.num_outcomes.set(num_outcomes);
+        self.flat_band.set(flat_band);
+        Ok(())
+    }
+    
+    /// Set the share of every bet that is burned
+    pub fn set_burn_fee_bps(&mut self, bps: u16) -> Result<(), String> {
+        if bps > MAX_BURN_FEE_BPS {
+            return Err(format!("Burn fee must be at most {} bps", MAX_BURN_FEE_BPS));
+        }
+        self.burn_fee_bps.set(bps);
+        Ok(())
+    }
+    
+    /// Set how many resolved rounds are kept (None = unlimited)
+    pub fn set_max_history_rounds(&mut self, max_rounds: Option<u64>) -> Result<(), String> {
+        if max_rounds == Some(0) {
+            return Err("At least one resolved round must be kept".to_string());
+        }
+        self.max_history_rounds.set(max_rounds);
+        Ok(())
+    }
+    
+    /// Set how many bets one owner may place per round (None = unlimited)
+    pub fn set_max_bets_per_user(&mut self, max_bets: Option<u32>) -> Result<(), String> {
+        if max_bets == Some(0) {
+            return Err("Owners must be allowed at least one bet per round".to_string());
+        }
+        self.max_bets_per_user.set(max_bets);
+        Ok(())
+    }
+    
+    /// Set how long rounds resolved from now on can be re-resolved (None = no window)
+    pub fn set_dispute_window(&mut self, window_micros: Option<u64>) -> Result<(), String> {
+        if window_micros == Some(0) {
+            return Err("A dispute window must be longer than zero; pass None to disable it".to_string());
+        }
+        self.dispute_window_micros.set(window_micros);
+        Ok(())
+    }
+    
+    /// Remove the oldest resolved rounds beyond `max_history_rounds`, with their bets.
+    /// Only resolved rounds are pruned, and of those only the ones whose dispute window has
+    /// closed at `now` and that owe no bettor an unclaimed payout; the others are kept until
+    /// a later prune. Returns the removed round IDs, oldest first.
+    pub async fn prune_resolved_rounds(&mut self, now: u64) -> Result<Vec<u64>, String> {
+        let Some(max_rounds) = *self.max_history_rounds.get() else {
+            return Ok(Vec::new());
+        };
+        let mut resolved = self.round_ids_with_status(RoundStatus::Resolved).await?;
+        if resolved.len() as u64 <= max_rounds {
+            return Ok(Vec::new());
+        }
+        resolved.sort_unstable();
+        resolved.truncate(resolved.len() - max_rounds as usize);
+        
+        let bet_keys = self.resolved_bets.indices().await
+            .map_err(|e: ViewError| format!("Failed to get resolved bet indices: {:?}", e))?;
+        let mut settled = Vec::with_capacity(resolved.len());
+        for round_id in resolved {
+            if self.dispute_deadline(round_id).await?.is_some_and(|deadline| now < deadline) {
+                continue;
+            }
+            let round = self.rounds.get(&round_id).await
+                .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))?
+                .ok_or("Round not found")?;
+            let mut owes_a_bettor = false;
+            for bet_key in bet_keys.iter().filter(|(id, _)| *id == round_id) {
+                let bet = self.resolved_bets.get(bet_key).await
+                    .map_err(|e: ViewError| format!("Failed to get resolved bet: {:?}", e))?;
+                if bet.is_some_and(|bet| !bet.claimed && !bet_owed(&bet, &round, round.result).is_zero()) {
+                    owes_a_bettor = true;
+                    break;
+                }
+            }
+            if !owes_a_bettor {
+                settled.push(round_id);
+            }
+        }
+        let resolved = settled;
+        
+        for bet_key in bet_keys.into_iter().filter(|(round_id, _)| resolved.binary_search(round_id).is_ok()) {
+            self.resolved_bets.remove(&bet_key)
+                .map_err(|e: ViewError| format!("Failed to remove resolved bet: {:?}", e))?;
+            self.bet_actions.remove(&bet_key)
+                .map_err(|e: ViewError| format!("Failed to remove bet count: {:?}", e))?;
+        }
+        for round_id in &resolved {
+            self.rounds.remove(round_id)
+                .map_err(|e: ViewError| format!("Failed to remove round: {:?}", e))?;
+            self.resolution_notes.remove(round_id)
+                .map_err(|e: ViewError| format!("Failed to remove resolution note: {:?}", e))?;
+            self.resolution_history.remove(round_id)
+                .map_err(|e: ViewError| format!("Failed to remove resolution history: {:?}", e))?;
+            self.dispute_deadlines.remove(round_id)
+                .map_err(|e: ViewError| format!("Failed to remove dispute deadline: {:?}", e))?;
+            self.reindex_round_status(*round_id, Some(RoundStatus::Resolved), None)?;
+        }
+        Ok(resolved)
+    }
+    
+    /// Check that `signer` may supply prices, i.e. no oracle is configured or it is the oracle
+    pub fn check_oracle(&self, signer: Option<AccountOwner>) -> Result<(), String> {
+        match *self.oracle.get() {
+            Some(oracle) if signer != Some(oracle) => {
+                Err(format!("Only the oracle {} can supply round prices (signer: {:?})", oracle, signer))
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    /// Check that `signer` may run admin operations, i.e. no admin is set or it is the admin
+    pub fn check_admin(&self, signer: Option<AccountOwner>) -> Result<(), String> {
+        match *self.admin.get() {
+            Some(admin) if signer != Some(admin) => {
+                Err(format!("Only the admin {} can run this operation (signer: {:?})", admin, signer))
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    /// The account holding the stakes and paying the rewards: the configured treasury, else the admin
+    pub fn treasury(&self) -> Result<AccountOwner, String> {
+        (*self.treasury.get())
+            .or(*self.admin.get())
+            .ok_or_else(|| "No treasury configured; set one with SetTreasury".to_string())
+    }
+    
     /// Creates a new prediction round
     pub async fn create_round(&mut self, timestamp: u64) -> Result<u64, String> {
+        // Bets already in active_bets were paid for; a new round must not drop them
+        let stranded = self.active_bets.indices().await
+            .map_err(|e: ViewError| format!("Failed to get active bet indices: {:?}", e))?
+            .len();
+        if stranded > 0 {
+            return Err(format!(
+                "{} active bets would be stranded; close the active round or use ForceClearActiveBets",
+                stranded
+            ));
+        }
+        
         let round_id = *self.round_counter.get() + 1;
         self.round_counter.set(round_id);
         
-        let round = PredictionRound {
-            id: round_id,
-            created_at: timestamp,
-            closed_at: None,
-            resolved_at: None,
-            status: RoundStatus::Active,
-            closing_price: None,
-            resolution_price: None,
-            up_bets: 0,
-            down_bets: 0,
-            up_bets_pool: Amount::default(),
-            down_bets_pool: Amount::default(),
-            prize_pool: Amount::default(),
-            result: None,
-        };
+        let round = PredictionRound::new(round_id, timestamp, self.configured_num_outcomes());
         
         self.rounds.insert(&round_id, round)
             .map_err(|e: ViewError| format!("Failed to insert round: {:?}", e))?;
+        self.reindex_round_status(round_id, None, Some(RoundStatus::Active))?;
         self.active_round.set(Some(round_id));
         
-        // Clear active bets for the new round by removing all entries
-        let keys: Vec<AccountOwner> = self.active_bets.indices().await
-            .map_err(|e: ViewError| format!("Failed to get active bet indices: {:?}", e))?
-            .into_iter()
-            .collect();
+        Ok(round_id)
+    }
+    
+    /// Remove every active bet and take its stake out of the active round's pools.
+    /// Returns the removed bets so their stakes can be refunded.
+    pub async fn clear_active_bets(&mut self) -> Result<Vec<PredictionBet>, String> {
+        let owners = self.active_bets.indices().await
+            .map_err(|e: ViewError| format!("Failed to get active bet indices: {:?}", e))?;
         
-        for key in keys {
-            self.active_bets.remove(&key)
+        let mut bets = Vec::with_capacity(owners.len());
+        for owner in owners {
+            if let Some(bet) = self.active_bets.get(&owner).await
+                .map_err(|e: ViewError| format!("Failed to get active bet: {:?}", e))? {
+                bets.push(bet);
+            }
+            self.active_bets.remove(&owner)
                 .map_err(|e: ViewError| format!("Failed to remove active bet: {:?}", e))?;
         }
+        if let Some(round_id) = *self.active_round.get() {
+            if let Some(mut round) = self.get_round(round_id).await? {
+                round.outcome_bets = vec![0; round.num_outcomes as usize];
+                round.outcome_pools = vec![Amount::ZERO; round.num_outcomes as usize];
+                round.prize_pool = Amount::ZERO;
+                round.unique_bettors = 0;
+                self.rounds.insert(&round_id, round)
+                    .map_err(|e: ViewError| format!("Failed to update round: {:?}", e))?;
+            }
+        }
         
-        Ok(round_id)
+        Ok(bets)
     }
     
     /// Close the active round
@@ -166,10 +674,9 @@ impl RoundsState {
             }
             
             // Calculate round statistics before closing
-            let mut up_bets = 0u64;
-            let mut down_bets = 0u64;
-            let mut up_bets_pool = Amount::default();
-            let mut down_bets_pool = Amount::default();
+            let num_outcomes = round.num_outcomes as usize;
+            let mut outcome_bets = vec![0u64; num_outcomes];
+            let mut outcome_pools = vec![Amount::ZERO; num_outcomes];
             let mut prize_pool = Amount::default();
             
             // Count bets and calculate prize pools
@@ -183,15 +690,12 @@ impl RoundsState {
                 if let Some(bet) = self.active_bets.get(owner).await
                     .map_err(|e: ViewError| format!("Failed to get active bet: {:?}", e))? {
                     
-                    if !bet.amount_up.is_zero() {
-                        up_bets += 1;
-                        up_bets_pool = up_bets_pool.saturating_add(bet.amount_up);
-                        prize_pool = prize_pool.saturating_add(bet.amount_up);
-                    }
-                    if !bet.amount_down.is_zero() {
-                        down_bets += 1;
-                        down_bets_pool = down_bets_pool.saturating_add(bet.amount_down);
-                        prize_pool = prize_pool.saturating_add(bet.amount_down);
+                    for (index, amount) in bet.amounts.iter().enumerate().take(num_outcomes) {
+                        if !amount.is_zero() {
+                            outcome_bets[index] += 1;
+                            outcome_pools[index] = outcome_pools[index].saturating_add(*amount);
+                            prize_pool = prize_pool.saturating_add(*amount);
+                        }
                     }
                     
                     // Collect bets to move for later processing
@@ -199,10 +703,8 @@ impl RoundsState {
                 }
             }
             
-            round.up_bets = up_bets;
-            round.down_bets = down_bets;
-            round.up_bets_pool = up_bets_pool;
-            round.down_bets_pool = down_bets_pool;
+            round.outcome_bets = outcome_bets;
+            round.outcome_pools = outcome_pools;
             round.prize_pool = prize_pool;
             
             round.status = RoundStatus::Closed;
@@ -211,6 +713,7 @@ impl RoundsState {
             
             self.rounds.insert(&round_id, round)
                 .map_err(|e: ViewError| format!("Failed to update round: {:?}", e))?;
+            self.reindex_round_status(round_id, Some(RoundStatus::Active), Some(RoundStatus::Closed))?;
             self.active_round.set(None);
             
             // Move active bets to closed bets in batch
@@ -226,24 +729,11 @@ impl RoundsState {
             let new_round_id = *self.round_counter.get() + 1;
             self.round_counter.set(new_round_id);
             
-            let new_round = PredictionRound {
-                id: new_round_id,
-                created_at: timestamp,
-                closed_at: None,
-                resolved_at: None,
-                status: RoundStatus::Active,
-                closing_price: None,
-                resolution_price: None,
-                up_bets: 0,
-                down_bets: 0,
-                up_bets_pool: Amount::default(),
-                down_bets_pool: Amount::default(),
-                prize_pool: Amount::default(),
-                result: None,
-            };
+            let new_round = PredictionRound::new(new_round_id, timestamp, self.configured_num_outcomes());
             
             self.rounds.insert(&new_round_id, new_round)
                 .map_err(|e: ViewError| format!("Failed to insert new round: {:?}", e))?;
+            self.reindex_round_status(new_round_id, None, Some(RoundStatus::Active))?;
             self.active_round.set(Some(new_round_id));
             
             // Clear active bets for the new round by removing all entries
@@ -257,6 +747,7 @@ impl RoundsState {
                     .map_err(|e: ViewError| format!("Failed to remove active bet: {:?}", e))?;
             }
             
+            
             Ok(new_round_id)
         } else {
             Err("No active round to close".to_string())
@@ -268,33 +759,196 @@ impl RoundsState {
     /// Resolve a closed round and return list of all bets with their outcomes for reward distribution and stats
     /// Returns: Vec<(AccountOwner, bet_amount, winnings, is_win, source_chain_id)>
     pub async fn resolve_round_and_distribute_rewards(&mut self, round_id: u64, resolution_price: Amount, timestamp: u64) -> Result<Vec<(AccountOwner, Amount, Amount, bool, Option<String>)>, String> {
+        let mut round = self.closed_round(round_id).await?;
+        
+        // Determine the winning outcome based on closing and resolution prices
+        let result = self.price_result(&round, resolution_price)?;
+        round.resolution_price = Some(resolution_price);
+        
+        self.distribute_rewards(round, result, timestamp).await
+    }
+    
+    /// Total winnings resolving closed round `round_id` at `resolution_price` would pay,
+    /// without changing any state
+    pub async fn resolution_payout(&self, round_id: u64, resolution_price: Amount) -> Result<Amount, String> {
+        let round = self.closed_round(round_id).await?;
+        self.round_payout(&round, resolution_price).await
+    }
+    
+    /// Park closed round `round_id` in ResolutionPending with its resolution price, so
+    /// ResumeResolution can pay it out later. Its bets stay in closed_bets.
+    pub async fn mark_resolution_pending(&mut self, round_id: u64, resolution_price: Amount) -> Result<(), String> {
+        let mut round = self.closed_round(round_id).await?;
+        self.begin_resolution(round_id)?;
+        round.resolution_price = Some(resolution_price);
+        round.status = RoundStatus::ResolutionPending;
+        self.rounds.insert(&round_id, round)
+            .map_err(|e: ViewError| format!("Failed to update round: {:?}", e))?;
+        self.reindex_round_status(round_id, Some(RoundStatus::Closed), Some(RoundStatus::ResolutionPending))
+    }
+    
+    /// Resolve a round left in ResolutionPending at its stored resolution price,
+    /// returning the same results as resolve_round_and_distribute_rewards
+    pub async fn resume_resolution(&mut self, round_id: u64, timestamp: u64) -> Result<Vec<(AccountOwner, Amount, Amount, bool, Option<String>)>, String> {
+        let round = self.pending_round(round_id).await?;
+        let resolution_price = round.resolution_price.ok_or("Round has no resolution price")?;
+        let result = self.price_result(&round, resolution_price)?;
+        self.distribute_rewards(round, result, timestamp).await
+    }
+    
+    /// Total winnings a round left in ResolutionPending will pay
+    pub async fn pending_payout(&self, round_id: u64) -> Result<Amount, String> {
+        let round = self.pending_round(round_id).await?;
+        let resolution_price = round.resolution_price.ok_or("Round has no resolution price")?;
+        self.round_payout(&round, resolution_price).await
+    }
+    
+    /// Sum of what every bet in `round` is owed at `resolution_price`, tie refunds included
+    async fn round_payout(&self, round: &PredictionRound, resolution_price: Amount) -> Result<Amount, String> {
+        let result = self.price_result(round, resolution_price)?;
+        let mut payout = Amount::ZERO;
+        for bet in self.get_round_bets(round.id).await? {
+            payout = payout.saturating_add(bet_owed(&bet, round, result));
+        }
+        Ok(payout)
+    }
+    
+    /// The winning outcome of `round` at `resolution_price` (None on a tie)
+    fn price_result(&self, round: &PredictionRound, resolution_price: Amount) -> Result<Option<Prediction>, String> {
+        let closing_price = round.closing_price.ok_or("Round has no closing price")?;
+        Ok(resolve_outcome(closing_price, resolution_price, round.num_outcomes, *self.flat_band.get()))
+    }
+    
+    async fn pending_round(&self, round_id: u64) -> Result<PredictionRound, String> {
+        let round = self.rounds.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))?
+            .ok_or("Round not found")?;
+        if round.status != RoundStatus::ResolutionPending {
+            return Err("Round is not pending resolution".to_string());
+        }
+        Ok(round)
+    }
+    
+    /// Resolve a closed round with an admin-chosen `result`, skipping the price
+    /// comparison, and record the justification. Rewards are computed as for
+    /// resolve_round_and_distribute_rewards.
+    pub async fn resolve_with_custom_winner(&mut self, round_id: u64, result: Prediction, justification: String, timestamp: u64) -> Result<Vec<(AccountOwner, Amount, Amount, bool, Option<String>)>, String> {
+        let mut round = self.closed_round(round_id).await?;
+        if result.index() >= round.num_outcomes as usize {
+            return Err(format!("Outcome {:?} does not exist in a round with {} outcomes", result, round.num_outcomes));
+        }
+        if justification.trim().is_empty() {
+            return Err("A manual resolution needs a justification".to_string());
+        }
+        
+        self.resolution_notes.insert(&round_id, justification)
+            .map_err(|e: ViewError| format!("Failed to store resolution note: {:?}", e))?;
+        round.manual_resolution = true;
+        
+        self.distribute_rewards(round, Some(result), timestamp).await
+    }
+    
+    /// Correct the resolution price of resolved round `round_id` while its dispute window is
+    /// open. The round keeps its resolution time, so the window is not extended; its bets are
+    /// all unclaimed until the window ends, so claims simply follow the corrected result.
+    pub async fn re_resolve(&mut self, round_id: u64, corrected_price: Amount, timestamp: u64) -> Result<(), String> {
         let mut round = self.rounds.get(&round_id).await
             .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))?
-            .ok_or("Round not found")?
-            .clone();
+            .ok_or("Round not found")?;
+        if round.status != RoundStatus::Resolved {
+            return Err("Round is not resolved".to_string());
+        }
+        let deadline = self.dispute_deadline(round_id).await?
+            .ok_or_else(|| format!("Round {} was paid on resolution and cannot be re-resolved", round_id))?;
+        if timestamp >= deadline {
+            return Err(format!("The dispute window of round {} closed at {}", round_id, deadline));
+        }
+        if round.manual_resolution {
+            return Err(format!("Round {} was resolved manually and has no price to correct", round_id));
+        }
         
+        round.result = self.price_result(&round, corrected_price)?;
+        round.resolution_price = Some(corrected_price);
+        self.rounds.insert(&round_id, round.clone())
+            .map_err(|e: ViewError| format!("Failed to update round: {:?}", e))?;
+        self.record_resolution(&round, timestamp).await
+    }
+    
+    /// End of the dispute window of `round_id`, None if it was paid on resolution
+    pub async fn dispute_deadline(&self, round_id: u64) -> Result<Option<u64>, String> {
+        self.dispute_deadlines.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get dispute deadline: {:?}", e))
+    }
+    
+    /// Every resolution of `round_id`, oldest first
+    pub async fn get_resolution_history(&self, round_id: u64) -> Result<Vec<ResolutionRecord>, String> {
+        Ok(self.resolution_history.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get resolution history: {:?}", e))?
+            .unwrap_or_default())
+    }
+    
+    async fn record_resolution(&mut self, round: &PredictionRound, timestamp: u64) -> Result<(), String> {
+        let mut history = self.get_resolution_history(round.id).await?;
+        history.push(ResolutionRecord {
+            resolved_at: timestamp,
+            resolution_price: round.resolution_price,
+            result: round.result,
+        });
+        self.resolution_history.insert(&round.id, history)
+            .map_err(|e: ViewError| format!("Failed to record resolution: {:?}", e))
+    }
+    
+    /// The admin's justification for a manually resolved round
+    pub async fn get_resolution_note(&self, round_id: u64) -> Result<Option<String>, String> {
+        self.resolution_notes.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get resolution note: {:?}", e))
+    }
+    
+    /// Take the resolution lock for `round_id`. Fails while another round's resolution is
+    /// unfinished, so a second operator cannot resolve a different round in between.
+    pub fn begin_resolution(&mut self, round_id: u64) -> Result<(), String> {
+        match *self.resolution_in_progress.get() {
+            Some(pending) if pending != round_id => {
+                Err(format!("Round {} is still being resolved; finish it before resolving round {}", pending, round_id))
+            }
+            _ => {
+                self.resolution_in_progress.set(Some(round_id));
+                Ok(())
+            }
+        }
+    }
+    
+    async fn closed_round(&self, round_id: u64) -> Result<PredictionRound, String> {
+        let round = self.rounds.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))?
+            .ok_or("Round not found")?;
         if round.status != RoundStatus::Closed {
             return Err("Round is not closed".to_string());
         }
-        
-        // Determine the result based on closing and resolution prices
-        let closing_price = round.closing_price.ok_or("Round has no closing price")?;
-        let result = if resolution_price > closing_price {
-            Some(Prediction::Up)
-        } else if resolution_price < closing_price {
-            Some(Prediction::Down)
-        } else {
-            // If prices are equal, no one wins
-            None
-        };
-        
+        Ok(round)
+    }
+    
+    /// Mark `round` resolved with `result`, move its bets to resolved_bets and return
+    /// (owner, total wagered, winnings, is_win, source chain) for every bettor
+    async fn distribute_rewards(&mut self, mut round: PredictionRound, result: Option<Prediction>, timestamp: u64) -> Result<Vec<(AccountOwner, Amount, Amount, bool, Option<String>)>, String> {
+        let round_id = round.id;
+        self.begin_resolution(round_id)?;
+        let previous_status = round.status;
         round.result = result;
         round.status = RoundStatus::Resolved;
         round.resolved_at = Some(timestamp);
-        round.resolution_price = Some(resolution_price);
         
         self.rounds.insert(&round_id, round.clone())
             .map_err(|e: ViewError| format!("Failed to update round: {:?}", e))?;
+        self.reindex_round_status(round_id, Some(previous_status), Some(RoundStatus::Resolved))?;
+        self.record_resolution(&round, timestamp).await?;
+        
+        // With a dispute window the rewards wait to be claimed until the round is final
+        let deadline = self.dispute_window_micros.get().map(|window| timestamp.saturating_add(window));
+        if let Some(deadline) = deadline {
+            self.dispute_deadlines.insert(&round_id, deadline)
+                .map_err(|e: ViewError| format!("Failed to store dispute deadline: {:?}", e))?;
+        }
         
         // Move closed bets to resolved bets
         let keys: Vec<(u64, AccountOwner)> = self.closed_bets.indices().await
@@ -314,8 +968,10 @@ impl RoundsState {
             }
         }
         
-        // Move bets in batch
-        for (bet_key, bet) in &bets_to_move {
+        // Move bets in batch; winnings are paid out right away, so winning bets are already
+        // claimed, unless a dispute window holds them back
+        for (bet_key, bet) in &mut bets_to_move {
+            bet.claimed = deadline.is_none() && !bet_winnings(bet, &round, result).is_zero();
             self.resolved_bets.insert(bet_key, bet.clone())
                 .map_err(|e: ViewError| format!("Failed to move bet to resolved: {:?}", e))?;
             self.closed_bets.remove(&bet_key)
@@ -325,38 +981,11 @@ impl RoundsState {
         // Initialize results vector
         let mut results = Vec::new();
         
-        // Calculate total prize pool and winner pool for calculations
-        let total_prize_pool = round.prize_pool;
-        let winner_pool = match result {
-            Some(Prediction::Up) => round.up_bets_pool,
-            Some(Prediction::Down) => round.down_bets_pool,
-            None => Amount::ZERO,
-        };
-
         // Reuse bets_to_move (which contains all bets for this round) to generate results
         for (_, bet) in &bets_to_move {
-             // Calculate winnings for UP and DOWN
-             let mut winnings_up = Amount::ZERO;
-             let mut winnings_down = Amount::ZERO;
+             let total_winnings = bet_winnings(bet, &round, result);
 
-             if !winner_pool.is_zero() {
-                 match result {
-                    Some(Prediction::Up) => {
-                         if !bet.amount_up.is_zero() {
-                             winnings_up = calculate_winnings_proportional(bet.amount_up, winner_pool, total_prize_pool);
-                         }
-                    },
-                    Some(Prediction::Down) => {
-                         if !bet.amount_down.is_zero() {
-                             winnings_down = calculate_winnings_proportional(bet.amount_down, winner_pool, total_prize_pool);
-                         }
-                    },
-                    None => {},
-                 }
-             }
-
-             let total_wagered = bet.amount_up.saturating_add(bet.amount_down);
-             let total_winnings = winnings_up.saturating_add(winnings_down);
+             let total_wagered = bet.total_amount();
              
              // Logic for leaderboard:
              // 1. Calculate Net Profit = Total Winnings - Total Wagered
@@ -364,17 +993,23 @@ impl RoundsState {
              // 3. If Net Profit <= 0: Player LOST (or broke even). Amount = Total Wagered - Total Winnings (Net Loss).
              
              let is_win = total_winnings > total_wagered;
-             let amount_for_leaderboard = if is_win {
-                 total_winnings.saturating_sub(total_wagered)
-             } else {
-                 total_wagered.saturating_sub(total_winnings)
-             };
 
              // We return a SINGLE entry per user for this round.
-             // The `amount` field in the result tuple will now represent the Clean Profit (if win) or Net Loss (if loss).
              // The `bet_amount` field usually isn't used for logic downstream other than display, so we put total_wagered there.
              // The `winnings` field usually represents generic winnings, we put total_winnings there.
              
+             if !total_winnings.is_zero() {
+                 self.update_chain_participation(bet.source_chain_id.as_deref(), |stats| stats.win_count += 1).await?;
+                 self.winner_feed.append(FeedEntry {
+                     game: Game::Prediction,
+                     owner: bet.owner,
+                     amount: total_winnings,
+                     round_id,
+                     timestamp,
+                     chain_id: bet.source_chain_id.clone(),
+                 })?;
+             }
+
              results.push((
                  bet.owner, 
                  total_wagered, 
@@ -384,13 +1019,53 @@ impl RoundsState {
              ));
         }
         
+        self.prune_resolved_rounds(timestamp).await?;
+        self.resolution_in_progress.set(None);
+        
         Ok(results)
     }
     
 
     
     /// Place a bet in the active round
-    pub async fn place_bet(&mut self, owner: AccountOwner, amount: Amount, prediction: Prediction, source_chain_id: Option<String>) -> Result<(), String> {
+    /// Place a bet in the active round. The burn fee is kept out of the pools;
+    /// returns the amount burned.
+    pub async fn place_bet(&mut self, owner: AccountOwner, amount: Amount, prediction: Prediction, source_chain_id: Option<String>, timestamp: u64) -> Result<Amount, String> {
+        self.place_stakes(owner, &[(prediction, amount)], source_chain_id, timestamp).await
+            .map(|(burned, _)| burned)
+    }
+    
+    /// Check that a bet of `amount` would not move the share of the active round's prize pool
+    /// staked on `prediction` by more than `max_slippage_bps`. The first stakes in an empty
+    /// round set the odds rather than move them, so they are always accepted.
+    pub async fn check_slippage(&self, amount: Amount, prediction: Prediction, max_slippage_bps: u16) -> Result<Result<(), StateError>, String> {
+        let round_id = (*self.active_round.get()).ok_or("No active round")?;
+        let round = self.rounds.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))?
+            .ok_or("Active round not found")?;
+        if !round.prize_pool.is_zero() {
+            let staked = amount.saturating_sub(burn_amount(amount, *self.burn_fee_bps.get()));
+            let side_pool = odds::side_pool(&round.outcome_pools, prediction);
+            let expected_bps = odds::share_bps(round.prize_pool, side_pool);
+            let actual_bps = odds::share_bps(round.prize_pool.saturating_add(staked), side_pool.saturating_add(staked));
+            if expected_bps.abs_diff(actual_bps) > max_slippage_bps {
+                return Ok(Err(StateError::SlippageExceeded { expected_bps, actual_bps }));
+            }
+        }
+        Ok(Ok(()))
+    }
+    
+    /// Stake on Up and Down in one bet action; returns the amount burned and the bet's sequence number
+    pub async fn place_split_bet(&mut self, owner: AccountOwner, amount_up: Amount, amount_down: Amount, source_chain_id: Option<String>, timestamp: u64) -> Result<(Amount, u64), String> {
+        if amount_up.is_zero() || amount_down.is_zero() {
+            return Err("A split bet needs a non-zero amount on both Up and Down".to_string());
+        }
+        self.place_stakes(owner, &[(Prediction::Up, amount_up), (Prediction::Down, amount_down)], source_chain_id, timestamp).await
+    }
+    
+    /// Add `stakes` to the owner's bet in the active round as a single bet action (one spend
+    /// limit check, one max_bets slot); returns the amount burned and the bet's sequence number
+    async fn place_stakes(&mut self, owner: AccountOwner, stakes: &[(Prediction, Amount)], source_chain_id: Option<String>, timestamp: u64) -> Result<(Amount, u64), String> {
         let round_id_opt = self.active_round.get();
         
         if let Some(round_id) = *round_id_opt {
@@ -403,75 +1078,277 @@ impl RoundsState {
                 return Err("No active round accepting bets".to_string());
             }
             
+            for (prediction, _) in stakes {
+                if prediction.index() >= round.num_outcomes as usize {
+                    return Err(format!("Invalid outcome {:?} for a round with {} outcomes", prediction, round.num_outcomes));
+                }
+            }
+            let total = stakes.iter().fold(Amount::ZERO, |total, (_, amount)| total.saturating_add(*amount));
+            
             // Check if user already placed a bet
             let existing_bet = self.active_bets.get(&owner).await
                 .map_err(|e: ViewError| format!("Failed to check bet existence: {:?}", e))?;
             
-            let bet = if let Some(mut old_bet) = existing_bet {
-                // Update existing bet
-                match prediction {
-                    Prediction::Up => {
-                        // Increment counter only if this side was previously empty (new unique bettor for this side)
-                        // Actually, logic is messy if we count unique bettors. Simplest is: don't increment counters on update. 
-                        // But if they had 0 on Up and now bet on Up, they ARE a new Up bettor.
-                        if old_bet.amount_up.is_zero() {
-                            round.up_bets += 1;
-                        }
-                        old_bet.amount_up = old_bet.amount_up.saturating_add(amount);
-                    },
-                    Prediction::Down => {
-                         if old_bet.amount_down.is_zero() {
-                            round.down_bets += 1;
-                        }
-                        old_bet.amount_down = old_bet.amount_down.saturating_add(amount);
-                    }
+            self.check_spend_limit(owner, existing_bet.as_ref(), total, timestamp).await?;
+            
+            // Up and down bets merge into one PredictionBet, so operations are counted separately
+            let bet_actions = self.bet_actions.get(&(round_id, owner)).await
+                .map_err(|e: ViewError| format!("Failed to get bet count: {:?}", e))?
+                .unwrap_or(0);
+            if let Some(max_bets) = *self.max_bets_per_user.get() {
+                if bet_actions >= max_bets {
+                    return Err(format!("{} has already placed the maximum of {} bets in this round", owner, max_bets));
                 }
-                old_bet
-            } else {
-                // New bet
-                let (amount_up, amount_down) = match prediction {
-                    Prediction::Up => {
-                        round.up_bets += 1;
-                        (amount, Amount::ZERO)
-                    },
-                    Prediction::Down => {
-                        round.down_bets += 1;
-                        (Amount::ZERO, amount)
-                    },
-                };
+            }
+            self.bet_actions.insert(&(round_id, owner), bet_actions + 1)
+                .map_err(|e: ViewError| format!("Failed to record bet count: {:?}", e))?;
+            
+            if existing_bet.is_none() {
+                round.unique_bettors += 1;
+            }
+            let mut bet = existing_bet.unwrap_or_else(|| PredictionBet {
+                owner,
+                amounts: vec![Amount::ZERO; round.num_outcomes as usize],
+                claimed: false,
+                source_chain_id,
+            });
+            
+            let mut total_burned = Amount::ZERO;
+            for &(prediction, amount) in stakes {
+                let index = prediction.index();
+                let burned = burn_amount(amount, *self.burn_fee_bps.get());
+                let amount = amount.saturating_sub(burned);
+                total_burned = total_burned.saturating_add(burned);
                 
-                PredictionBet {
-                    owner,
-                    amount_up,
-                    amount_down,
-                    claimed: false,
-                    source_chain_id,
+                // Count a new bettor on this outcome only if they had nothing on it yet
+                if bet.amount_on(prediction).is_zero() {
+                    round.outcome_bets[index] += 1;
                 }
-            };
+                // Stored bets only carry the outcomes they have staked on
+                if bet.amounts.len() <= index {
+                    bet.amounts.resize(index + 1, Amount::ZERO);
+                }
+                bet.amounts[index] = bet.amounts[index].saturating_add(amount);
+                
+                // Update outcome pool and prize pool
+                round.outcome_pools[index] = round.outcome_pools[index].saturating_add(amount);
+                round.prize_pool = round.prize_pool.saturating_add(amount);
+            }
+            round.total_burned_this_round = round.total_burned_this_round.saturating_add(total_burned);
             
+            // One bet action counts once, however many outcomes it stakes on
+            let staked = total.saturating_sub(total_burned);
+            self.update_chain_participation(bet.source_chain_id.as_deref(), |stats| {
+                stats.bet_count += 1;
+                stats.total_volume = stats.total_volume.saturating_add(staked);
+            }).await?;
             self.active_bets.insert(&owner, bet)
                 .map_err(|e: ViewError| format!("Failed to place bet: {:?}", e))?;
             
-            // Update global pools and prize pool
-            match prediction {
-                Prediction::Up => {
-                    round.up_bets_pool = round.up_bets_pool.saturating_add(amount);
-                },
-                Prediction::Down => {
-                    round.down_bets_pool = round.down_bets_pool.saturating_add(amount);
-                },
-            }
-            round.prize_pool = round.prize_pool.saturating_add(amount);
-            
             // Save updated round
             self.rounds.insert(&round_id, round.clone())
                 .map_err(|e: ViewError| format!("Failed to update round statistics: {:?}", e))?;
+            
+            let total_burned_all_time = self.total_burned_all_time.get().saturating_add(total_burned);
+            self.total_burned_all_time.set(total_burned_all_time);
+            let bet_id = *self.bet_sequence.get() + 1;
+            self.bet_sequence.set(bet_id);
+            Ok((total_burned, bet_id))
         } else {
-            return Err("No active round".to_string());
+            Err("No active round".to_string())
         }
-
+    }
+    
+    /// Apply `update` to the participation stats of a bet's source chain
+    async fn update_chain_participation(&mut self, source_chain_id: Option<&str>, update: impl FnOnce(&mut ChainParticipation)) -> Result<(), String> {
+        let chain_id = source_chain_id.unwrap_or(LOCAL_CHAIN_KEY).to_string();
+        let mut stats = self.chain_participation_stats.get(&chain_id).await
+            .map_err(|e: ViewError| format!("Failed to get chain participation: {:?}", e))?
+            .unwrap_or_else(|| ChainParticipation {
+                chain_id: chain_id.clone(),
+                bet_count: 0,
+                total_volume: Amount::ZERO,
+                win_count: 0,
+            });
+        update(&mut stats);
+        self.chain_participation_stats.insert(&chain_id, stats)
+            .map_err(|e: ViewError| format!("Failed to update chain participation: {:?}", e))
+    }
+    
+    /// Betting activity of every chain that has placed a bet, ordered by chain ID
+    pub async fn get_network_participation(&self) -> Result<Vec<ChainParticipation>, String> {
+        let chain_ids = self.chain_participation_stats.indices().await
+            .map_err(|e: ViewError| format!("Failed to get chain participation indices: {:?}", e))?;
         
-        Ok(())
+        let mut participation = Vec::with_capacity(chain_ids.len());
+        for chain_id in chain_ids {
+            if let Some(stats) = self.chain_participation_stats.get(&chain_id).await
+                .map_err(|e: ViewError| format!("Failed to get chain participation: {:?}", e))? {
+                participation.push(stats);
+            }
+        }
+        participation.sort_by(|a, b| a.chain_id.cmp(&b.chain_id));
+        Ok(participation)
+    }
+    
+    /// Get an owner's spend limit, dropping it if its pending removal is due
+    pub async fn get_spend_limit(&self, owner: AccountOwner, now: u64) -> Result<Option<SpendLimit>, String> {
+        let limit = self.spend_limits.get(&owner).await
+            .map_err(|e: ViewError| format!("Failed to get spend limit: {:?}", e))?;
+        Ok(limit.filter(|limit| limit.removal_at.map_or(true, |removal_at| now < removal_at)))
+    }
+    
+    /// Set an owner's spend limit. A limit may only be tightened directly; clearing both
+    /// fields schedules its removal after SPEND_LIMIT_REMOVAL_DELAY_MICROS.
+    pub async fn set_spend_limit(&mut self, owner: AccountOwner, per_round: Option<Amount>, cooldown_micros: Option<u64>, now: u64) -> Result<(), String> {
+        let current = self.get_spend_limit(owner, now).await?;
+        
+        if per_round.is_none() && cooldown_micros.is_none() {
+            let Some(mut limit) = current else {
+                return Ok(());
+            };
+            if limit.removal_at.is_none() {
+                limit.removal_at = Some(now.saturating_add(SPEND_LIMIT_REMOVAL_DELAY_MICROS));
+            }
+            return self.spend_limits.insert(&owner, limit)
+                .map_err(|e: ViewError| format!("Failed to schedule spend limit removal: {:?}", e));
+        }
+        
+        let last_bet_at = match current {
+            Some(current) => {
+                let loosens_cap = match (current.per_round, per_round) {
+                    (Some(old), Some(new)) => new > old,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                };
+                let loosens_cooldown = match (current.cooldown_micros, cooldown_micros) {
+                    (Some(old), Some(new)) => new < old,
+                    (Some(_), None) => true,
+                    (None, _) => false,
+                };
+                if loosens_cap || loosens_cooldown {
+                    return Err("Spend limits can only be tightened; remove the limit first (takes effect after 24 hours)".to_string());
+                }
+                current.last_bet_at
+            }
+            None => None,
+        };
+        
+        let limit = SpendLimit {
+            per_round,
+            cooldown_micros,
+            last_bet_at,
+            removal_at: None,
+        };
+        self.spend_limits.insert(&owner, limit)
+            .map_err(|e: ViewError| format!("Failed to set spend limit: {:?}", e))
+    }
+    
+    /// Enforce an owner's spend limit for a new stake of `amount` and record the bet time
+    async fn check_spend_limit(&mut self, owner: AccountOwner, existing_bet: Option<&PredictionBet>, amount: Amount, now: u64) -> Result<(), String> {
+        let Some(mut limit) = self.spend_limits.get(&owner).await
+            .map_err(|e: ViewError| format!("Failed to get spend limit: {:?}", e))? else {
+            return Ok(());
+        };
+        if limit.removal_at.is_some_and(|removal_at| now >= removal_at) {
+            // The removal delay has passed
+            return self.spend_limits.remove(&owner)
+                .map_err(|e: ViewError| format!("Failed to remove spend limit: {:?}", e));
+        }
+        
+        if let (Some(cooldown), Some(last_bet_at)) = (limit.cooldown_micros, limit.last_bet_at) {
+            let next_bet_at = last_bet_at.saturating_add(cooldown);
+            if now < next_bet_at {
+                return Err(format!("Bet cooldown active until {}", next_bet_at));
+            }
+        }
+        
+        if let Some(per_round) = limit.per_round {
+            let staked = existing_bet.map_or(Amount::ZERO, PredictionBet::total_amount);
+            if staked.saturating_add(amount) > per_round {
+                return Err(format!("Bet of {} exceeds per-round limit {} (already staked {})", amount, per_round, staked));
+            }
+        }
+        
+        limit.last_bet_at = Some(now);
+        self.spend_limits.insert(&owner, limit)
+            .map_err(|e: ViewError| format!("Failed to record bet time: {:?}", e))
+    }
+    
+    /// Register (or replace) an owner's recurring bet
+    pub fn set_recurring_bet(&mut self, owner: AccountOwner, amount: Amount, prediction: Prediction, max_rounds: Option<u64>) -> Result<(), String> {
+        if amount.is_zero() {
+            return Err("Recurring bet amount must be greater than zero".to_string());
+        }
+        if prediction.index() >= self.configured_num_outcomes() as usize {
+            return Err(format!("Invalid outcome {:?} for rounds with {} outcomes", prediction, self.configured_num_outcomes()));
+        }
+        if max_rounds == Some(0) {
+            return Err("max_rounds must be at least 1".to_string());
+        }
+        
+        let recurring_bet = RecurringBet {
+            amount,
+            prediction,
+            max_rounds,
+            rounds_remaining: max_rounds.unwrap_or(0),
+            active: true,
+        };
+        self.recurring_bets.insert(&owner, recurring_bet)
+            .map_err(|e: ViewError| format!("Failed to set recurring bet: {:?}", e))
+    }
+    
+    /// Cancel an owner's recurring bet
+    pub fn cancel_recurring_bet(&mut self, owner: AccountOwner) -> Result<(), String> {
+        self.recurring_bets.remove(&owner)
+            .map_err(|e: ViewError| format!("Failed to cancel recurring bet: {:?}", e))
+    }
+    
+    /// Get an owner's recurring bet
+    pub async fn get_recurring_bet(&self, owner: AccountOwner) -> Result<Option<RecurringBet>, String> {
+        self.recurring_bets.get(&owner).await
+            .map_err(|e: ViewError| format!("Failed to get recurring bet: {:?}", e))
+    }
+    
+    /// Every active recurring bet, with its owner
+    pub async fn active_recurring_bets(&self) -> Result<Vec<(AccountOwner, RecurringBet)>, String> {
+        let owners = self.recurring_bets.indices().await
+            .map_err(|e: ViewError| format!("Failed to get recurring bet indices: {:?}", e))?;
+        
+        let mut active = Vec::new();
+        for owner in owners {
+            let Some(recurring_bet) = self.recurring_bets.get(&owner).await
+                .map_err(|e: ViewError| format!("Failed to get recurring bet: {:?}", e))? else {
+                continue;
+            };
+            if recurring_bet.active {
+                active.push((owner, recurring_bet));
+            }
+        }
+        Ok(active)
+    }
+    
+    /// Record whether `owner`'s recurring bet was placed in the new round: a placed bet uses
+    /// up one of its rounds, one that could not be placed is deactivated
+    pub fn record_recurring_bet(&mut self, owner: AccountOwner, mut recurring_bet: RecurringBet, placed: bool) -> Result<(), String> {
+        if !placed {
+            recurring_bet.active = false;
+        } else if recurring_bet.max_rounds.is_some() {
+            recurring_bet.rounds_remaining = recurring_bet.rounds_remaining.saturating_sub(1);
+            if recurring_bet.rounds_remaining == 0 {
+                recurring_bet.active = false;
+            }
+        }
+        self.recurring_bets.insert(&owner, recurring_bet)
+            .map_err(|e: ViewError| format!("Failed to update recurring bet: {:?}", e))
+    }
+    
+    /// Distinct owners who have bet in the active round (0 without one)
+    pub async fn active_unique_bettors(&self) -> Result<u64, String> {
+        let Some(round_id) = *self.active_round.get() else {
+            return Ok(0);
+        };
+        Ok(self.get_round(round_id).await?.map_or(0, |round| round.unique_bettors))
     }
     
     /// Get the active round ID
@@ -481,16 +1358,12 @@ impl RoundsState {
     
     /// Get all active bets
     pub async fn get_active_bets(&self) -> Result<Vec<(AccountOwner, PredictionBet)>, String> {
-        let mut bets = Vec::new();
         let indices = self.active_bets.indices().await
             .map_err(|e: ViewError| format!("Failed to get active bet indices: {:?}", e))?;
-        
-        for owner in indices {
-            if let Some(bet) = self.active_bets.get(&owner).await
-                .map_err(|e: ViewError| format!("Failed to get active bet: {:?}", e))? {
-                bets.push((owner, bet));
-            }
-        }
+        let mut bets = BatchLoader::new(&self.active_bets).load_present(indices).await
+            .map_err(|e: ViewError| format!("Failed to get active bets: {:?}", e))?;
+        // Stable order for clients that diff repeated queries (one bet per owner)
+        bets.sort_by_key(|(owner, _)| *owner);
         Ok(bets)
     }
     
@@ -517,6 +1390,314 @@ impl RoundsState {
         Ok(rounds)
     }
     
+    /// Move a round between entries of the status index (None = not indexed)
+    fn reindex_round_status(&mut self, round_id: u64, previous: Option<RoundStatus>, current: Option<RoundStatus>) -> Result<(), String> {
+        // BCS of (status key, round_id): the status byte, then the ID in little-endian
+        let index_key = |status: RoundStatus| [&[status.index_key()][..], &round_id.to_le_bytes()].concat();
+        if let Some(previous) = previous {
+            self.rounds_by_status.remove(index_key(previous));
+        }
+        if let Some(current) = current {
+            self.rounds_by_status.insert(index_key(current), ());
+        }
+        Ok(())
+    }
+    
+    /// IDs of the rounds in `status`, read by prefix from the status index (in no particular order)
+    pub async fn round_ids_with_status(&self, status: RoundStatus) -> Result<Vec<u64>, String> {
+        self.rounds_by_status.keys_by_prefix(vec![status.index_key()]).await
+            .map_err(|e: ViewError| format!("Failed to get round status index: {:?}", e))?
+            .into_iter()
+            .map(|key| key.get(1..)
+                .and_then(|round_id| <[u8; 8]>::try_from(round_id).ok())
+                .map(u64::from_le_bytes)
+                .ok_or_else(|| format!("Invalid round status index key: {:?}", key)))
+            .collect()
+    }
+    
+    /// Get up to `limit` rounds in `status`, newest first (uses the status index)
+    pub async fn get_rounds_by_status(&self, status: RoundStatus, limit: u64) -> Result<Vec<PredictionRound>, String> {
+        let filter = RoundFilter { status: Some(status), ..RoundFilter::default() };
+        self.filter_rounds(&filter, SortOrder::Desc, limit).await
+    }
+    
+    /// Get up to `limit` closed rounds not yet resolved, oldest close first (reads only the
+    /// closed rounds, by prefix of the status index)
+    pub async fn get_rounds_awaiting_resolution(&self, limit: u64) -> Result<Vec<PredictionRound>, String> {
+        let mut rounds = Vec::new();
+        for round_id in self.round_ids_with_status(RoundStatus::Closed).await? {
+            if let Some(round) = self.rounds.get(&round_id).await
+                .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))? {
+                rounds.push(round);
+            }
+        }
+        rounds.retain(|round| round.resolved_at.is_none());
+        rounds.sort_by_key(|round| (round.closed_at, round.id));
+        rounds.truncate(usize::try_from(limit).unwrap_or(usize::MAX));
+        Ok(rounds)
+    }
+    
+    /// Up to `limit` rounds matching `filter`, by ID in `order`. A status filter reads
+    /// the status index instead of every round, and since IDs grow with creation time
+    /// the walk stops at the first round past the far creation bound.
+    pub async fn filter_rounds(&self, filter: &RoundFilter, order: SortOrder, limit: u64) -> Result<Vec<PredictionRound>, String> {
+        let mut round_ids: Vec<u64> = match filter.status {
+            Some(status) => self.round_ids_with_status(status).await?,
+            None => self.rounds.indices().await
+                .map_err(|e: ViewError| format!("Failed to get round indices: {:?}", e))?,
+        };
+        order.sort(&mut round_ids);
+        
+        let created = filter.created_range();
+        let mut rounds = Vec::new();
+        for round_id in round_ids {
+            if rounds.len() as u64 >= limit {
+                break;
+            }
+            let Some(round) = self.rounds.get(&round_id).await
+                .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))? else {
+                continue;
+            };
+            if created.is_exhausted(round.created_at, order) {
+                break;
+            }
+            if created.contains(round.created_at) {
+                rounds.push(round);
+            }
+        }
+        Ok(rounds)
+    }
+    
+    /// Get the price inputs for the round an operator has to price next:
+    /// the latest closed round awaiting resolution, otherwise the active round
+    pub async fn get_round_price_inputs(&self, now: u64) -> Result<Option<RoundPriceInputs>, String> {
+        let awaiting_resolution = self.get_all_rounds().await?
+            .into_iter()
+            .filter(|round| round.status == RoundStatus::Closed && round.resolved_at.is_none())
+            .max_by_key(|round| round.id);
+        
+        let round = match awaiting_resolution {
+            Some(round) => Some(round),
+            None => match *self.active_round.get() {
+                Some(round_id) => self.get_round(round_id).await?,
+                None => None,
+            },
+        };
+        
+        Ok(round.map(|round| RoundPriceInputs {
+            round_id: round.id,
+            status: round.status,
+            created_at: round.created_at,
+            elapsed_micros: now.saturating_sub(round.created_at),
+            closing_price: round.closing_price,
+        }))
+    }
+    
+    /// Get all bets of a round, wherever they currently live (active, closed or resolved)
+    pub async fn get_round_bets(&self, round_id: u64) -> Result<Vec<PredictionBet>, String> {
+        let round = self.rounds.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))?
+            .ok_or("Round not found")?;
+        
+        let bets_view = match round.status {
+            RoundStatus::Active => {
+                if *self.active_round.get() != Some(round_id) {
+                    return Ok(Vec::new());
+                }
+                return Ok(self.get_active_bets().await?.into_iter().map(|(_, bet)| bet).collect());
+            }
+            RoundStatus::Closed | RoundStatus::ResolutionPending => &self.closed_bets,
+            RoundStatus::Resolved => &self.resolved_bets,
+        };
+        
+        let keys: Vec<(u64, AccountOwner)> = bets_view.indices().await
+            .map_err(|e: ViewError| format!("Failed to get bet indices: {:?}", e))?
+            .into_iter()
+            .filter(|(id, _)| *id == round_id)
+            .collect();
+        
+        let mut bets = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(bet) = bets_view.get(&key).await
+                .map_err(|e: ViewError| format!("Failed to get bet: {:?}", e))? {
+                bets.push(bet);
+            }
+        }
+        Ok(bets)
+    }
+    
+    /// Histogram of a round's bet sizes (one bet per owner and outcome staked on)
+    pub async fn get_bet_size_histogram(&self, round_id: u64, boundaries: &[Amount]) -> Result<Vec<BetSizeBucket>, String> {
+        let amounts = self.get_round_bets(round_id).await?
+            .into_iter()
+            .flat_map(|bet| bet.amounts)
+            .filter(|amount| !amount.is_zero());
+        bet_size_histogram(amounts, boundaries)
+    }
+    
+    /// Export a round's bets as CSV bytes, one row per owner and outcome staked on
+    /// Columns: owner,outcome,amount,is_winner,winnings,claimed,source_chain_id
+    pub async fn export_round_bets_csv(&self, round_id: u64) -> Result<Vec<u8>, String> {
+        let round = self.get_round(round_id).await?.ok_or("Round not found")?;
+        let winner_pool = round.result.map(|outcome| round.pool_for(outcome)).unwrap_or(Amount::ZERO);
+        
+        let mut writer = CsvWriter::new();
+        writer.write_record(["owner", "outcome", "amount", "is_winner", "winnings", "claimed", "source_chain_id"]);
+        
+        for bet in self.get_round_bets(round_id).await? {
+            for (outcome, amount) in bet.stakes() {
+                let is_winner = round.result == Some(outcome);
+                let winnings = if is_winner && !winner_pool.is_zero() {
+                    calculate_winnings_proportional(amount, winner_pool, round.prize_pool)
+                } else {
+                    Amount::ZERO
+                };
+                
+                writer.write_record([
+                    bet.owner.to_string(),
+                    outcome.index().to_string(),
+                    amount.to_string(),
+                    is_winner.to_string(),
+                    winnings.to_string(),
+                    bet.claimed.to_string(),
+                    bet.source_chain_id.clone().unwrap_or_default(),
+                ]);
+            }
+        }
+        
+        Ok(writer.into_bytes())
+    }
+    
+    /// Canonical archive of a resolved round: every stake and every winner's payout
+    pub async fn round_archive(&self, round_id: u64) -> Result<RoundArchive, String> {
+        let round = self.get_round(round_id).await?.ok_or("Round not found")?;
+        if round.status != RoundStatus::Resolved {
+            return Err("Only resolved rounds can be archived".to_string());
+        }
+        let winner_pool = round.result.map(|outcome| round.pool_for(outcome)).unwrap_or(Amount::ZERO);
+        
+        let mut bets = Vec::new();
+        let mut winners = Vec::new();
+        for bet in self.get_round_bets(round_id).await? {
+            for (outcome, amount) in bet.stakes() {
+                if round.result == Some(outcome) && !winner_pool.is_zero() {
+                    winners.push(RoundWinnerInfo {
+                        owner: bet.owner,
+                        bet_amount: amount,
+                        winnings: calculate_winnings_proportional(amount, winner_pool, round.prize_pool),
+                        source_chain_id: bet.source_chain_id.clone(),
+                    });
+                }
+                bets.push(ArchivedBet {
+                    owner: bet.owner,
+                    outcome,
+                    amount,
+                    claimed: bet.claimed,
+                    source_chain_id: bet.source_chain_id.clone(),
+                });
+            }
+        }
+        bets.sort_by(|a, b| (a.owner, a.outcome).cmp(&(b.owner, b.outcome)));
+        winners.sort_by_key(|winner| winner.owner);
+        
+        let mut participants: Vec<AccountOwner> = bets.iter().map(|bet| bet.owner).collect();
+        participants.dedup();
+        
+        Ok(RoundArchive {
+            round,
+            participants,
+            bets,
+            winners,
+        })
+    }
+    
+    /// Staked, burned, paid and retained totals of resolved round `round_id`
+    pub async fn round_net_flow(&self, round_id: u64) -> Result<RoundNetFlow, String> {
+        let round = self.rounds.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))?
+            .ok_or("Round not found")?;
+        if round.status != RoundStatus::Resolved {
+            return Err("Round is not resolved".to_string());
+        }
+        
+        let mut total_paid = Amount::ZERO;
+        for bet in self.get_round_bets(round_id).await? {
+            let owed = match round.result {
+                Some(result) => bet_winnings(&bet, &round, Some(result)),
+                None => bet.total_amount(),
+            };
+            total_paid = total_paid.saturating_add(owed);
+        }
+        
+        Ok(RoundNetFlow {
+            round_id,
+            total_staked: round.prize_pool.saturating_add(round.total_burned_this_round),
+            fees_taken: round.total_burned_this_round,
+            prize_pool: round.prize_pool,
+            total_paid,
+            dust_retained: round.prize_pool.saturating_sub(total_paid),
+        })
+    }
+    
+    /// Mark `owner`'s bet in resolved round `round_id` claimed and queue what it is still owed
+    /// for the treasury to pay: the whole stake back if the round tied, else its proportional
+    /// winnings. Returns the queued amount, None when the bet is already claimed (winnings are
+    /// paid on resolution) or earns nothing. Fails until the round's dispute window, if any,
+    /// has ended at `now`.
+    pub async fn claim_winnings(&mut self, round_id: u64, owner: AccountOwner, now: u64) -> Result<Option<Amount>, String> {
+        let round = self.rounds.get(&round_id).await
+            .map_err(|e: ViewError| format!("Failed to get round: {:?}", e))?
+            .ok_or("Round not found")?;
+        if round.status != RoundStatus::Resolved {
+            return Err("Round is not resolved".to_string());
+        }
+        if let Some(deadline) = self.dispute_deadline(round_id).await? {
+            if now < deadline {
+                return Err(format!("Round {} can be re-resolved until {}; claim after that", round_id, deadline));
+            }
+        }
+        
+        let Some(mut bet) = self.resolved_bets.get(&(round_id, owner)).await
+            .map_err(|e: ViewError| format!("Failed to get bet: {:?}", e))? else {
+            return Ok(None);
+        };
+        if bet.claimed {
+            return Ok(None);
+        }
+        let owed = bet_owed(&bet, &round, round.result);
+        if owed.is_zero() {
+            return Ok(None);
+        }
+        
+        bet.claimed = true;
+        let source_chain_id = bet.source_chain_id.clone();
+        self.resolved_bets.insert(&(round_id, owner), bet)
+            .map_err(|e: ViewError| format!("Failed to update bet: {:?}", e))?;
+        self.queued_claims.insert(&(round_id, owner), (owed, source_chain_id))
+            .map_err(|e: ViewError| format!("Failed to queue claim: {:?}", e))?;
+        Ok(Some(owed))
+    }
+    
+    /// The first `limit` queued claims, oldest round first
+    pub async fn queued_claims(&self, limit: usize) -> Result<Vec<((u64, AccountOwner), (Amount, Option<String>))>, String> {
+        let keys = self.queued_claims.indices().await
+            .map_err(|e: ViewError| format!("Failed to get queued claims: {:?}", e))?;
+        let mut claims = Vec::new();
+        for key in keys.into_iter().take(limit) {
+            if let Some(claim) = self.queued_claims.get(&key).await
+                .map_err(|e: ViewError| format!("Failed to get queued claim: {:?}", e))? {
+                claims.push((key, claim));
+            }
+        }
+        Ok(claims)
+    }
+    
+    /// Drop a queued claim once the treasury has paid it
+    pub fn remove_queued_claim(&mut self, key: (u64, AccountOwner)) -> Result<(), String> {
+        self.queued_claims.remove(&key)
+            .map_err(|e: ViewError| format!("Failed to remove queued claim: {:?}", e))
+    }
+    
     /// Get winners for a resolved round (returns: owner, bet_amount, winnings, source_chain_id)
     pub async fn get_round_winners(&self, round_id: u64) -> Result<Vec<(AccountOwner, Amount, Amount, Option<String>)>, String> {
         let round = self.rounds.get(&round_id).await
@@ -531,10 +1712,7 @@ impl RoundsState {
         
         // Calculate total prize pool and winner pool
         let total_prize_pool = round.prize_pool;
-        let winner_pool = match result {
-            Prediction::Up => round.up_bets_pool,
-            Prediction::Down => round.down_bets_pool,
-        };
+        let winner_pool = round.pool_for(result);
         
         if winner_pool.is_zero() {
             return Ok(Vec::new()); // No winners
@@ -557,36 +1735,15 @@ impl RoundsState {
             if let Some(bet) = self.resolved_bets.get(&(id, owner.clone())).await
                 .map_err(|e: ViewError| format!("Failed to get bet: {:?}", e))? {
                 
-                // Only include winners who haven't claimed yet
-                // Logic check: verify they won on the winning side and haven't claimed
-                // Since `claimed` is a single bool, it's global for the user in this round.
-                // Assuming "claimed" means "claimed everything".
-                
-                if !bet.claimed { // Not yet claimed
-                    let mut winnings = Amount::ZERO;
-                    let mut bet_amount = Amount::ZERO;
-                    
-                    match result {
-                        Prediction::Up => {
-                            if !bet.amount_up.is_zero() {
-                                bet_amount = bet.amount_up;
-                                winnings = calculate_winnings_proportional(bet.amount_up, winner_pool, total_prize_pool);
-                            }
-                        },
-                        Prediction::Down => {
-                            if !bet.amount_down.is_zero() {
-                                bet_amount = bet.amount_down;
-                                winnings = calculate_winnings_proportional(bet.amount_down, winner_pool, total_prize_pool);
-                            }
-                        }
-                    }
-                    
-                    if !winnings.is_zero() {
-                        winners.push((owner, bet_amount, winnings, bet.source_chain_id.clone()));
-                    }
+                // Winners are listed whether their reward was paid on resolution or claimed later
+                let bet_amount = bet.amount_on(result);
+                if !bet_amount.is_zero() {
+                    let winnings = calculate_winnings_proportional(bet_amount, winner_pool, total_prize_pool);
+                    winners.push((owner, bet_amount, winnings, bet.source_chain_id.clone()));
                 }
             }
         }
+        winners.sort_by_key(|(owner, ..)| *owner);
         
         Ok(winners)
     }
@@ -611,4 +1768,27 @@ mod tests {
         println!("Winnings: {:?}", winnings);
         assert_eq!(winnings, token_amount, "Winnings calculation overflowed!");
     }
+
+    #[test]
+    fn test_resolve_outcome_binary() {
+        let closing = Amount::from_tokens(100);
+        let flat_band = Amount::from_tokens(1);
+        
+        assert_eq!(resolve_outcome(closing, Amount::from_tokens(101), 2, flat_band), Some(Prediction::Up));
+        assert_eq!(resolve_outcome(closing, Amount::from_tokens(99), 2, flat_band), Some(Prediction::Down));
+        // Binary markets ignore the flat band and tie on an unchanged price
+        assert_eq!(resolve_outcome(closing, closing, 2, flat_band), None);
+    }
+
+    #[test]
+    fn test_resolve_outcome_three_outcomes() {
+        let closing = Amount::from_tokens(100);
+        let flat_band = Amount::from_tokens(1);
+        
+        assert_eq!(resolve_outcome(closing, Amount::from_tokens(102), 3, flat_band), Some(Prediction::Up));
+        assert_eq!(resolve_outcome(closing, Amount::from_tokens(98), 3, flat_band), Some(Prediction::Down));
+        assert_eq!(resolve_outcome(closing, Amount::from_tokens(101), 3, flat_band), Some(Prediction::Flat));
+        assert_eq!(resolve_outcome(closing, Amount::from_tokens(99), 3, flat_band), Some(Prediction::Flat));
+        assert_eq!(resolve_outcome(closing, closing, 3, Amount::ZERO), Some(Prediction::Flat));
+    }
 }
@@ -0,0 +1,90 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Compares loading 20 rounds one at a time with loading them through `load_all`, against
+a store where every read takes a fixed latency. Run with `cargo bench`; fails unless the
+batched load is at least 2x faster. */
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
+use futures::executor::block_on;
+use linera_sdk::views::ViewError;
+use view_batch::load_all;
+
+const ROUNDS: u64 = 20;
+const READ_LATENCY: Duration = Duration::from_millis(5);
+const MIN_SPEEDUP: f64 = 2.0;
+
+/// Whether the read has completed, and who to wake when it does
+type ReadState = Arc<Mutex<(bool, Option<Waker>)>>;
+
+/// A storage read that completes `READ_LATENCY` after it is first polled
+struct Read {
+    value: u64,
+    state: Option<ReadState>,
+}
+
+impl Future for Read {
+    type Output = Result<Option<u64>, ViewError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let state = this.state.get_or_insert_with(|| {
+            let state: ReadState = Arc::new(Mutex::new((false, None)));
+            let timer = state.clone();
+            thread::spawn(move || {
+                thread::sleep(READ_LATENCY);
+                let mut guard = timer.lock().unwrap();
+                guard.0 = true;
+                if let Some(waker) = guard.1.take() {
+                    waker.wake();
+                }
+            });
+            state
+        });
+        let mut guard = state.lock().unwrap();
+        if guard.0 {
+            Poll::Ready(Ok(Some(this.value)))
+        } else {
+            guard.1 = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+fn read(round_id: &u64) -> Read {
+    Read { value: round_id * 10, state: None }
+}
+
+fn main() {
+    let round_ids: Vec<u64> = (1..=ROUNDS).collect();
+
+    let start = Instant::now();
+    let sequential = block_on(async {
+        let mut values = Vec::with_capacity(round_ids.len());
+        for round_id in &round_ids {
+            values.push(read(round_id).await.unwrap());
+        }
+        values
+    });
+    let sequential_time = start.elapsed();
+
+    let start = Instant::now();
+    let batched = block_on(load_all(&round_ids, read)).unwrap();
+    let batched_time = start.elapsed();
+
+    assert_eq!(batched, sequential);
+    let speedup = sequential_time.as_secs_f64() / batched_time.as_secs_f64();
+    println!(
+        "{} rounds: one at a time {:?}, batched {:?} ({:.1}x)",
+        ROUNDS, sequential_time, batched_time, speedup
+    );
+    assert!(speedup >= MIN_SPEEDUP, "batched load was only {:.1}x faster, expected at least {}x", speedup, MIN_SPEEDUP);
+}
@@ -0,0 +1,50 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Concurrent lookups of many keys of a MapView */
+
+use std::future::Future;
+
+use futures::future::join_all;
+use linera_sdk::views::{MapView, ViewError};
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Runs `get` for every key in `keys`, starting every lookup before awaiting any of them,
+/// and returns the results in the order of `keys`
+pub async fn load_all<'k, K, V, F, Fut>(keys: &'k [K], get: F) -> Result<Vec<Option<V>>, ViewError>
+where
+    F: FnMut(&'k K) -> Fut,
+    Fut: Future<Output = Result<Option<V>, ViewError>>,
+{
+    join_all(keys.iter().map(get)).await.into_iter().collect()
+}
+
+/// Loads many keys of a `MapView` by starting every `get` up front and awaiting them
+/// together, instead of waiting for each lookup before issuing the next
+pub struct BatchLoader<'a, K, V> {
+    map: &'a MapView<K, V>,
+}
+
+impl<'a, K, V> BatchLoader<'a, K, V>
+where
+    K: Serialize + Send + Sync,
+    V: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    pub fn new(map: &'a MapView<K, V>) -> Self {
+        BatchLoader { map }
+    }
+
+    /// The value of every key in `keys`, in the same order (None for missing keys)
+    pub async fn load(&self, keys: &[K]) -> Result<Vec<Option<V>>, ViewError> {
+        load_all(keys, |key| self.map.get(key)).await
+    }
+
+    /// The keys that have a value, paired with it, in the order of `keys`
+    pub async fn load_present(&self, keys: Vec<K>) -> Result<Vec<(K, V)>, ViewError> {
+        let values = self.load(&keys).await?;
+        Ok(keys.into_iter()
+            .zip(values)
+            .filter_map(|(key, value)| value.map(|value| (key, value)))
+            .collect())
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+/*! Shared "recent winners" feed for the prediction and lottery games */
+
+use async_graphql::SimpleObject;
+use linera_sdk::linera_base_types::{AccountOwner, Amount};
+use linera_sdk::views::linera_views::{self, context::Context, map_view::MapView, register_view::RegisterView};
+use linera_sdk::views::{View, ViewError};
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of entries kept in the feed; older entries are pruned on append
+pub const WINNER_FEED_CAPACITY: u64 = 200;
+
+/// Game a feed entry comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
+pub enum Game {
+    Prediction,
+    Lottery,
+}
+
+/// A finalized reward or prize
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SimpleObject)]
+pub struct FeedEntry {
+    pub game: Game,
+    pub owner: AccountOwner,
+    pub amount: Amount,
+    pub round_id: u64,
+    pub timestamp: u64,
+    pub chain_id: Option<String>, // Winner's chain for cross-chain players
+}
+
+/// Ring buffer of the most recent winners, keyed by sequence number. Generic over the
+/// context so it can be a field of an application's `RootView`.
+#[derive(View)]
+pub struct WinnerFeed<C> {
+    /// Sequence number the next entry will be stored under
+    pub next_sequence: RegisterView<C, u64>,
+    /// The last WINNER_FEED_CAPACITY entries
+    pub entries: MapView<C, u64, FeedEntry>,
+}
+
+impl<C: Context> WinnerFeed<C> {
+    /// Append an entry, pruning the oldest one once the feed is full
+    pub fn append(&mut self, entry: FeedEntry) -> Result<(), String> {
+        let sequence = *self.next_sequence.get();
+        self.entries.insert(&sequence, entry)
+            .map_err(|e: ViewError| format!("Failed to append to winner feed: {:?}", e))?;
+        self.next_sequence.set(sequence + 1);
+        
+        if sequence >= WINNER_FEED_CAPACITY {
+            self.entries.remove(&(sequence - WINNER_FEED_CAPACITY))
+                .map_err(|e: ViewError| format!("Failed to prune winner feed: {:?}", e))?;
+        }
+        Ok(())
+    }
+    
+    /// Get up to `limit` entries, newest first
+    pub async fn recent(&self, limit: u64) -> Result<Vec<FeedEntry>, String> {
+        let next_sequence = *self.next_sequence.get();
+        let start = next_sequence.saturating_sub(limit.min(WINNER_FEED_CAPACITY));
+        
+        let mut entries = Vec::new();
+        for sequence in (start..next_sequence).rev() {
+            if let Some(entry) = self.entries.get(&sequence).await
+                .map_err(|e: ViewError| format!("Failed to get winner feed entry: {:?}", e))? {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+}